@@ -0,0 +1,342 @@
+//! Just enough of the Arrow IPC streaming format to stream tables of numbers
+//! to another process as they're produced, e.g. to `pyarrow.ipc.open_stream`
+//! or `polars`, without a dependency on the whole of Arrow: a schema of
+//! non-nullable 64-bit integer and float columns, then record batches of them,
+//! uncompressed, then the end-of-stream marker.
+//!
+//! Each message is a flatbuffer of its metadata, following `Schema.fbs` and
+//! `Message.fbs` of the Arrow format, then its body. The flatbuffers are laid
+//! out front to back by `Builder`, with each table just after the vtable it
+//! uses and every object after whatever refers to it.
+
+use std::io::{self, Write};
+
+/// The type of the values of a column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnType {
+    Int64,
+    Float64,
+}
+
+/// The values of a column in a record batch.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Column {
+    Int64(Vec<i64>),
+    Float64(Vec<f64>),
+}
+
+impl Column {
+    pub fn column_type(&self) -> ColumnType {
+        match self {
+            Column::Int64(_) => ColumnType::Int64,
+            Column::Float64(_) => ColumnType::Float64,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Column::Int64(values) => values.len(),
+            Column::Float64(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the values as little-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Column::Int64(values) => values.iter().flat_map(|value| value.to_le_bytes()).collect(),
+            Column::Float64(values) => values.iter().flat_map(|value| value.to_le_bytes()).collect(),
+        }
+    }
+}
+
+/// Marks the start of a message, and with a metadata length of 0, the end of
+/// the stream.
+const CONTINUATION: u32 = 0xFFFF_FFFF;
+
+/// The version of the format, V5.
+const METADATA_VERSION: i16 = 4;
+
+/// The members of the `MessageHeader` and `Type` unions used.
+const HEADER_SCHEMA: u8 = 1;
+const HEADER_RECORD_BATCH: u8 = 3;
+const TYPE_INT: u8 = 2;
+const TYPE_FLOATING_POINT: u8 = 3;
+
+/// The `DOUBLE` member of the `Precision` enum.
+const PRECISION_DOUBLE: i16 = 2;
+
+/// Writes a stream of record batches with the same columns.
+pub struct StreamWriter<W: Write> {
+    writer: W,
+    column_types: Vec<ColumnType>,
+}
+
+impl<W: Write> StreamWriter<W> {
+    /// Creates a writer of batches with the given columns, and writes the
+    /// schema.
+    pub fn new(mut writer: W, columns: &[(&str, ColumnType)]) -> io::Result<Self> {
+        write_message(&mut writer, &schema(columns), &[])?;
+
+        Ok(StreamWriter {
+            writer,
+            column_types: columns.iter().map(|&(_, column_type)| column_type).collect(),
+        })
+    }
+
+    /// Writes a batch of rows, given by their columns in the order of the
+    /// schema, each as long as the others.
+    pub fn write(&mut self, columns: &[Column]) -> io::Result<()> {
+        let num_rows = columns.first().map_or(0, Column::len);
+        let matches_schema = columns.len() == self.column_types.len()
+            && columns
+                .iter()
+                .zip(&self.column_types)
+                .all(|(column, &column_type)| column.column_type() == column_type && column.len() == num_rows);
+        if !matches_schema {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a record batch needs columns of the schema's types, all the same length",
+            ));
+        }
+
+        // Every column is a validity buffer, left empty as nothing is null,
+        // and its values, which are 8 bytes each and so stay aligned.
+        let body = columns.iter().flat_map(Column::to_bytes).collect::<Vec<_>>();
+        let mut buffers = Vec::new();
+        let mut offset = 0;
+        for column in columns {
+            let length = column.len() as i64 * 8;
+            buffers.push((offset, 0));
+            buffers.push((offset, length));
+            offset += length;
+        }
+
+        write_message(&mut self.writer, &record_batch(num_rows, &buffers, body.len()), &body)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Writes the end-of-stream marker, and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.write_all(&CONTINUATION.to_le_bytes())?;
+        self.writer.write_all(&0u32.to_le_bytes())?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Writes an encapsulated message: the continuation marker, the length of the
+/// metadata, padded so that the body starts 8-byte aligned, and the body.
+fn write_message<W: Write>(writer: &mut W, metadata: &[u8], body: &[u8]) -> io::Result<()> {
+    let padded_length = metadata.len().div_ceil(8) * 8;
+
+    writer.write_all(&CONTINUATION.to_le_bytes())?;
+    writer.write_all(&(padded_length as u32).to_le_bytes())?;
+    writer.write_all(metadata)?;
+    writer.write_all(&vec![0; padded_length - metadata.len()])?;
+    writer.write_all(body)
+}
+
+/// Builds the `Message` of a schema.
+fn schema(columns: &[(&str, ColumnType)]) -> Vec<u8> {
+    let mut builder = Builder::new();
+    let header = message(&mut builder, HEADER_SCHEMA, 0);
+
+    // Schema { fields }, with little-endian byte order by default.
+    let schema = builder.table(&[Field::Absent, Field::Offset]);
+    builder.patch(header, schema.start);
+    let fields = builder.offset_vector(columns.len());
+    builder.patch(schema.offsets[0], fields.start);
+
+    for (&(name, column_type), &slot) in columns.iter().zip(&fields.offsets) {
+        // Field { name, nullable, type_type, type, dictionary, children }
+        let field = builder.table(&[
+            Field::Offset,
+            Field::U8(0),
+            Field::U8(match column_type {
+                ColumnType::Int64 => TYPE_INT,
+                ColumnType::Float64 => TYPE_FLOATING_POINT,
+            }),
+            Field::Offset,
+            Field::Absent,
+            Field::Offset,
+        ]);
+        builder.patch(slot, field.start);
+
+        let name = builder.string(name);
+        builder.patch(field.offsets[0], name);
+        // Int { bitWidth, is_signed } or FloatingPoint { precision }.
+        let column_type = match column_type {
+            ColumnType::Int64 => builder.table(&[Field::I32(64), Field::U8(1)]),
+            ColumnType::Float64 => builder.table(&[Field::I16(PRECISION_DOUBLE)]),
+        };
+        builder.patch(field.offsets[1], column_type.start);
+        // Readers expect the children of every field, even if there are none.
+        let children = builder.offset_vector(0);
+        builder.patch(field.offsets[2], children.start);
+    }
+
+    builder.finish()
+}
+
+/// Builds the `Message` of a record batch of `num_rows` rows, with the
+/// `(offset, length)` of each of its buffers in a body of `body_length`
+/// bytes.
+fn record_batch(num_rows: usize, buffers: &[(i64, i64)], body_length: usize) -> Vec<u8> {
+    let mut builder = Builder::new();
+    let header = message(&mut builder, HEADER_RECORD_BATCH, body_length);
+
+    // RecordBatch { length, nodes, buffers }
+    let batch = builder.table(&[Field::I64(num_rows as i64), Field::Offset, Field::Offset]);
+    builder.patch(header, batch.start);
+
+    // A FieldNode { length, null_count } for each column, which has two
+    // buffers.
+    let nodes = (0..buffers.len() / 2).map(|_| (num_rows as i64, 0)).collect::<Vec<_>>();
+    let nodes = builder.struct_vector(&nodes);
+    builder.patch(batch.offsets[0], nodes);
+    // A Buffer { offset, length } for each buffer.
+    let buffers = builder.struct_vector(buffers);
+    builder.patch(batch.offsets[1], buffers);
+
+    builder.finish()
+}
+
+/// Starts the `Message` table at the root of `builder`, and returns the slot
+/// of the offset of its header.
+fn message(builder: &mut Builder, header_type: u8, body_length: usize) -> usize {
+    // Message { version, header_type, header, bodyLength }
+    let message = builder.table(&[
+        Field::I16(METADATA_VERSION),
+        Field::U8(header_type),
+        Field::Offset,
+        Field::I64(body_length as i64),
+    ]);
+    builder.patch(0, message.start);
+    message.offsets[0]
+}
+
+/// A field of a flatbuffer table, in the order of the schema.
+enum Field {
+    Absent,
+    U8(u8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    /// An offset to another object, filled in with [`Builder::patch`] once
+    /// that's written.
+    Offset,
+}
+
+/// Where a table or vector was written, and the slots of the offsets in it
+/// still to be filled in.
+struct Written {
+    start: usize,
+    offsets: Vec<usize>,
+}
+
+/// Lays out a flatbuffer front to back, starting with the offset of the root
+/// table.
+struct Builder {
+    buffer: Vec<u8>,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Self { buffer: vec![0; 4] }
+    }
+
+    fn align(&mut self, alignment: usize) {
+        while !self.buffer.len().is_multiple_of(alignment) {
+            self.buffer.push(0);
+        }
+    }
+
+    /// Writes a table and its vtable just before it.
+    fn table(&mut self, fields: &[Field]) -> Written {
+        self.align(2);
+        let vtable = self.buffer.len();
+        self.buffer.resize(vtable + 4 + 2 * fields.len(), 0);
+
+        self.align(4);
+        let start = self.buffer.len();
+        self.buffer.extend_from_slice(&((start - vtable) as i32).to_le_bytes());
+
+        let mut offsets = Vec::new();
+        for (i, field) in fields.iter().enumerate() {
+            let bytes = match *field {
+                Field::Absent => continue,
+                Field::U8(value) => value.to_le_bytes().to_vec(),
+                Field::I16(value) => value.to_le_bytes().to_vec(),
+                Field::I32(value) => value.to_le_bytes().to_vec(),
+                Field::I64(value) => value.to_le_bytes().to_vec(),
+                Field::Offset => vec![0; 4],
+            };
+            self.align(bytes.len());
+            let position = self.buffer.len();
+            if let Field::Offset = field {
+                offsets.push(position);
+            }
+            self.set_u16(vtable + 4 + 2 * i, position - start);
+            self.buffer.extend_from_slice(&bytes);
+        }
+
+        self.set_u16(vtable, 4 + 2 * fields.len());
+        self.set_u16(vtable + 2, self.buffer.len() - start);
+        Written { start, offsets }
+    }
+
+    /// Writes a vector of `len` offsets to be filled in.
+    fn offset_vector(&mut self, len: usize) -> Written {
+        self.align(4);
+        let start = self.buffer.len();
+        self.buffer.extend_from_slice(&(len as u32).to_le_bytes());
+        let offsets = (0..len).map(|i| start + 4 + 4 * i).collect();
+        self.buffer.resize(start + 4 + 4 * len, 0);
+        Written { start, offsets }
+    }
+
+    /// Writes a vector of structs of two longs, which are 8-byte aligned.
+    fn struct_vector(&mut self, structs: &[(i64, i64)]) -> usize {
+        self.align(4);
+        if self.buffer.len().is_multiple_of(8) {
+            self.buffer.extend_from_slice(&[0; 4]);
+        }
+        let start = self.buffer.len();
+        self.buffer.extend_from_slice(&(structs.len() as u32).to_le_bytes());
+        for &(a, b) in structs {
+            self.buffer.extend_from_slice(&a.to_le_bytes());
+            self.buffer.extend_from_slice(&b.to_le_bytes());
+        }
+        start
+    }
+
+    /// Writes a null-terminated string.
+    fn string(&mut self, string: &str) -> usize {
+        self.align(4);
+        let start = self.buffer.len();
+        self.buffer.extend_from_slice(&(string.len() as u32).to_le_bytes());
+        self.buffer.extend_from_slice(string.as_bytes());
+        self.buffer.push(0);
+        start
+    }
+
+    /// Points the offset in `slot` at `target`, which comes after it.
+    fn patch(&mut self, slot: usize, target: usize) {
+        self.buffer[slot..slot + 4].copy_from_slice(&((target - slot) as u32).to_le_bytes());
+    }
+
+    fn set_u16(&mut self, position: usize, value: usize) {
+        self.buffer[position..position + 2].copy_from_slice(&(value as u16).to_le_bytes());
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.buffer
+    }
+}
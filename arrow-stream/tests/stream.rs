@@ -0,0 +1,240 @@
+use arrow_stream::{Column, ColumnType, StreamWriter};
+
+/// Reads the flatbuffer tables of a message, as in `Message.fbs`.
+struct Table<'a> {
+    buffer: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Table<'a> {
+    fn root(buffer: &'a [u8]) -> Self {
+        Self::at(buffer, 0)
+    }
+
+    /// Follows the offset at `position`.
+    fn at(buffer: &'a [u8], position: usize) -> Self {
+        Table {
+            buffer,
+            position: position + u32_at(buffer, position) as usize,
+        }
+    }
+
+    /// Returns where field `index` is, if it's present.
+    fn field(&self, index: usize) -> Option<usize> {
+        let vtable = (self.position as i64 - i32_at(self.buffer, self.position) as i64) as usize;
+        let vtable_length = u16_at(self.buffer, vtable) as usize;
+        if 4 + 2 * index >= vtable_length {
+            return None;
+        }
+        match u16_at(self.buffer, vtable + 4 + 2 * index) {
+            0 => None,
+            offset => Some(self.position + offset as usize),
+        }
+    }
+
+    fn u8(&self, index: usize) -> u8 {
+        self.field(index).map_or(0, |position| self.buffer[position])
+    }
+
+    fn i16(&self, index: usize) -> i16 {
+        self.field(index).map_or(0, |position| u16_at(self.buffer, position) as i16)
+    }
+
+    fn i32(&self, index: usize) -> i32 {
+        self.field(index).map_or(0, |position| i32_at(self.buffer, position))
+    }
+
+    fn i64(&self, index: usize) -> i64 {
+        self.field(index).map_or(0, |position| i64_at(self.buffer, position))
+    }
+
+    fn table(&self, index: usize) -> Table<'a> {
+        Table::at(self.buffer, self.field(index).unwrap())
+    }
+
+    fn string(&self, index: usize) -> &'a str {
+        let start = self.vector(index);
+        let length = u32_at(self.buffer, start) as usize;
+        assert_eq!(self.buffer[start + 4 + length], 0);
+        std::str::from_utf8(&self.buffer[start + 4..start + 4 + length]).unwrap()
+    }
+
+    /// Returns where the vector in field `index` starts, at its length.
+    fn vector(&self, index: usize) -> usize {
+        let slot = self.field(index).unwrap();
+        slot + u32_at(self.buffer, slot) as usize
+    }
+
+    fn tables(&self, index: usize) -> Vec<Table<'a>> {
+        let start = self.vector(index);
+        (0..u32_at(self.buffer, start) as usize)
+            .map(|i| Table::at(self.buffer, start + 4 + 4 * i))
+            .collect()
+    }
+
+    /// Returns a vector of structs of two longs.
+    fn pairs(&self, index: usize) -> Vec<(i64, i64)> {
+        let start = self.vector(index);
+        assert_eq!((start + 4) % 8, 0, "structs of longs are aligned");
+        (0..u32_at(self.buffer, start) as usize)
+            .map(|i| {
+                let position = start + 4 + 16 * i;
+                (i64_at(self.buffer, position), i64_at(self.buffer, position + 8))
+            })
+            .collect()
+    }
+}
+
+fn u16_at(buffer: &[u8], position: usize) -> u16 {
+    u16::from_le_bytes([buffer[position], buffer[position + 1]])
+}
+
+fn u32_at(buffer: &[u8], position: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&buffer[position..position + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+fn i32_at(buffer: &[u8], position: usize) -> i32 {
+    u32_at(buffer, position) as i32
+}
+
+fn i64_at(buffer: &[u8], position: usize) -> i64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&buffer[position..position + 8]);
+    i64::from_le_bytes(bytes)
+}
+
+/// Splits a stream into the metadata and body of each message, checking the
+/// framing and the end-of-stream marker.
+fn messages(stream: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut messages = Vec::new();
+    let mut position = 0;
+    loop {
+        assert_eq!(u32_at(stream, position), 0xFFFF_FFFF);
+        let length = u32_at(stream, position + 4) as usize;
+        position += 8;
+        if length == 0 {
+            assert_eq!(position, stream.len());
+            return messages;
+        }
+        assert_eq!(length % 8, 0);
+
+        let metadata = &stream[position..position + length];
+        let body_length = Table::root(metadata).i64(3) as usize;
+        position += length;
+        messages.push((metadata, &stream[position..position + body_length]));
+        position += body_length;
+    }
+}
+
+const COLUMNS: [(&str, ColumnType); 3] = [
+    ("step", ColumnType::Int64),
+    ("mean_myelination", ColumnType::Float64),
+    ("edges", ColumnType::Int64),
+];
+
+fn stream(batches: &[Vec<Column>]) -> Vec<u8> {
+    let mut writer = StreamWriter::new(Vec::new(), &COLUMNS).unwrap();
+    for batch in batches {
+        writer.write(batch).unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+#[test]
+fn schema_lists_the_columns() {
+    let stream = stream(&[]);
+    let messages = messages(&stream);
+    assert_eq!(messages.len(), 1);
+
+    let (metadata, body) = messages[0];
+    let message = Table::root(metadata);
+    assert_eq!(message.i16(0), 4);
+    assert_eq!(message.u8(1), 1);
+    assert!(body.is_empty());
+
+    let fields = message.table(2).tables(1);
+    let names = fields.iter().map(|field| field.string(0)).collect::<Vec<_>>();
+    assert_eq!(names, ["step", "mean_myelination", "edges"]);
+    for (field, &(_, column_type)) in fields.iter().zip(&COLUMNS) {
+        assert_eq!(field.u8(1), 0, "columns aren't nullable");
+        assert!(field.tables(5).is_empty());
+        match column_type {
+            ColumnType::Int64 => {
+                assert_eq!(field.u8(2), 2);
+                assert_eq!((field.table(3).i32(0), field.table(3).u8(1)), (64, 1));
+            }
+            ColumnType::Float64 => {
+                assert_eq!(field.u8(2), 3);
+                assert_eq!(field.table(3).i16(0), 2);
+            }
+        }
+    }
+}
+
+#[test]
+fn batches_hold_the_values_of_their_columns() {
+    let batches = vec![
+        vec![
+            Column::Int64(vec![1, 2, 3]),
+            Column::Float64(vec![0.5, 1.25, -2.]),
+            Column::Int64(vec![10, 20, i64::MAX]),
+        ],
+        vec![Column::Int64(vec![4]), Column::Float64(vec![f64::NAN]), Column::Int64(vec![-1])],
+    ];
+    let stream = stream(&batches);
+    let messages = messages(&stream);
+    assert_eq!(messages.len(), 3);
+
+    for ((metadata, body), columns) in messages[1..].iter().zip(&batches) {
+        let message = Table::root(metadata);
+        assert_eq!(message.u8(1), 3);
+        let batch = message.table(2);
+        let num_rows = columns[0].len() as i64;
+        assert_eq!(batch.i64(0), num_rows);
+        assert_eq!(batch.pairs(1), vec![(num_rows, 0); 3]);
+
+        let buffers = batch.pairs(2);
+        assert_eq!(buffers.len(), 6);
+        for (i, column) in columns.iter().enumerate() {
+            let (offset, length) = buffers[2 * i];
+            assert_eq!(length, 0, "nothing is null");
+            assert_eq!(offset % 8, 0);
+
+            let (offset, length) = buffers[2 * i + 1];
+            assert_eq!(offset % 8, 0);
+            assert_eq!(length, num_rows * 8);
+            let values = body[offset as usize..(offset + length) as usize].chunks(8).map(|bytes| {
+                let mut value = [0; 8];
+                value.copy_from_slice(bytes);
+                value
+            });
+            let read = match column {
+                Column::Int64(_) => Column::Int64(values.map(i64::from_le_bytes).collect()),
+                Column::Float64(_) => Column::Float64(values.map(f64::from_le_bytes).collect()),
+            };
+            match (&read, column) {
+                (Column::Float64(read), Column::Float64(written)) => {
+                    let bits = |values: &[f64]| values.iter().map(|value| value.to_bits()).collect::<Vec<_>>();
+                    assert_eq!(bits(read), bits(written));
+                }
+                _ => assert_eq!(&read, column),
+            }
+        }
+    }
+}
+
+#[test]
+fn batches_not_matching_the_schema_are_rejected() {
+    let mut writer = StreamWriter::new(Vec::new(), &COLUMNS).unwrap();
+    let wrong_type = [Column::Int64(vec![1]), Column::Int64(vec![1]), Column::Int64(vec![1])];
+    assert!(writer.write(&wrong_type).is_err());
+    let uneven = [Column::Int64(vec![1]), Column::Float64(vec![1.]), Column::Int64(vec![1, 2])];
+    assert!(writer.write(&uneven).is_err());
+    assert!(writer.write(&[Column::Int64(vec![1])]).is_err());
+
+    // Nothing was written after the schema.
+    let stream = writer.finish().unwrap();
+    assert_eq!(messages(&stream).len(), 1);
+}
@@ -0,0 +1,29 @@
+//! Grows a Bose-Einstein network, prints its summary metrics, and exports it
+//! as GraphML for Gephi or Cytoscape.
+//!
+//! `cargo run -p bose-einstein --example growth_to_graphml -- [steps] [path]`
+
+use std::path::Path;
+
+use bose_einstein::Simulation;
+use graph_io::{graphml::GraphMl, GraphExporter};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::Uniform;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let num_steps = args.next().map_or(1000, |steps| steps.parse().expect("steps must be an integer"));
+    let path = args.next().unwrap_or_else(|| "growth.graphml".to_string());
+
+    // Fitnesses uniform on [0, 1), all at a temperature of 1.
+    let mut simulation = Simulation::init(StdRng::seed_from_u64(435), Uniform::new(0., 1.), 1.);
+    for _ in 0..num_steps {
+        simulation.step();
+    }
+
+    let summary = net_metrics::summarize(simulation.graph(), &mut StdRng::seed_from_u64(0));
+    println!("{}", serde_json::to_string_pretty(&summary).unwrap());
+
+    GraphMl.export_to_path(&simulation.attributed_graph(), Path::new(&path)).unwrap();
+    println!("wrote {} nodes to {}", simulation.graph().node_count(), path);
+}
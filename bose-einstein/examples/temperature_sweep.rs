@@ -0,0 +1,50 @@
+//! Sweeps the temperature of a Bose-Einstein network, from the
+//! winner-takes-all phase to the fit-get-rich one, and prints how
+//! concentrated the edges of each are.
+//!
+//! A temperature shared by every node scales their energy levels alike, which
+//! leaves who attaches where unchanged, so each run also raises the energy
+//! levels to the inverse temperature through its [`Kernel`], as the Boltzmann
+//! factor of the energy does in Bianconi and Barabási's model: the colder the
+//! network, the more the fittest node's edge over the rest is amplified.
+//!
+//! `cargo run -p bose-einstein --example temperature_sweep -- [steps]`
+
+use bose_einstein::{CondensationDetector, Kernel, Simulation};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::Uniform;
+
+/// The share of the edges the hub has to keep to count as condensed.
+const THRESHOLD: f64 = 0.1;
+
+fn main() {
+    let num_steps = std::env::args()
+        .nth(1)
+        .map_or(2000, |steps| steps.parse().expect("steps must be an integer"));
+
+    println!("temperature,phase,condensed_at,hub_share,participation_ratio");
+    for &temperature in &[0.05, 0.1, 0.2, 0.5, 1., 2., 5.] {
+        let rng = StdRng::seed_from_u64(435);
+        let kernel = Kernel {
+            degree_exp: 1.,
+            energy_exp: 1. / temperature,
+        };
+        let mut simulation = Simulation::init(rng, Uniform::new(0., 1.), temperature).with_kernel(kernel);
+        let mut detector = CondensationDetector::new(THRESHOLD);
+        detector.record(0, simulation.graph());
+        for step in 1..=num_steps {
+            simulation.step();
+            detector.record(step, simulation.graph());
+        }
+
+        let condensation = detector.report();
+        println!(
+            "{},{:?},{},{:.4},{:.4}",
+            temperature,
+            condensation.phase,
+            condensation.condensed_at.map_or(String::new(), |step| step.to_string()),
+            condensation.hub_share,
+            condensation.participation_ratio
+        );
+    }
+}
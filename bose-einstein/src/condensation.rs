@@ -0,0 +1,135 @@
+//! Detects Bose-Einstein condensation as a network grows. In the
+//! fit-get-rich phase the best connected node's share of the edges shrinks
+//! towards 0 as nodes arrive, while in the winner-takes-all phase a single hub
+//! keeps a finite share, and the edges concentrate on a handful of nodes. Both
+//! are followed step by step, updating the degrees with the edges of each new
+//! node.
+
+use petgraph::{graph::DiGraph, Direction};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// The phase a network grew in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Phase {
+    /// Hubs emerge, but none keeps a finite share of the edges.
+    FitGetRich,
+    /// A single hub keeps a finite share of the edges.
+    WinnerTakesAll,
+}
+
+/// Whether and when a network condensed, and how concentrated its edges were.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Condensation {
+    pub phase: Phase,
+    /// The step from which the hub's share of the edges stayed at or above
+    /// the threshold until the end, if it did.
+    pub condensed_at: Option<usize>,
+    /// The share of the edges held by the node of highest degree at the end:
+    /// its degree over the sum of the degrees.
+    pub hub_share: f64,
+    /// The largest hub share after any step.
+    pub peak_hub_share: f64,
+    /// The participation ratio of the degrees at the end, `(Σk)² / (N Σk²)`:
+    /// the fraction of the nodes the edges are effectively spread over, near
+    /// 1 when every node has the same degree and near 0 when a few hold them
+    /// all.
+    pub participation_ratio: f64,
+}
+
+/// Follows the degrees of a growing network, in which every edge added joins
+/// the node added with it, and the concentration of its edges on the hub.
+pub struct CondensationDetector {
+    threshold: f64,
+    degrees: Vec<usize>,
+    total_degree: usize,
+    sum_of_squares: usize,
+    max_degree: usize,
+    condensed_at: Option<usize>,
+    peak_hub_share: f64,
+}
+
+impl CondensationDetector {
+    /// Creates a detector which counts the network as condensed while the hub
+    /// holds at least `threshold` of its edges.
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            degrees: Vec::new(),
+            total_degree: 0,
+            sum_of_squares: 0,
+            max_degree: 0,
+            condensed_at: None,
+            peak_hub_share: 0.,
+        }
+    }
+
+    /// Catches up on the nodes added to `graph` since the last call, with
+    /// their edges, after the step numbered `step`.
+    pub fn record<N, E>(&mut self, step: usize, graph: &DiGraph<N, E>) {
+        for node in graph.node_indices().skip(self.degrees.len()) {
+            self.degrees.push(0);
+
+            // Edges between nodes which are both new are counted from the
+            // later one.
+            for direction in [Direction::Outgoing, Direction::Incoming] {
+                for other in graph.neighbors_directed(node, direction) {
+                    if other < node || (other == node && direction == Direction::Outgoing) {
+                        self.increment(node.index());
+                        self.increment(other.index());
+                    }
+                }
+            }
+        }
+
+        let hub_share = self.hub_share();
+        self.peak_hub_share = self.peak_hub_share.max(hub_share);
+        if hub_share < self.threshold {
+            self.condensed_at = None;
+        } else if self.condensed_at.is_none() {
+            self.condensed_at = Some(step);
+        }
+    }
+
+    fn increment(&mut self, node: usize) {
+        let degree = &mut self.degrees[node];
+        self.sum_of_squares += 2 * *degree + 1;
+        *degree += 1;
+        self.total_degree += 1;
+        self.max_degree = self.max_degree.max(*degree);
+    }
+
+    /// Returns the share of the edges held by the node of highest degree.
+    pub fn hub_share(&self) -> f64 {
+        if self.total_degree == 0 {
+            0.
+        } else {
+            self.max_degree as f64 / self.total_degree as f64
+        }
+    }
+
+    /// Returns the participation ratio of the degrees, or 0 if there are no
+    /// edges.
+    pub fn participation_ratio(&self) -> f64 {
+        if self.sum_of_squares == 0 {
+            0.
+        } else {
+            let total = self.total_degree as f64;
+            total * total / (self.degrees.len() as f64 * self.sum_of_squares as f64)
+        }
+    }
+
+    /// Returns whether and when the network condensed, as of the last step.
+    pub fn report(&self) -> Condensation {
+        Condensation {
+            phase: if self.condensed_at.is_some() { Phase::WinnerTakesAll } else { Phase::FitGetRich },
+            condensed_at: self.condensed_at,
+            hub_share: self.hub_share(),
+            peak_hub_share: self.peak_hub_share,
+            participation_ratio: self.participation_ratio(),
+        }
+    }
+}
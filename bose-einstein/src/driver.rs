@@ -0,0 +1,553 @@
+//! Runs simulations to completion and writes out their graphs and metrics.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use compression::Compression;
+use csv::Writer;
+use distributions::{Sampler, Spec};
+use graph_io::{AttributedGraph, Attributes};
+use model::Model;
+use net_metrics::graphlets::GRAPHLET_SAMPLE_NODES;
+use net_metrics::robustness::ROBUSTNESS_POINTS;
+use net_metrics::{Direction, Graphlets, Removal, RobustnessCurve, Summary};
+use petgraph::EdgeDirection;
+use progress::Progress;
+use provenance::Ledger;
+use results_db::ResultsDb;
+use rand::{prelude::*, rngs::StdRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::condensation::{Condensation, CondensationDetector};
+use crate::ensemble::{Ensemble, RankedGraph};
+use crate::sim::{DirectedKernels, Duplication, Kernel, Simulation, Temperature};
+
+/// The exponents of the kernels of directed attachment, in which new nodes
+/// link to nodes by their in-degree, as citations do, and are linked to by
+/// nodes by their out-degree, as followers are.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DirectedAttachment {
+    pub in_degree_exponent: f64,
+    pub in_energy_exponent: f64,
+    pub out_degree_exponent: f64,
+    pub out_energy_exponent: f64,
+}
+
+impl Default for DirectedAttachment {
+    fn default() -> Self {
+        Self {
+            in_degree_exponent: 1.0,
+            in_energy_exponent: 1.0,
+            out_degree_exponent: 1.0,
+            out_energy_exponent: 1.0,
+        }
+    }
+}
+
+impl DirectedAttachment {
+    pub(crate) fn kernels(&self) -> DirectedKernels {
+        DirectedKernels {
+            inbound: Kernel {
+                degree_exp: self.in_degree_exponent,
+                energy_exp: self.in_energy_exponent,
+            },
+            outbound: Kernel {
+                degree_exp: self.out_degree_exponent,
+                energy_exp: self.out_energy_exponent,
+            },
+        }
+    }
+}
+
+/// Duplication-divergence growth, in which a new node copies a node chosen
+/// uniformly with probability `probability`, in place of attaching by the
+/// kernel: it inherits the node's fitness, perturbed by a log-normal factor
+/// whose logarithm has a standard deviation of `fitness_noise`, and each of
+/// its links with probability `retention`, and links to the node itself with
+/// probability `parent_link`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DuplicationDivergence {
+    pub probability: f64,
+    pub retention: f64,
+    pub parent_link: f64,
+    pub fitness_noise: f64,
+}
+
+impl Default for DuplicationDivergence {
+    fn default() -> Self {
+        Self {
+            probability: 0.5,
+            retention: 0.5,
+            parent_link: 0.5,
+            fitness_noise: 0.1,
+        }
+    }
+}
+
+impl DuplicationDivergence {
+    pub(crate) fn duplication(&self) -> Duplication {
+        Duplication {
+            probability: self.probability,
+            retention: self.retention,
+            parent_link: self.parent_link,
+            fitness_noise: self.fitness_noise,
+        }
+    }
+}
+
+/// How each node's temperature is picked, in place of every node having the
+/// initial temperature, to model a heterogeneous environment.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum NodeTemperature {
+    /// Drawn from a distribution, independently for each node.
+    Distribution { distribution: Spec },
+    /// Following the order nodes arrive in, counting from 0, interpolated
+    /// linearly between `[arrival, temperature]` points, e.g. `[[0, 2], [10000,
+    /// 0.5]]` for an environment which cools as the network grows.
+    Schedule { points: Vec<(usize, f64)> },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub num_steps: u64,
+    /// How many independent runs a sweep is made of.
+    pub num_runs: u64,
+    pub initial_temperature: f64,
+    /// How each node's temperature is picked, if not every node has the
+    /// initial temperature.
+    pub node_temperature: Option<NodeTemperature>,
+    /// The exponent of a node's degree in the weight new nodes attach to it
+    /// with, `energy^energy_exponent * degree^degree_exponent`.
+    pub degree_exponent: f64,
+    /// The exponent of a node's energy level in that weight.
+    pub energy_exponent: f64,
+    /// Attaches new nodes in both directions, with these kernels in place of
+    /// the one above, if given.
+    pub directed_attachment: Option<DirectedAttachment>,
+    /// Has some new nodes copy existing ones in place of attaching by the
+    /// kernel, if given, to compare growth driven by fitness with growth
+    /// driven by duplication.
+    pub duplication_divergence: Option<DuplicationDivergence>,
+    /// The mean of the inverse Gaussian distribution fitnesses are drawn from.
+    pub fitness_mean: f64,
+    /// The shape of the inverse Gaussian distribution fitnesses are drawn
+    /// from.
+    pub fitness_shape: f64,
+    /// The distribution fitnesses are drawn from in place of the inverse
+    /// Gaussian above, if given: e.g. that one truncated to `[0, η_max]`,
+    /// where `η_max` decides whether the network condenses.
+    pub fitness_distribution: Option<Spec>,
+    /// The share of the edges the best connected node has to keep, from some
+    /// step to the end, for a run to count as condensed; see
+    /// [`crate::condensation`].
+    pub condensation_threshold: f64,
+    /// The seed of the random number generator, or `None` to pick one at
+    /// random. Each run of a sweep is seeded from this plus its index.
+    pub seed: Option<u64>,
+    /// How the CSV and JSON lines output is compressed.
+    pub compression: Compression,
+    /// The format, by name or file extension, a sweep exports the consensus
+    /// of its runs' final graphs in, if given; see [`crate::ensemble`].
+    pub ensemble_format: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            num_steps: 10000,
+            num_runs: 1000,
+            initial_temperature: 1.0,
+            node_temperature: None,
+            degree_exponent: 1.0,
+            energy_exponent: 1.0,
+            directed_attachment: None,
+            duplication_divergence: None,
+            fitness_mean: 1.0,
+            fitness_shape: 10.0,
+            fitness_distribution: None,
+            condensation_threshold: 0.1,
+            seed: None,
+            compression: Compression::None,
+            ensemble_format: None,
+        }
+    }
+}
+
+impl Config {
+    /// Returns this configuration with a seed, picking one at random if it
+    /// has none, so that its runs can be reproduced.
+    pub fn seeded(&self) -> Self {
+        Self {
+            seed: Some(self.seed.unwrap_or_else(random)),
+            ..self.clone()
+        }
+    }
+
+    /// Checks that the parameters describe a simulation which can be run.
+    pub fn validate(&self) -> io::Result<()> {
+        let directed_exponents = self.directed_attachment.iter().flat_map(|directed| {
+            [
+                directed.in_degree_exponent,
+                directed.in_energy_exponent,
+                directed.out_degree_exponent,
+                directed.out_energy_exponent,
+            ]
+        });
+        if ![self.degree_exponent, self.energy_exponent]
+            .iter()
+            .copied()
+            .chain(directed_exponents)
+            .all(f64::is_finite)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the degree and energy exponents have to be finite",
+            ));
+        }
+
+        if let Some(duplication) = &self.duplication_divergence {
+            let probabilities = [duplication.probability, duplication.retention, duplication.parent_link];
+            if !probabilities.iter().all(|probability| (0. ..=1.).contains(probability))
+                || duplication.retention + duplication.parent_link == 0.
+                || !(duplication.fitness_noise >= 0. && duplication.fitness_noise.is_finite())
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "duplication needs probabilities of copying and keeping links, not both 0, and a finite, \
+                     nonnegative fitness noise",
+                ));
+            }
+        }
+
+        if !(0. ..=1.).contains(&self.condensation_threshold) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("condensation threshold {} isn't a share of the edges", self.condensation_threshold),
+            ));
+        }
+
+        if let Some(format) = &self.ensemble_format {
+            if graph_io::exporter(format).is_none() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown ensemble format {}", format),
+                ));
+            }
+        }
+
+        self.fitness()?;
+        self.temperature().map(|_| ())
+    }
+
+    /// Builds the distribution fitnesses are drawn from, which can only draw
+    /// positive ones, so that every energy level is positive.
+    pub fn fitness(&self) -> io::Result<Sampler> {
+        let spec = match &self.fitness_distribution {
+            Some(spec) => spec.clone(),
+            None => Spec::InverseGaussian {
+                mean: self.fitness_mean,
+                shape: self.fitness_shape,
+            },
+        };
+
+        if !spec.is_positive() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("fitness distribution {:?} can draw fitnesses which aren't positive", spec),
+            ));
+        }
+
+        spec.build()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, format!("fitness distribution: {}", err)))
+    }
+
+    /// Builds the way each node's temperature is picked, which can only pick
+    /// positive ones, as for fitnesses.
+    pub fn temperature(&self) -> io::Result<Temperature<Sampler>> {
+        match &self.node_temperature {
+            None => {
+                if !(self.initial_temperature > 0. && self.initial_temperature.is_finite()) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("temperature {} isn't positive", self.initial_temperature),
+                    ));
+                }
+
+                Ok(Temperature::Constant(self.initial_temperature))
+            }
+            Some(NodeTemperature::Distribution { distribution }) => {
+                if !distribution.is_positive() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "temperature distribution {:?} can draw temperatures which aren't positive",
+                            distribution
+                        ),
+                    ));
+                }
+
+                distribution.build().map(Temperature::Sampled).map_err(|err| {
+                    io::Error::new(io::ErrorKind::InvalidInput, format!("temperature distribution: {}", err))
+                })
+            }
+            Some(NodeTemperature::Schedule { points }) => {
+                let increasing = points.windows(2).all(|pair| pair[0].0 < pair[1].0);
+                let positive = points.iter().all(|&(_, temperature)| temperature > 0. && temperature.is_finite());
+                if points.is_empty() || !increasing || !positive {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "a temperature schedule needs points with increasing arrivals and positive temperatures",
+                    ));
+                }
+
+                Ok(Temperature::Schedule(points.clone()))
+            }
+        }
+    }
+}
+
+/// The name runs are added to a results database under.
+const PROGRAM: &str = "bose-einstein";
+
+/// The extension of the ledger of a sweep's finished runs, in place of that of
+/// the file it writes to.
+pub const LEDGER_EXTENSION: &str = "ledger.jsonl";
+
+/// The metrics of the graph from one run, with the number of nodes of each
+/// in-degree and out-degree, its graphlet counts, the size of its giant
+/// component as nodes are removed by degree and at random, and whether it
+/// condensed as it grew.
+#[derive(Serialize)]
+struct RunMetrics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run: Option<u64>,
+    #[serde(flatten)]
+    summary: Summary,
+    in_degree_distribution: BTreeMap<usize, usize>,
+    out_degree_distribution: BTreeMap<usize, usize>,
+    graphlets: Graphlets,
+    targeted_robustness: RobustnessCurve,
+    random_robustness: RobustnessCurve,
+    condensation: Condensation,
+}
+
+impl RunMetrics {
+    /// Collects the metrics of the graph of `simulation`, estimating its
+    /// graphlet counts from a sample of its nodes drawn from `rng` if it's
+    /// large, and removing its nodes at random in an order drawn from it.
+    fn new<R: Rng, D: Distribution<f64>, S: Rng>(
+        run: Option<u64>,
+        summary: Summary,
+        simulation: &Simulation<R, D>,
+        condensation: Condensation,
+        rng: &mut S,
+    ) -> Self {
+        let graph = simulation.graph();
+        Self {
+            run,
+            summary,
+            in_degree_distribution: net_metrics::degree_distribution(graph, Direction::In),
+            out_degree_distribution: net_metrics::degree_distribution(graph, Direction::Out),
+            graphlets: net_metrics::sample_graphlet_counts(graph, GRAPHLET_SAMPLE_NODES, rng),
+            targeted_robustness: net_metrics::robustness_curve(graph, Removal::Degree, ROBUSTNESS_POINTS, rng),
+            random_robustness: net_metrics::robustness_curve(graph, Removal::Random, ROBUSTNESS_POINTS, rng),
+            condensation,
+        }
+    }
+}
+
+/// Runs a single simulation, writes the in-degree, out-degree, fitness,
+/// temperature and core number of each of its nodes to a CSV file at `path`,
+/// with the extension of its compression appended, and the metrics of its
+/// graph, including its graphlet counts, its robustness curves and whether it
+/// condensed, to a JSON file alongside it, with the extension `metrics.json`,
+/// and adds the run to `db` if given. Returns the final graph for export.
+pub fn run<P: AsRef<Path>>(config: &Config, path: P, db: Option<&ResultsDb>) -> io::Result<AttributedGraph> {
+    let path = path.as_ref();
+    let config = &config.seeded();
+    config.validate()?;
+    let mut csv = Writer::from_writer(config.compression.create(path)?);
+    csv.write_record(["id", "in_degree", "out_degree", "fitness", "temperature", "core"])?;
+
+    let mut rng = StdRng::seed_from_u64(config.seed.unwrap());
+    let (simulation, condensation) = simulate(config, &mut rng)?;
+    for record in records(&simulation, None) {
+        csv.write_record(&record[..])?;
+    }
+    csv.into_inner().map_err(|err| err.into_error())?.finish()?;
+
+    let summary = net_metrics::summarize(simulation.graph(), &mut rng);
+    let metrics = BufWriter::new(File::create(path.with_extension("metrics.json"))?);
+    let run_metrics = RunMetrics::new(None, summary.clone(), &simulation, condensation, &mut rng);
+    serde_json::to_writer_pretty(metrics, &run_metrics)?;
+
+    let graph = simulation.attributed_graph();
+    if let Some(db) = db {
+        db.insert_results(PROGRAM, config, 0, config.seed, &graph, &[(None, summary)])?;
+    }
+
+    Ok(graph)
+}
+
+/// Returns the configuration a sweep writing to `path` runs with: that of an
+/// interrupted sweep there, if this one has no seed and is otherwise the same,
+/// so that it resumes, or else this one seeded.
+pub fn sweep_config<P: AsRef<Path>>(config: &Config, path: P) -> io::Result<Config> {
+    let previous = Ledger::parameters::<_, Config>(path.as_ref().with_extension(LEDGER_EXTENSION))?;
+
+    match previous {
+        Some(previous) if config.seed.is_none() && Config { seed: None, ..previous.clone() } == *config => Ok(previous),
+        _ => Ok(config.seeded()),
+    }
+}
+
+/// Runs `num_runs` simulations in parallel, and writes the in-degree,
+/// out-degree, fitness, temperature and core number of each of their nodes to
+/// a CSV file at `path`, and the metrics of each run's graph to a JSON lines
+/// file alongside it, with the extension `metrics.jsonl`, each with the
+/// extension of their compression appended. Each run is also added to `db` if
+/// given. With an ensemble format, the consensus of the runs' final graphs,
+/// with the weight of each edge as an attribute, is exported alongside them
+/// too, with the extension `ensemble` followed by that of the format.
+///
+/// Each run is first written to its own shard in a directory alongside
+/// `path`, with the extension `shards`, and recorded in a ledger with the
+/// extension `ledger.jsonl`, so that a sweep which is interrupted resumes
+/// with only the runs it's missing when it's run again. Once every run is
+/// done, the shards are merged, and removed along with the ledger.
+pub fn sweep<P: AsRef<Path>>(config: &Config, path: P, db: Option<&ResultsDb>) -> io::Result<()> {
+    let path = path.as_ref();
+    let config = sweep_config(config, path)?;
+    let seed = config.seed.unwrap();
+    config.validate()?;
+
+    let shards_dir = path.with_extension("shards");
+    fs::create_dir_all(&shards_dir)?;
+    let ledger = Ledger::open(path.with_extension(LEDGER_EXTENSION), &config)?;
+    let remaining = (0..config.num_runs)
+        .filter(|&run| !ledger.is_complete(run))
+        .collect::<Vec<_>>();
+
+    let progress = Progress::new("bose-einstein sweep", Some(remaining.len() as u64));
+
+    remaining.into_par_iter().try_for_each(|run| -> io::Result<()> {
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(run));
+        let (simulation, condensation) = simulate(&config, &mut rng)?;
+        let summary = net_metrics::summarize(simulation.graph(), &mut rng);
+
+        let (csv_shard, metrics_shard, ensemble_shard) = shard_paths(&shards_dir, run);
+
+        let mut csv = Writer::from_writer(config.compression.create(&csv_shard)?);
+        for record in records(&simulation, Some(run)) {
+            csv.write_record(&record)?;
+        }
+        csv.into_inner().map_err(|err| err.into_error())?.finish()?;
+
+        let mut metrics = config.compression.create(&metrics_shard)?;
+        let run_metrics = RunMetrics::new(Some(run), summary.clone(), &simulation, condensation, &mut rng);
+        serde_json::to_writer(&mut metrics, &run_metrics)?;
+        writeln!(metrics)?;
+        metrics.finish()?;
+
+        let mut shards = vec![config.compression.path(csv_shard), config.compression.path(metrics_shard)];
+        if config.ensemble_format.is_some() {
+            let mut ensemble = config.compression.create(&ensemble_shard)?;
+            serde_json::to_writer(&mut ensemble, &RankedGraph::new(simulation.graph()))?;
+            ensemble.finish()?;
+            shards.push(config.compression.path(ensemble_shard));
+        }
+
+        // Added just before the run is marked complete, so that a run which
+        // is interrupted is left out of the database as well as the ledger.
+        if let Some(db) = db {
+            let graph = simulation.attributed_graph();
+            db.insert_results(PROGRAM, &config, run, Some(seed.wrapping_add(run)), &graph, &[(None, summary)])?;
+        }
+
+        ledger.complete(run, &shards)?;
+        progress.inc(1);
+        Ok(())
+    })?;
+    progress.finish();
+
+    let mut csv = config.compression.create(path)?;
+    writeln!(csv, "id,run,in_degree,out_degree,fitness,temperature,core")?;
+    let mut metrics = config.compression.create(path.with_extension("metrics.jsonl"))?;
+    let mut ensemble = Ensemble::new();
+
+    for run in 0..config.num_runs {
+        let (csv_shard, metrics_shard, ensemble_shard) = shard_paths(&shards_dir, run);
+        io::copy(&mut compression::open(config.compression.path(csv_shard))?, &mut csv)?;
+        io::copy(&mut compression::open(config.compression.path(metrics_shard))?, &mut metrics)?;
+        if config.ensemble_format.is_some() {
+            let shard = compression::open(config.compression.path(ensemble_shard))?;
+            ensemble.add(&serde_json::from_reader(shard)?);
+        }
+    }
+
+    csv.finish()?;
+    metrics.finish()?;
+    if let Some(format) = &config.ensemble_format {
+        let exporter = graph_io::exporter(format).unwrap();
+        let graph = AttributedGraph::from_graph(
+            &ensemble.consensus(),
+            |_| Attributes::new(),
+            |&weight| std::iter::once(("weight".to_string(), weight.into())).collect(),
+        );
+        let ensemble_path = path.with_extension(format!("ensemble.{}", exporter.extension()));
+        exporter.export_to_path(&graph, &ensemble_path)?;
+    }
+    fs::remove_dir_all(&shards_dir)?;
+    ledger.remove()
+}
+
+/// Returns the paths of the CSV, metrics and ensemble shards of a run of a
+/// sweep, before the extension of their compression.
+fn shard_paths(shards_dir: &Path, run: u64) -> (PathBuf, PathBuf, PathBuf) {
+    (
+        shards_dir.join(format!("run_{}.csv", run)),
+        shards_dir.join(format!("run_{}.metrics.jsonl", run)),
+        shards_dir.join(format!("run_{}.ensemble.json", run)),
+    )
+}
+
+/// Runs a simulation, detecting whether it condenses as it grows.
+fn simulate(config: &Config, rng: &mut StdRng) -> io::Result<(Simulation<StdRng, Sampler>, Condensation)> {
+    let mut simulation = <Simulation<_, _> as Model>::init(config, StdRng::from_rng(rng).unwrap())?;
+    let mut detector = CondensationDetector::new(config.condensation_threshold);
+    detector.record(0, simulation.graph());
+    model::run(&mut simulation, config.num_steps as usize, 1, None, |simulation| {
+        detector.record(Model::timestep(simulation), simulation.graph());
+        Ok(())
+    })?;
+    Ok((simulation, detector.report()))
+}
+
+/// Returns a record for each node of a simulation: its id, the run it's from
+/// if given, its in-degree and out-degree, its fitness, its temperature and
+/// its core number.
+fn records<R: Rng, D: Distribution<f64>>(simulation: &Simulation<R, D>, run: Option<u64>) -> Vec<Vec<String>> {
+    let graph = simulation.graph();
+    graph
+        .node_indices()
+        .zip(net_metrics::core_numbers(graph))
+        .map(|(node, core)| {
+            std::iter::once(node.index().to_string())
+                .chain(run.map(|run| run.to_string()))
+                .chain([
+                    graph.neighbors_directed(node, EdgeDirection::Incoming).count().to_string(),
+                    graph.neighbors_directed(node, EdgeDirection::Outgoing).count().to_string(),
+                ])
+                .chain([graph[node].fitness.to_string(), graph[node].temperature.to_string()])
+                .chain(std::iter::once(core.to_string()))
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,103 @@
+//! Merges the final graphs of many runs with the same parameters into a single
+//! weighted consensus graph, showing the structure the model typically grows
+//! rather than that of any one realization. Nodes of different runs have
+//! nothing in common but their place in the structure, so they're matched by
+//! degree rank: the `r`th node of the consensus graph stands for the node of
+//! `r`th highest degree in each run, and an edge between two ranks weighs the
+//! fraction of the runs with an edge between the nodes at those ranks.
+
+use std::collections::BTreeMap;
+
+use petgraph::{graph::DiGraph, graph::NodeIndex, Direction};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The edges of a graph with its nodes relabelled by degree rank.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RankedGraph {
+    pub num_nodes: usize,
+    /// The ranks of the source and target of each edge, without repeats, in
+    /// sorted order.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl RankedGraph {
+    /// Relabels the nodes of `graph` by their degree, counting edges both
+    /// ways, from highest to lowest, with nodes of the same degree ranked in
+    /// the order they arrived.
+    pub fn new<N, E>(graph: &DiGraph<N, E>) -> Self {
+        let degree = |node: NodeIndex| {
+            graph.neighbors_directed(node, Direction::Incoming).count()
+                + graph.neighbors_directed(node, Direction::Outgoing).count()
+        };
+        let mut nodes = graph.node_indices().collect::<Vec<_>>();
+        nodes.sort_by_key(|&node| (std::cmp::Reverse(degree(node)), node));
+
+        let mut ranks = vec![0; graph.node_count()];
+        for (rank, node) in nodes.into_iter().enumerate() {
+            ranks[node.index()] = rank;
+        }
+
+        let mut edges = graph
+            .edge_indices()
+            .map(|edge| {
+                let (source, target) = graph.edge_endpoints(edge).unwrap();
+                (ranks[source.index()], ranks[target.index()])
+            })
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges.dedup();
+
+        Self {
+            num_nodes: graph.node_count(),
+            edges,
+        }
+    }
+}
+
+/// Counts the edges between each pair of degree ranks over the runs added.
+#[derive(Clone, Debug, Default)]
+pub struct Ensemble {
+    num_runs: usize,
+    num_nodes: usize,
+    counts: BTreeMap<(usize, usize), usize>,
+}
+
+impl Ensemble {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the graph of a run.
+    pub fn add(&mut self, graph: &RankedGraph) {
+        self.num_runs += 1;
+        self.num_nodes = self.num_nodes.max(graph.num_nodes);
+        for &edge in &graph.edges {
+            *self.counts.entry(edge).or_insert(0) += 1;
+        }
+    }
+
+    /// Returns how many runs have been added.
+    pub fn num_runs(&self) -> usize {
+        self.num_runs
+    }
+
+    /// Returns the consensus graph: a node for each rank, up to the most
+    /// nodes any run had, and an edge between two ranks wherever any run had
+    /// one, weighing the fraction of the runs which did.
+    pub fn consensus(&self) -> DiGraph<(), f64> {
+        let mut graph = DiGraph::with_capacity(self.num_nodes, self.counts.len());
+        for _ in 0..self.num_nodes {
+            graph.add_node(());
+        }
+        for (&(source, target), &count) in &self.counts {
+            graph.add_edge(
+                NodeIndex::new(source),
+                NodeIndex::new(target),
+                count as f64 / self.num_runs as f64,
+            );
+        }
+        graph
+    }
+}
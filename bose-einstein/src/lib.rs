@@ -0,0 +1,31 @@
+//! Growing networks after Bianconi and Barabási's Bose-Einstein condensation
+//! model, in which each new node attaches to existing ones by their degree
+//! and energy level, and detecting whether they condense.
+//!
+//! The items re-exported here are the stable API, which only changes in a
+//! breaking way with the minor version while the crate is below 1.0; the rest
+//! of each module may change with the command line it serves. A
+//! [`Simulation`] is built with [`Simulation::init`], configured with its
+//! `with_*` methods, and grown a node at a time with [`Simulation::step`],
+//! after which its [`graph`](Simulation::graph) can be measured with
+//! `net-metrics`, checked with a [`CondensationDetector`], or exported with
+//! [`attributed_graph`](Simulation::attributed_graph). With the `driver`
+//! feature, [`run`] and [`sweep`] do all of that from a [`Config`], writing
+//! their results to files. The `examples` directory has programs using each.
+
+pub mod condensation;
+pub mod ensemble;
+pub mod sim;
+
+pub use condensation::{Condensation, CondensationDetector, Phase};
+pub use sim::{DirectedKernels, Duplication, Kernel, NodeWeight, Simulation, Temperature};
+
+#[cfg(feature = "driver")]
+mod driver;
+#[cfg(feature = "driver")]
+pub mod model;
+
+#[cfg(feature = "driver")]
+pub use driver::{
+    run, sweep, sweep_config, Config, DirectedAttachment, DuplicationDivergence, NodeTemperature, LEDGER_EXTENSION,
+};
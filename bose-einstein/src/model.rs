@@ -0,0 +1,100 @@
+//! The Bose-Einstein simulation as a [`Model`], set up from a [`Config`] and
+//! checkpointed as the list of its nodes and edges.
+
+use std::io;
+
+use distributions::Sampler;
+use graph_io::AttributedGraph;
+use model::Model;
+use net_metrics::Summary;
+use petgraph::graph::{DiGraph, NodeIndex};
+use rand::{rngs::StdRng, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::sim::{Kernel, NodeWeight, Simulation};
+use crate::Config;
+
+/// The number of nodes a simulation starts with, before its first step.
+const INITIAL_NODES: usize = 3;
+
+/// The state of a simulation: its nodes, by index, and its edges, by the
+/// indices of their source and target.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub nodes: Vec<NodeWeight>,
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Model for Simulation<StdRng, Sampler> {
+    type Config = Config;
+    type Checkpoint = Checkpoint;
+
+    fn init(config: &Config, rng: StdRng) -> io::Result<Self> {
+        let kernel = Kernel {
+            degree_exp: config.degree_exponent,
+            energy_exp: config.energy_exponent,
+        };
+        let simulation =
+            Simulation::init_with_temperature(rng, config.fitness()?, config.temperature()?).with_kernel(kernel);
+
+        let simulation = match &config.directed_attachment {
+            Some(directed) => simulation.with_directed_kernels(directed.kernels()),
+            None => simulation,
+        };
+        Ok(match &config.duplication_divergence {
+            Some(duplication) => simulation.with_duplication(duplication.duplication()),
+            None => simulation,
+        })
+    }
+
+    /// Adds a node.
+    fn step(&mut self) {
+        Simulation::step(self);
+    }
+
+    fn timestep(&self) -> usize {
+        self.graph().node_count() - INITIAL_NODES
+    }
+
+    fn graph(&self) -> AttributedGraph {
+        self.attributed_graph()
+    }
+
+    fn metrics<R: Rng>(&self, rng: &mut R) -> Summary {
+        net_metrics::summarize(Simulation::graph(self), rng)
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        let graph = Simulation::graph(self);
+        Checkpoint {
+            nodes: graph.node_indices().map(|node| graph[node]).collect(),
+            edges: graph
+                .edge_indices()
+                .map(|edge| {
+                    let (source, target) = graph.edge_endpoints(edge).unwrap();
+                    (source.index(), target.index())
+                })
+                .collect(),
+        }
+    }
+
+    fn restore(config: &Config, checkpoint: Checkpoint, rng: StdRng) -> io::Result<Self> {
+        let num_nodes = checkpoint.nodes.len();
+        if num_nodes < INITIAL_NODES || checkpoint.edges.iter().any(|&(a, b)| a.max(b) >= num_nodes) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "a checkpoint needs its initial nodes, and edges between its nodes",
+            ));
+        }
+
+        let mut graph = DiGraph::with_capacity(num_nodes, checkpoint.edges.len());
+        for node in checkpoint.nodes {
+            graph.add_node(node);
+        }
+        for (source, target) in checkpoint.edges {
+            graph.add_edge(NodeIndex::new(source), NodeIndex::new(target), ());
+        }
+
+        Ok(<Self as Model>::init(config, rng)?.with_graph(graph))
+    }
+}
@@ -0,0 +1,394 @@
+#[cfg(feature = "graph-io")]
+use graph_io::{Attributes, AttributedGraph};
+use petgraph::{graph::DiGraph, graph::NodeIndex, Direction};
+use rand::prelude::*;
+use rand_distr::LogNormal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The weight a node is attached to with, `energy^energy_exp *
+/// degree^degree_exp`; both exponents are 1 in Bianconi and Barabási's model.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Kernel {
+    pub degree_exp: f64,
+    pub energy_exp: f64,
+}
+
+impl Default for Kernel {
+    fn default() -> Self {
+        Self {
+            degree_exp: 1.,
+            energy_exp: 1.,
+        }
+    }
+}
+
+impl Kernel {
+    /// Returns the weight of a node with the given energy level and degree.
+    pub fn weight(&self, energy_level: f64, degree: f64) -> f64 {
+        energy_level.powf(self.energy_exp) * degree.powf(self.degree_exp)
+    }
+
+    /// Returns the logarithm of [`weight`](Self::weight), which stays finite
+    /// for large exponents where the weight itself overflows.
+    pub fn ln_weight(&self, energy_level: f64, degree: f64) -> f64 {
+        self.energy_exp * energy_level.ln() + self.degree_exp * degree.ln()
+    }
+}
+
+/// The kernels of directed attachment, in which a new node both links to
+/// existing nodes and is linked to by them. Links out of the new node, as a
+/// paper cites earlier ones, go to nodes weighted by `inbound` of their
+/// in-degree; links into it, as active accounts follow a newcomer, come from
+/// nodes weighted by `outbound` of their out-degree. Each degree is counted
+/// one higher, so that nodes with none of those links yet can still get them.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DirectedKernels {
+    pub inbound: Kernel,
+    pub outbound: Kernel,
+}
+
+/// Duplication-divergence growth, after Vázquez et al. and Solé et al.: with
+/// probability `probability`, a new node copies a node chosen uniformly in
+/// place of attaching by the kernel. It inherits the node's fitness, scaled
+/// by a log-normal factor with `fitness_noise` as the standard deviation of
+/// its logarithm so that it stays positive, and each of its links, in the
+/// same direction, with probability `retention` each, and links to the node
+/// itself with probability `parent_link`. Its temperature is picked as any
+/// other node's is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Duplication {
+    pub probability: f64,
+    pub retention: f64,
+    pub parent_link: f64,
+    pub fitness_noise: f64,
+}
+
+/// How the temperature of each node is picked as it arrives, which sets its
+/// energy level from its fitness.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Temperature<D> {
+    /// Every node has the same temperature.
+    Constant(f64),
+    /// Each node's temperature is drawn from a distribution.
+    Sampled(D),
+    /// Each node's temperature follows from the order it arrived in,
+    /// interpolating linearly between `(arrival, temperature)` points sorted
+    /// by arrival, and holding the first and last temperatures outside them.
+    /// An empty schedule gives every node a temperature of 1.
+    Schedule(Vec<(usize, f64)>),
+}
+
+impl<D: Distribution<f64>> Temperature<D> {
+    /// Returns the temperature of the node arriving `arrival`th, counting
+    /// from 0.
+    pub fn sample<R: Rng>(&self, arrival: usize, rng: &mut R) -> f64 {
+        match self {
+            Temperature::Constant(temperature) => *temperature,
+            Temperature::Sampled(dist) => dist.sample(rng),
+            Temperature::Schedule(points) => {
+                let after = points.partition_point(|&(at, _)| at <= arrival);
+                match (after.checked_sub(1).map(|before| points[before]), points.get(after)) {
+                    (Some((start, from)), Some(&(end, to))) => {
+                        from + (to - from) * (arrival - start) as f64 / (end - start) as f64
+                    }
+                    (Some((_, temperature)), None) | (None, Some(&(_, temperature))) => temperature,
+                    (None, None) => 1.,
+                }
+            }
+        }
+    }
+}
+
+/// A node of the network.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NodeWeight {
+    pub fitness: f64,
+    pub temperature: f64,
+    pub energy_level: f64,
+}
+
+/// A growing network in which each new node attaches to existing ones in
+/// proportion to their degree and energy level, after Bianconi and Barabási's
+/// Bose-Einstein condensation model, or to some other [`Kernel`] of them. Each
+/// node is weighted by its fitness, its temperature, and the energy level they
+/// give it.
+pub struct Simulation<R, D> {
+    rng: R,
+    fitness_dist: D,
+    temperature: Temperature<D>,
+    kernel: Kernel,
+    directed: Option<DirectedKernels>,
+    duplication: Option<Duplication>,
+    graph: DiGraph<NodeWeight, ()>,
+}
+
+impl<R, D> Simulation<R, D>
+where
+    R: Rng,
+    D: Distribution<f64>,
+{
+    pub fn init(rng: R, fitness_dist: D, temperature: f64) -> Self {
+        Self::init_with_temperature(rng, fitness_dist, Temperature::Constant(temperature))
+    }
+
+    /// Starts a simulation whose nodes, from the first on, each get a
+    /// temperature picked by `temperature`.
+    pub fn init_with_temperature(rng: R, fitness_dist: D, temperature: Temperature<D>) -> Self {
+        let mut sim = Self {
+            rng,
+            fitness_dist,
+            temperature,
+            kernel: Kernel::default(),
+            directed: None,
+            duplication: None,
+            graph: DiGraph::new(),
+        };
+
+        let node_a = sim.add_sampled_node();
+        let node_b = sim.add_sampled_node();
+        let node_c = sim.add_sampled_node();
+
+        sim.graph.add_edge(node_a, node_b, ());
+        sim.graph.add_edge(node_b, node_c, ());
+        sim.graph.add_edge(node_c, node_a, ());
+
+        sim
+    }
+
+    /// Attaches new nodes with `kernel` in place of the product of energy
+    /// level and degree.
+    pub fn with_kernel(self, kernel: Kernel) -> Self {
+        Self { kernel, ..self }
+    }
+
+    /// Attaches new nodes in both directions with `kernels`, in place of only
+    /// linking from them with a kernel of total degree.
+    pub fn with_directed_kernels(self, kernels: DirectedKernels) -> Self {
+        Self {
+            directed: Some(kernels),
+            ..self
+        }
+    }
+
+    /// Grows by duplication and divergence as `duplication` says, as well as
+    /// by attachment.
+    pub fn with_duplication(self, duplication: Duplication) -> Self {
+        Self {
+            duplication: Some(duplication),
+            ..self
+        }
+    }
+
+    /// Carries on growing `graph` in place of the one the simulation started
+    /// with.
+    pub fn with_graph(self, graph: DiGraph<NodeWeight, ()>) -> Self {
+        Self { graph, ..self }
+    }
+
+    fn sample_node_properties(&mut self) -> NodeWeight {
+        let fitness = self.fitness_dist.sample(&mut self.rng);
+        self.node_properties(fitness)
+    }
+
+    /// Picks the temperature of a node arriving now with `fitness`.
+    fn node_properties(&mut self, fitness: f64) -> NodeWeight {
+        let temperature = self.temperature.sample(self.graph.node_count(), &mut self.rng);
+
+        // The fitness distribution is expected to include only positive values.
+        // `ln_1p` is used to ensure that the energy level is also positive.
+        let energy_level = temperature * fitness.ln_1p();
+
+        NodeWeight {
+            fitness,
+            temperature,
+            energy_level,
+        }
+    }
+
+    fn add_sampled_node(&mut self) -> NodeIndex<u32> {
+        let props = self.sample_node_properties();
+
+        self.graph.add_node(props)
+    }
+
+    fn try_add_node(&mut self) -> Option<NodeIndex<u32>> {
+        // Only a simulation which duplicates draws whether to, so that the
+        // runs of those which don't are unchanged.
+        if let Some(duplication) = self.duplication {
+            if self.rng.gen_bool(duplication.probability) {
+                return self.try_duplicate_node(duplication);
+            }
+        }
+
+        if let Some(kernels) = self.directed {
+            return self.try_add_directed_node(kernels);
+        }
+
+        // Kept in node order, so that the draws from `rng` below, and so the
+        // whole run, depend only on its seed.
+        let mut attach_weights = Vec::with_capacity(self.graph.node_count());
+
+        for node in self.graph.node_indices() {
+            let energy_level = self.graph.node_weight(node).unwrap().energy_level;
+            let degree = self.graph.neighbors_undirected(node).count() as f64;
+
+            attach_weights.push((node, energy_level, degree));
+        }
+
+        let new_node = self.add_sampled_node();
+        let degree = self.attach(new_node, self.kernel, attach_weights, Direction::Outgoing);
+
+        if degree < 1 {
+            self.graph.remove_node(new_node);
+            return None;
+        }
+
+        Some(new_node)
+    }
+
+    fn try_add_directed_node(&mut self, kernels: DirectedKernels) -> Option<NodeIndex<u32>> {
+        let mut cite_weights = Vec::with_capacity(self.graph.node_count());
+        let mut follow_weights = Vec::with_capacity(self.graph.node_count());
+
+        for node in self.graph.node_indices() {
+            let energy_level = self.graph.node_weight(node).unwrap().energy_level;
+            let in_degree = self.graph.neighbors_directed(node, Direction::Incoming).count() as f64;
+            let out_degree = self.graph.neighbors_directed(node, Direction::Outgoing).count() as f64;
+
+            cite_weights.push((node, energy_level, in_degree + 1.));
+            follow_weights.push((node, energy_level, out_degree + 1.));
+        }
+
+        let new_node = self.add_sampled_node();
+        let degree = self.attach(new_node, kernels.inbound, cite_weights, Direction::Outgoing)
+            + self.attach(new_node, kernels.outbound, follow_weights, Direction::Incoming);
+
+        if degree < 1 {
+            self.graph.remove_node(new_node);
+            return None;
+        }
+
+        Some(new_node)
+    }
+
+    /// Copies a node chosen uniformly, as [`Duplication`] says. Returns `None`
+    /// if the copy kept no links.
+    fn try_duplicate_node(&mut self, duplication: Duplication) -> Option<NodeIndex<u32>> {
+        let parent = NodeIndex::new(self.rng.gen_range(0, self.graph.node_count()));
+        // Both directions, in node order, so that the draws from `rng` below
+        // depend only on the seed.
+        let mut targets = self.graph.neighbors_directed(parent, Direction::Outgoing).collect::<Vec<_>>();
+        let mut sources = self.graph.neighbors_directed(parent, Direction::Incoming).collect::<Vec<_>>();
+        targets.sort_unstable();
+        sources.sort_unstable();
+
+        let factor = LogNormal::new(0., duplication.fitness_noise).unwrap().sample(&mut self.rng);
+        let props = self.node_properties(self.graph[parent].fitness * factor);
+        let new_node = self.graph.add_node(props);
+
+        let mut degree = 0;
+        for target in targets {
+            if self.rng.gen_bool(duplication.retention) {
+                self.graph.add_edge(new_node, target, ());
+                degree += 1;
+            }
+        }
+        for source in sources {
+            if self.rng.gen_bool(duplication.retention) {
+                self.graph.add_edge(source, new_node, ());
+                degree += 1;
+            }
+        }
+        if self.rng.gen_bool(duplication.parent_link) {
+            self.graph.add_edge(new_node, parent, ());
+            degree += 1;
+        }
+
+        if degree < 1 {
+            self.graph.remove_node(new_node);
+            return None;
+        }
+
+        Some(new_node)
+    }
+
+    /// Links `new_node` with each node in turn, with probability its share of
+    /// the weights `kernel` gives the `(node, energy_level, degree)` of each,
+    /// or with every node if the weights are all 0, in `direction` from
+    /// `new_node`. Returns the number of links made.
+    fn attach(
+        &mut self,
+        new_node: NodeIndex<u32>,
+        kernel: Kernel,
+        nodes: Vec<(NodeIndex<u32>, f64, f64)>,
+        direction: Direction,
+    ) -> usize {
+        let mut weights = nodes
+            .iter()
+            .map(|&(node, energy_level, degree)| (node, kernel.weight(energy_level, degree)))
+            .collect::<Vec<_>>();
+        let mut weights_sum: f64 = weights.iter().map(|&(_, weight)| weight).sum();
+
+        // Large exponents overflow the weights, so they're scaled by the
+        // largest of them, in log space, before they're normalized.
+        if !weights_sum.is_finite() {
+            let ln_weights = nodes
+                .iter()
+                .map(|&(node, energy_level, degree)| (node, kernel.ln_weight(energy_level, degree)))
+                .collect::<Vec<_>>();
+            let max = ln_weights.iter().map(|&(_, ln_weight)| ln_weight).fold(f64::NEG_INFINITY, f64::max);
+            weights = ln_weights
+                .into_iter()
+                .map(|(node, ln_weight)| (node, (ln_weight - max).exp()))
+                .collect();
+            weights_sum = weights.iter().map(|&(_, weight)| weight).sum();
+        }
+
+        let mut degree = 0;
+
+        for (node, weight) in weights {
+            if weights_sum > 0. && !self.rng.gen_bool(weight / weights_sum) {
+                continue;
+            }
+
+            match direction {
+                Direction::Outgoing => self.graph.add_edge(new_node, node, ()),
+                Direction::Incoming => self.graph.add_edge(node, new_node, ()),
+            };
+            degree += 1;
+        }
+
+        degree
+    }
+
+    pub fn step(&mut self) {
+        let _new_node = loop {
+            if let Some(node) = self.try_add_node() {
+                break node;
+            }
+        };
+    }
+
+    pub fn graph(&self) -> &DiGraph<NodeWeight, ()> {
+        &self.graph
+    }
+
+    /// Returns the graph for export, with the fitness, temperature and energy
+    /// level of each node.
+    #[cfg(feature = "graph-io")]
+    pub fn attributed_graph(&self) -> AttributedGraph {
+        AttributedGraph::from_graph(
+            &self.graph,
+            |node| {
+                let mut attributes = Attributes::new();
+                attributes.insert("fitness".to_string(), node.fitness.into());
+                attributes.insert("temperature".to_string(), node.temperature.into());
+                attributes.insert("energy_level".to_string(), node.energy_level.into());
+                attributes
+            },
+            |_| Attributes::new(),
+        )
+    }
+}
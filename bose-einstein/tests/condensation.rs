@@ -0,0 +1,73 @@
+use bose_einstein::condensation::{CondensationDetector, Phase};
+use bose_einstein::sim::Simulation;
+use petgraph::graph::{DiGraph, NodeIndex};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::Uniform;
+
+#[test]
+fn stars_condense_from_the_start() {
+    let mut graph = DiGraph::<(), ()>::new();
+    let hub = graph.add_node(());
+    let mut detector = CondensationDetector::new(0.4);
+
+    for step in 1..=10 {
+        let node = graph.add_node(());
+        graph.add_edge(node, hub, ());
+        detector.record(step, &graph);
+    }
+
+    let condensation = detector.report();
+    assert_eq!(condensation.phase, Phase::WinnerTakesAll);
+    assert_eq!(condensation.condensed_at, Some(1));
+    assert_eq!(condensation.hub_share, 0.5);
+    // (2 * 10)^2 / (11 * (10^2 + 10)).
+    assert!((condensation.participation_ratio - 400. / 1210.).abs() < 1e-12);
+}
+
+#[test]
+fn condensation_is_counted_from_when_it_lasts() {
+    // A path, whose hub share falls, and then a star on its last node.
+    let mut graph = DiGraph::<(), ()>::new();
+    let mut detector = CondensationDetector::new(0.3);
+    graph.add_node(());
+    for step in 1..=5 {
+        let node = graph.add_node(());
+        graph.add_edge(node, NodeIndex::new(step - 1), ());
+        detector.record(step, &graph);
+    }
+    assert_eq!(detector.report().phase, Phase::FitGetRich);
+
+    for step in 6..=20 {
+        let node = graph.add_node(());
+        graph.add_edge(node, NodeIndex::new(5), ());
+        detector.record(step, &graph);
+    }
+    let condensation = detector.report();
+    assert_eq!(condensation.phase, Phase::WinnerTakesAll);
+    // The star's center has a degree of s - 4 of 2s after step s.
+    assert_eq!(condensation.condensed_at, Some(10));
+    assert!((condensation.hub_share - 0.4).abs() < 1e-12);
+}
+
+#[test]
+fn detectors_follow_the_degrees_of_a_simulation() {
+    let rng = StdRng::seed_from_u64(435);
+    let mut simulation = Simulation::init(rng, Uniform::new(0.5, 1.5), 1.);
+    let mut detector = CondensationDetector::new(0.1);
+    detector.record(0, simulation.graph());
+    for step in 1..=200 {
+        simulation.step();
+        detector.record(step, simulation.graph());
+    }
+
+    let degrees = simulation
+        .graph()
+        .node_indices()
+        .map(|node| simulation.graph().neighbors_undirected(node).count())
+        .collect::<Vec<_>>();
+    let total = degrees.iter().sum::<usize>() as f64;
+    let max = *degrees.iter().max().unwrap() as f64;
+    let sum_of_squares = degrees.iter().map(|&degree| (degree * degree) as f64).sum::<f64>();
+    assert!((detector.hub_share() - max / total).abs() < 1e-12);
+    assert!((detector.participation_ratio() - total * total / (degrees.len() as f64 * sum_of_squares)).abs() < 1e-12);
+}
@@ -0,0 +1,69 @@
+use bose_einstein::sim::{DirectedKernels, Kernel, Simulation};
+use petgraph::EdgeDirection;
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::Uniform;
+
+/// Returns the largest in-degree and out-degree after growing a network with
+/// directed attachment by `kernels`.
+fn max_degrees(kernels: DirectedKernels) -> (usize, usize) {
+    let rng = StdRng::seed_from_u64(435);
+    let mut simulation = Simulation::init(rng, Uniform::new(0.5, 1.5), 1.).with_directed_kernels(kernels);
+    for _ in 0..300 {
+        simulation.step();
+    }
+
+    let graph = simulation.graph();
+    let max_degree = |direction| {
+        graph
+            .node_indices()
+            .map(|node| graph.neighbors_directed(node, direction).count())
+            .max()
+            .unwrap()
+    };
+
+    (max_degree(EdgeDirection::Incoming), max_degree(EdgeDirection::Outgoing))
+}
+
+#[test]
+fn new_nodes_link_both_ways() {
+    let rng = StdRng::seed_from_u64(435);
+    let mut simulation =
+        Simulation::init(rng, Uniform::new(0.5, 1.5), 1.).with_directed_kernels(DirectedKernels::default());
+    for _ in 0..100 {
+        simulation.step();
+    }
+
+    let graph = simulation.graph();
+    let new_nodes = graph.node_indices().skip(3);
+    assert!(new_nodes.clone().all(|node| graph.neighbors_undirected(node).count() > 0));
+    assert!(new_nodes
+        .clone()
+        .any(|node| graph.neighbors_directed(node, EdgeDirection::Incoming).count() > 0));
+    assert!(new_nodes
+        .clone()
+        .any(|node| graph.neighbors_directed(node, EdgeDirection::Outgoing).count() > 0));
+}
+
+#[test]
+fn each_kernel_shapes_its_own_degrees() {
+    let flat = Kernel {
+        degree_exp: 0.,
+        energy_exp: 0.,
+    };
+    let steep = Kernel {
+        degree_exp: 1.5,
+        ..Kernel::default()
+    };
+
+    let (citation_in, citation_out) = max_degrees(DirectedKernels {
+        inbound: steep,
+        outbound: flat,
+    });
+    let (follower_in, follower_out) = max_degrees(DirectedKernels {
+        inbound: flat,
+        outbound: steep,
+    });
+
+    assert!(citation_in > 2 * follower_in, "{} vs {}", citation_in, follower_in);
+    assert!(follower_out > 2 * citation_out, "{} vs {}", follower_out, citation_out);
+}
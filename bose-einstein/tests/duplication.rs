@@ -0,0 +1,85 @@
+use bose_einstein::sim::{Duplication, Simulation};
+use petgraph::graph::NodeIndex;
+use petgraph::EdgeDirection;
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::Uniform;
+
+fn grow(duplication: Duplication) -> Simulation<StdRng, Uniform<f64>> {
+    let rng = StdRng::seed_from_u64(435);
+    let mut simulation = Simulation::init(rng, Uniform::new(0.5, 1.5), 1.).with_duplication(duplication);
+    for _ in 0..200 {
+        simulation.step();
+    }
+    simulation
+}
+
+#[test]
+fn copies_inherit_the_fitness_and_links_of_their_parents() {
+    let simulation = grow(Duplication {
+        probability: 1.,
+        retention: 1.,
+        parent_link: 1.,
+        fitness_noise: 0.,
+    });
+    let graph = simulation.graph();
+    assert_eq!(graph.node_count(), 203);
+
+    for node in graph.node_indices().skip(3) {
+        // Every copy links to its parent, the one earlier node with its
+        // fitness it links to, and to every node its parent linked to.
+        let targets = graph.neighbors_directed(node, EdgeDirection::Outgoing).collect::<Vec<_>>();
+        let parent = targets
+            .iter()
+            .copied()
+            .find(|&target| target < node && graph[target].fitness == graph[node].fitness)
+            .unwrap();
+        for target in graph.neighbors_directed(parent, EdgeDirection::Outgoing) {
+            if target < node {
+                assert!(targets.contains(&target));
+            }
+        }
+    }
+}
+
+#[test]
+fn divergence_perturbs_the_copies() {
+    let simulation = grow(Duplication {
+        probability: 1.,
+        retention: 0.2,
+        parent_link: 0.,
+        fitness_noise: 0.5,
+    });
+    let graph = simulation.graph();
+
+    // Fitnesses drift outside of the range they were first drawn from.
+    assert!(graph.node_indices().any(|node| !(0.5..1.5).contains(&graph[node].fitness)));
+    assert!(graph.node_indices().all(|node| graph[node].fitness > 0.));
+    // Every node still has a link, and the copies keep only some of theirs.
+    let degree = |node| graph.neighbors_undirected(node).count();
+    assert!(graph.node_indices().all(|node| degree(node) > 0));
+    assert!(degree(NodeIndex::new(202)) < degree(NodeIndex::new(0)));
+}
+
+#[cfg(feature = "driver")]
+#[test]
+fn duplication_needs_a_way_to_keep_links() {
+    use bose_einstein::{Config, DuplicationDivergence};
+
+    let config = Config {
+        num_steps: 20,
+        seed: Some(435),
+        duplication_divergence: Some(DuplicationDivergence::default()),
+        ..Config::default()
+    };
+    assert!(config.validate().is_ok());
+
+    let invalid = Config {
+        duplication_divergence: Some(DuplicationDivergence {
+            retention: 0.,
+            parent_link: 0.,
+            ..DuplicationDivergence::default()
+        }),
+        ..config
+    };
+    assert!(invalid.validate().is_err());
+}
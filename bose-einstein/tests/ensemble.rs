@@ -0,0 +1,92 @@
+use bose_einstein::ensemble::{Ensemble, RankedGraph};
+use petgraph::graph::{DiGraph, NodeIndex};
+
+/// A star of `leaves` nodes pointing at the node `hub`.
+fn star(leaves: usize, hub: usize) -> DiGraph<(), ()> {
+    let mut graph = DiGraph::new();
+    for _ in 0..=leaves {
+        graph.add_node(());
+    }
+    for node in (0..=leaves).filter(|&node| node != hub) {
+        graph.add_edge(NodeIndex::new(node), NodeIndex::new(hub), ());
+    }
+    graph
+}
+
+#[test]
+fn nodes_are_matched_by_degree_rank() {
+    // Whichever node is the hub, it ranks first, and the leaves follow in
+    // the order they arrived.
+    let ranked = RankedGraph::new(&star(3, 2));
+    assert_eq!(ranked.num_nodes, 4);
+    assert_eq!(ranked.edges, vec![(1, 0), (2, 0), (3, 0)]);
+    assert_eq!(RankedGraph::new(&star(3, 0)), ranked);
+
+    let mut path = DiGraph::<(), ()>::new();
+    let nodes = (0..4).map(|_| path.add_node(())).collect::<Vec<_>>();
+    for pair in nodes.windows(2) {
+        path.add_edge(pair[1], pair[0], ());
+    }
+
+    let mut ensemble = Ensemble::new();
+    ensemble.add(&RankedGraph::new(&star(3, 2)));
+    ensemble.add(&RankedGraph::new(&star(3, 1)));
+    ensemble.add(&RankedGraph::new(&path));
+    assert_eq!(ensemble.num_runs(), 3);
+
+    // The path's middle nodes rank first, then its ends: 1 <- 2 becomes
+    // 0 <- 1, 0 <- 1 becomes 2 <- 0 and 2 <- 3 becomes 1 <- 3.
+    let consensus = ensemble.consensus();
+    assert_eq!(consensus.node_count(), 4);
+    let weight = |source: usize, target: usize| {
+        consensus
+            .find_edge(NodeIndex::new(source), NodeIndex::new(target))
+            .map(|edge| consensus[edge])
+    };
+    assert_eq!(weight(1, 0), Some(1.));
+    assert_eq!(weight(2, 0), Some(2. / 3.));
+    assert_eq!(weight(3, 0), Some(2. / 3.));
+    assert_eq!(weight(0, 2), Some(1. / 3.));
+    assert_eq!(weight(3, 1), Some(1. / 3.));
+    assert_eq!(consensus.edge_count(), 5);
+}
+
+#[cfg(feature = "driver")]
+#[test]
+fn sweeps_export_the_consensus_of_their_runs() {
+    use bose_einstein::Config;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().join("bose-einstein-ensemble");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("sweep.csv");
+    let config = Config {
+        num_steps: 100,
+        num_runs: 4,
+        seed: Some(435),
+        ensemble_format: Some("csv".to_string()),
+        ..Config::default()
+    };
+    bose_einstein::sweep(&config, &path, None).unwrap();
+
+    let mut csv = csv::Reader::from_path(dir.join("sweep.ensemble.csv")).unwrap();
+    assert_eq!(csv.headers().unwrap(), vec!["source", "target", "weight"]);
+    let weights = csv
+        .records()
+        .map(|record| record.unwrap()[2].parse::<f64>().unwrap())
+        .collect::<Vec<_>>();
+    assert!(!weights.is_empty());
+    assert!(weights.iter().all(|&weight| weight > 0. && weight <= 1.));
+    assert!(weights.iter().all(|&weight| (weight * 4.).fract() == 0.));
+    // Every run's hub has edges from the next best connected nodes.
+    assert!(weights.contains(&1.));
+
+    let invalid = Config {
+        ensemble_format: Some("xlsx".to_string()),
+        ..config
+    };
+    assert_eq!(
+        bose_einstein::sweep(&invalid, &path, None).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "driver")]
+
+use bose_einstein::Config;
+use distributions::Spec;
+
+#[test]
+fn fitnesses_come_from_the_configured_distribution() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("bose-einstein-truncated.csv");
+    let config = Config {
+        num_steps: 200,
+        seed: Some(435),
+        fitness_distribution: Some(Spec::Truncated {
+            inner: Box::new(Spec::InverseGaussian { mean: 1.0, shape: 10.0 }),
+            low: 0.0,
+            high: 0.8,
+        }),
+        ..Config::default()
+    };
+
+    let graph = bose_einstein::run(&config, &path, None).unwrap();
+
+    assert!(graph.nodes.len() > 200);
+    for (_, attributes) in &graph.nodes {
+        match attributes["fitness"] {
+            graph_io::AttributeValue::Float(fitness) => assert!((0.0..=0.8).contains(&fitness)),
+            ref other => panic!("fitness {:?}", other),
+        }
+    }
+
+    let invalid = Config {
+        fitness_distribution: Some(Spec::Uniform { low: 1.0, high: 0.0 }),
+        ..config.clone()
+    };
+    assert_eq!(
+        bose_einstein::run(&invalid, &path, None).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+
+    let negative = Config {
+        fitness_distribution: Some(Spec::Uniform { low: -0.9, high: 1.0 }),
+        ..config
+    };
+    assert_eq!(negative.validate().unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+}
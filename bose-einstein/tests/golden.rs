@@ -0,0 +1,76 @@
+//! Runs a short simulation with a fixed seed and compares its output with the
+//! files checked in under `tests/golden`, so that changes to the step logic
+//! which change results don't go unnoticed. Run with `UPDATE_GOLDEN=1` to
+//! rewrite the golden files after an intended change.
+
+#![cfg(feature = "driver")]
+
+use std::path::Path;
+use std::{env, fs};
+
+use bose_einstein::Config;
+
+const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+
+/// Compares a file with its golden copy line by line, with numbers equal to
+/// within a relative tolerance, as the last bits of `exp` and `ln` can vary
+/// between platforms.
+fn assert_matches_golden(actual: &Path, name: &str) {
+    let golden = Path::new(GOLDEN_DIR).join(name);
+    let actual = fs::read_to_string(actual).unwrap();
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&golden, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&golden).unwrap();
+    let tokens = |line: &str| {
+        line.split(|c: char| ",:{}[]".contains(c) || c.is_whitespace())
+            .filter(|token| !token.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(actual.lines().count(), expected.lines().count(), "{}", name);
+
+    for (number, (actual_line, expected_line)) in actual.lines().zip(expected.lines()).enumerate() {
+        let (actual_tokens, expected_tokens) = (tokens(actual_line), tokens(expected_line));
+        let matches = actual_tokens.len() == expected_tokens.len()
+            && actual_tokens
+                .iter()
+                .zip(&expected_tokens)
+                .all(|(a, e)| match (a.parse::<f64>(), e.parse::<f64>()) {
+                    (Ok(a), Ok(e)) => (a - e).abs() <= 1e-9 * e.abs().max(1.0),
+                    _ => a == e,
+                });
+
+        assert!(
+            matches,
+            "{} differs at line {}:\n  actual:   {}\n  expected: {}",
+            name,
+            number + 1,
+            actual_line,
+            expected_line
+        );
+    }
+}
+
+#[test]
+fn seeded_run_matches_golden_output() {
+    let config = Config {
+        num_steps: 50,
+        seed: Some(435),
+        ..Config::default()
+    };
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("math435-golden-bose-einstein");
+    fs::create_dir_all(&out_dir).unwrap();
+
+    let path = out_dir.join("run.csv");
+    bose_einstein::run(&config, &path, None).unwrap();
+
+    assert_matches_golden(&path, "run.csv");
+    assert_matches_golden(&path.with_extension("metrics.json"), "run.metrics.json");
+}
@@ -0,0 +1,59 @@
+use bose_einstein::sim::{Kernel, Simulation};
+use petgraph::EdgeDirection;
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::Uniform;
+
+/// Returns the largest in-degree after growing a network with `kernel`.
+fn max_in_degree(kernel: Kernel) -> usize {
+    let rng = StdRng::seed_from_u64(435);
+    let mut simulation = Simulation::init(rng, Uniform::new(0.5, 1.5), 1.).with_kernel(kernel);
+    for _ in 0..500 {
+        simulation.step();
+    }
+
+    let graph = simulation.graph();
+    graph
+        .node_indices()
+        .map(|node| graph.neighbors_directed(node, EdgeDirection::Incoming).count())
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn kernel_weights_energy_and_degree() {
+    let kernel = Kernel {
+        degree_exp: 2.,
+        energy_exp: 0.5,
+    };
+
+    assert_eq!(kernel.weight(4., 3.), 18.);
+    assert_eq!(Kernel::default().weight(0.7, 3.), 0.7 * 3.);
+}
+
+#[test]
+fn superlinear_degree_exponents_concentrate_links() {
+    let uniform = max_in_degree(Kernel {
+        degree_exp: 0.,
+        energy_exp: 0.,
+    });
+    let superlinear = max_in_degree(Kernel {
+        degree_exp: 1.5,
+        ..Kernel::default()
+    });
+
+    assert!(superlinear > 5 * uniform, "{} vs {}", superlinear, uniform);
+}
+
+#[test]
+fn large_exponents_attach_without_overflowing() {
+    let kernel = Kernel {
+        degree_exp: 400.,
+        energy_exp: 1.,
+    };
+    assert!(kernel.weight(1., 6.).is_infinite());
+    assert!(kernel.ln_weight(1., 6.).is_finite());
+
+    // Every new node links to the one of highest degree, which has one more
+    // link from the triangle the network starts as.
+    assert_eq!(max_in_degree(kernel), 501);
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "driver")]
+
+use bose_einstein::sim::Simulation;
+use bose_einstein::Config;
+use model::Model;
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn checkpoints_restore_the_graph() {
+    let config = Config {
+        num_steps: 50,
+        ..Config::default()
+    };
+    let mut simulation = <Simulation<_, _> as Model>::init(&config, StdRng::seed_from_u64(435)).unwrap();
+    model::run(&mut simulation, 50, 0, None, |_| Ok(())).unwrap();
+    assert_eq!(Model::timestep(&simulation), 50);
+
+    let json = serde_json::to_string(&simulation.checkpoint()).unwrap();
+    let restored =
+        <Simulation<_, _> as Model>::restore(&config, serde_json::from_str(&json).unwrap(), StdRng::seed_from_u64(0))
+            .unwrap();
+
+    assert_eq!(Model::timestep(&restored), 50);
+    assert_eq!(restored.checkpoint(), simulation.checkpoint());
+    assert_eq!(Model::graph(&restored), Model::graph(&simulation));
+}
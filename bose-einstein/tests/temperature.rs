@@ -0,0 +1,63 @@
+use bose_einstein::sim::{Simulation, Temperature};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::Uniform;
+
+#[test]
+fn schedules_interpolate_between_arrivals() {
+    let schedule = Temperature::<Uniform<f64>>::Schedule(vec![(10, 2.), (20, 1.)]);
+    let mut rng = StdRng::seed_from_u64(435);
+
+    assert_eq!(schedule.sample(0, &mut rng), 2.);
+    assert_eq!(schedule.sample(15, &mut rng), 1.5);
+    assert_eq!(schedule.sample(20, &mut rng), 1.);
+    assert_eq!(schedule.sample(100, &mut rng), 1.);
+}
+
+#[test]
+fn nodes_keep_their_own_temperature() {
+    let rng = StdRng::seed_from_u64(435);
+    let temperature = Temperature::Sampled(Uniform::new(0.5, 2.));
+    let mut simulation = Simulation::init_with_temperature(rng, Uniform::new(0.5, 1.5), temperature);
+    for _ in 0..200 {
+        simulation.step();
+    }
+
+    let graph = simulation.graph();
+    let nodes = graph.node_indices().map(|node| graph[node]).collect::<Vec<_>>();
+    assert!(nodes.iter().all(|node| (0.5..2.).contains(&node.temperature)));
+    assert!(nodes
+        .iter()
+        .all(|node| (node.energy_level - node.temperature * node.fitness.ln_1p()).abs() < 1e-12));
+    assert!(nodes.windows(2).any(|pair| pair[0].temperature != pair[1].temperature));
+}
+
+#[cfg(feature = "driver")]
+#[test]
+fn configs_reject_unordered_schedules() {
+    use bose_einstein::{Config, NodeTemperature};
+
+    let config = Config {
+        node_temperature: Some(NodeTemperature::Schedule {
+            points: vec![(10, 1.), (5, 2.)],
+        }),
+        ..Config::default()
+    };
+
+    assert_eq!(config.validate().unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[cfg(feature = "driver")]
+#[test]
+fn configs_reject_temperatures_which_can_be_negative() {
+    use bose_einstein::{Config, NodeTemperature};
+    use distributions::Spec;
+
+    let config = Config {
+        node_temperature: Some(NodeTemperature::Distribution {
+            distribution: Spec::Uniform { low: -1., high: 1. },
+        }),
+        ..Config::default()
+    };
+
+    assert_eq!(config.validate().unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+}
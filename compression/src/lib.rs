@@ -0,0 +1,229 @@
+//! Transparent compression of output files, through the `gzip` and `zstd`
+//! programs, so that large sweeps don't fill the disk with CSVs.
+//!
+//! A [`Writer`] from [`Compression::create`] compresses whatever is written to
+//! it into a file with the compression's extension, and a [`Reader`] from
+//! [`open`] decompresses a file according to its extension, so that anything
+//! reading output back doesn't need to know how it was written.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// How output files are compressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// The names the compressions are parsed from.
+    pub const NAMES: [&'static str; 3] = ["none", "gzip", "zstd"];
+
+    /// Returns the extension of files with this compression.
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gz"),
+            Compression::Zstd => Some("zst"),
+        }
+    }
+
+    /// Returns the compression of a file, going by its extension.
+    pub fn of_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|extension| extension.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// Returns `path` with the extension of this compression appended, e.g.
+    /// `metrics.jsonl.zst`.
+    pub fn path<P: AsRef<Path>>(self, path: P) -> PathBuf {
+        let mut path = path.as_ref().as_os_str().to_os_string();
+        if let Some(extension) = self.extension() {
+            path.push(".");
+            path.push(extension);
+        }
+
+        path.into()
+    }
+
+    /// Creates a file at `path`, with the extension of this compression
+    /// appended, and returns a writer compressing into it.
+    pub fn create<P: AsRef<Path>>(self, path: P) -> io::Result<Writer> {
+        let file = File::create(self.path(path))?;
+
+        let (child, sink): (_, Box<dyn Write + Send>) = match self.program() {
+            Some(program) => {
+                let mut child = spawn(Command::new(program).arg("-c").stdin(Stdio::piped()).stdout(file))?;
+                let stdin = child.stdin.take().unwrap();
+                (Some(child), Box::new(stdin))
+            }
+            None => (None, Box::new(file)),
+        };
+
+        Ok(Writer {
+            inner: Some(BufWriter::new(sink)),
+            child,
+        })
+    }
+
+    /// The program which compresses and decompresses files.
+    fn program(self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gzip"),
+            Compression::Zstd => Some("zstd"),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        };
+
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "none" => Ok(Compression::None),
+            "gzip" => Ok(Compression::Gzip),
+            "zstd" => Ok(Compression::Zstd),
+            _ => Err(format!("unknown compression: {}", name)),
+        }
+    }
+}
+
+/// Writes to a file through its compression. The file is only complete once
+/// the writer is [finished](Writer::finish); dropping it finishes it too, but
+/// ignores any errors.
+pub struct Writer {
+    inner: Option<BufWriter<Box<dyn Write + Send>>>,
+    child: Option<Child>,
+}
+
+impl Writer {
+    /// Flushes everything written, and waits for the compression to finish.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.close()
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        if let Some(mut inner) = self.inner.take() {
+            inner.flush()?;
+        }
+
+        match self.child.take() {
+            Some(child) => wait(child),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Write for Writer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.as_mut().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.as_mut().unwrap().flush()
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+/// Reads a file through its compression.
+pub struct Reader {
+    inner: Box<dyn Read + Send>,
+    child: Option<Child>,
+}
+
+/// Opens a file for reading, decompressing it according to its extension.
+pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Reader> {
+    let path = path.as_ref();
+    let file = File::open(path)?;
+
+    match Compression::of_path(path).program() {
+        Some(program) => {
+            let mut child = spawn(Command::new(program).args(["-d", "-c"]).stdin(file).stdout(Stdio::piped()))?;
+            let stdout = child.stdout.take().unwrap();
+
+            Ok(Reader {
+                inner: Box::new(stdout),
+                child: Some(child),
+            })
+        }
+        None => Ok(Reader {
+            inner: Box::new(file),
+            child: None,
+        }),
+    }
+}
+
+impl Read for Reader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+
+        // A file which fails to decompress ends early, so the end has to be
+        // checked against how the decompression exited.
+        if len == 0 && !buf.is_empty() {
+            if let Some(child) = self.child.take() {
+                wait(child)?;
+            }
+        }
+
+        Ok(len)
+    }
+}
+
+impl Drop for Reader {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+fn spawn(command: &mut Command) -> io::Result<Child> {
+    command.spawn().map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            format!("failed to run `{}`: {}", command.get_program().to_string_lossy(), err),
+        )
+    })
+}
+
+fn wait(mut child: Child) -> io::Result<()> {
+    let status = child.wait()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("compression failed: {}", status)))
+    }
+}
@@ -0,0 +1,48 @@
+use std::io::{Read, Write};
+
+use compression::Compression;
+
+#[test]
+fn compressed_files_read_back() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().join("math435-compression");
+    std::fs::create_dir_all(&dir).unwrap();
+    let contents = "id,in_degree,fitness\n".to_string() + &"0,12,0.75\n".repeat(1000);
+
+    for &(compression, name, magic) in &[
+        (Compression::None, "nodes.csv", &b"id"[..]),
+        (Compression::Gzip, "nodes.csv.gz", &[0x1f, 0x8b][..]),
+        (Compression::Zstd, "nodes.csv.zst", &[0x28, 0xb5, 0x2f, 0xfd][..]),
+    ] {
+        let mut writer = compression.create(dir.join("nodes.csv")).unwrap();
+        writer.write_all(contents.as_bytes()).unwrap();
+        writer.finish().unwrap();
+
+        let path = dir.join(name);
+        assert_eq!(compression.path(dir.join("nodes.csv")), path);
+        assert_eq!(Compression::of_path(&path), compression);
+        assert!(std::fs::read(&path).unwrap().starts_with(magic));
+
+        let mut read = String::new();
+        compression::open(&path).unwrap().read_to_string(&mut read).unwrap();
+        assert_eq!(read, contents);
+    }
+}
+
+#[test]
+fn corrupt_file_is_an_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("math435-corrupt.csv.zst");
+    std::fs::write(&path, "not zstd").unwrap();
+
+    let mut read = Vec::new();
+    assert!(compression::open(&path).unwrap().read_to_end(&mut read).is_err());
+}
+
+#[test]
+fn names() {
+    for &name in &Compression::NAMES {
+        assert_eq!(name.parse::<Compression>().unwrap().to_string(), name);
+    }
+    assert!("bzip2".parse::<Compression>().is_err());
+}
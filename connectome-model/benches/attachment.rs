@@ -0,0 +1,92 @@
+//! Benchmarks of the attachment pass of a step: the batched evaluation of
+//! attachment probabilities against evaluating each pair on its own, and whole
+//! steps on grids where every node is a candidate.
+
+use connectome_model::{attachment::Candidates, sim::Simulation};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use nalgebra::{distance, Point3};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const CONNECTIVITY_RATE: f64 = 1.;
+const DISTANCE_EXP: i32 = 4;
+
+/// A grid of `n^3` nodes, each last active a random number of timesteps ago.
+fn simulation(n: u32) -> Simulation<StdRng> {
+    let mut simulation = Simulation::new(CONNECTIVITY_RATE, 0.5, 0.01, 5, DISTANCE_EXP, 3, StdRng::seed_from_u64(0));
+    simulation.init_uniform(1, n);
+    simulation.timestep = 100;
+
+    let mut rng = StdRng::seed_from_u64(1);
+    for id in simulation.graph.node_indices().collect::<Vec<_>>() {
+        simulation.graph[id].last_active = Some(rng.gen_range(0, 100));
+    }
+
+    simulation
+}
+
+fn bench_probs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("attachment_probs");
+
+    for &n in &[10, 20] {
+        let simulation = simulation(n);
+        let target = Point3::new(0.5, 0.5, 0.5);
+        let nodes = simulation
+            .graph
+            .node_indices()
+            .map(|id| {
+                let node = &simulation.graph[id];
+                (node.position, simulation.timestep + 1 - node.last_active.unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        group.bench_with_input(BenchmarkId::new("per pair", nodes.len()), &nodes, |b, nodes| {
+            let mut probs = Vec::new();
+            b.iter(|| {
+                probs.clear();
+                probs.extend(nodes.iter().map(|(position, elapsed)| {
+                    let distance = distance(&target, position).powi(DISTANCE_EXP);
+                    CONNECTIVITY_RATE * ((*elapsed as f64).exp() * distance).recip()
+                }));
+                probs.len()
+            })
+        });
+
+        let mut candidates = Candidates::default();
+        for (id, (position, elapsed)) in nodes.iter().enumerate() {
+            candidates.push(id, position, *elapsed);
+        }
+
+        group.bench_with_input(BenchmarkId::new("batched", nodes.len()), &candidates, |b, candidates| {
+            let mut probs = Vec::new();
+            b.iter(|| {
+                candidates.attachment_probs(&target, CONNECTIVITY_RATE, DISTANCE_EXP, &mut probs);
+                probs.len()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("step");
+    group.sample_size(20);
+
+    for &n in &[10, 20] {
+        let num_nodes = n as usize * n as usize * n as usize;
+        let activations = (0..16).map(|i| i * num_nodes / 16).collect::<Vec<_>>();
+
+        group.bench_with_input(BenchmarkId::from_parameter(num_nodes), &activations, |b, activations| {
+            b.iter_batched(
+                || simulation(n),
+                |mut simulation| simulation.step(activations),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_probs, bench_step);
+criterion_main!(benches);
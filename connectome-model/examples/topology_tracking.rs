@@ -0,0 +1,54 @@
+//! Grows a connectome on a grid of nodes driven at random, keeping the
+//! simplicial complex of its graph in sync with each step, and prints its
+//! betti numbers, conduction and wiring cost as it grows.
+//!
+//! `cargo run -p connectome-model --example topology_tracking -- [steps]`
+
+use connectome_model::{conduction, wiring, Simulation, SimplicialComplex};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// The nodes along each side of the grid.
+const SIDE: u32 = 4;
+/// How many steps apart the topology and metrics are printed.
+const INTERVAL: usize = 50;
+
+fn main() {
+    let num_steps = std::env::args()
+        .nth(1)
+        .map_or(500, |steps| steps.parse().expect("steps must be an integer"));
+
+    let mut simulation = Simulation::new(1., 0.5, 0.01, 5, 4, 3, StdRng::seed_from_u64(435));
+    simulation.init_uniform(1, SIDE);
+    let num_nodes = (SIDE * SIDE * SIDE) as usize;
+
+    let mut simplicial_complex = SimplicialComplex::new((0..num_nodes).collect(), None);
+    let minimal = wiring::MinimalWiring::new(&simulation);
+    let mut input_rng = StdRng::seed_from_u64(0);
+
+    println!("timestep,edges,betti_numbers,f_vector,global_efficiency,normalized_cost");
+    for timestep in 1..=num_steps {
+        // Drives a random node each step.
+        let step_result = simulation.step(&[input_rng.gen_range(0, num_nodes)]);
+
+        // Edges are removed before any are added, so that an edge which decays
+        // and reattaches in the same step ends up in the complex.
+        let removed_edges = step_result.removed_edges.iter().map(|&(a, b)| vec![a, b]).collect::<Vec<_>>();
+        simplicial_complex.remove_batch(&removed_edges).unwrap();
+        let added_edges = step_result.added_edges.iter().map(|&(a, b)| vec![a, b]).collect::<Vec<_>>();
+        simplicial_complex.add_batch(&added_edges).unwrap();
+
+        if timestep % INTERVAL == 0 {
+            let conduction = conduction::conduction(&simulation);
+            let wiring_cost = wiring::wiring_cost(&simulation, &minimal);
+            println!(
+                "{},{},{:?},{:?},{:.4},{}",
+                timestep,
+                simulation.graph.edge_count(),
+                simplicial_complex.betti_numbers(),
+                simplicial_complex.f_vector(),
+                conduction.global_efficiency,
+                wiring_cost.normalized_cost.map_or(String::new(), |cost| format!("{:.4}", cost))
+            );
+        }
+    }
+}
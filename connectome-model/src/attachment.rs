@@ -0,0 +1,107 @@
+//! The attachment probabilities of a step, evaluated a batch at a time: from
+//! each activated node to every candidate source, over positions kept in
+//! struct-of-arrays layout so that the distance and power loops vectorize.
+//!
+//! The results are bit-for-bit those of evaluating each pair on its own, with
+//! `nalgebra::distance`, `powi` and `exp`, so runs don't change with the
+//! layout.
+
+use nalgebra::Point3;
+
+/// The nodes which may gain an edge to an activated node in a step: those
+/// which have been active before, with their positions and how recently they
+/// were active.
+#[derive(Default)]
+pub struct Candidates {
+    ids: Vec<usize>,
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    zs: Vec<f64>,
+    /// `exp` of the timesteps since each candidate was last active, which
+    /// depends only on the candidate, so is computed once per step rather than
+    /// once per pair.
+    recency: Vec<f64>,
+}
+
+impl Candidates {
+    /// Adds a candidate at `position`, last active `elapsed` timesteps ago.
+    pub fn push(&mut self, id: usize, position: &Point3<f64>, elapsed: usize) {
+        self.ids.push(id);
+        self.xs.push(position.x);
+        self.ys.push(position.y);
+        self.zs.push(position.z);
+        self.recency.push((elapsed as f64).exp());
+    }
+
+    /// The candidates, in the order they were added.
+    pub fn ids(&self) -> &[usize] {
+        &self.ids
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Writes the probability of each candidate attaching to a node at
+    /// `target` into `probs`, in the order of [`ids`](Self::ids):
+    /// `connectivity_rate / (exp(elapsed) * distance^distance_exp)`.
+    pub fn attachment_probs(
+        &self,
+        target: &Point3<f64>,
+        connectivity_rate: f64,
+        distance_exp: i32,
+        probs: &mut Vec<f64>,
+    ) {
+        probs.clear();
+        probs.extend(
+            self.xs
+                .iter()
+                .zip(&self.ys)
+                .zip(&self.zs)
+                .map(|((&x, &y), &z)| {
+                    let (dx, dy, dz) = (x - target.x, y - target.y, z - target.z);
+                    (dx * dx + dy * dy + dz * dz).sqrt()
+                }),
+        );
+
+        powi_in_place(probs, distance_exp);
+
+        for (prob, &recency) in probs.iter_mut().zip(&self.recency) {
+            *prob = connectivity_rate * (recency * *prob).recip();
+        }
+    }
+}
+
+/// Raises every value to the power `exp`, by squaring and multiplying in the
+/// same order as `f64::powi`, one bit of the exponent at a time across the
+/// whole batch, rather than one value at a time.
+fn powi_in_place(values: &mut [f64], exp: i32) {
+    let mut bits = exp.unsigned_abs();
+    let mut bases = values.to_vec();
+    values.iter_mut().for_each(|value| *value = 1.0);
+
+    loop {
+        if bits & 1 != 0 {
+            for (value, &base) in values.iter_mut().zip(&bases) {
+                *value *= base;
+            }
+        }
+
+        bits >>= 1;
+        if bits == 0 {
+            break;
+        }
+
+        for base in &mut bases {
+            *base *= *base;
+        }
+    }
+
+    if exp < 0 {
+        values.iter_mut().for_each(|value| *value = value.recip());
+    }
+}
@@ -0,0 +1,224 @@
+//! Coarse-graining the graph into blocks of nodes, either spatial boxes or
+//! detected communities, and reducing it to the weighted graph between them,
+//! so that large simulations can be compared with mesoscale connectomes,
+//! which only resolve how strongly each region connects to each other.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+#[cfg(feature = "graph-io")]
+use graph_io::{AttributedGraph, Attributes};
+use nalgebra::{Point3, Vector3};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences, NodeIndexable};
+use petgraph::EdgeDirection;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::sim::Simulation;
+use crate::stimulus;
+
+/// The most rounds of label propagation communities are detected in.
+const MAX_ROUNDS: usize = 100;
+
+/// How the nodes are partitioned into blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "kebab-case"))]
+pub enum Partition {
+    /// Splits the box around the nodes into `divisions` boxes along each
+    /// axis, each a block whether or not any nodes are in it, so that the
+    /// blocks of every snapshot line up.
+    Boxes { divisions: usize },
+    /// Detects communities by label propagation over the edges, taken both
+    /// ways: every node starts in a block of its own, and, in order of their
+    /// ids, nodes join the block most of their neighbors are in, staying put
+    /// on a tie with their own and otherwise joining the lowest, until no node
+    /// moves. Blocks are numbered in the order of their first nodes.
+    Communities,
+}
+
+impl Partition {
+    /// Returns whether the partition makes any blocks at all.
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Partition::Boxes { divisions } => divisions > 0,
+            Partition::Communities => true,
+        }
+    }
+
+    /// Returns the block of each node, by node index, or `None` for the
+    /// indices of lesioned nodes, along with the number of blocks.
+    pub fn blocks<R: Rng>(&self, simulation: &Simulation<R>) -> (Vec<Option<usize>>, usize) {
+        let graph = &simulation.graph;
+        let mut blocks = vec![None; graph.node_bound()];
+
+        match *self {
+            Partition::Boxes { divisions } => {
+                let positions = graph.node_indices().map(|node| graph[node].position).collect::<Vec<_>>();
+                for (node, region) in graph.node_indices().zip(stimulus::regions_of(&positions, divisions)) {
+                    blocks[node.index()] = Some(region);
+                }
+                (blocks, divisions.pow(3))
+            }
+            Partition::Communities => {
+                for node in graph.node_indices() {
+                    blocks[node.index()] = Some(node.index());
+                }
+
+                for _ in 0..MAX_ROUNDS {
+                    let mut moved = false;
+                    for node in graph.node_indices() {
+                        let mut counts = BTreeMap::new();
+                        let neighbors = graph
+                            .neighbors_directed(node, EdgeDirection::Outgoing)
+                            .chain(graph.neighbors_directed(node, EdgeDirection::Incoming));
+                        for neighbor in neighbors {
+                            *counts.entry(blocks[neighbor.index()].unwrap()).or_insert(0) += 1;
+                        }
+
+                        let current = blocks[node.index()].unwrap();
+                        let most = counts.values().copied().max().unwrap_or(0);
+                        if most > 0 && counts.get(&current) != Some(&most) {
+                            // The lowest of the blocks with the most neighbors.
+                            let (&block, _) = counts.iter().find(|&(_, &count)| count == most).unwrap();
+                            blocks[node.index()] = Some(block);
+                            moved = true;
+                        }
+                    }
+
+                    if !moved {
+                        break;
+                    }
+                }
+
+                // Blocks are renumbered as they're first met in node order.
+                let mut numbers = BTreeMap::new();
+                for block in blocks.iter_mut().flatten() {
+                    let next = numbers.len();
+                    *block = *numbers.entry(*block).or_insert(next);
+                }
+                (blocks, numbers.len())
+            }
+        }
+    }
+}
+
+/// A block of nodes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Block {
+    pub size: usize,
+    /// The mean position of its nodes, if it has any.
+    pub centroid: Option<Point3<f64>>,
+}
+
+/// The edges from the nodes of one block to those of another, or within one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Connection {
+    pub num_edges: usize,
+    /// The sum of the myelination of the edges.
+    pub myelination: usize,
+}
+
+/// The graph reduced to its blocks: an edge joins two blocks, or a block to
+/// itself, wherever an edge joins any of their nodes, weighing how many do.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoarseGraph {
+    /// The block of each node, by node index, as in [`Partition::blocks`].
+    pub membership: Vec<Option<usize>>,
+    pub blocks: Vec<Block>,
+    /// The connection from each block to each other it has edges to, by
+    /// `(source, target)`.
+    pub connections: BTreeMap<(usize, usize), Connection>,
+}
+
+impl CoarseGraph {
+    /// Partitions the nodes of the simulation and reduces its graph to the
+    /// blocks.
+    pub fn new<R: Rng>(simulation: &Simulation<R>, partition: Partition) -> Self {
+        let graph = &simulation.graph;
+        let (membership, num_blocks) = partition.blocks(simulation);
+
+        let mut sums = vec![(0, Vector3::zeros()); num_blocks];
+        for node in graph.node_indices() {
+            let (size, sum) = &mut sums[membership[node.index()].unwrap()];
+            *size += 1;
+            *sum += graph[node].position.coords;
+        }
+        let blocks = sums
+            .into_iter()
+            .map(|(size, sum)| Block {
+                size,
+                centroid: if size > 0 { Some(Point3::from(sum / size as f64)) } else { None },
+            })
+            .collect();
+
+        let mut connections = BTreeMap::<_, Connection>::new();
+        for edge in graph.edge_references() {
+            let source = membership[edge.source().index()].unwrap();
+            let target = membership[edge.target().index()].unwrap();
+            let connection = connections.entry((source, target)).or_default();
+            connection.num_edges += 1;
+            connection.myelination += edge.weight().myelination;
+        }
+
+        Self {
+            membership,
+            blocks,
+            connections,
+        }
+    }
+
+    /// Returns the coarse graph for export: each block with its `size` and
+    /// centroid, as `x`, `y` and `z`, if it has one, and each connection with
+    /// its number of edges as its `weight` and their mean `myelination`.
+    #[cfg(feature = "graph-io")]
+    pub fn attributed_graph(&self) -> AttributedGraph {
+        let nodes = self
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(id, block)| {
+                let mut attributes = Attributes::new();
+                attributes.insert("size".to_string(), block.size.into());
+                if let Some(centroid) = block.centroid {
+                    attributes.insert("x".to_string(), centroid.x.into());
+                    attributes.insert("y".to_string(), centroid.y.into());
+                    attributes.insert("z".to_string(), centroid.z.into());
+                }
+                (id, attributes)
+            })
+            .collect();
+        let edges = self
+            .connections
+            .iter()
+            .map(|(&(source, target), connection)| {
+                let mut attributes = Attributes::new();
+                attributes.insert("weight".to_string(), connection.num_edges.into());
+                let myelination = connection.myelination as f64 / connection.num_edges as f64;
+                attributes.insert("myelination".to_string(), myelination.into());
+                (source, target, attributes)
+            })
+            .collect();
+
+        AttributedGraph {
+            directed: true,
+            nodes,
+            edges,
+        }
+    }
+}
+
+/// Writes the block of each node of a coarse graph as CSV, one row per node
+/// which hasn't been lesioned.
+pub fn write_membership<W: Write>(coarse_graph: &CoarseGraph, mut writer: W) -> io::Result<()> {
+    writeln!(writer, "node,block")?;
+
+    for (node, block) in coarse_graph.membership.iter().enumerate() {
+        if let Some(block) = block {
+            writeln!(writer, "{},{}", node, block)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! How quickly activity can travel through the connectome, with each edge
+//! taking as long to cross as an activation queued on it does: one timestep
+//! when fully myelinated, and one more for each level of myelination it lacks.
+//! Comparing the efficiency of the network with that of the same edges left
+//! unmyelinated measures what myelination buys as the network matures.
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use petgraph::algo::dijkstra;
+use rand::Rng;
+
+use crate::sim::Simulation;
+
+/// The shortest conduction delays between the nodes of the graph at one
+/// timestep, along its directed edges.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Conduction {
+    /// The mean shortest delay, in timesteps, over ordered pairs of nodes
+    /// joined by a path, or 0 if none are.
+    pub mean_delay: f64,
+    /// The mean over ordered pairs of distinct nodes of the inverse of their
+    /// shortest delay, counting 0 for pairs with no path between them.
+    pub global_efficiency: f64,
+    /// The global efficiency the graph would have with none of its edges
+    /// myelinated.
+    pub unmyelinated_efficiency: f64,
+    /// How much more efficient myelination makes the graph, as the ratio of
+    /// its efficiency to the unmyelinated one, or `None` if it has no edges.
+    pub myelination_gain: Option<f64>,
+}
+
+/// Returns how long an edge with the given myelination takes to cross.
+pub fn delay(myelination: usize, max_myelination: usize) -> usize {
+    1 + max_myelination.saturating_sub(myelination)
+}
+
+/// Measures the shortest conduction delays from every node to every other.
+pub fn conduction<R: Rng>(simulation: &Simulation<R>) -> Conduction {
+    let max = simulation.max_myelination;
+    let (mean_delay, global_efficiency) = shortest_delays(simulation, |myelination| delay(myelination, max));
+    let (_, unmyelinated_efficiency) = shortest_delays(simulation, |_| delay(0, max));
+
+    Conduction {
+        mean_delay,
+        global_efficiency,
+        unmyelinated_efficiency,
+        myelination_gain: if unmyelinated_efficiency > 0. {
+            Some(global_efficiency / unmyelinated_efficiency)
+        } else {
+            None
+        },
+    }
+}
+
+/// Returns the mean shortest delay between the nodes joined by a path, and
+/// the global efficiency, with edges taking `edge_delay` of their myelination
+/// to cross.
+fn shortest_delays<R: Rng, F: Fn(usize) -> usize>(simulation: &Simulation<R>, edge_delay: F) -> (f64, f64) {
+    let graph = &simulation.graph;
+    let num_nodes = graph.node_count();
+    let mut total_delay = 0;
+    let mut num_paths = 0;
+    let mut total_efficiency = 0.;
+
+    for source in graph.node_indices() {
+        let delays = dijkstra(graph, source, None, |edge| edge_delay(edge.weight().myelination));
+
+        for (&target, &delay) in &delays {
+            if target != source {
+                total_delay += delay;
+                num_paths += 1;
+                total_efficiency += 1. / delay as f64;
+            }
+        }
+    }
+
+    let num_pairs = num_nodes * num_nodes.saturating_sub(1);
+    (
+        if num_paths > 0 { total_delay as f64 / num_paths as f64 } else { 0. },
+        if num_pairs > 0 { total_efficiency / num_pairs as f64 } else { 0. },
+    )
+}
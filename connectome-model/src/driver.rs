@@ -0,0 +1,1286 @@
+//! Runs the simulation, keeping its simplicial complex in sync and recording
+//! its topology as it goes.
+
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use graph_io::gexf::DynamicGexf;
+use graph_io::{AttributedGraph, DynamicGraph};
+use model::Model;
+use nalgebra::Point3;
+use petgraph::EdgeDirection;
+use compression::Compression;
+use net_metrics::graphlets::GRAPHLET_SAMPLE_NODES;
+use net_metrics::robustness::ROBUSTNESS_POINTS;
+use net_metrics::{Removal, Summary};
+use progress::Progress;
+use provenance::{Ledger, Manifest};
+use results_db::ResultsDb;
+use rand::{random, rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use topology::null_models;
+use topology::persistence::{self, WeightOrder};
+use topology::recorder::{BettiRecorder, ControlRecorder, EventRecorder};
+use topology::summaries::{self, Grid};
+
+use crate::coarse::{self, CoarseGraph, Partition};
+use crate::frames::{FrameFormat, FrameWriter, FRAMES_DIR};
+use crate::functional::{self, SpikeRecorder};
+use crate::conduction::{self, Conduction};
+use crate::experiment::{self, Action, Event, Parameter};
+use crate::lesion::RecoveryRecorder;
+use crate::motifs::MotifRecorder;
+use crate::myelination::{HistogramRecorder, MyelinationRecorder, SaturationTracker};
+use crate::noise::PositionNoise;
+use crate::protocol::{self, Protocol};
+use crate::queue::{self, QueueLimit, QueueStats};
+use crate::reservoir::Evaluation;
+use crate::sim::{Simulation, StepResult};
+use crate::snapshots::{Schedule, Scheduler};
+use crate::simplex::SimplicialComplex;
+use crate::stats::StatsCollector;
+use crate::stimulus::Selector;
+use crate::stream::{StepStream, StreamTarget};
+use crate::trajectory::TrajectoryBuffer;
+use crate::wiring::{self, MinimalWiring, WiringCost};
+
+/// The number of betti numbers recorded for each complex.
+const NUM_DIMS: usize = 3;
+
+/// The name runs are added to a results database under.
+const PROGRAM: &str = "connectome";
+
+/// The name of the ledger of a sweep's finished runs in its output directory.
+pub const LEDGER_FILE: &str = "ledger.jsonl";
+
+/// The name of the dynamic GEXF file of a run's history in its output
+/// directory.
+pub const HISTORY_FILE: &str = "graph_history.gexf";
+
+/// The names of the functional connectivity and structural adjacency
+/// matrices, and of how they compare, in a run's output directory.
+pub const FUNCTIONAL_CONNECTIVITY_FILE: &str = "functional_connectivity.csv";
+pub const STRUCTURAL_ADJACENCY_FILE: &str = "structural_adjacency.csv";
+pub const CONNECTIVITY_REPORT_FILE: &str = "connectivity.json";
+
+/// The names of the graphlet counts of a run's final graph, of the table of
+/// its nodes, and of its robustness curves, in its output directory.
+pub const GRAPHLETS_FILE: &str = "graphlets.json";
+pub const NODES_FILE: &str = "nodes.csv";
+pub const ROBUSTNESS_FILE: &str = "robustness.csv";
+
+/// The name of the table of changes to each edge's myelination in a run's
+/// output directory.
+pub const MYELINATION_FILE: &str = "myelination.csv";
+
+/// The names of the tables of how many edges have each level of myelination
+/// over a run, and of when each edge saturated, in its output directory.
+pub const MYELINATION_HISTOGRAM_FILE: &str = "myelination_histogram.csv";
+pub const SATURATION_FILE: &str = "myelination_saturation.csv";
+
+/// The name of the table of a run's temporal motifs in its output directory.
+pub const MOTIFS_FILE: &str = "temporal_motifs.csv";
+
+/// The name of the table comparing a run's complexes with those of shuffled
+/// controls in its output directory.
+pub const SHUFFLED_CONTROL_FILE: &str = "shuffled_control.csv";
+
+/// The name of the log of every simplex born into or dying out of a run's
+/// complex in its output directory.
+pub const SIMPLEX_EVENTS_FILE: &str = "simplex_events.csv";
+
+/// The name of the directory of a run's coarse-grained graphs in its output
+/// directory.
+pub const COARSE_GRAINED_DIR: &str = "coarse_grained";
+
+/// The name of the summaries of the persistence of a run's complexes in its
+/// output directory.
+pub const PERSISTENCE_SUMMARIES_FILE: &str = "persistence_summaries.jsonl";
+
+/// The name of the trajectory of a run's key metrics in its output directory.
+pub const TRAJECTORY_FILE: &str = "trajectory.csv";
+
+/// The names of the tables of latencies of a run's responses to its stimulus,
+/// at every node and at its readouts, in its output directory.
+pub const STIMULUS_RESPONSE_FILE: &str = "stimulus_response.csv";
+pub const READOUT_LATENCY_FILE: &str = "readout_latency.csv";
+
+/// The name of the scores of the readouts of a run's reservoir evaluation in
+/// its output directory.
+pub const RESERVOIR_FILE: &str = "reservoir.json";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// The number of nodes along each side of the grid.
+    pub grid_size: u32,
+    /// The populations nodes are assigned to when the grid is laid out, each
+    /// over those of the populations before it.
+    pub populations: Vec<Population>,
+    /// How many steps to run for, or `None` to run until interrupted.
+    pub num_steps: Option<usize>,
+    /// How the nodes activated every step, to drive the network, are chosen;
+    /// a single one uniformly at random by default.
+    pub input: Selector,
+    /// How many steps apart the complexes are recorded, with the default
+    /// `snapshots` schedule.
+    pub record_interval: usize,
+    /// When the complexes and metrics are recorded; see [`crate::snapshots`].
+    pub snapshots: Schedule,
+    /// Whether to compare the complex with that of a degree-preserving
+    /// randomization of the graph whenever it's recorded, in
+    /// [`SHUFFLED_CONTROL_FILE`].
+    pub shuffled_controls: bool,
+    /// Reduces the graph to the weighted graph between blocks of its nodes
+    /// whenever the complexes are recorded, in [`COARSE_GRAINED_DIR`], if
+    /// given.
+    pub coarse_graining: Option<CoarseGraining>,
+    /// Whether to log every simplex born into or dying out of the complex,
+    /// with the step it happened at, in [`SIMPLEX_EVENTS_FILE`]; the vertices
+    /// of the nodes are there from the start, and aren't logged.
+    pub simplex_events: bool,
+    /// How many milliseconds the betti numbers of each recorded complex may
+    /// take, if limited, after which the rest are estimated from its simplex
+    /// counts and the metrics flag the timeout, so that a dense complex can't
+    /// stall the run.
+    pub betti_budget_ms: Option<u64>,
+    /// Summarizes the persistence of the complex whenever it's recorded, in
+    /// [`PERSISTENCE_SUMMARIES_FILE`], if given.
+    pub persistence_summaries: Option<PersistenceSummaries>,
+    pub connectivity_rate: f64,
+    pub myelination_rate: f64,
+    pub decay_rate: f64,
+    pub max_myelination: usize,
+    pub distance_exp: i32,
+    pub refractory_period: usize,
+    /// The probability that an edge delivers each activation queued on it.
+    pub transmission_reliability: f64,
+    /// Whether myelination makes delivery more reliable, up to certain on a
+    /// fully myelinated edge.
+    pub myelinated_transmission: bool,
+    /// How many activations each edge can have queued, and what happens to
+    /// those beyond that, or `None` for no limit; see [`crate::queue`].
+    pub activation_queue: Option<QueueLimit>,
+    /// Jitters the nodes off of the grid, and has them drift as the run goes
+    /// on, if given; see [`crate::noise`].
+    pub position_noise: Option<PositionNoise>,
+    /// How many timesteps of spikes the functional complex is built from.
+    pub spike_window: usize,
+    /// How many timesteps apart two spikes can be and still count as
+    /// co-active.
+    pub coincidence: usize,
+    /// How to compute the functional connectivity matrix written at the end
+    /// of the run, if at all; see [`write_connectivity`].
+    pub connectivity_matrix: Option<ConnectivityMeasure>,
+    /// A lesion to make partway through the run, if any, with the recovery
+    /// from it recorded in `lesion_recovery.csv`.
+    pub lesion: Option<Lesion>,
+    /// The events of an experiment script, done as the run goes; see
+    /// [`crate::experiment`].
+    pub events: Vec<Event>,
+    /// A stimulus to probe the network with once it has run its steps, if
+    /// any, with the responses to it in [`STIMULUS_RESPONSE_FILE`] and
+    /// [`READOUT_LATENCY_FILE`].
+    pub stimulation: Option<Stimulation>,
+    /// Evaluates the final network as a reservoir, with the scores of its
+    /// readouts in [`RESERVOIR_FILE`], if given; see [`crate::reservoir`].
+    pub reservoir: Option<Reservoir>,
+    /// How many independent runs a sweep is made of.
+    pub num_runs: usize,
+    /// The seed of the random number generators, or `None` to pick one at
+    /// random. Each run of a sweep is seeded from this plus its index.
+    pub seed: Option<u64>,
+    /// How the CSV and JSON lines output is compressed.
+    pub compression: Compression,
+    /// How many steps apart runtime statistics are printed to stderr and
+    /// written to `stats.jsonl`, or `None` not to collect them; see
+    /// [`crate::stats`].
+    pub stats_interval: Option<usize>,
+    /// Writes a frame of the graph every so many steps, to animate its
+    /// growth; see [`crate::frames`].
+    pub frames: Option<Frames>,
+    /// Whether to record when each edge was added and removed, and write the
+    /// whole history of the graph to [`HISTORY_FILE`] as a dynamic GEXF file.
+    pub history: bool,
+    /// Whether to record every change to an edge's myelination, in
+    /// [`MYELINATION_FILE`].
+    pub myelination_trajectories: bool,
+    /// Whether to record how many edges have each level of myelination
+    /// whenever the complexes are, in [`MYELINATION_HISTOGRAM_FILE`], and when
+    /// each edge is first fully myelinated, in [`SATURATION_FILE`].
+    pub myelination_statistics: bool,
+    /// Counts the temporal motifs of the edges added, in [`MOTIFS_FILE`], if
+    /// given.
+    pub temporal_motifs: Option<TemporalMotifs>,
+    /// Keeps a downsampled trajectory of the run's key metrics in memory,
+    /// written to [`TRAJECTORY_FILE`] at the end, if given.
+    pub trajectory: Option<Trajectory>,
+    /// Streams a record of every step to another process as the run goes, if
+    /// given; see [`crate::stream`].
+    pub stream: Option<Stream>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            grid_size: 6,
+            populations: Vec::new(),
+            num_steps: None,
+            input: Selector::Uniform,
+            record_interval: 10,
+            snapshots: Schedule::Interval,
+            shuffled_controls: false,
+            coarse_graining: None,
+            simplex_events: false,
+            betti_budget_ms: None,
+            persistence_summaries: None,
+            connectivity_rate: 1.,
+            myelination_rate: 0.5,
+            decay_rate: 0.01,
+            max_myelination: 5,
+            distance_exp: 4,
+            refractory_period: 3,
+            transmission_reliability: 1.,
+            myelinated_transmission: false,
+            activation_queue: None,
+            position_noise: None,
+            spike_window: 50,
+            coincidence: 2,
+            connectivity_matrix: None,
+            lesion: None,
+            events: Vec::new(),
+            stimulation: None,
+            reservoir: None,
+            num_runs: 8,
+            seed: None,
+            compression: Compression::None,
+            stats_interval: None,
+            frames: None,
+            history: false,
+            myelination_trajectories: false,
+            myelination_statistics: false,
+            temporal_motifs: None,
+            trajectory: None,
+            stream: None,
+        }
+    }
+}
+
+impl Config {
+    /// Returns this configuration with a seed, picking one at random if it
+    /// has none, so that its runs can be reproduced.
+    pub fn seeded(&self) -> Self {
+        Self {
+            seed: Some(self.seed.unwrap_or_else(random)),
+            ..self.clone()
+        }
+    }
+}
+
+/// A named population of nodes: those listed, and those within `radius` of
+/// `center` if given, which is the center of the grid by default.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Population {
+    pub name: String,
+    #[serde(default)]
+    pub nodes: Vec<usize>,
+    #[serde(default)]
+    pub center: [f64; 3],
+    pub radius: Option<f64>,
+}
+
+/// Lesions every node within `radius` of the center of the grid after `step`
+/// steps.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Lesion {
+    pub step: usize,
+    pub radius: f64,
+}
+
+/// A parameter changed partway through a run by its script, as logged in its
+/// manifest.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ParameterChange {
+    pub run: u64,
+    pub step: usize,
+    pub parameter: Parameter,
+    pub from: f64,
+    pub to: f64,
+}
+
+/// Drives `nodes` at the start of each of `num_trials` trials of
+/// `trial_length` steps, after the run's steps have let the network
+/// equilibrate, and records how long each node takes to fire in response in
+/// [`STIMULUS_RESPONSE_FILE`], and how long activity from each of `nodes`
+/// takes to reach each of `readouts` in [`READOUT_LATENCY_FILE`]; see
+/// [`crate::protocol`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stimulation {
+    pub nodes: Vec<usize>,
+    #[serde(default)]
+    pub readouts: Vec<usize>,
+    pub num_trials: usize,
+    pub trial_length: usize,
+}
+
+/// Drives the nodes `input` chooses, once, with a random binary signal of
+/// `num_symbols` symbols, each `symbol_length` steps long, and trains linear
+/// readouts of the network's activity to recall it at delays up to
+/// `max_delay`, after a washout of `washout` symbols, with a ridge penalty of
+/// `ridge`; their scores are written to [`RESERVOIR_FILE`]. See
+/// [`crate::reservoir`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Reservoir {
+    pub input: Selector,
+    pub num_symbols: usize,
+    pub symbol_length: usize,
+    pub washout: usize,
+    pub max_delay: usize,
+    pub ridge: f64,
+}
+
+impl Default for Reservoir {
+    fn default() -> Self {
+        Self {
+            input: Selector::Hubs { count: 4 },
+            num_symbols: 400,
+            symbol_length: 4,
+            washout: 20,
+            max_delay: 5,
+            ridge: 1.,
+        }
+    }
+}
+
+impl Reservoir {
+    /// Returns the evaluation of this reservoir with `input` as its input
+    /// nodes.
+    fn evaluation(&self, input: Vec<usize>) -> Evaluation {
+        Evaluation {
+            input,
+            num_symbols: self.num_symbols,
+            symbol_length: self.symbol_length,
+            washout: self.washout,
+            max_delay: self.max_delay,
+            ridge: self.ridge,
+        }
+    }
+}
+
+/// How the functional connectivity between each pair of nodes is measured
+/// from their spikes.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum ConnectivityMeasure {
+    /// The correlation between their spike trains.
+    Correlation,
+    /// How many times they fired within `lag` timesteps of each other.
+    Coincidence { lag: usize },
+    /// The transfer entropy from one to the other, conditioned on `history`
+    /// timesteps of each, with the source's ending `lag` timesteps before the
+    /// target fires; directed, so compared with the directed adjacency.
+    TransferEntropy { history: usize, lag: usize },
+}
+
+/// How the functional connectivity of a run compares with its structure.
+#[derive(Serialize)]
+struct ConnectivityReport {
+    timestep: usize,
+    measure: ConnectivityMeasure,
+    /// How many timesteps of spikes the functional connectivity is from.
+    num_bins: usize,
+    /// The correlation between the functional connectivity and the
+    /// structural adjacency over pairs of distinct nodes, if neither is
+    /// constant.
+    structure_function_correlation: Option<f64>,
+}
+
+/// Writes a frame of the graph, in `format`, before the first step and after
+/// every `interval` steps, or as `schedule` says, to the `frames` directory of
+/// the run.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Frames {
+    pub interval: usize,
+    #[serde(default)]
+    pub format: FrameFormat,
+    #[serde(default)]
+    pub schedule: Schedule,
+}
+
+/// Counts temporal motifs in windows of `window` steps, with triangles closed
+/// within `delta` steps counted apart; see [`crate::motifs`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TemporalMotifs {
+    pub window: usize,
+    pub delta: usize,
+}
+
+/// Summarizes the persistent homology of the complex, filtered by the
+/// conduction delays of its edges so that the fastest enter first, as the
+/// first `num_landscapes` persistence landscapes, each sampled at
+/// `resolution` points, and a persistence image of `resolution` by
+/// `resolution` pixels, spreading classes by `sigma`; see
+/// [`topology::summaries`]. The grids span the delays edges can have with the
+/// configured maximum myelination, so that the vectors of every recording, and
+/// of every run with the same maximum, line up.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PersistenceSummaries {
+    pub num_landscapes: usize,
+    pub resolution: usize,
+    pub sigma: f64,
+}
+
+/// The summaries of the persistence of the classes of one dimension of the
+/// complex at one timestep.
+#[derive(Serialize)]
+struct PersistenceSummary {
+    timestep: usize,
+    dim: usize,
+    landscapes: Vec<f64>,
+    image: Vec<f64>,
+}
+
+/// Partitions the nodes as `partition` says and exports the weighted graph
+/// between the blocks, in `format`, by name or file extension, to
+/// `step_<step>.<extension>` in the run's [`COARSE_GRAINED_DIR`], with the
+/// block of each node in `step_<step>_membership.csv` there; see
+/// [`crate::coarse`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CoarseGraining {
+    pub partition: Partition,
+    pub format: String,
+}
+
+/// Keeps the last `capacity` points of the trajectory, one every `stride`
+/// steps; see [`crate::trajectory`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Trajectory {
+    pub capacity: usize,
+    pub stride: usize,
+}
+
+/// Streams the record of every step to `target`, in batches of `batch_size`
+/// steps; see [`crate::stream`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stream {
+    pub target: StreamTarget,
+    pub batch_size: usize,
+}
+
+/// The metrics of the graph at one timestep, with how its in-degrees and
+/// out-degrees correlate, its wiring cost, conduction delays and activation
+/// queues.
+#[derive(Serialize)]
+struct TimestepMetrics {
+    timestep: usize,
+    #[serde(flatten)]
+    summary: Summary,
+    /// The correlation between the in-degree and out-degree of each node. Its
+    /// reciprocity isn't reported, as a step never joins two nodes already
+    /// joined in either direction, which leaves it always 0.
+    in_out_degree_correlation: Option<f64>,
+    wiring: WiringCost,
+    conduction: Conduction,
+    queues: QueueStats,
+    /// Whether the betti numbers were computed within their budget, if they
+    /// have one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    betti: Option<BettiTiming>,
+}
+
+/// How many of the betti numbers of a recorded complex were computed within
+/// their budget.
+#[derive(Serialize)]
+struct BettiTiming {
+    timed_out: bool,
+    exact_dims: usize,
+}
+
+/// Runs a simulation, writing the betti curves of its complexes, the metrics
+/// of its graph whenever they're recorded, and the graphlet counts, nodes and
+/// robustness curves of its final graph to `out_dir`, along with whatever else
+/// `config` asks for, in the files its fields name. The run is added to `db`
+/// if given, and its final graph returned for export.
+pub fn run<P: AsRef<Path>>(config: &Config, out_dir: P, db: Option<&ResultsDb>) -> io::Result<AttributedGraph> {
+    run_indexed(&config.seeded(), out_dir.as_ref(), db, 0, None)
+}
+
+/// Runs a simulation as in [`run`], logging each parameter its script changes
+/// in `manifest`.
+pub fn run_logged<P: AsRef<Path>>(
+    config: &Config,
+    out_dir: P,
+    db: Option<&ResultsDb>,
+    manifest: &Mutex<Manifest>,
+) -> io::Result<AttributedGraph> {
+    run_indexed(&config.seeded(), out_dir.as_ref(), db, 0, Some(manifest))
+}
+
+/// Runs a simulation as in [`run`], as the run with the given index of a
+/// sweep, logging parameter changes in `manifest` if given.
+fn run_indexed(
+    config: &Config,
+    out_dir: &Path,
+    db: Option<&ResultsDb>,
+    run_index: u64,
+    manifest: Option<&Mutex<Manifest>>,
+) -> io::Result<AttributedGraph> {
+    if !(0. ..=1.).contains(&config.transmission_reliability) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("transmission reliability {} isn't a probability", config.transmission_reliability),
+        ));
+    }
+    if let Some(ConnectivityMeasure::TransferEntropy { history, lag }) = config.connectivity_matrix {
+        if !(1..=functional::MAX_HISTORY).contains(&history) || lag == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "transfer entropy needs a history of 1 to {} timesteps and a lag of at least 1",
+                    functional::MAX_HISTORY
+                ),
+            ));
+        }
+    }
+
+    let num_nodes = config.grid_size.pow(3) as usize;
+    if let Some(stimulation) = &config.stimulation {
+        if config.num_steps.is_none() || stimulation.trial_length == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a stimulation needs a number of steps to equilibrate for and trials at least a step long",
+            ));
+        }
+        if let Some(node) = stimulation.nodes.iter().chain(&stimulation.readouts).find(|&&node| node >= num_nodes) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("stimulated node {} isn't in a grid of {} nodes", node, num_nodes),
+            ));
+        }
+    }
+
+    let mut schedules = std::iter::once(&config.snapshots).chain(config.frames.iter().map(|frames| &frames.schedule));
+    if let Some(schedule) = schedules.find(|schedule| !schedule.is_valid()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("snapshots can't be taken on the schedule {:?}", schedule),
+        ));
+    }
+    if matches!(&config.temporal_motifs, Some(motifs) if motifs.window == 0) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "temporal motifs need windows at least a step long",
+        ));
+    }
+
+    if !config.input.is_valid() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("the input {:?} doesn't choose any nodes", config.input),
+        ));
+    }
+    if matches!(
+        &config.persistence_summaries,
+        Some(summaries) if summaries.resolution == 0 || summaries.sigma.is_nan() || summaries.sigma <= 0.
+    ) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "persistence summaries need a resolution of at least 1 and a positive sigma",
+        ));
+    }
+    if let Some(reservoir) = &config.reservoir {
+        if !reservoir.input.is_valid() || !reservoir.evaluation(Vec::new()).is_valid() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a reservoir needs input nodes, symbols at least a step long, a positive ridge penalty, and at least \
+                 four symbols after its washout and longest delay",
+            ));
+        }
+    }
+    if let Some(coarse_graining) = &config.coarse_graining {
+        if !coarse_graining.partition.is_valid() || graph_io::exporter(&coarse_graining.format).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "can't coarse-grain into {:?} in the format {}",
+                    coarse_graining.partition, coarse_graining.format
+                ),
+            ));
+        }
+    }
+    if matches!(&config.position_noise, Some(noise) if !noise.is_valid()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "position noise needs finite, nonnegative standard deviations",
+        ));
+    }
+    if config.betti_budget_ms == Some(0) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "betti numbers need a budget of at least a millisecond",
+        ));
+    }
+    if matches!(&config.activation_queue, Some(limit) if limit.capacity == 0) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "activation queues need room for at least one activation",
+        ));
+    }
+    if matches!(&config.stream, Some(stream) if stream.batch_size == 0) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "a stream needs batches of at least a step",
+        ));
+    }
+
+    experiment::validate(&config.events, num_nodes)?;
+    for population in &config.populations {
+        if let Some(node) = population.nodes.iter().find(|&&node| node >= num_nodes) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("node {} of population {} isn't in a grid of {} nodes", node, population.name, num_nodes),
+            ));
+        }
+    }
+
+    std::fs::create_dir_all(out_dir)?;
+    // Added to `db` all at once when the run is done, so that one which is
+    // interrupted leaves nothing there.
+    let mut db_metrics = Vec::new();
+
+    let mut rng = StdRng::seed_from_u64(config.seed.unwrap());
+    let mut simulation = <Simulation<StdRng> as Model>::init(config, StdRng::from_rng(&mut rng).unwrap())?;
+    simulation.timed = config.stats_interval.is_some();
+    simulation.track_myelination = config.myelination_trajectories || config.myelination_statistics;
+    let mut simplicial_complex = SimplicialComplex::new((0..num_nodes).collect(), None);
+    let mut vec: HashSet<(usize, usize)> = HashSet::new();
+    let compression = config.compression;
+    let betti_budget = config.betti_budget_ms.map(Duration::from_millis);
+    let mut recorder = BettiRecorder::new(compression.create(out_dir.join("betti_curve.csv"))?, NUM_DIMS)?
+        .with_budget(betti_budget);
+    let mut functional_recorder =
+        BettiRecorder::new(compression.create(out_dir.join("functional_betti_curve.csv"))?, NUM_DIMS)?
+            .with_budget(betti_budget);
+    // The controls draw from a generator of their own, so that turning them on
+    // doesn't change the run.
+    let mut controls = if config.shuffled_controls {
+        let recorder = ControlRecorder::new(compression.create(out_dir.join(SHUFFLED_CONTROL_FILE))?, NUM_DIMS)?;
+        Some((recorder, StdRng::seed_from_u64(!config.seed.unwrap())))
+    } else {
+        None
+    };
+    let mut event_recorder = if config.simplex_events {
+        simplicial_complex.record_events();
+        Some(EventRecorder::new(compression.create(out_dir.join(SIMPLEX_EVENTS_FILE))?)?)
+    } else {
+        None
+    };
+    let coarse_graining = match &config.coarse_graining {
+        Some(coarse_graining) => {
+            std::fs::create_dir_all(out_dir.join(COARSE_GRAINED_DIR))?;
+            Some((coarse_graining.partition, graph_io::exporter(&coarse_graining.format).unwrap()))
+        }
+        None => None,
+    };
+    let mut persistence_summaries = match &config.persistence_summaries {
+        Some(summaries) => Some((summaries, compression.create(out_dir.join(PERSISTENCE_SUMMARIES_FILE))?)),
+        None => None,
+    };
+    let mut spikes = SpikeRecorder::new(config.spike_window);
+    let mut metrics = compression.create(out_dir.join("metrics.jsonl"))?;
+    let mut recovery_recorder = None;
+    let mut minimal_wiring = MinimalWiring::new(&simulation);
+    let mut myelination_recorder = if config.myelination_trajectories {
+        Some(MyelinationRecorder::new(compression.create(out_dir.join(MYELINATION_FILE))?)?)
+    } else {
+        None
+    };
+    let (mut histogram_recorder, mut saturation) = if config.myelination_statistics {
+        let writer = compression.create(out_dir.join(MYELINATION_HISTOGRAM_FILE))?;
+        (Some(HistogramRecorder::new(writer)?), Some(SaturationTracker::new()))
+    } else {
+        (None, None)
+    };
+    let mut motif_recorder = match &config.temporal_motifs {
+        Some(motifs) => Some(MotifRecorder::new(
+            compression.create(out_dir.join(MOTIFS_FILE))?,
+            motifs.window,
+            motifs.delta,
+        )?),
+        None => None,
+    };
+    let mut trajectory = config
+        .trajectory
+        .as_ref()
+        .map(|trajectory| TrajectoryBuffer::new(trajectory.capacity, trajectory.stride));
+    let mut stream = match &config.stream {
+        Some(stream) => Some(StepStream::new(stream.target.open()?, stream.batch_size)?),
+        None => None,
+    };
+    let mut snapshots = Scheduler::new(config.snapshots, config.record_interval, 0);
+    let mut stats = match config.stats_interval {
+        Some(interval) => Some(StatsCollector::new(interval, compression.create(out_dir.join("stats.jsonl"))?)),
+        None => None,
+    };
+    let mut frames = match &config.frames {
+        Some(frames) => {
+            let mut writer = FrameWriter::new(out_dir.join(FRAMES_DIR), frames.format, &simulation)?;
+            writer.write(&simulation)?;
+            Some((Scheduler::new(frames.schedule, frames.interval, 0), writer))
+        }
+        None => None,
+    };
+    let mut history = if config.history {
+        let mut history = DynamicGraph::new(true);
+        for (id, attributes) in simulation.attributed_graph().nodes {
+            history.add_node(0, id, attributes);
+        }
+        Some(history)
+    } else {
+        None
+    };
+    // The built-in lesion is done as an event, before any of the script's at
+    // the same step.
+    let events = config
+        .lesion
+        .iter()
+        .map(|lesion| Event {
+            step: lesion.step,
+            action: Action::Lesion {
+                center: [0.; 3],
+                radius: lesion.radius,
+            },
+        })
+        .chain(config.events.iter().cloned())
+        .collect::<Vec<_>>();
+    let mut stimulus = Vec::new();
+    let mut i = 0;
+
+    while !matches!(config.num_steps, Some(num_steps) if i >= num_steps) {
+        let mut activations = config.input.select(&simulation, num_nodes, &mut rng);
+        activations.append(&mut stimulus);
+        let step_result = simulation.step(&activations);
+        spikes.record(simulation.timestep, &step_result.activated_nodes);
+
+        // The edges removed in a step last existed the step before.
+        if let Some(history) = &mut history {
+            record(history, &step_result, simulation.timestep - 1, simulation.timestep);
+        }
+        if let Some(myelination_recorder) = &mut myelination_recorder {
+            myelination_recorder.record(simulation.timestep, &step_result)?;
+        }
+        if let Some(motif_recorder) = &mut motif_recorder {
+            motif_recorder.record(simulation.timestep, &step_result)?;
+        }
+        if let Some(saturation) = &mut saturation {
+            saturation.record(simulation.timestep, &step_result, simulation.max_myelination);
+        }
+        if let Some(trajectory) = &mut trajectory {
+            trajectory.record(&simulation, &step_result);
+        }
+        if let Some(stream) = &mut stream {
+            stream.record(&simulation, &step_result)?;
+        }
+
+        let timings = step_result.timings;
+        let topology_start = Instant::now();
+        apply(step_result, &mut simplicial_complex, &mut vec);
+        if let (Some(stats), Some(timings)) = (&mut stats, timings) {
+            stats.record_step(timings, topology_start.elapsed());
+        }
+
+        i += 1;
+
+        for event in events.iter().filter(|event| event.step == i) {
+            match &event.action {
+                Action::Stimulate { nodes } => stimulus.extend(nodes),
+                Action::StimulateSelected { selector } => {
+                    stimulus.extend(selector.select(&simulation, num_nodes, &mut rng));
+                }
+                Action::Lesion { center, radius } => {
+                    // Recovery is measured from the first lesion.
+                    if recovery_recorder.is_none() {
+                        recovery_recorder = Some(RecoveryRecorder::new(
+                            compression.create(out_dir.join("lesion_recovery.csv"))?,
+                            &simulation,
+                            NUM_DIMS,
+                        )?);
+                    }
+
+                    let lesion_result = simulation.lesion_region(Point3::from(*center), *radius);
+                    if let Some(history) = &mut history {
+                        record(history, &lesion_result, simulation.timestep, simulation.timestep);
+                    }
+                    if let Some(motif_recorder) = &mut motif_recorder {
+                        motif_recorder.record(simulation.timestep, &lesion_result)?;
+                    }
+                    apply(lesion_result, &mut simplicial_complex, &mut vec);
+                }
+                Action::Set { parameter, value } => {
+                    let mut params = simulation.params();
+                    let from = parameter.get(&params);
+                    parameter.set(&mut params, *value);
+                    simulation.set_params(params);
+
+                    if let Some(manifest) = manifest {
+                        let change = ParameterChange {
+                            run: run_index,
+                            step: i,
+                            parameter: *parameter,
+                            from,
+                            to: *value,
+                        };
+                        manifest.lock().unwrap().log(&change)?;
+                    }
+                }
+            }
+        }
+
+        // The events of the step, and of any lesion after it, at the timestep
+        // the step ended at.
+        if let Some(event_recorder) = &mut event_recorder {
+            event_recorder.record(simulation.timestep, &mut simplicial_complex)?;
+        }
+
+        if snapshots.is_due(i, simulation.graph.edge_count()) {
+            let betti = recorder.record(i, &simplicial_complex)?;
+            recorder.flush()?;
+
+            if let Some((control_recorder, control_rng)) = &mut controls {
+                let mut edges = vec.iter().copied().collect::<Vec<_>>();
+                edges.sort_unstable();
+                let vertices = simulation.graph.node_indices().map(|node| node.index()).collect();
+                let control = null_models::degree_preserving_flag(vertices, &edges, None, control_rng);
+                control_recorder.record(i, &simplicial_complex, &control)?;
+                control_recorder.flush()?;
+            }
+
+            if let Some((partition, exporter)) = &coarse_graining {
+                let dir = out_dir.join(COARSE_GRAINED_DIR);
+                let coarse_graph = CoarseGraph::new(&simulation, *partition);
+                let path = dir.join(format!("step_{:06}.{}", i, exporter.extension()));
+                exporter.export_to_path(&coarse_graph.attributed_graph(), &path)?;
+                let mut writer = compression.create(dir.join(format!("step_{:06}_membership.csv", i)))?;
+                coarse::write_membership(&coarse_graph, &mut writer)?;
+                writer.finish()?;
+            }
+
+            if let Some((summaries, writer)) = &mut persistence_summaries {
+                let max_myelination = config.max_myelination;
+                write_persistence_summaries(summaries, i, &simulation, &simplicial_complex, max_myelination, writer)?;
+            }
+
+            let functional_complex = spikes.functional_complex(num_nodes, config.coincidence, NUM_DIMS);
+            functional_recorder.record(i, &functional_complex)?;
+            functional_recorder.flush()?;
+
+            let summary = net_metrics::summarize(&simulation.graph, &mut rng);
+            if db.is_some() {
+                db_metrics.push((Some(i), summary.clone()));
+            }
+            if !minimal_wiring.is_current(&simulation) {
+                minimal_wiring = MinimalWiring::new(&simulation);
+            }
+            let wiring = wiring::wiring_cost(&simulation, &minimal_wiring);
+            let metrics_line = TimestepMetrics {
+                timestep: i,
+                summary,
+                in_out_degree_correlation: net_metrics::in_out_degree_correlation(&simulation.graph),
+                wiring,
+                conduction: conduction::conduction(&simulation),
+                queues: queue::queue_stats(&simulation),
+                betti: betti_budget.map(|_| BettiTiming {
+                    timed_out: betti.timed_out(),
+                    exact_dims: betti.exact_dims,
+                }),
+            };
+            serde_json::to_writer(&mut metrics, &metrics_line)?;
+            writeln!(metrics)?;
+            metrics.flush()?;
+
+            if let Some(recovery_recorder) = &mut recovery_recorder {
+                recovery_recorder.record(&simulation, &simplicial_complex)?;
+                recovery_recorder.flush()?;
+            }
+            if let Some(histogram_recorder) = &mut histogram_recorder {
+                histogram_recorder.record(i, &simulation)?;
+                histogram_recorder.flush()?;
+            }
+
+            if betti.betti_numbers.iter().any(|&betti_number| betti_number < 0) {
+                eprintln!("{}", simplicial_complex.validate());
+            }
+        }
+
+        if let Some((scheduler, writer)) = &mut frames {
+            if scheduler.is_due(i, simulation.graph.edge_count()) {
+                writer.write(&simulation)?;
+            }
+        }
+
+        if let Some(stats) = &mut stats {
+            if let Some(report) = stats.sample(i, &simulation.graph, &simplicial_complex)? {
+                eprintln!("run {} {}", run_index, report);
+            }
+        }
+    }
+
+    recorder.into_inner().finish()?;
+    functional_recorder.into_inner().finish()?;
+    metrics.finish()?;
+    if let Some((_, writer)) = persistence_summaries {
+        writer.finish()?;
+    }
+    if let Some((control_recorder, _)) = controls {
+        control_recorder.into_inner().finish()?;
+    }
+    if let Some(stats) = stats {
+        stats.into_inner().finish()?;
+    }
+    if let Some(recovery_recorder) = recovery_recorder {
+        recovery_recorder.into_inner().finish()?;
+    }
+
+    if let Some(measure) = config.connectivity_matrix {
+        write_connectivity(measure, &spikes, &simulation, compression, out_dir)?;
+    }
+    if let Some(stimulation) = &config.stimulation {
+        let protocol = Protocol {
+            stimulus: stimulation.nodes.clone(),
+            readouts: stimulation.readouts.clone(),
+            num_trials: stimulation.num_trials,
+            trial_length: stimulation.trial_length,
+        };
+        let mut recorded = Ok(());
+        let trials = protocol.run(&mut simulation, |simulation, step_result| {
+            if let Some(history) = &mut history {
+                record(history, &step_result, simulation.timestep - 1, simulation.timestep);
+            }
+            if let (Some(myelination_recorder), Ok(())) = (&mut myelination_recorder, &recorded) {
+                recorded = myelination_recorder.record(simulation.timestep, &step_result);
+            }
+            if let (Some(motif_recorder), Ok(())) = (&mut motif_recorder, &recorded) {
+                recorded = motif_recorder.record(simulation.timestep, &step_result);
+            }
+            if let Some(saturation) = &mut saturation {
+                saturation.record(simulation.timestep, &step_result, simulation.max_myelination);
+            }
+            if let Some(trajectory) = &mut trajectory {
+                trajectory.record(simulation, &step_result);
+            }
+            if let (Some(stream), Ok(())) = (&mut stream, &recorded) {
+                recorded = stream.record(simulation, &step_result);
+            }
+            apply(step_result, &mut simplicial_complex, &mut vec);
+            if let (Some(event_recorder), Ok(())) = (&mut event_recorder, &recorded) {
+                recorded = event_recorder.record(simulation.timestep, &mut simplicial_complex);
+            }
+        });
+        recorded?;
+
+        let mut writer = compression.create(out_dir.join(STIMULUS_RESPONSE_FILE))?;
+        protocol::write_latencies(&trials, num_nodes, &mut writer)?;
+        writer.finish()?;
+        if !stimulation.readouts.is_empty() {
+            let mut writer = compression.create(out_dir.join(READOUT_LATENCY_FILE))?;
+            protocol::write_readout_latencies(&trials, &mut writer)?;
+            writer.finish()?;
+        }
+    }
+
+    if let Some(myelination_recorder) = myelination_recorder {
+        myelination_recorder.into_inner().finish()?;
+    }
+    if let Some(event_recorder) = event_recorder {
+        event_recorder.into_inner().finish()?;
+    }
+    if let Some(motif_recorder) = motif_recorder {
+        motif_recorder.finish()?.finish()?;
+    }
+    if let Some(histogram_recorder) = histogram_recorder {
+        histogram_recorder.into_inner().finish()?;
+    }
+    if let Some(saturation) = saturation {
+        let mut writer = compression.create(out_dir.join(SATURATION_FILE))?;
+        saturation.write(&mut writer)?;
+        writer.finish()?;
+    }
+    if let Some(stream) = stream {
+        stream.finish()?;
+    }
+    if let Some(trajectory) = trajectory {
+        let mut writer = compression.create(out_dir.join(TRAJECTORY_FILE))?;
+        trajectory.write(&mut writer)?;
+        writer.finish()?;
+    }
+    if let Some(history) = history {
+        DynamicGexf.export_to_path(&history, &out_dir.join(HISTORY_FILE))?;
+    }
+
+    let graphlets = net_metrics::sample_graphlet_counts(&simulation.graph, GRAPHLET_SAMPLE_NODES, &mut rng);
+    serde_json::to_writer_pretty(std::fs::File::create(out_dir.join(GRAPHLETS_FILE))?, &graphlets)?;
+    let mut writer = compression.create(out_dir.join(NODES_FILE))?;
+    write_nodes(&simulation, &mut writer)?;
+    writer.finish()?;
+    let mut writer = compression.create(out_dir.join(ROBUSTNESS_FILE))?;
+    write_robustness(&simulation, &mut rng, &mut writer)?;
+    writer.finish()?;
+
+    let graph = simulation.attributed_graph();
+    if let Some(db) = db {
+        db.insert_results(PROGRAM, config, run_index, config.seed, &graph, &db_metrics)?;
+    }
+
+    // Evaluated last, as its steps leave the structure of the network as it
+    // is but not when its nodes were last active.
+    if let Some(reservoir) = &config.reservoir {
+        let input = reservoir.input.select(&simulation, num_nodes, &mut rng);
+        let report = reservoir.evaluation(input).run(&mut simulation);
+        serde_json::to_writer_pretty(std::fs::File::create(out_dir.join(RESERVOIR_FILE))?, &report)?;
+    }
+
+    Ok(graph)
+}
+
+/// Returns the configuration a sweep writing to `out_dir` runs with: that of
+/// an interrupted sweep there, if this one has no seed and is otherwise the
+/// same, so that it resumes, or else this one seeded.
+pub fn sweep_config<P: AsRef<Path>>(config: &Config, out_dir: P) -> io::Result<Config> {
+    let previous = Ledger::parameters::<_, Config>(out_dir.as_ref().join(LEDGER_FILE))?;
+
+    match previous {
+        Some(previous) if config.seed.is_none() && Config { seed: None, ..previous.clone() } == *config => Ok(previous),
+        _ => Ok(config.seeded()),
+    }
+}
+
+/// Runs `num_runs` simulations in parallel, each writing to its own `run_<i>`
+/// directory in `out_dir` and to `db` if given, as in [`run`]. Finished runs
+/// are recorded in a ledger in `out_dir`, so that a sweep which is interrupted
+/// resumes with only the runs it's missing when it's run again.
+pub fn sweep<P: AsRef<Path>>(config: &Config, out_dir: P, db: Option<&ResultsDb>) -> io::Result<()> {
+    sweep_indexed(config, out_dir.as_ref(), db, None)
+}
+
+/// Runs a sweep as in [`sweep`], logging each parameter the script of each
+/// run changes in `manifest`.
+pub fn sweep_logged<P: AsRef<Path>>(
+    config: &Config,
+    out_dir: P,
+    db: Option<&ResultsDb>,
+    manifest: &Mutex<Manifest>,
+) -> io::Result<()> {
+    sweep_indexed(config, out_dir.as_ref(), db, Some(manifest))
+}
+
+fn sweep_indexed(
+    config: &Config,
+    out_dir: &Path,
+    db: Option<&ResultsDb>,
+    manifest: Option<&Mutex<Manifest>>,
+) -> io::Result<()> {
+    if config.num_steps.is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "a sweep needs a number of steps to stop at",
+        ));
+    }
+    if config.stream.is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the runs of a sweep can't share a stream",
+        ));
+    }
+
+    let config = sweep_config(config, out_dir)?;
+    let seed = config.seed.unwrap();
+
+    std::fs::create_dir_all(out_dir)?;
+    let ledger = Ledger::open(out_dir.join(LEDGER_FILE), &config)?;
+
+    let remaining = (0..config.num_runs)
+        .filter(|&run_index| !ledger.is_complete(run_index as u64))
+        .collect::<Vec<_>>();
+    let progress = Progress::new("connectome sweep", Some(remaining.len() as u64));
+
+    remaining.into_par_iter().try_for_each(|run_index| -> io::Result<()> {
+        let run_dir = out_dir.join(format!("run_{}", run_index));
+        let config = Config {
+            seed: Some(seed.wrapping_add(run_index as u64)),
+            ..config.clone()
+        };
+
+        run_indexed(&config, &run_dir, db, run_index as u64, manifest)?;
+        ledger.complete(run_index as u64, &[run_dir])?;
+        progress.inc(1);
+        Ok(())
+    })?;
+
+    progress.finish();
+    Ok(())
+}
+
+/// Writes the functional connectivity matrix of the spikes in the window,
+/// measured by `measure`, and the adjacency matrix of the graph, to CSV files
+/// in `out_dir`, with how they correlate in a JSON file alongside them. A
+/// directed measure is compared with the directed adjacency, from the rows to
+/// the columns.
+pub fn write_connectivity<R: Rng>(
+    measure: ConnectivityMeasure,
+    spikes: &SpikeRecorder,
+    simulation: &Simulation<R>,
+    compression: Compression,
+    out_dir: &Path,
+) -> io::Result<()> {
+    let num_nodes = simulation.graph.node_indices().map(|id| id.index() + 1).max().unwrap_or(0);
+    let functional = match measure {
+        ConnectivityMeasure::Correlation => spikes.correlation_matrix(num_nodes),
+        ConnectivityMeasure::Coincidence { lag } => spikes.coincidence_matrix(num_nodes, lag),
+        ConnectivityMeasure::TransferEntropy { history, lag } => spikes.transfer_entropy_matrix(num_nodes, history, lag),
+    };
+    let directed = matches!(measure, ConnectivityMeasure::TransferEntropy { .. });
+    let structural = functional::adjacency_matrix(&simulation.graph, num_nodes, directed);
+
+    let mut writer = compression.create(out_dir.join(FUNCTIONAL_CONNECTIVITY_FILE))?;
+    functional::write_matrix(&functional, &mut writer)?;
+    writer.finish()?;
+    let mut writer = compression.create(out_dir.join(STRUCTURAL_ADJACENCY_FILE))?;
+    functional::write_matrix(&structural, &mut writer)?;
+    writer.finish()?;
+
+    let report = ConnectivityReport {
+        timestep: simulation.timestep,
+        measure,
+        num_bins: spikes.num_bins(),
+        structure_function_correlation: functional::matrix_correlation(&functional, &structural),
+    };
+    let file = std::fs::File::create(out_dir.join(CONNECTIVITY_REPORT_FILE))?;
+    serde_json::to_writer_pretty(file, &report)?;
+    Ok(())
+}
+
+/// Writes the summaries of the persistence of the complex in each dimension,
+/// as a line of JSON each, with the grids spanning the delays of edges with up
+/// to `max_myelination`.
+fn write_persistence_summaries<R: Rng, W: Write>(
+    summaries: &PersistenceSummaries,
+    timestep: usize,
+    simulation: &Simulation<R>,
+    complex: &SimplicialComplex,
+    max_myelination: usize,
+    mut writer: W,
+) -> io::Result<()> {
+    let diagram = persistence::weight_persistence(complex, WeightOrder::Ascending, |a, b| {
+        let myelination = simulation.myelination(a, b).unwrap_or(0);
+        conduction::delay(myelination, simulation.max_myelination) as f64
+    });
+    let delays = Grid {
+        min: conduction::delay(max_myelination, max_myelination) as f64,
+        max: conduction::delay(0, max_myelination) as f64,
+        resolution: summaries.resolution,
+    };
+    let persistence = Grid {
+        min: 0.,
+        max: delays.max - delays.min,
+        ..delays
+    };
+
+    for dim in 0..NUM_DIMS {
+        let summary = PersistenceSummary {
+            timestep,
+            dim,
+            landscapes: summaries::landscapes(&diagram, dim, summaries.num_landscapes, &delays),
+            image: summaries::image(&diagram, dim, &delays, &persistence, summaries.sigma),
+        };
+        serde_json::to_writer(&mut writer, &summary)?;
+        writeln!(writer)?;
+    }
+    writer.flush()
+}
+
+/// Writes the nodes of the graph as CSV, with the id, in-degree, out-degree
+/// and core number of each.
+pub fn write_nodes<R: Rng, W: Write>(simulation: &Simulation<R>, mut writer: W) -> io::Result<()> {
+    let graph = &simulation.graph;
+    writeln!(writer, "id,in_degree,out_degree,core")?;
+
+    for (id, core) in graph.node_indices().zip(net_metrics::core_numbers(graph)) {
+        writeln!(
+            writer,
+            "{},{},{},{}",
+            id.index(),
+            graph.neighbors_directed(id, EdgeDirection::Incoming).count(),
+            graph.neighbors_directed(id, EdgeDirection::Outgoing).count(),
+            core
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Writes how the giant component of the graph shrinks as its nodes are
+/// removed, by degree and in a random order drawn from `rng`, as CSV, with the
+/// fraction removed and the size of the giant component under each.
+pub fn write_robustness<R: Rng, S: Rng, W: Write>(
+    simulation: &Simulation<R>,
+    rng: &mut S,
+    mut writer: W,
+) -> io::Result<()> {
+    let targeted = net_metrics::robustness_curve(&simulation.graph, Removal::Degree, ROBUSTNESS_POINTS, rng);
+    let random = net_metrics::robustness_curve(&simulation.graph, Removal::Random, ROBUSTNESS_POINTS, rng);
+    writeln!(writer, "fraction_removed,targeted,random")?;
+
+    for (i, fraction) in targeted.fractions_removed.iter().enumerate() {
+        writeln!(writer, "{},{},{}", fraction, targeted.giant_component[i], random.giant_component[i])?;
+    }
+
+    Ok(())
+}
+
+/// Records the changes made to the graph in its history: the edges and nodes
+/// removed as last existing at `last`, and the edges added as existing from
+/// `first` on.
+fn record(history: &mut DynamicGraph, step_result: &StepResult, last: usize, first: usize) {
+    for &(in_node, out_node) in &step_result.removed_edges {
+        history.remove_edge(last, in_node, out_node);
+    }
+    for &node in &step_result.removed_nodes {
+        history.remove_node(last, node);
+    }
+    for &(in_node, out_node) in &step_result.added_edges {
+        history.add_edge(first, in_node, out_node);
+    }
+}
+
+/// Brings the simplicial complex, and the set of edges it's built from, in
+/// line with the changes made to the graph.
+fn apply(step_result: StepResult, simplicial_complex: &mut SimplicialComplex, vec: &mut HashSet<(usize, usize)>) {
+    let mut removed_edges = Vec::new();
+    for (in_node, out_node) in step_result.removed_edges {
+        assert!(vec.remove(&(in_node, out_node)));
+        removed_edges.push(vec![in_node, out_node]);
+    }
+    if let Err(err) = simplicial_complex.remove_batch(&removed_edges) {
+        eprintln!("failed to remove edges {:?}: {}", removed_edges, err);
+    }
+
+    for node in step_result.removed_nodes {
+        if let Err(err) = simplicial_complex.remove_vertex(node) {
+            eprintln!("failed to remove node {}: {}", node, err);
+        }
+    }
+
+    let mut added_edges = Vec::new();
+    for (in_node, out_node) in step_result.added_edges {
+        vec.insert((in_node, out_node));
+        added_edges.push(vec![in_node, out_node]);
+    }
+    if let Err(err) = simplicial_complex.add_batch(&added_edges) {
+        eprintln!("failed to add edges {:?}: {}", added_edges, err);
+    }
+}
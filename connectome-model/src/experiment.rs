@@ -0,0 +1,138 @@
+//! Experiment scripts: lists of timed events for the driver to perform as a
+//! run goes, such as stimulating a set of nodes, lesioning a region or
+//! changing a parameter, so that a protocol is a file rather than a new
+//! binary. A script is a JSON list of events, each with the step it happens
+//! after and its `action`, e.g.
+//!
+//! ```json
+//! [
+//!     { "step": 500, "action": "stimulate", "nodes": [0, 1, 2] },
+//!     { "step": 600, "action": "stimulate-selected", "selector": { "kind": "hubs", "count": 3 } },
+//!     { "step": 800, "action": "lesion", "center": [0, 0, 0], "radius": 1.5 },
+//!     { "step": 800, "action": "set", "parameter": "decay-rate", "value": 0.02 }
+//! ]
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sim::Params;
+use crate::stimulus::Selector;
+
+/// Something done to a run after one of its steps.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum Action {
+    /// Activates the nodes on the next step, along with its usual input.
+    Stimulate { nodes: Vec<usize> },
+    /// Activates the nodes `selector` chooses, from the graph as it is, on
+    /// the next step, along with its usual input.
+    StimulateSelected { selector: Selector },
+    /// Lesions every node within `radius` of `center`, the center of the grid
+    /// by default.
+    Lesion {
+        #[serde(default)]
+        center: [f64; 3],
+        radius: f64,
+    },
+    /// Changes a parameter of the simulation from then on.
+    Set { parameter: Parameter, value: f64 },
+}
+
+/// The parameters of a simulation a script can change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Parameter {
+    ConnectivityRate,
+    MyelinationRate,
+    DecayRate,
+    MaxMyelination,
+    DistanceExp,
+    RefractoryPeriod,
+    TransmissionReliability,
+}
+
+/// An action, done once `step` steps have been run, from 1 on. Events at the
+/// same step are done in the order they're listed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub step: usize,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+/// Loads a script from a JSON file.
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Vec<Event>> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(serde_json::from_reader(reader)?)
+}
+
+/// Checks that every event of a script can be done to a grid of `num_nodes`
+/// nodes.
+pub fn validate(events: &[Event], num_nodes: usize) -> io::Result<()> {
+    for event in events {
+        let valid = event.step > 0
+            && match &event.action {
+                Action::Stimulate { nodes } => nodes.iter().all(|&node| node < num_nodes),
+                Action::StimulateSelected { selector } => selector.is_valid(),
+                Action::Lesion { center, radius } => center.iter().all(|x| x.is_finite()) && *radius >= 0.,
+                Action::Set { parameter, value } => parameter.accepts(*value),
+            };
+
+        if !valid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid event {:?} in a grid of {} nodes", event, num_nodes),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+impl Parameter {
+    /// Whether `value` is one the parameter can take: a probability for the
+    /// rates of myelination and decay and the transmission reliability, a
+    /// whole number for the others but the connectivity rate, and nothing
+    /// negative for any but the distance exponent.
+    pub fn accepts(self, value: f64) -> bool {
+        match self {
+            Parameter::ConnectivityRate => value >= 0. && value.is_finite(),
+            Parameter::MyelinationRate | Parameter::DecayRate | Parameter::TransmissionReliability => {
+                (0. ..=1.).contains(&value)
+            }
+            Parameter::MaxMyelination | Parameter::RefractoryPeriod => value >= 0. && value.fract() == 0.,
+            Parameter::DistanceExp => value.fract() == 0. && value.abs() <= i32::MAX as f64,
+        }
+    }
+
+    /// Returns the value of the parameter among `params`.
+    pub fn get(self, params: &Params) -> f64 {
+        match self {
+            Parameter::ConnectivityRate => params.connectivity_rate,
+            Parameter::MyelinationRate => params.myelination_rate,
+            Parameter::DecayRate => params.decay_rate,
+            Parameter::MaxMyelination => params.max_myelination as f64,
+            Parameter::DistanceExp => params.distance_exp as f64,
+            Parameter::RefractoryPeriod => params.refractory_period as f64,
+            Parameter::TransmissionReliability => params.transmission.reliability,
+        }
+    }
+
+    /// Sets the parameter among `params` to `value`, which it has to
+    /// [accept](Self::accepts).
+    pub fn set(self, params: &mut Params, value: f64) {
+        match self {
+            Parameter::ConnectivityRate => params.connectivity_rate = value,
+            Parameter::MyelinationRate => params.myelination_rate = value,
+            Parameter::DecayRate => params.decay_rate = value,
+            Parameter::MaxMyelination => params.max_myelination = value as usize,
+            Parameter::DistanceExp => params.distance_exp = value as i32,
+            Parameter::RefractoryPeriod => params.refractory_period = value as usize,
+            Parameter::TransmissionReliability => params.transmission.reliability = value,
+        }
+    }
+}
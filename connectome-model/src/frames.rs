@@ -0,0 +1,160 @@
+//! Frames of the growing graph, written every so many steps so that its
+//! growth can be assembled into an animation, e.g. with
+//! `ffmpeg -framerate 10 -i frames/frame_%06d.png growth.mp4`.
+//!
+//! Each node is drawn where its position in the grid projects to from a fixed
+//! oblique viewpoint. Unless they drift, nodes never move, so the layout is
+//! computed once and pinned in every frame, and PNG frames are rendered by
+//! Graphviz's `neato` without laying anything out.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use graph_io::{dot::Dot, GraphExporter};
+use nalgebra::{Point3, Rotation3, Vector3};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::sim::Simulation;
+
+/// The name of the directory of frames in a run's output directory.
+pub const FRAMES_DIR: &str = "frames";
+
+/// How far apart, in points, nodes one unit apart in the grid are drawn.
+const POINTS_PER_UNIT: f64 = 72.;
+
+/// The side of a PNG frame, in inches at [`PNG_DPI`], which every frame is
+/// scaled to so that they can be assembled into a video.
+const PNG_SIZE: u32 = 8;
+const PNG_DPI: u32 = 96;
+
+/// The format frames are written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameFormat {
+    /// Graphviz's DOT, with each node's position pinned; render with
+    /// `neato -n2`.
+    #[default]
+    Dot,
+    /// PNG, rendered from the DOT by `neato`.
+    Png,
+}
+
+impl FrameFormat {
+    /// The names the formats are parsed from.
+    pub const NAMES: [&'static str; 2] = ["dot", "png"];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            FrameFormat::Dot => "dot",
+            FrameFormat::Png => "png",
+        }
+    }
+}
+
+/// Writes the frames of a run to numbered files in a directory, starting from
+/// `frame_000000`.
+pub struct FrameWriter {
+    dir: PathBuf,
+    format: FrameFormat,
+    /// The position each node is drawn at, by its index, as a DOT `pos`.
+    positions: Vec<Option<String>>,
+    /// How many times the nodes had moved when they were laid out.
+    moves: usize,
+    num_frames: usize,
+}
+
+impl FrameWriter {
+    /// Lays out the nodes of `simulation`, which are all the nodes any frame
+    /// will have, and creates `dir` to write frames to.
+    pub fn new<P: AsRef<Path>, R: Rng>(dir: P, format: FrameFormat, simulation: &Simulation<R>) -> io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            format,
+            positions: layout(simulation),
+            moves: simulation.moves,
+            num_frames: 0,
+        })
+    }
+
+    /// Writes the graph as it is now as the next frame, with the core number of
+    /// each node, and returns its path.
+    pub fn write<R: Rng>(&mut self, simulation: &Simulation<R>) -> io::Result<PathBuf> {
+        if self.moves != simulation.moves {
+            self.positions = layout(simulation);
+            self.moves = simulation.moves;
+        }
+
+        let mut graph = simulation.attributed_graph();
+        for ((id, attributes), core) in graph.nodes.iter_mut().zip(net_metrics::core_numbers(&simulation.graph)) {
+            if let Some(Some(pos)) = self.positions.get(*id) {
+                attributes.insert("pos".to_string(), pos.as_str().into());
+            }
+            attributes.insert("core".to_string(), core.into());
+        }
+
+        let path = self
+            .dir
+            .join(format!("frame_{:06}.{}", self.num_frames, self.format.extension()));
+        match self.format {
+            FrameFormat::Dot => Dot.export_to_path(&graph, &path)?,
+            FrameFormat::Png => {
+                let mut dot = Vec::new();
+                Dot.export(&graph, &mut dot)?;
+                render_png(&dot, &path)?;
+            }
+        }
+
+        self.num_frames += 1;
+        Ok(path)
+    }
+
+    /// How many frames have been written.
+    pub fn num_frames(&self) -> usize {
+        self.num_frames
+    }
+}
+
+/// Returns the position each node of `simulation` is drawn at, by its index.
+fn layout<R: Rng>(simulation: &Simulation<R>) -> Vec<Option<String>> {
+    let mut positions = vec![None; simulation.graph.node_indices().map(|id| id.index() + 1).max().unwrap_or(0)];
+    for id in simulation.graph.node_indices() {
+        let (x, y) = project(&simulation.graph[id].position);
+        positions[id.index()] = Some(format!("{:.1},{:.1}!", x * POINTS_PER_UNIT, y * POINTS_PER_UNIT));
+    }
+    positions
+}
+
+/// Projects a position onto the plane of the frames, looking at the grid from
+/// a little above and to the side, so that no two nodes of the grid overlap.
+pub fn project(position: &Point3<f64>) -> (f64, f64) {
+    let rotation = Rotation3::from_axis_angle(&Vector3::x_axis(), 20f64.to_radians())
+        * Rotation3::from_axis_angle(&Vector3::y_axis(), 30f64.to_radians());
+    let projected = rotation * position;
+
+    (projected.x, projected.y)
+}
+
+/// Renders a DOT graph with its positions pinned to a PNG at `path`, through
+/// `neato`.
+fn render_png(dot: &[u8], path: &Path) -> io::Result<()> {
+    let size = format!("-Gsize={},{}!", PNG_SIZE, PNG_SIZE);
+    let dpi = format!("-Gdpi={}", PNG_DPI);
+    let mut child = Command::new("neato")
+        .args(["-n2", "-Tpng", &size, "-Gratio=fill", &dpi, "-Nshape=point", "-Earrowsize=0.3", "-o"])
+        .arg(path)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|err| io::Error::new(err.kind(), format!("failed to run `neato`: {}", err)))?;
+
+    child.stdin.take().unwrap().write_all(dot)?;
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("rendering a frame failed: {}", status)))
+    }
+}
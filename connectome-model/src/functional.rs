@@ -0,0 +1,338 @@
+//! The functional connectivity of a simulation: which nodes fire together,
+//! regardless of whether they're connected, as a complex or as a matrix over
+//! pairs of nodes to compare with the structural adjacency.
+
+use std::collections::{HashSet, VecDeque};
+use std::io::{self, Write};
+
+use nalgebra::DMatrix;
+use petgraph::graph::{NodeIndex, UnGraph};
+use petgraph::stable_graph::StableDiGraph;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use topology::simplex::SimplicialComplex;
+
+/// Records which nodes fired at each timestep, keeping only the last `window`
+/// timesteps.
+pub struct SpikeRecorder {
+    pub window: usize,
+    /// The nodes which fired at each recorded timestep, oldest first.
+    spikes: VecDeque<(usize, Vec<usize>)>,
+    /// The last timestep recorded, which the window ends at.
+    last_timestep: usize,
+}
+
+impl SpikeRecorder {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            spikes: VecDeque::new(),
+            last_timestep: 0,
+        }
+    }
+
+    /// Records the nodes which fired at a timestep, and forgets the spikes
+    /// which have fallen out of the window. Timesteps must be recorded in
+    /// order.
+    pub fn record(&mut self, timestep: usize, activated_nodes: &[usize]) {
+        self.last_timestep = timestep;
+        if !activated_nodes.is_empty() {
+            self.spikes.push_back((timestep, activated_nodes.to_vec()));
+        }
+
+        while self
+            .spikes
+            .front()
+            .is_some_and(|&(spiked_at, _)| spiked_at + self.window <= timestep)
+        {
+            self.spikes.pop_front();
+        }
+    }
+
+    /// Returns the number of spikes within the window.
+    pub fn num_spikes(&self) -> usize {
+        self.spikes.iter().map(|(_, nodes)| nodes.len()).sum()
+    }
+
+    /// Returns the pairs of distinct nodes which fired within `coincidence`
+    /// timesteps of each other during the window, as `(a, b)` with `a < b`.
+    pub fn co_active_pairs(&self, coincidence: usize) -> HashSet<(usize, usize)> {
+        let mut pairs = HashSet::new();
+
+        for (i, (timestep, nodes)) in self.spikes.iter().enumerate() {
+            let coincident = self
+                .spikes
+                .iter()
+                .skip(i)
+                .take_while(|&&(other_timestep, _)| other_timestep - timestep <= coincidence);
+
+            for (_, other_nodes) in coincident {
+                for &a in nodes {
+                    for &b in other_nodes {
+                        if a != b {
+                            pairs.insert((a.min(b), a.max(b)));
+                        }
+                    }
+                }
+            }
+        }
+
+        pairs
+    }
+
+    /// Returns the clique complex of the functional graph on `num_nodes`
+    /// nodes, which joins each pair of nodes which were co-active during the
+    /// window, up to dimension `max_dim`.
+    pub fn functional_complex(
+        &self,
+        num_nodes: usize,
+        coincidence: usize,
+        max_dim: usize,
+    ) -> SimplicialComplex {
+        let mut graph = UnGraph::<(), ()>::with_capacity(num_nodes, 0);
+
+        for _ in 0..num_nodes {
+            graph.add_node(());
+        }
+
+        for (a, b) in self.co_active_pairs(coincidence) {
+            graph.add_edge(NodeIndex::new(a), NodeIndex::new(b), ());
+        }
+
+        SimplicialComplex::clique_complex(&graph, max_dim)
+    }
+
+    /// The number of timesteps the window covers so far: `window`, or fewer
+    /// before that many have been recorded.
+    pub fn num_bins(&self) -> usize {
+        self.window.min(self.last_timestep)
+    }
+
+    /// Returns the Pearson correlation between the spike trains of each pair
+    /// of the `num_nodes` nodes over the window, binned by timestep. Pairs
+    /// with a node which never or always fired are uncorrelated, as is each
+    /// node with itself.
+    pub fn correlation_matrix(&self, num_nodes: usize) -> DMatrix<f64> {
+        let num_bins = self.num_bins() as f64;
+        let mut counts = vec![0.; num_nodes];
+        let mut joint = DMatrix::<f64>::zeros(num_nodes, num_nodes);
+
+        for (_, nodes) in &self.spikes {
+            for &a in nodes {
+                counts[a] += 1.;
+                for &b in nodes {
+                    joint[(a, b)] += 1.;
+                }
+            }
+        }
+
+        DMatrix::from_fn(num_nodes, num_nodes, |a, b| {
+            let variance = counts[a] * (num_bins - counts[a]) * counts[b] * (num_bins - counts[b]);
+            if a == b || variance <= 0. {
+                return 0.;
+            }
+
+            (num_bins * joint[(a, b)] - counts[a] * counts[b]) / variance.sqrt()
+        })
+    }
+
+    /// Returns how many times each pair of distinct nodes among `num_nodes`
+    /// fired within `lag` timesteps of each other during the window, counting
+    /// each pair of spikes once.
+    pub fn coincidence_matrix(&self, num_nodes: usize, lag: usize) -> DMatrix<f64> {
+        let mut counts = DMatrix::<f64>::zeros(num_nodes, num_nodes);
+
+        for (i, (timestep, nodes)) in self.spikes.iter().enumerate() {
+            let coincident = self
+                .spikes
+                .iter()
+                .skip(i)
+                .take_while(|&&(other_timestep, _)| other_timestep - timestep <= lag);
+
+            for (other_timestep, other_nodes) in coincident {
+                for &a in nodes {
+                    for &b in other_nodes {
+                        // Spikes at the same timestep meet each other twice.
+                        if a != b && (other_timestep != timestep || a < b) {
+                            counts[(a, b)] += 1.;
+                            counts[(b, a)] += 1.;
+                        }
+                    }
+                }
+            }
+        }
+
+        counts
+    }
+}
+
+/// The longest history [`transfer_entropy`] conditions on, which keeps its
+/// table of states small.
+pub const MAX_HISTORY: usize = 8;
+
+impl SpikeRecorder {
+    /// Returns the spike train of each of the `num_nodes` nodes over the
+    /// window: whether it fired at each timestep, oldest first.
+    pub fn spike_trains(&self, num_nodes: usize) -> Vec<Vec<bool>> {
+        let num_bins = self.num_bins();
+        let first = self.last_timestep + 1 - num_bins;
+        let mut trains = vec![vec![false; num_bins]; num_nodes];
+
+        for (timestep, nodes) in self.spikes.iter().filter(|&&(timestep, _)| timestep >= first) {
+            for &node in nodes {
+                trains[node][timestep - first] = true;
+            }
+        }
+
+        trains
+    }
+
+    /// Returns the transfer entropy from each of the `num_nodes` nodes, by
+    /// row, to each other one, by column, over the window, as in
+    /// [`transfer_entropy`].
+    pub fn transfer_entropy_matrix(&self, num_nodes: usize, history: usize, lag: usize) -> DMatrix<f64> {
+        let trains = self.spike_trains(num_nodes);
+
+        DMatrix::from_fn(num_nodes, num_nodes, |source, target| {
+            if source == target {
+                0.
+            } else {
+                transfer_entropy(&trains[source], &trains[target], history, lag)
+            }
+        })
+    }
+}
+
+/// Returns the transfer entropy, in bits, from the binary `source` train to
+/// the `target` one: how much knowing the last `history` values of the source
+/// up to `lag` steps back tells of the target's next value, beyond what the
+/// target's own last `history` values do. A lag of 1 ends the source's history
+/// alongside the target's.
+///
+/// # Panics
+///
+/// If `history` isn't between 1 and [`MAX_HISTORY`], or `lag` is 0.
+pub fn transfer_entropy(source: &[bool], target: &[bool], history: usize, lag: usize) -> f64 {
+    assert!((1..=MAX_HISTORY).contains(&history), "history has to be between 1 and {}", MAX_HISTORY);
+    assert!(lag >= 1, "lag has to be at least 1");
+
+    // The target's next value follows timestep `t`, and the two histories end
+    // at `t` and `t + 1 - lag`.
+    let first = (history - 1).max(history + lag - 2);
+    let len = target.len().min(source.len());
+    if len <= first + 1 {
+        return 0.;
+    }
+
+    let bits = |train: &[bool], end: usize| {
+        train[end + 1 - history..=end]
+            .iter()
+            .fold(0, |bits, &spike| (bits << 1) | spike as usize)
+    };
+
+    // Counts of (next, target history, source history), indexed as bits.
+    let num_histories = 1 << history;
+    let index = |next: usize, target_history: usize, source_history: usize| {
+        (next * num_histories + target_history) * num_histories + source_history
+    };
+    let mut counts = vec![0usize; 2 * num_histories * num_histories];
+    for t in first..len - 1 {
+        counts[index(target[t + 1] as usize, bits(target, t), bits(source, t + 1 - lag))] += 1;
+    }
+
+    // The marginal counts, without the next value, the source history, or
+    // either.
+    let mut histories = vec![0usize; num_histories * num_histories];
+    let mut target_nexts = vec![0usize; 2 * num_histories];
+    let mut target_histories = vec![0usize; num_histories];
+    for next in 0..2 {
+        for target_history in 0..num_histories {
+            for source_history in 0..num_histories {
+                let count = counts[index(next, target_history, source_history)];
+                histories[target_history * num_histories + source_history] += count;
+                target_nexts[next * num_histories + target_history] += count;
+                target_histories[target_history] += count;
+            }
+        }
+    }
+
+    let num_samples = (len - 1 - first) as f64;
+    let mut entropy = 0.;
+    for next in 0..2 {
+        for target_history in 0..num_histories {
+            for source_history in 0..num_histories {
+                let joint = counts[index(next, target_history, source_history)] as f64;
+                if joint == 0. {
+                    continue;
+                }
+
+                let ratio = joint * target_histories[target_history] as f64
+                    / (histories[target_history * num_histories + source_history] as f64
+                        * target_nexts[next * num_histories + target_history] as f64);
+                entropy += joint / num_samples * ratio.log2();
+            }
+        }
+    }
+
+    entropy.max(0.)
+}
+
+/// Returns the adjacency matrix of a graph on `num_nodes` nodes, with a 1 for
+/// each edge: from its source's row to its target's column if `directed`, or
+/// both ways if not.
+pub fn adjacency_matrix<N, E>(graph: &StableDiGraph<N, E>, num_nodes: usize, directed: bool) -> DMatrix<f64> {
+    let mut adjacency = DMatrix::<f64>::zeros(num_nodes, num_nodes);
+
+    for edge in graph.edge_references() {
+        let (source, target) = (edge.source().index(), edge.target().index());
+        adjacency[(source, target)] = 1.;
+        if !directed {
+            adjacency[(target, source)] = 1.;
+        }
+    }
+
+    adjacency
+}
+
+/// Returns the Pearson correlation between the entries of two matrices off
+/// their diagonals, such as a functional connectivity matrix and a structural
+/// adjacency matrix, or `None` if either is constant there.
+pub fn matrix_correlation(a: &DMatrix<f64>, b: &DMatrix<f64>) -> Option<f64> {
+    let pairs = (0..a.nrows())
+        .flat_map(|row| (0..a.ncols()).map(move |col| (row, col)))
+        .filter(|(row, col)| row != col)
+        .map(|index| (a[index], b[index]))
+        .collect::<Vec<_>>();
+
+    let n = pairs.len() as f64;
+    let mean_a = pairs.iter().map(|(a, _)| a).sum::<f64>() / n;
+    let mean_b = pairs.iter().map(|(_, b)| b).sum::<f64>() / n;
+    let covariance = pairs.iter().map(|(a, b)| (a - mean_a) * (b - mean_b)).sum::<f64>();
+    let variance_a = pairs.iter().map(|(a, _)| (a - mean_a).powi(2)).sum::<f64>();
+    let variance_b = pairs.iter().map(|(_, b)| (b - mean_b).powi(2)).sum::<f64>();
+
+    if variance_a <= 0. || variance_b <= 0. {
+        return None;
+    }
+
+    Some(covariance / (variance_a * variance_b).sqrt())
+}
+
+/// Writes a square matrix over nodes as CSV: a header of the node indices,
+/// then a row for each node, led by its index.
+pub fn write_matrix<W: Write>(matrix: &DMatrix<f64>, mut writer: W) -> io::Result<()> {
+    write!(writer, "node")?;
+    for col in 0..matrix.ncols() {
+        write!(writer, ",{}", col)?;
+    }
+    writeln!(writer)?;
+
+    for row in 0..matrix.nrows() {
+        write!(writer, "{}", row)?;
+        for col in 0..matrix.ncols() {
+            write!(writer, ",{}", matrix[(row, col)])?;
+        }
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
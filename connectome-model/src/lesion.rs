@@ -0,0 +1,91 @@
+//! Recording how a simulation recovers after a lesion.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use rand::Rng;
+use topology::simplex::SimplicialComplex;
+
+use crate::sim::Simulation;
+
+/// Writes recovery metrics after a lesion as CSV, one row per call to
+/// [`record`](Self::record): the number of surviving nodes and edges, the
+/// edge count as a fraction of the one just before the lesion, the mean
+/// myelination, and the betti numbers of the structural complex.
+pub struct RecoveryRecorder<W: Write> {
+    writer: W,
+    num_dims: usize,
+    lesioned_at: usize,
+    baseline_edges: usize,
+}
+
+impl RecoveryRecorder<BufWriter<File>> {
+    /// Creates a recorder writing to a new file at `path`.
+    pub fn create<P: AsRef<Path>, R: Rng>(
+        path: P,
+        simulation: &Simulation<R>,
+        num_dims: usize,
+    ) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), simulation, num_dims)
+    }
+}
+
+impl<W: Write> RecoveryRecorder<W> {
+    /// Creates a recorder writing to `writer`, measured against `simulation`
+    /// as it is just before its lesion, and writes the header row.
+    pub fn new<R: Rng>(mut writer: W, simulation: &Simulation<R>, num_dims: usize) -> io::Result<Self> {
+        let header = ["steps_since_lesion", "nodes", "edges", "edge_recovery", "mean_myelination"]
+            .iter()
+            .map(|column| column.to_string())
+            .chain((0..num_dims).map(|dim| format!("betti_{}", dim)))
+            .collect::<Vec<_>>();
+        writeln!(writer, "{}", header.join(","))?;
+
+        Ok(RecoveryRecorder {
+            writer,
+            num_dims,
+            lesioned_at: simulation.timestep,
+            baseline_edges: simulation.graph.edge_count(),
+        })
+    }
+
+    /// Records the state of the simulation and its structural complex.
+    pub fn record<R: Rng>(&mut self, simulation: &Simulation<R>, complex: &SimplicialComplex) -> io::Result<()> {
+        let graph = &simulation.graph;
+        let edges = graph.edge_count();
+        let edge_recovery = if self.baseline_edges > 0 {
+            edges as f64 / self.baseline_edges as f64
+        } else {
+            1.0
+        };
+        let mean_myelination = if edges > 0 {
+            graph.edge_indices().map(|id| graph[id].myelination).sum::<usize>() as f64 / edges as f64
+        } else {
+            0.0
+        };
+        let betti_numbers = complex.betti_numbers_full();
+
+        let row = [
+            (simulation.timestep - self.lesioned_at).to_string(),
+            graph.node_count().to_string(),
+            edges.to_string(),
+            edge_recovery.to_string(),
+            mean_myelination.to_string(),
+        ]
+        .iter()
+        .cloned()
+        .chain((0..self.num_dims).map(|dim| betti_numbers.get(dim).unwrap_or(&0).to_string()))
+        .collect::<Vec<_>>();
+        writeln!(self.writer, "{}", row.join(","))
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
@@ -1,2 +1,54 @@
+//! A model of a connectome growing on a grid of nodes: nodes fire as they're
+//! driven and as activity reaches them along edges, edges attach between
+//! nodes which fire close together in time and space, and myelinate with use
+//! to conduct faster, or decay without it. The simplicial complex of the graph
+//! is kept as it grows, so that its topology can be followed.
+//!
+//! The items re-exported here are the stable API, which only changes in a
+//! breaking way with the minor version while the crate is below 1.0; the rest
+//! of each module may change with the command line it serves. A
+//! [`Simulation`] is built with [`Simulation::new`], laid out with
+//! [`Simulation::init_uniform`], and run a step at a time with
+//! [`Simulation::step`], whose [`StepResult`] lists the edges added and
+//! removed, which keep a [`SimplicialComplex`] of the graph in sync. Its
+//! graph can be measured with [`conduction`](conduction::conduction) and
+//! [`wiring_cost`](wiring::wiring_cost), or exported with
+//! `attributed_graph`. With the `driver` feature, [`run`] and [`sweep`] do all
+//! of that from a [`Config`], writing their results to files. The `examples`
+//! directory has programs using each.
+
+pub mod attachment;
+pub mod coarse;
+pub mod conduction;
+#[cfg(feature = "driver")]
+pub mod driver;
+#[cfg(feature = "driver")]
+pub mod experiment;
+#[cfg(feature = "driver")]
+pub mod frames;
+pub mod functional;
+pub mod lesion;
+#[cfg(feature = "driver")]
+pub mod model;
+pub mod motifs;
+pub mod myelination;
+pub mod noise;
+pub mod protocol;
+pub mod queue;
+pub mod reservoir;
 pub mod sim;
-pub mod simplex;
+pub mod snapshots;
+#[cfg(feature = "driver")]
+pub mod stats;
+pub mod stimulus;
+#[cfg(feature = "driver")]
+pub mod stream;
+pub mod trajectory;
+pub mod wiring;
+
+pub use topology::simplex;
+
+#[cfg(feature = "driver")]
+pub use driver::{run, sweep, Config};
+pub use sim::{Params, Simulation, StepResult};
+pub use simplex::SimplicialComplex;
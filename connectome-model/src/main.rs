@@ -1,54 +1,85 @@
-use connectome_model::{
-    sim::Simulation,
-    simplex::{faces, SimplicialComplex},
-};
-use petgraph::graph::{DiGraph, NodeIndex};
-use rand::{rngs::ThreadRng, seq::IteratorRandom, thread_rng, Rng};
-use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
 
-const NUM_NODES: u32 = 6;
+use compression::Compression;
+use connectome_model::driver::{self, Config, Lesion, Stream};
+use connectome_model::experiment;
+use connectome_model::stream::StreamTarget;
+use provenance::Manifest;
 
 fn main() {
-    let rng = rand::thread_rng();
-
-    let mut simulation = Simulation::<ThreadRng>::new(1., 0.5, 0.01, 5, rng);
-    let mut rng = rand::thread_rng();
-    simulation.init_uniform(1, NUM_NODES);
-    let mut simplicial_complex = SimplicialComplex::new((0..NUM_NODES.pow(3) as usize).collect());
-    let mut vec: HashSet<(usize, usize)> = HashSet::new();
-    let mut i = 0;
-    loop {
-        let step_result = simulation.step(&[rng.gen_range(0, NUM_NODES.pow(3) as usize)]);
-        for (in_node, out_node) in step_result.removed_edges {
-            
-            // println!("{:?} {}", vec, vec.len());
-            assert!(vec.remove(&(in_node, out_node)));
-            simplicial_complex.remove(vec![in_node, out_node]);
-        }
-        for (in_node, out_node) in step_result.added_edges {
-            // println!("{:?} {}", vec, vec.len());
-            vec.insert((in_node, out_node));
-            simplicial_complex.add(vec![in_node, out_node]);
-        }
+    // With `lesion <step> <radius>`, every node within `radius` of the center
+    // of the grid is lesioned after `step` steps, and the recovery is recorded.
+    // With `--compress <gzip|zstd>`, the output is compressed. With `--script
+    // <path>`, the events of an experiment script are done as the run goes.
+    // With `--stream <path>`, a record of every step is streamed to the Unix
+    // socket at `path`, or to stdout if it's `-`.
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let mut events = Vec::new();
+    if let Some(i) = args.iter().position(|arg| arg == "--script") {
+        let path = args.get(i + 1).cloned().unwrap_or_default();
+        events = experiment::load(&path).unwrap_or_else(|err| {
+            eprintln!("failed to load the script {}: {}", path, err);
+            std::process::exit(2);
+        });
+        args.drain(i..(i + 2).min(args.len()));
+    }
+    let mut compression = Compression::None;
+    if let Some(i) = args.iter().position(|arg| arg == "--compress") {
+        let name = args.get(i + 1).cloned().unwrap_or_default();
+        compression = name.parse().unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        });
+        args.drain(i..(i + 2).min(args.len()));
+    }
 
-        i += 1;
+    let mut stream = None;
+    if let Some(i) = args.iter().position(|arg| arg == "--stream") {
+        let target = match args.get(i + 1).map(String::as_str) {
+            Some("-") => StreamTarget::Stdout,
+            Some(path) => StreamTarget::UnixSocket { path: path.into() },
+            None => {
+                eprintln!("--stream needs a socket path, or - for stdout");
+                std::process::exit(2);
+            }
+        };
+        stream = Some(Stream { target, batch_size: 64 });
+        args.drain(i..(i + 2).min(args.len()));
+    }
 
-        if i % 10 == 0 {
-            
-            let lengths: Vec<usize> = simplicial_complex
-                .simplices
-                .iter()
-                .map(|simplex| simplex.len())
-                .collect();
-            let betti_numbers = simplicial_complex.betti_numbers();
-            // let betti_numbers = vec![0];
-            println!(
-                "simplex sizes: {:?}\n\n betti numbers: {:?}\n\n",
-                lengths, betti_numbers
+    let lesion = match args.as_slice() {
+        [] => None,
+        [mode, step, radius] if mode == "lesion" => Some(Lesion {
+            step: step.parse().expect("lesion step must be an integer"),
+            radius: radius.parse().expect("lesion radius must be a number"),
+        }),
+        _ => {
+            eprintln!(
+                "usage: connectome-model [--compress <none|gzip|zstd>] [--script <path>] [--stream <path|->] \
+                 [lesion <step> <radius>]"
             );
-            if betti_numbers.contains(&-1) {
-                println!("{:?}", simplicial_complex.simplex_indices);
-            }
+            std::process::exit(2);
         }
+    };
+
+    let config = Config {
+        lesion,
+        events,
+        compression,
+        stream,
+        ..Config::default()
     }
+    .seeded();
+    let manifest = Manifest::start(
+        "out",
+        "connectome-model",
+        env!("CARGO_PKG_VERSION"),
+        &config,
+        &[config.seed.unwrap()],
+    )
+    .unwrap();
+    let manifest = Mutex::new(manifest);
+
+    driver::run_logged(&config, "out", None, &manifest).unwrap();
+    manifest.into_inner().unwrap().finish().unwrap();
 }
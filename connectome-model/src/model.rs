@@ -0,0 +1,204 @@
+//! The connectome simulation as a [`Model`], set up from a [`Config`] and
+//! checkpointed as its parameters, its nodes and its edges with the
+//! activations queued on them.
+
+use std::io;
+
+use graph_io::AttributedGraph;
+use model::Model;
+use nalgebra::Point3;
+use net_metrics::Summary;
+use petgraph::graph::NodeIndex;
+use rand::{rngs::StdRng, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::driver::Config;
+use crate::noise;
+use crate::sim::{Activation, EdgeWeight, NodeWeight, Params, Simulation, Transmission};
+
+/// The state of a simulation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub timestep: usize,
+    pub params: Params,
+    /// The nodes left, in order of their ids.
+    pub nodes: Vec<NodeCheckpoint>,
+    pub edges: Vec<EdgeCheckpoint>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeCheckpoint {
+    pub id: usize,
+    pub position: [f64; 3],
+    pub last_active: Option<usize>,
+    pub population: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EdgeCheckpoint {
+    pub source: usize,
+    pub target: usize,
+    pub myelination: usize,
+    /// The activations on their way along the edge, soonest first.
+    pub queue: Vec<Activation>,
+}
+
+impl Model for Simulation<StdRng> {
+    type Config = Config;
+    type Checkpoint = Checkpoint;
+
+    /// Sets up the grid of nodes and their populations as a run does.
+    fn init(config: &Config, rng: StdRng) -> io::Result<Self> {
+        let mut simulation = Simulation::new(
+            config.connectivity_rate,
+            config.myelination_rate,
+            config.decay_rate,
+            config.max_myelination,
+            config.distance_exp,
+            config.refractory_period,
+            rng,
+        );
+        simulation.init_uniform(1, config.grid_size);
+        if let Some(noise) = config.position_noise {
+            noise::displace(&mut simulation, noise.jitter);
+            simulation.position_drift = noise.drift;
+        }
+        for population in &config.populations {
+            simulation.assign_population(&population.name, &population.nodes);
+            if let Some(radius) = population.radius {
+                let nodes = simulation.nodes_within(&Point3::from(population.center), radius);
+                simulation.assign_population(&population.name, &nodes);
+            }
+        }
+        simulation.transmission = Transmission {
+            reliability: config.transmission_reliability,
+            myelinated: config.myelinated_transmission,
+        };
+        simulation.queue_limit = config.activation_queue;
+
+        Ok(simulation)
+    }
+
+    /// Steps with a single node, chosen uniformly from those left, activated.
+    fn step(&mut self) {
+        let num_nodes = self.graph.node_count();
+        let activations = if num_nodes > 0 {
+            let index = self.rng.gen_range(0, num_nodes);
+            self.graph.node_indices().nth(index).map(|node| node.index()).into_iter().collect()
+        } else {
+            Vec::new()
+        };
+        Simulation::step(self, &activations);
+    }
+
+    fn timestep(&self) -> usize {
+        self.timestep
+    }
+
+    fn graph(&self) -> AttributedGraph {
+        self.attributed_graph()
+    }
+
+    fn metrics<R: Rng>(&self, rng: &mut R) -> Summary {
+        net_metrics::summarize(&self.graph, rng)
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        let mut nodes = self
+            .graph
+            .node_indices()
+            .map(|node| {
+                let weight = &self.graph[node];
+                NodeCheckpoint {
+                    id: node.index(),
+                    position: [weight.position.x, weight.position.y, weight.position.z],
+                    last_active: weight.last_active,
+                    population: weight.population.clone(),
+                }
+            })
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|node| node.id);
+
+        let edges = self
+            .graph
+            .edge_indices()
+            .map(|edge| {
+                let (source, target) = self.graph.edge_endpoints(edge).unwrap();
+                let weight = &self.graph[edge];
+                EdgeCheckpoint {
+                    source: source.index(),
+                    target: target.index(),
+                    myelination: weight.myelination,
+                    queue: weight.activation_queue.clone().into_sorted_vec().into_iter().rev().collect(),
+                }
+            })
+            .collect();
+
+        Checkpoint {
+            timestep: self.timestep,
+            params: self.params(),
+            nodes,
+            edges,
+        }
+    }
+
+    /// Restores the nodes under the ids they had, adding placeholders for
+    /// those lesioned and removing them again. The order the graph keeps its
+    /// edges in may differ from the original's, so a restored simulation
+    /// needn't make the same draws from then on as the original would have.
+    fn restore(config: &Config, checkpoint: Checkpoint, rng: StdRng) -> io::Result<Self> {
+        if checkpoint.nodes.windows(2).any(|pair| pair[0].id >= pair[1].id) {
+            return Err(invalid("a checkpoint lists its nodes in order of their ids, each once"));
+        }
+        let num_nodes = checkpoint.nodes.last().map_or(0, |node| node.id + 1);
+        let mut present = vec![false; num_nodes];
+        for node in &checkpoint.nodes {
+            present[node.id] = true;
+        }
+        let is_present = |id: usize| present.get(id).copied().unwrap_or(false);
+        if checkpoint.edges.iter().any(|edge| !is_present(edge.source) || !is_present(edge.target)) {
+            return Err(invalid("a checkpoint has edges between nodes it doesn't have"));
+        }
+
+        let mut simulation = <Self as Model>::init(config, rng)?;
+        simulation.graph.clear();
+        let mut nodes = checkpoint.nodes.into_iter().peekable();
+        for id in 0..num_nodes {
+            let weight = match nodes.next_if(|node| node.id == id) {
+                Some(node) => NodeWeight {
+                    position: Point3::from(node.position),
+                    last_active: node.last_active,
+                    population: node.population,
+                },
+                None => NodeWeight {
+                    position: Point3::origin(),
+                    last_active: None,
+                    population: None,
+                },
+            };
+            simulation.graph.add_node(weight);
+        }
+        for id in (0..num_nodes).filter(|&id| !present[id]) {
+            simulation.graph.remove_node(NodeIndex::new(id));
+        }
+
+        for edge in checkpoint.edges {
+            simulation.graph.add_edge(
+                NodeIndex::new(edge.source),
+                NodeIndex::new(edge.target),
+                EdgeWeight {
+                    myelination: edge.myelination,
+                    activation_queue: edge.queue.into_iter().collect(),
+                },
+            );
+        }
+
+        simulation.set_params(checkpoint.params);
+        simulation.timestep = checkpoint.timestep;
+        Ok(simulation)
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,155 @@
+//! Temporal motifs of the wiring: patterns in when edges are added, rather
+//! than just which edges there are, since the timing of wiring events is
+//! itself something the model predicts. The edges added and removed by each
+//! step are followed, and counted in windows of steps:
+//!
+//! - triangle closures, edges added between two nodes with a neighbor in
+//!   common, and how many of those close a wedge both of whose edges were
+//!   added in the last `delta` steps;
+//! - reciprocations, edges added in the reverse direction of one added
+//!   between the same nodes before, which the model only allows once the
+//!   first is gone, and how long after it they come.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+use crate::sim::StepResult;
+
+/// The counts of one window of steps.
+#[derive(Default)]
+struct WindowCounts {
+    edges_added: usize,
+    closures: usize,
+    recent_closures: usize,
+    reciprocations: usize,
+    total_latency: usize,
+}
+
+/// Counts temporal motifs in windows of `window` steps, and writes them as
+/// CSV, one row per window: the last step of the window, the number of edges
+/// added, of triangles closed, of those closed within `delta` steps, and of
+/// reciprocations, and the mean number of steps between an edge and its
+/// reciprocation, left empty if there were none.
+pub struct MotifRecorder<W: Write> {
+    writer: W,
+    window: usize,
+    delta: usize,
+    /// The step each present edge was added at, by its ends in either order.
+    added: BTreeMap<(usize, usize), usize>,
+    neighbors: BTreeMap<usize, BTreeSet<usize>>,
+    /// The step each directed edge was last added at, whether or not it's
+    /// still present.
+    last_added: BTreeMap<(usize, usize), usize>,
+    counts: WindowCounts,
+    window_end: usize,
+    last_timestep: usize,
+}
+
+impl<W: Write> MotifRecorder<W> {
+    /// Creates a recorder writing to `writer` with windows of `window` steps
+    /// from the first, and writes the header row. The graph has to start with
+    /// no edges.
+    pub fn new(mut writer: W, window: usize, delta: usize) -> io::Result<Self> {
+        writeln!(
+            writer,
+            "step,edges_added,triangle_closures,recent_closures,reciprocations,mean_reciprocation_latency"
+        )?;
+
+        Ok(MotifRecorder {
+            writer,
+            window,
+            delta,
+            added: BTreeMap::new(),
+            neighbors: BTreeMap::new(),
+            last_added: BTreeMap::new(),
+            counts: WindowCounts::default(),
+            window_end: window,
+            last_timestep: 0,
+        })
+    }
+
+    /// Records the edges removed and then added by the step which ended at
+    /// `timestep`, or by a lesion after it, writing the windows before it.
+    pub fn record(&mut self, timestep: usize, step_result: &StepResult) -> io::Result<()> {
+        while timestep > self.window_end {
+            self.write_window()?;
+        }
+        self.last_timestep = timestep;
+
+        for &(source, target) in &step_result.removed_edges {
+            self.added.remove(&ordered(source, target));
+            for (node, other) in [(source, target), (target, source)] {
+                if let Some(neighbors) = self.neighbors.get_mut(&node) {
+                    neighbors.remove(&other);
+                }
+            }
+        }
+
+        for &(source, target) in &step_result.added_edges {
+            self.counts.edges_added += 1;
+
+            if let Some(&added_at) = self.last_added.get(&(target, source)) {
+                self.counts.reciprocations += 1;
+                self.counts.total_latency += timestep - added_at;
+            }
+
+            let recent = timestep.saturating_sub(self.delta);
+            if let (Some(a), Some(b)) = (self.neighbors.get(&source), self.neighbors.get(&target)) {
+                for &common in a.intersection(b) {
+                    self.counts.closures += 1;
+                    let opened = self.added[&ordered(source, common)].min(self.added[&ordered(target, common)]);
+                    if opened >= recent {
+                        self.counts.recent_closures += 1;
+                    }
+                }
+            }
+
+            self.added.insert(ordered(source, target), timestep);
+            self.last_added.insert((source, target), timestep);
+            self.neighbors.entry(source).or_default().insert(target);
+            self.neighbors.entry(target).or_default().insert(source);
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Writes the last window, if any steps of it were recorded, and returns
+    /// the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.last_timestep + self.window > self.window_end {
+            self.write_window()?;
+        }
+        Ok(self.writer)
+    }
+
+    fn write_window(&mut self) -> io::Result<()> {
+        let counts = std::mem::take(&mut self.counts);
+        let mean_latency = if counts.reciprocations > 0 {
+            (counts.total_latency as f64 / counts.reciprocations as f64).to_string()
+        } else {
+            String::new()
+        };
+
+        writeln!(
+            self.writer,
+            "{},{},{},{},{},{}",
+            self.window_end,
+            counts.edges_added,
+            counts.closures,
+            counts.recent_closures,
+            counts.reciprocations,
+            mean_latency
+        )?;
+        self.window_end += self.window;
+        Ok(())
+    }
+}
+
+/// Returns the ends of an edge, smallest first.
+fn ordered(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
@@ -0,0 +1,125 @@
+//! Recording how the myelination of each edge changes over a run, so that the
+//! trajectories of individual axons can be followed rather than just where
+//! they end up, along with how myelination is distributed over the edges as
+//! it goes and when each edge saturates.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use rand::Rng;
+
+use crate::sim::{Simulation, StepResult};
+
+/// Writes each change to an edge's myelination as CSV, one row per change:
+/// the timestep, the edge's source and target, and its new myelination. The
+/// simulation has to [track myelination](crate::sim::Simulation::track_myelination)
+/// for its steps to report any.
+pub struct MyelinationRecorder<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> MyelinationRecorder<W> {
+    /// Creates a recorder writing to `writer`, and writes the header row.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writeln!(writer, "step,source,target,myelination")?;
+        Ok(MyelinationRecorder { writer })
+    }
+
+    /// Records the changes to myelination made by the step which ended at
+    /// `timestep`.
+    pub fn record(&mut self, timestep: usize, step_result: &StepResult) -> io::Result<()> {
+        for &(source, target, myelination) in &step_result.myelination_changes {
+            writeln!(self.writer, "{},{},{},{}", timestep, source, target, myelination)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Writes how many edges have each level of myelination, every time it's
+/// asked to, as CSV, one row per level: the timestep, the level, and the
+/// number of edges at it.
+pub struct HistogramRecorder<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> HistogramRecorder<W> {
+    /// Creates a recorder writing to `writer`, and writes the header row.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writeln!(writer, "step,myelination,edges")?;
+        Ok(HistogramRecorder { writer })
+    }
+
+    /// Records the myelination of the edges of `simulation` at `timestep`, at
+    /// every level up to its maximum.
+    pub fn record<R: Rng>(&mut self, timestep: usize, simulation: &Simulation<R>) -> io::Result<()> {
+        let mut counts = vec![0; simulation.max_myelination + 1];
+        for id in simulation.graph.edge_indices() {
+            counts[simulation.graph[id].myelination.min(simulation.max_myelination)] += 1;
+        }
+
+        for (myelination, count) in counts.into_iter().enumerate() {
+            writeln!(self.writer, "{},{},{}", timestep, myelination, count)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Follows when each edge first becomes fully myelinated, to characterize how
+/// quickly myelination saturates. Like a [`MyelinationRecorder`], it needs
+/// the simulation to track myelination.
+#[derive(Clone, Debug, Default)]
+pub struct SaturationTracker {
+    /// The timestep each edge which has saturated first did, by its source
+    /// and target.
+    saturated_at: BTreeMap<(usize, usize), usize>,
+}
+
+impl SaturationTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the edges which reached `max_myelination` in the step which
+    /// ended at `timestep`, unless they had before.
+    pub fn record(&mut self, timestep: usize, step_result: &StepResult, max_myelination: usize) {
+        for &(source, target, myelination) in &step_result.myelination_changes {
+            if myelination >= max_myelination {
+                self.saturated_at.entry((source, target)).or_insert(timestep);
+            }
+        }
+    }
+
+    /// Returns the timestep the edge from `source` to `target` first became
+    /// fully myelinated, if it has.
+    pub fn saturated_at(&self, source: usize, target: usize) -> Option<usize> {
+        self.saturated_at.get(&(source, target)).copied()
+    }
+
+    /// Writes the timestep each edge which has saturated first did as CSV,
+    /// one row per edge: its source and target, and the timestep.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "source,target,step")?;
+        for (&(source, target), step) in &self.saturated_at {
+            writeln!(writer, "{},{},{}", source, target, step)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+//! Noise in the positions of the nodes, so that the robustness of the wiring
+//! rules to imperfect geometry can be tested: jitter, which offsets each node
+//! from its place on the grid once, as it's laid out, and drift, a slow random
+//! walk each node takes from step to step. Distances are always measured
+//! between the current positions, so nodes which have moved attach by where
+//! they are now, and their wiring costs are measured the same way.
+
+use nalgebra::Vector3;
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::sim::Simulation;
+
+/// The standard deviations of the noise in each coordinate of each node's
+/// position. Without any, the nodes sit on the grid for good.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PositionNoise {
+    /// Of the offset of each node from the grid as it's laid out.
+    pub jitter: f64,
+    /// Of the step each node takes every timestep.
+    pub drift: f64,
+}
+
+impl PositionNoise {
+    /// Returns whether both standard deviations are finite and nonnegative.
+    pub fn is_valid(&self) -> bool {
+        [self.jitter, self.drift]
+            .iter()
+            .all(|&std_dev| std_dev.is_finite() && std_dev >= 0.)
+    }
+}
+
+/// Moves every node by an offset with independent normal coordinates of
+/// standard deviation `std_dev`, drawn from the simulation's generator in the
+/// order of the nodes' ids. Does nothing, and draws nothing, if `std_dev` is
+/// 0.
+pub fn displace<R: Rng>(simulation: &mut Simulation<R>, std_dev: f64) {
+    if std_dev <= 0. {
+        return;
+    }
+
+    let normal = Normal::new(0., std_dev).unwrap();
+    for id in simulation.graph.node_indices().collect::<Vec<_>>() {
+        let rng = &mut simulation.rng;
+        let offset = Vector3::new(normal.sample(rng), normal.sample(rng), normal.sample(rng));
+        simulation.graph[id].position += offset;
+    }
+    simulation.moves += 1;
+}
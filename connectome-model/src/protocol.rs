@@ -0,0 +1,160 @@
+//! Stimulus-response experiments: driving the same nodes at the start of each
+//! of a series of trials, and recording which nodes fire in response and how
+//! long after the stimulus they first do, to probe what the grown network does
+//! with its input.
+//!
+//! Activity is also traced from each stimulated node to a set of readout
+//! nodes, by following the activations delivered along edges back to the
+//! firing that queued them, which measures how fast the network conducts
+//! from input to output, and so how much myelination speeds it up.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+use rand::Rng;
+
+use crate::sim::{Simulation, StepResult};
+
+/// A stimulus applied repeatedly: `stimulus` is driven on the first step of
+/// each of `num_trials` trials, each of which lasts `trial_length` steps with
+/// no other input. The network keeps growing and myelinating as it responds.
+/// Activity from each stimulated node is traced to each of the `readouts`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Protocol {
+    pub stimulus: Vec<usize>,
+    pub readouts: Vec<usize>,
+    pub num_trials: usize,
+    pub trial_length: usize,
+}
+
+/// The response evoked by one trial.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trial {
+    /// The timestep the stimulus was applied at.
+    pub onset: usize,
+    /// How many timesteps after the onset each node which fired first did;
+    /// nodes driven by the stimulus itself fire after 0.
+    pub latencies: BTreeMap<usize, usize>,
+    /// How many timesteps after the onset activity from each stimulated node
+    /// first reached each readout it reached, by `(stimulated, readout)`.
+    pub readout_latencies: BTreeMap<(usize, usize), usize>,
+}
+
+impl Protocol {
+    /// Runs every trial on `simulation`, passing the result of each step to
+    /// `on_step` so that anything tracking the graph can follow along, and
+    /// returns the response to each.
+    pub fn run<R: Rng, F: FnMut(&Simulation<R>, StepResult)>(
+        &self,
+        simulation: &mut Simulation<R>,
+        mut on_step: F,
+    ) -> Vec<Trial> {
+        let track_deliveries = simulation.track_deliveries;
+        simulation.track_deliveries = true;
+
+        let trials = (0..self.num_trials)
+            .map(|_| {
+                let mut trial = Trial::default();
+                // The stimulated nodes whose activity has reached each node
+                // which fired, with when it first fired with it.
+                let mut origins = BTreeMap::<usize, BTreeMap<usize, usize>>::new();
+
+                for step in 0..self.trial_length {
+                    let input = if step == 0 { &self.stimulus[..] } else { &[] };
+                    let step_result = simulation.step(input);
+                    let timestep = simulation.timestep;
+                    if step == 0 {
+                        trial.onset = timestep;
+                    }
+
+                    for &node in &step_result.activated_nodes {
+                        trial.latencies.entry(node).or_insert(timestep - trial.onset);
+                    }
+                    trace(&mut origins, &step_result, timestep, step == 0);
+                    on_step(simulation, step_result);
+                }
+
+                for &readout in &self.readouts {
+                    for (&stimulated, &fired_at) in origins.get(&readout).into_iter().flatten() {
+                        trial.readout_latencies.insert((stimulated, readout), fired_at - trial.onset);
+                    }
+                }
+
+                trial
+            })
+            .collect();
+
+        simulation.track_deliveries = track_deliveries;
+        trials
+    }
+}
+
+/// Follows activity from the stimulated nodes through a step which ended at
+/// `timestep`: each node which fired carries on the origins of the activity
+/// delivered to it, from sources which had them by the time they queued it.
+/// At the onset, the nodes which fired are the origins themselves.
+fn trace(
+    origins: &mut BTreeMap<usize, BTreeMap<usize, usize>>,
+    step_result: &StepResult,
+    timestep: usize,
+    onset: bool,
+) {
+    if onset {
+        for &node in &step_result.activated_nodes {
+            origins.entry(node).or_default().insert(node, timestep);
+        }
+        return;
+    }
+
+    let fired = step_result.activated_nodes.iter().copied().collect::<BTreeSet<_>>();
+    let mut reached = Vec::new();
+    for delivery in step_result.deliveries.iter().filter(|delivery| fired.contains(&delivery.target)) {
+        for (&stimulated, &fired_at) in origins.get(&delivery.source).into_iter().flatten() {
+            if fired_at <= delivery.queued_at {
+                reached.push((delivery.target, stimulated));
+            }
+        }
+    }
+
+    for (node, stimulated) in reached {
+        origins.entry(node).or_default().entry(stimulated).or_insert(timestep);
+    }
+}
+
+/// Writes the latencies of the trials as CSV, one row per trial and one column
+/// per node, after the trial's index and onset, leaving the latency of a node
+/// which didn't fire empty.
+pub fn write_latencies<W: Write>(trials: &[Trial], num_nodes: usize, mut writer: W) -> io::Result<()> {
+    let header = ["trial".to_string(), "onset".to_string()]
+        .iter()
+        .cloned()
+        .chain((0..num_nodes).map(|node| node.to_string()))
+        .collect::<Vec<_>>();
+    writeln!(writer, "{}", header.join(","))?;
+
+    for (index, trial) in trials.iter().enumerate() {
+        let row = [index.to_string(), trial.onset.to_string()]
+            .iter()
+            .cloned()
+            .chain((0..num_nodes).map(|node| trial.latencies.get(&node).map_or(String::new(), |l| l.to_string())))
+            .collect::<Vec<_>>();
+        writeln!(writer, "{}", row.join(","))?;
+    }
+
+    Ok(())
+}
+
+/// Writes how long activity took to get from each stimulated node to each
+/// readout it reached in each trial as CSV, one row per trial, stimulated node
+/// and readout.
+pub fn write_readout_latencies<W: Write>(trials: &[Trial], mut writer: W) -> io::Result<()> {
+    writeln!(writer, "trial,stimulated,readout,latency")?;
+
+    for (index, trial) in trials.iter().enumerate() {
+        for (&(stimulated, readout), latency) in &trial.readout_latencies {
+            writeln!(writer, "{},{},{},{}", index, stimulated, readout, latency)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! Bounds on the activations queued on each edge. A node which fires every
+//! step queues activations on its edges faster than slow, unmyelinated ones
+//! deliver them, so without a bound their queues grow for as long as it
+//! keeps firing. With one, what happens to an activation which doesn't fit is
+//! up to the [`Overflow`] policy, and the activations lost to it are counted,
+//! so that runaway regimes show up in the [`QueueStats`].
+
+use std::collections::BinaryHeap;
+
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::sim::{Activation, Simulation};
+
+/// What to do with an activation queued on an edge which is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Overflow {
+    /// Drop the activation queued earliest to make room.
+    #[default]
+    DropOldest,
+    /// Merge the two activations due closest together into one, due at the
+    /// sooner of the two, to make room. As a node fires at most once a step,
+    /// activations due at the same step are merged at no cost.
+    Coalesce,
+    /// Drop the new activation, leaving the queue as it is.
+    Saturate,
+}
+
+/// How many activations each edge can have queued, and what happens to those
+/// beyond that.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QueueLimit {
+    pub capacity: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub overflow: Overflow,
+}
+
+impl QueueLimit {
+    /// Queues an activation, and returns whether one was lost for lack of
+    /// room. The capacity is at least 1.
+    pub fn push(&self, queue: &mut BinaryHeap<Activation>, activation: Activation) -> bool {
+        if queue.len() < self.capacity {
+            queue.push(activation);
+            return false;
+        }
+
+        match self.overflow {
+            Overflow::DropOldest => {
+                let mut activations = std::mem::take(queue).into_vec();
+                activations.push(activation);
+                let oldest = (0..activations.len())
+                    .min_by_key(|&i| (activations[i].queued_at, activations[i].at))
+                    .unwrap();
+                activations.swap_remove(oldest);
+                *queue = activations.into();
+            }
+            Overflow::Coalesce => {
+                let mut activations = std::mem::take(queue).into_vec();
+                activations.push(activation);
+                // Sorted by the reverse of when they're due, latest first.
+                activations.sort();
+                let closest = (1..activations.len())
+                    .min_by_key(|&i| activations[i - 1].at - activations[i].at)
+                    .unwrap();
+                let later = activations.remove(closest - 1);
+                let sooner = &mut activations[closest - 1];
+                sooner.queued_at = sooner.queued_at.min(later.queued_at);
+                *queue = activations.into();
+            }
+            Overflow::Saturate => {}
+        }
+        true
+    }
+}
+
+/// How many activations are queued on the edges of the graph at one
+/// timestep.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct QueueStats {
+    /// The activations queued on all of the edges.
+    pub queued: usize,
+    /// The mean length of the queues of the edges, or 0 if there are none.
+    pub mean_length: f64,
+    pub max_length: usize,
+    /// The activations lost to full queues so far in the run, dropped or
+    /// merged into others.
+    pub overflowed: usize,
+}
+
+/// Measures the queues of the edges of the simulation.
+pub fn queue_stats<R: Rng>(simulation: &Simulation<R>) -> QueueStats {
+    let lengths = simulation
+        .graph
+        .edge_indices()
+        .map(|edge| simulation.graph[edge].activation_queue.len())
+        .collect::<Vec<_>>();
+    let queued = lengths.iter().sum::<usize>();
+
+    QueueStats {
+        queued,
+        mean_length: if lengths.is_empty() { 0. } else { queued as f64 / lengths.len() as f64 },
+        max_length: lengths.into_iter().max().unwrap_or(0),
+        overflowed: simulation.overflowed_activations,
+    }
+}
@@ -0,0 +1,192 @@
+//! Evaluates the grown network as a reservoir, as a functional benchmark for
+//! its structure: a random binary signal drives a fixed set of input nodes,
+//! one symbol every few steps, and linear readouts of which nodes fired during
+//! each symbol are trained to recall the signal. A network whose structure
+//! echoes its input for longer, and mixes it more, recalls it further back
+//! and can compute more of it.
+//!
+//! Each readout is fit by ridge regression on the first half of the symbols
+//! after the washout, and scored on the second half, for two tasks at each
+//! delay `d`: memory, recalling the symbol `d` before, and XOR, recalling
+//! whether the symbols `d` and `d + 1` before differ.
+
+use nalgebra::{DMatrix, DVector};
+use petgraph::visit::NodeIndexable;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::sim::Simulation;
+
+/// A reservoir evaluation: `input` is driven on the first step of each of the
+/// `num_symbols` symbols which are 1, each of which lasts `symbol_length`
+/// steps. The first `washout` symbols only settle the network, and the
+/// readouts are fit with a ridge penalty of `ridge`, for delays up to
+/// `max_delay`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evaluation {
+    pub input: Vec<usize>,
+    pub num_symbols: usize,
+    pub symbol_length: usize,
+    pub washout: usize,
+    pub max_delay: usize,
+    pub ridge: f64,
+}
+
+/// What a readout is trained to recall.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Task {
+    Memory,
+    Xor,
+}
+
+/// How well a readout did on the held-out symbols.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Score {
+    pub task: Task,
+    pub delay: usize,
+    /// The fraction of the symbols the readout, thresholded at a half, got
+    /// right.
+    pub accuracy: f64,
+    /// The squared correlation between the readout and its target, or 0 if
+    /// either is constant.
+    pub r_squared: f64,
+}
+
+/// The scores of every readout of an evaluation.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Report {
+    pub num_train: usize,
+    pub num_test: usize,
+    /// The sum of `r_squared` over the memory tasks: how many symbols back the
+    /// network remembers.
+    pub memory_capacity: f64,
+    pub scores: Vec<Score>,
+}
+
+impl Evaluation {
+    /// Returns whether the symbols last a step, the penalty is positive, and
+    /// there are at least two symbols, after the washout and the longest
+    /// delay, to fit the readouts on and two to score them on.
+    pub fn is_valid(&self) -> bool {
+        self.symbol_length > 0 && self.ridge > 0. && self.num_symbols >= self.washout + self.max_delay + 5
+    }
+
+    /// Runs the evaluation on `simulation`, drawing the signal from its
+    /// generator. The network is frozen while it's driven, so that the
+    /// readouts are of the structure it grew: nothing attaches, myelinates,
+    /// decays or drifts, and its parameters are restored afterwards.
+    pub fn run<R: Rng>(&self, simulation: &mut Simulation<R>) -> Report {
+        let params = simulation.params();
+        let position_drift = simulation.position_drift;
+        let mut frozen = params;
+        frozen.connectivity_rate = 0.;
+        frozen.myelination_rate = 0.;
+        frozen.decay_rate = 0.;
+        simulation.set_params(frozen);
+        simulation.position_drift = 0.;
+
+        let num_nodes = simulation.graph.node_bound();
+        let mut signal = Vec::with_capacity(self.num_symbols);
+        // A row for each symbol: how many times each node fired during it,
+        // and a constant for the readout's bias.
+        let mut states = DMatrix::<f64>::zeros(self.num_symbols, num_nodes + 1);
+
+        for symbol in 0..self.num_symbols {
+            let bit = simulation.rng.gen_bool(0.5);
+            signal.push(bit);
+            states[(symbol, num_nodes)] = 1.;
+
+            for step in 0..self.symbol_length {
+                let input = if step == 0 && bit { &self.input[..] } else { &[] };
+                for node in simulation.step(input).activated_nodes {
+                    states[(symbol, node)] += 1.;
+                }
+            }
+        }
+
+        simulation.set_params(params);
+        simulation.position_drift = position_drift;
+
+        // Every task starts from the same symbol, so that they're all scored
+        // on the same ones.
+        let first = self.washout + self.max_delay + 1;
+        let num_train = (self.num_symbols - first) / 2;
+        let train = first..first + num_train;
+        let test = first + num_train..self.num_symbols;
+
+        let mut scores = Vec::new();
+        for &task in &[Task::Memory, Task::Xor] {
+            for delay in 0..=self.max_delay {
+                let target = |symbol: usize| {
+                    let recalled = match task {
+                        Task::Memory => signal[symbol - delay],
+                        Task::Xor => signal[symbol - delay] != signal[symbol - delay - 1],
+                    };
+                    recalled as u8 as f64
+                };
+
+                let weights = fit(&states, train.clone(), &target, self.ridge);
+                let pairs = test
+                    .clone()
+                    .map(|symbol| ((states.row(symbol) * &weights)[0], target(symbol)))
+                    .collect::<Vec<_>>();
+                let correct = pairs.iter().filter(|&&(output, target)| (output > 0.5) == (target > 0.5)).count();
+
+                scores.push(Score {
+                    task,
+                    delay,
+                    accuracy: correct as f64 / pairs.len() as f64,
+                    r_squared: correlation(&pairs).map_or(0., |r| r * r),
+                });
+            }
+        }
+
+        Report {
+            num_train: train.len(),
+            num_test: test.len(),
+            memory_capacity: scores
+                .iter()
+                .filter(|score| score.task == Task::Memory)
+                .map(|score| score.r_squared)
+                .sum(),
+            scores,
+        }
+    }
+}
+
+/// Fits the weights of a linear readout of the states of `symbols` to their
+/// targets by ridge regression, solving `(XᵀX + ridge I) w = Xᵀy`.
+fn fit<F: Fn(usize) -> f64>(
+    states: &DMatrix<f64>,
+    symbols: std::ops::Range<usize>,
+    target: &F,
+    ridge: f64,
+) -> DVector<f64> {
+    let x = states.rows(symbols.start, symbols.len());
+    let y = DVector::from_iterator(symbols.len(), symbols.map(target));
+    let gram = x.transpose() * x + DMatrix::identity(x.ncols(), x.ncols()) * ridge;
+    // The penalty makes the Gram matrix positive definite.
+    gram.cholesky().unwrap().solve(&(x.transpose() * y))
+}
+
+/// Returns the Pearson correlation between the outputs and targets of a
+/// readout, or `None` if either is constant.
+fn correlation(pairs: &[(f64, f64)]) -> Option<f64> {
+    let n = pairs.len() as f64;
+    let mean_a = pairs.iter().map(|(a, _)| a).sum::<f64>() / n;
+    let mean_b = pairs.iter().map(|(_, b)| b).sum::<f64>() / n;
+    let covariance = pairs.iter().map(|(a, b)| (a - mean_a) * (b - mean_b)).sum::<f64>();
+    let variance_a = pairs.iter().map(|(a, _)| (a - mean_a).powi(2)).sum::<f64>();
+    let variance_b = pairs.iter().map(|(_, b)| (b - mean_b).powi(2)).sum::<f64>();
+
+    if variance_a <= 0. || variance_b <= 0. {
+        return None;
+    }
+
+    Some(covariance / (variance_a * variance_b).sqrt())
+}
@@ -1,12 +1,24 @@
-use std::collections::{BinaryHeap, HashSet};
+use std::collections::{BTreeSet, BinaryHeap};
+use std::time::{Duration, Instant};
 
+#[cfg(feature = "graph-io")]
+use graph_io::{Attributes, AttributedGraph};
 use nalgebra::{distance, Point3};
 use petgraph::{graph::NodeIndex, stable_graph::StableDiGraph, visit::EdgeRef, EdgeDirection};
 use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::attachment::Candidates;
+use crate::noise;
+use crate::queue::QueueLimit;
 
 pub struct NodeWeight {
     pub position: Point3<f64>,
     pub last_active: Option<usize>,
+    /// The population the node belongs to, such as sensory or internal, if
+    /// it was assigned one, so that results can be grouped by it.
+    pub population: Option<String>,
 }
 
 impl NodeWeight {
@@ -22,7 +34,8 @@ impl NodeWeight {
     }
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Activation {
     pub at: usize,
     pub queued_at: usize,
@@ -52,9 +65,93 @@ impl EdgeWeight {
     }
 }
 
+/// How reliably edges deliver the activations queued on them. Each
+/// activation is delivered, or lost, on its own.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Transmission {
+    /// The probability that an unmyelinated edge delivers an activation.
+    pub reliability: f64,
+    /// Whether myelination makes delivery more reliable, from `reliability`
+    /// on an unmyelinated edge up to certain on a fully myelinated one.
+    pub myelinated: bool,
+}
+
+impl Default for Transmission {
+    /// Every activation is delivered.
+    fn default() -> Self {
+        Self {
+            reliability: 1.,
+            myelinated: false,
+        }
+    }
+}
+
+impl Transmission {
+    /// Returns the probability that an edge with the given myelination
+    /// delivers an activation.
+    pub fn delivery_prob(&self, myelination: usize, max_myelination: usize) -> f64 {
+        if self.myelinated && max_myelination > 0 {
+            let gain = myelination.min(max_myelination) as f64 / max_myelination as f64;
+            self.reliability + (1. - self.reliability) * gain
+        } else {
+            self.reliability
+        }
+    }
+}
+
+/// An activation delivered along an edge, from a source which fired at
+/// `queued_at`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Delivery {
+    pub source: usize,
+    pub target: usize,
+    pub queued_at: usize,
+}
+
+/// The parameters of a simulation which can be changed while it runs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Params {
+    pub connectivity_rate: f64,
+    pub myelination_rate: f64,
+    pub decay_rate: f64,
+    pub max_myelination: usize,
+    pub distance_exp: i32,
+    pub refractory_period: usize,
+    pub transmission: Transmission,
+}
+
+#[derive(Default)]
 pub struct StepResult {
     pub removed_edges: Vec<(usize, usize)>,
     pub added_edges: Vec<(usize, usize)>,
+    /// The nodes which fired during the step.
+    pub activated_nodes: Vec<usize>,
+    /// The nodes which were lesioned, whose edges are all in `removed_edges`.
+    pub removed_nodes: Vec<usize>,
+    /// The edges whose myelination changed during the step, with their new
+    /// myelination, if the simulation
+    /// [tracks myelination](Simulation::track_myelination).
+    pub myelination_changes: Vec<(usize, usize, usize)>,
+    /// The activations delivered during the step, whether or not their
+    /// targets could fire, if the simulation
+    /// [tracks deliveries](Simulation::track_deliveries).
+    pub deliveries: Vec<Delivery>,
+    /// How long each phase of the step took, if the simulation is
+    /// [timed](Simulation::timed).
+    pub timings: Option<StepTimings>,
+}
+
+/// How long each phase of a step took.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StepTimings {
+    /// Decaying edges and collecting the activations they deliver.
+    pub decay: Duration,
+    /// Attaching activated nodes to recently active ones.
+    pub attachment: Duration,
+    /// Firing the activated nodes and myelinating their edges.
+    pub activation: Duration,
 }
 
 pub struct Simulation<R: Rng> {
@@ -67,6 +164,27 @@ pub struct Simulation<R: Rng> {
     pub refractory_period: usize,
     pub graph: StableDiGraph<NodeWeight, EdgeWeight>,
     pub rng: R,
+    /// Whether each step reports how long its phases took. Off by default, as
+    /// the clock isn't available everywhere the simulation runs.
+    pub timed: bool,
+    /// How reliably edges deliver activations; every one is by default.
+    pub transmission: Transmission,
+    /// Whether each step reports the edges whose myelination changed. Off by
+    /// default, as most runs only look at the final myelination.
+    pub track_myelination: bool,
+    /// Whether each step reports the activations it delivered, so that
+    /// activity can be traced back to where it started. Off by default.
+    pub track_deliveries: bool,
+    /// How many activations each edge can have queued, if any limit.
+    pub queue_limit: Option<QueueLimit>,
+    /// How many activations have been lost to full queues.
+    pub overflowed_activations: usize,
+    /// The standard deviation of the step each node's position takes at the
+    /// start of every step, if the nodes drift; see [`crate::noise`].
+    pub position_drift: f64,
+    /// How many times the nodes have moved, so that anything computed from
+    /// their positions can tell whether it's out of date.
+    pub moves: usize,
 }
 
 impl<R> Simulation<R>
@@ -92,6 +210,14 @@ where
             refractory_period,
             graph: StableDiGraph::new(),
             rng,
+            timed: false,
+            transmission: Transmission::default(),
+            track_myelination: false,
+            track_deliveries: false,
+            queue_limit: None,
+            overflowed_activations: 0,
+            position_drift: 0.,
+            moves: 0,
         }
     }
 
@@ -113,21 +239,123 @@ where
                     self.graph.add_node(NodeWeight {
                         position: Point3::new(x, y, z),
                         last_active: None,
+                        population: None,
                     });
                 }
             }
         }
     }
 
+    /// Assigns nodes to the population called `name`, in place of any they
+    /// were in. Nodes which are gone are skipped.
+    pub fn assign_population(&mut self, name: &str, nodes: &[usize]) {
+        for &id in nodes {
+            if let Some(node) = self.graph.node_weight_mut(NodeIndex::new(id)) {
+                node.population = Some(name.to_string());
+            }
+        }
+    }
+
+    /// Returns the nodes within `radius` of `center`.
+    pub fn nodes_within(&self, center: &Point3<f64>, radius: f64) -> Vec<usize> {
+        self.graph
+            .node_indices()
+            .filter(|&id| distance(&self.graph[id].position, center) <= radius)
+            .map(|id| id.index())
+            .collect()
+    }
+
+    /// Returns the parameters the simulation is running with.
+    pub fn params(&self) -> Params {
+        Params {
+            connectivity_rate: self.connectivity_rate,
+            myelination_rate: self.myelination_rate,
+            decay_rate: self.decay_rate,
+            max_myelination: self.max_myelination,
+            distance_exp: self.distance_exp,
+            refractory_period: self.refractory_period,
+            transmission: self.transmission,
+        }
+    }
+
+    /// Changes the parameters the simulation runs with from the next step on,
+    /// keeping its graph and the activations queued on it. Lowering the
+    /// maximum myelination lowers that of any edge above it.
+    pub fn set_params(&mut self, params: Params) {
+        self.connectivity_rate = params.connectivity_rate;
+        self.myelination_rate = params.myelination_rate;
+        self.decay_rate = params.decay_rate;
+        self.distance_exp = params.distance_exp;
+        self.refractory_period = params.refractory_period;
+        self.transmission = params.transmission;
+
+        if params.max_myelination < self.max_myelination {
+            for edge in self.graph.edge_weights_mut() {
+                edge.myelination = edge.myelination.min(params.max_myelination);
+            }
+        }
+        self.max_myelination = params.max_myelination;
+    }
+
+    /// Returns the myelination of the edge between two nodes, in either
+    /// direction, if they're connected.
+    pub fn myelination(&self, a: usize, b: usize) -> Option<usize> {
+        self.graph
+            .find_edge_undirected(NodeIndex::new(a), NodeIndex::new(b))
+            .map(|(id, _)| self.graph[id].myelination)
+    }
+
+    /// Returns the graph for export, with the position, last activation and
+    /// population of each node, and the myelination of each edge.
+    #[cfg(feature = "graph-io")]
+    pub fn attributed_graph(&self) -> AttributedGraph {
+        AttributedGraph::from_graph(
+            &self.graph,
+            |node| {
+                let mut attributes = Attributes::new();
+                attributes.insert("x".to_string(), node.position.x.into());
+                attributes.insert("y".to_string(), node.position.y.into());
+                attributes.insert("z".to_string(), node.position.z.into());
+                if let Some(last_active) = node.last_active {
+                    attributes.insert("last_active".to_string(), last_active.into());
+                }
+                if let Some(population) = &node.population {
+                    attributes.insert("population".to_string(), population.as_str().into());
+                }
+                attributes
+            },
+            |edge| {
+                let mut attributes = Attributes::new();
+                attributes.insert("myelination".to_string(), edge.myelination.into());
+                attributes
+            },
+        )
+    }
+
+    /// Returns the distance between two nodes.
+    pub fn distance(&self, a: usize, b: usize) -> f64 {
+        distance(
+            &self.graph[NodeIndex::new(a)].position,
+            &self.graph[NodeIndex::new(b)].position,
+        )
+    }
+
     /// Steps the simulation forward by a single timestep.
     pub fn step(&mut self, activations: &[usize]) -> StepResult {
         let next_timestep = self.timestep + 1;
-
-        let mut pending_removed_edges = HashSet::new();
+        let mut phase_start = if self.timed { Some(Instant::now()) } else { None };
+        noise::displace(self, self.position_drift);
+
+        // Ordered sets, so that the draws from `rng`, and so the whole run,
+        // depend only on its seed.
+        let mut pending_removed_edges = BTreeSet::new();
+        let mut myelination_changes = Vec::new();
+        let mut deliveries = Vec::new();
         let mut pending_activations = activations
             .iter()
             .map(|&id| NodeIndex::new(id))
-            .collect::<HashSet<_>>();
+            .filter(|&id| self.graph.contains_node(id))
+            .collect::<BTreeSet<_>>();
 
         for id in self.graph.edge_indices().collect::<Vec<_>>() {
             let edge = &mut self.graph[id];
@@ -146,17 +374,34 @@ where
                 }
 
                 edge.myelination -= 1;
+                if self.track_myelination {
+                    let (source, target) = self.graph.edge_endpoints(id).unwrap();
+                    myelination_changes.push((source.index(), target.index(), self.graph[id].myelination));
+                }
             }
 
+            let edge = &mut self.graph[id];
+
             let mut should_activate = false;
+            let delivery_prob = self
+                .transmission
+                .delivery_prob(edge.myelination, self.max_myelination);
 
             while edge
                 .activation_queue
                 .peek()
-                .map_or(false, |activation| activation.at <= next_timestep)
+                .is_some_and(|activation| activation.at <= next_timestep)
             {
-                edge.activation_queue.pop();
-                should_activate = true;
+                let activation = edge.activation_queue.pop().unwrap();
+
+                // Only unreliable edges draw from `rng`, so that runs with
+                // reliable ones don't change.
+                if delivery_prob >= 1. || self.rng.gen_bool(delivery_prob) {
+                    should_activate = true;
+                    if self.track_deliveries {
+                        deliveries.push((id, activation.queued_at));
+                    }
+                }
             }
 
             if !should_activate {
@@ -167,17 +412,36 @@ where
             pending_activations.insert(target_id);
         }
 
-        let mut pending_added_edges = HashSet::new();
+        let decay = lap(&mut phase_start);
+        let mut pending_added_edges = BTreeSet::new();
+
+        let mut candidates = Candidates::default();
+        for id in self.graph.node_indices() {
+            let node = &self.graph[id];
+
+            if let Some(last_active) = node.last_active {
+                candidates.push(id.index(), &node.position, next_timestep - last_active);
+            }
+        }
+
+        let mut attachment_probs = Vec::with_capacity(candidates.len());
 
         for &target_id in &pending_activations {
-            let target_node = &self.graph[target_id];
+            candidates.attachment_probs(
+                &self.graph[target_id].position,
+                self.connectivity_rate,
+                self.distance_exp,
+                &mut attachment_probs,
+            );
+
+            for (&source, &attachment_prob) in candidates.ids().iter().zip(&attachment_probs) {
+                let source_id = NodeIndex::new(source);
 
-            for source_id in self.graph.node_indices() {
                 if target_id == source_id {
                     continue;
                 }
 
-                // An edge already exists between these nodes; don't bother trying to compute attachment.
+                // An edge already exists between these nodes; don't bother trying to attach.
                 if self
                     .graph
                     .find_edge_undirected(source_id, target_id)
@@ -188,17 +452,10 @@ where
                     continue;
                 }
 
-                let source_node = &self.graph[source_id];
-
-                if let Some(last_active) = source_node.last_active {
-                    let delta_timestep = (next_timestep - last_active) as f64;
-                    let distance = distance(&target_node.position, &source_node.position).powi(self.distance_exp);
-                    let attachment_prob =
-                        self.connectivity_rate * (delta_timestep.exp() * distance).recip();
-
-                    if self.rng.gen_bool(attachment_prob) {
-                        pending_added_edges.insert((source_id, target_id));
-                    }
+                // Only nodes which have moved can come close enough to be
+                // certain to attach; those don't draw from `rng`.
+                if attachment_prob >= 1. || self.rng.gen_bool(attachment_prob) {
+                    pending_added_edges.insert((source_id, target_id));
                 }
             }
         }
@@ -210,6 +467,9 @@ where
                 .add_edge(*source_id, *target_id, EdgeWeight::default());
         }
 
+        let attachment = lap(&mut phase_start);
+        let mut activated_nodes = Vec::new();
+
         for &id in &pending_activations {
             let node = &mut self.graph[id];
 
@@ -220,6 +480,7 @@ where
             }
 
             node.set_active(self.timestep);
+            activated_nodes.push(id.index());
 
             for edge_id in self
                 .graph
@@ -228,10 +489,18 @@ where
                 .collect::<Vec<_>>()
             {
                 let edge = &mut self.graph[edge_id];
-                edge.activation_queue.push(Activation {
+                let activation = Activation {
                     at: self.timestep + 1 + (self.max_myelination - edge.myelination),
                     queued_at: self.timestep,
-                });
+                };
+                match &self.queue_limit {
+                    Some(limit) => {
+                        if limit.push(&mut edge.activation_queue, activation) {
+                            self.overflowed_activations += 1;
+                        }
+                    }
+                    None => edge.activation_queue.push(activation),
+                }
 
                 if edge.myelination >= self.max_myelination {
                     continue;
@@ -242,10 +511,16 @@ where
 
                 if self.rng.gen_bool(myelination_prob) {
                     edge.myelination += 1;
+                    if self.track_myelination {
+                        let (source, target) = self.graph.edge_endpoints(edge_id).unwrap();
+                        myelination_changes.push((source.index(), target.index(), self.graph[edge_id].myelination));
+                    }
                 }
             }
         }
 
+        let activation = lap(&mut phase_start);
+
         StepResult {
             removed_edges: pending_removed_edges
                 .iter()
@@ -255,6 +530,85 @@ where
                 .iter()
                 .map(|(a, b)| (a.index(), b.index()))
                 .collect(),
+            activated_nodes,
+            removed_nodes: Vec::new(),
+            myelination_changes,
+            deliveries: deliveries
+                .into_iter()
+                .map(|(id, queued_at)| {
+                    let (source, target) = self.graph.edge_endpoints(id).unwrap();
+                    Delivery {
+                        source: source.index(),
+                        target: target.index(),
+                        queued_at,
+                    }
+                })
+                .collect(),
+            timings: phase_start.map(|_| StepTimings {
+                decay,
+                attachment,
+                activation,
+            }),
+        }
+    }
+
+    /// Removes nodes along with all of their edges, as if they had been
+    /// lesioned. Nodes which are already gone are skipped. The removed edges
+    /// and nodes are reported like those of a step, so that anything tracking
+    /// the graph can follow along.
+    pub fn lesion_nodes(&mut self, nodes: &[usize]) -> StepResult {
+        let mut removed_edges = Vec::new();
+        let mut removed_nodes = Vec::new();
+
+        for &id in nodes {
+            let index = NodeIndex::new(id);
+
+            if !self.graph.contains_node(index) {
+                continue;
+            }
+
+            for direction in &[EdgeDirection::Outgoing, EdgeDirection::Incoming] {
+                removed_edges.extend(
+                    self.graph
+                        .edges_directed(index, *direction)
+                        .map(|edge_ref| (edge_ref.source().index(), edge_ref.target().index())),
+                );
+            }
+
+            self.graph.remove_node(index);
+            removed_nodes.push(id);
+        }
+
+        StepResult {
+            removed_edges,
+            added_edges: Vec::new(),
+            activated_nodes: Vec::new(),
+            removed_nodes,
+            myelination_changes: Vec::new(),
+            deliveries: Vec::new(),
+            timings: None,
+        }
+    }
+
+    /// Lesions every node within `radius` of `center`, as in
+    /// [`lesion_nodes`](Self::lesion_nodes).
+    pub fn lesion_region(&mut self, center: Point3<f64>, radius: f64) -> StepResult {
+        let nodes = self.nodes_within(&center, radius);
+
+        self.lesion_nodes(&nodes)
+    }
+}
+
+/// Returns the time since `start` and restarts it there, or zero if the step
+/// isn't timed.
+fn lap(start: &mut Option<Instant>) -> Duration {
+    match start {
+        Some(start) => {
+            let now = Instant::now();
+            let elapsed = now - *start;
+            *start = now;
+            elapsed
         }
+        None => Duration::ZERO,
     }
 }
@@ -0,0 +1,95 @@
+//! When to take snapshots of a run, such as its complexes and metrics or
+//! frames of its graph. Fixed intervals either miss the fast changes early in
+//! a run or pile up near-identical snapshots late in a long one, so snapshots
+//! can instead be spaced evenly in the logarithm of the step, or taken when
+//! the graph has changed enough since the last.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How the steps snapshots are taken after are chosen.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "kebab-case"))]
+pub enum Schedule {
+    /// Every so many steps, as the snapshots are configured.
+    #[default]
+    Interval,
+    /// `per_decade` times for each tenfold increase in the step, after every
+    /// step at first and fewer and fewer after.
+    Logarithmic { per_decade: usize },
+    /// Whenever the number of edges has changed by more than the fraction
+    /// `change` of what it was at the last snapshot, but no more often than
+    /// every `min_interval` steps.
+    Adaptive { change: f64, min_interval: usize },
+}
+
+impl Schedule {
+    /// Returns whether the schedule can take snapshots at all.
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Schedule::Interval => true,
+            Schedule::Logarithmic { per_decade } => per_decade > 0,
+            Schedule::Adaptive { change, min_interval } => change >= 0. && min_interval > 0,
+        }
+    }
+}
+
+/// Decides, step by step, whether a snapshot is due.
+pub struct Scheduler {
+    schedule: Schedule,
+    interval: usize,
+    /// The number of logarithmic snapshots due so far.
+    num_due: usize,
+    last_step: usize,
+    last_edges: usize,
+}
+
+impl Scheduler {
+    /// Creates a scheduler following `schedule`, with snapshots every
+    /// `interval` steps if it's [`Schedule::Interval`], for a graph which
+    /// starts with `num_edges` edges.
+    pub fn new(schedule: Schedule, interval: usize, num_edges: usize) -> Self {
+        Self {
+            schedule,
+            interval,
+            num_due: 0,
+            last_step: 0,
+            last_edges: num_edges,
+        }
+    }
+
+    /// Returns whether a snapshot is due after `step`, when the graph has
+    /// `num_edges` edges, and if so counts it as taken. Steps are numbered
+    /// from 1 and have to be given in order.
+    pub fn is_due(&mut self, step: usize, num_edges: usize) -> bool {
+        let due = match self.schedule {
+            Schedule::Interval => step.is_multiple_of(self.interval),
+            Schedule::Logarithmic { per_decade } => {
+                let due = step >= logarithmic_step(self.num_due, per_decade);
+                while step >= logarithmic_step(self.num_due, per_decade) {
+                    self.num_due += 1;
+                }
+                due
+            }
+            Schedule::Adaptive { change, min_interval } => {
+                let difference = (num_edges as f64 - self.last_edges as f64).abs();
+                step - self.last_step >= min_interval && difference > change * self.last_edges as f64
+            }
+        };
+
+        if due {
+            self.last_step = step;
+            self.last_edges = num_edges;
+        }
+        due
+    }
+}
+
+/// Returns the step the `k`th logarithmic snapshot is due after, the first
+/// step at least `10^(k / per_decade)`.
+fn logarithmic_step(k: usize, per_decade: usize) -> usize {
+    let step = 10f64.powf(k as f64 / per_decade as f64);
+    // Powers of ten which should be whole can come out a hair above.
+    (step - 1e-9).ceil() as usize
+}
@@ -0,0 +1,132 @@
+//! Runtime statistics of a run, to find which part of it is the bottleneck
+//! for a set of parameters: the memory in use, the size of the graph and of
+//! the boundary matrices of its complex, and how long each phase of a step
+//! takes on average, reported every so many steps.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use petgraph::stable_graph::StableDiGraph;
+use serde::Serialize;
+
+use crate::sim::{EdgeWeight, NodeWeight, StepTimings};
+use crate::simplex::SimplicialComplex;
+
+/// The statistics of a run at a timestep, with the mean time each phase of a
+/// step took since the last report.
+#[derive(Clone, Debug, Serialize)]
+pub struct StatsReport {
+    pub timestep: usize,
+    /// The resident set size of the process, where it can be read.
+    pub rss_bytes: Option<u64>,
+    pub num_nodes: usize,
+    pub num_edges: usize,
+    /// The rows and columns of each boundary matrix of the complex.
+    pub boundary_shapes: Vec<(usize, usize)>,
+    pub decay_ms: f64,
+    pub attachment_ms: f64,
+    pub activation_ms: f64,
+    /// Bringing the complex in line with the changes to the graph.
+    pub topology_ms: f64,
+}
+
+impl fmt::Display for StatsReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "step {}: ", self.timestep)?;
+        if let Some(rss_bytes) = self.rss_bytes {
+            write!(f, "rss {:.1} MiB, ", rss_bytes as f64 / (1024. * 1024.))?;
+        }
+        write!(f, "{} nodes, {} edges, boundary matrices", self.num_nodes, self.num_edges)?;
+        for (rows, cols) in &self.boundary_shapes {
+            write!(f, " {}x{}", rows, cols)?;
+        }
+        write!(
+            f,
+            "; per step: decay {:.3} ms, attachment {:.3} ms, activation {:.3} ms, topology {:.3} ms",
+            self.decay_ms, self.attachment_ms, self.activation_ms, self.topology_ms
+        )
+    }
+}
+
+/// Accumulates the timings of steps, and every `interval` steps writes a
+/// [`StatsReport`] to `writer` as a line of JSON.
+pub struct StatsCollector<W> {
+    interval: usize,
+    writer: W,
+    num_steps: u32,
+    totals: StepTimings,
+    topology: Duration,
+}
+
+impl<W: Write> StatsCollector<W> {
+    pub fn new(interval: usize, writer: W) -> Self {
+        Self {
+            interval,
+            writer,
+            num_steps: 0,
+            totals: StepTimings::default(),
+            topology: Duration::ZERO,
+        }
+    }
+
+    /// Adds the timings of a step, and how long the complex took to follow
+    /// it.
+    pub fn record_step(&mut self, timings: StepTimings, topology: Duration) {
+        self.num_steps += 1;
+        self.totals.decay += timings.decay;
+        self.totals.attachment += timings.attachment;
+        self.totals.activation += timings.activation;
+        self.topology += topology;
+    }
+
+    /// Writes and returns a report if `timestep` is one to report at, and
+    /// starts averaging the timings afresh.
+    pub fn sample(
+        &mut self,
+        timestep: usize,
+        graph: &StableDiGraph<NodeWeight, EdgeWeight>,
+        complex: &SimplicialComplex,
+    ) -> io::Result<Option<StatsReport>> {
+        if !timestep.is_multiple_of(self.interval) {
+            return Ok(None);
+        }
+
+        let mean_ms = |total: Duration| total.as_secs_f64() * 1000. / self.num_steps.max(1) as f64;
+        let report = StatsReport {
+            timestep,
+            rss_bytes: resident_set_size(),
+            num_nodes: graph.node_count(),
+            num_edges: graph.edge_count(),
+            boundary_shapes: complex.boundary_matrices.iter().map(|matrix| matrix.shape()).collect(),
+            decay_ms: mean_ms(self.totals.decay),
+            attachment_ms: mean_ms(self.totals.attachment),
+            activation_ms: mean_ms(self.totals.activation),
+            topology_ms: mean_ms(self.topology),
+        };
+
+        serde_json::to_writer(&mut self.writer, &report)?;
+        writeln!(self.writer)?;
+        self.writer.flush()?;
+
+        self.num_steps = 0;
+        self.totals = StepTimings::default();
+        self.topology = Duration::ZERO;
+
+        Ok(Some(report))
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Returns the resident set size of this process, on Linux, where it's read
+/// from `/proc`.
+pub fn resident_set_size() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+
+    Some(kib * 1024)
+}
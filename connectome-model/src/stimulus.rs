@@ -0,0 +1,113 @@
+//! Which nodes to stimulate, chosen by where they sit in the structure of the
+//! graph rather than uniformly at random, so that experiments can compare how
+//! stimulating hubs or the periphery shapes the wiring that follows.
+
+use std::cmp::Reverse;
+
+use nalgebra::Point3;
+use petgraph::{stable_graph::NodeIndex, EdgeDirection};
+use rand::{seq::SliceRandom, Rng};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::sim::Simulation;
+
+/// How the nodes to stimulate are chosen.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "kebab-case"))]
+pub enum Selector {
+    /// A single node of the grid, chosen uniformly, which is skipped if it's
+    /// been lesioned.
+    #[default]
+    Uniform,
+    /// The `count` nodes of highest in-degree.
+    Hubs { count: usize },
+    /// The `count` nodes of lowest degree, counting edges both ways.
+    Periphery { count: usize },
+    /// A node chosen uniformly from each region with any left, splitting the
+    /// box around the nodes into `divisions` regions along each axis.
+    Regions { divisions: usize },
+}
+
+impl Selector {
+    /// Returns whether the selector chooses any nodes at all.
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Selector::Uniform => true,
+            Selector::Hubs { count } | Selector::Periphery { count } => count > 0,
+            Selector::Regions { divisions } => divisions > 0,
+        }
+    }
+
+    /// Chooses nodes of the simulation, which was laid out with `num_nodes`
+    /// nodes, drawing from `rng`. Nodes with the same degree are ranked in a
+    /// random order.
+    pub fn select<R: Rng, S: Rng>(&self, simulation: &Simulation<R>, num_nodes: usize, rng: &mut S) -> Vec<usize> {
+        let graph = &simulation.graph;
+        match *self {
+            Selector::Uniform => vec![rng.gen_range(0, num_nodes)],
+            Selector::Hubs { count } => ranked(simulation, count, rng, |node| {
+                Reverse(graph.neighbors_directed(node, EdgeDirection::Incoming).count())
+            }),
+            Selector::Periphery { count } => ranked(simulation, count, rng, |node| {
+                graph.neighbors_directed(node, EdgeDirection::Incoming).count()
+                    + graph.neighbors_directed(node, EdgeDirection::Outgoing).count()
+            }),
+            Selector::Regions { divisions } => {
+                let positions = graph.node_indices().map(|node| graph[node].position).collect::<Vec<_>>();
+                let mut regions = vec![Vec::new(); divisions.pow(3)];
+                for (node, region) in graph.node_indices().zip(regions_of(&positions, divisions)) {
+                    regions[region].push(node.index());
+                }
+
+                regions.iter().filter_map(|nodes| nodes.choose(rng).copied()).collect()
+            }
+        }
+    }
+}
+
+/// Returns the `count` nodes with the lowest `key`, with ties broken at
+/// random.
+fn ranked<R: Rng, S: Rng, T: Ord, K: Fn(NodeIndex) -> T>(
+    simulation: &Simulation<R>,
+    count: usize,
+    rng: &mut S,
+    key: K,
+) -> Vec<usize> {
+    let mut nodes = simulation.graph.node_indices().collect::<Vec<_>>();
+    nodes.shuffle(rng);
+    nodes.sort_by_key(|&node| key(node));
+    nodes.into_iter().take(count).map(|node| node.index()).collect()
+}
+
+/// Returns the region each position falls in, splitting the box around them
+/// into `divisions` regions along each axis, numbered with x varying slowest
+/// and z fastest.
+pub(crate) fn regions_of(positions: &[Point3<f64>], divisions: usize) -> Vec<usize> {
+    let (min, max) = match bounds(positions) {
+        Some(bounds) => bounds,
+        None => return Vec::new(),
+    };
+
+    positions
+        .iter()
+        .map(|position| {
+            let mut region = 0;
+            for axis in 0..3 {
+                let extent = max[axis] - min[axis];
+                let fraction = if extent > 0. { (position[axis] - min[axis]) / extent } else { 0. };
+                region = region * divisions + ((fraction * divisions as f64) as usize).min(divisions - 1);
+            }
+            region
+        })
+        .collect()
+}
+
+/// Returns the corners of the box around the positions, if there are any.
+fn bounds(positions: &[Point3<f64>]) -> Option<(Point3<f64>, Point3<f64>)> {
+    let first = *positions.first()?;
+    Some(positions.iter().fold((first, first), |(min, max), position| {
+        (min.inf(position), max.sup(position))
+    }))
+}
@@ -0,0 +1,126 @@
+//! Streams a record of every step of a run to another process as it goes, as
+//! Arrow IPC record batches, so that it can be analysed live, e.g. with
+//! `pyarrow.ipc.open_stream` or `polars`, without waiting for the run to
+//! finish or going through files.
+
+use std::io::{self, BufWriter, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+use arrow_stream::{Column, ColumnType, StreamWriter};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::sim::{Simulation, StepResult};
+
+/// The columns of the records of each step.
+pub const COLUMNS: [(&str, ColumnType); 7] = [
+    ("step", ColumnType::Int64),
+    ("nodes", ColumnType::Int64),
+    ("edges", ColumnType::Int64),
+    ("edges_added", ColumnType::Int64),
+    ("edges_removed", ColumnType::Int64),
+    ("activations", ColumnType::Int64),
+    ("mean_myelination", ColumnType::Float64),
+];
+
+/// Where a stream is written to.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum StreamTarget {
+    Stdout,
+    /// A Unix socket the reader is listening on.
+    UnixSocket { path: PathBuf },
+}
+
+impl StreamTarget {
+    /// Connects to the target.
+    pub fn open(&self) -> io::Result<Box<dyn Write>> {
+        Ok(match self {
+            StreamTarget::Stdout => Box::new(BufWriter::new(io::stdout())),
+            StreamTarget::UnixSocket { path } => Box::new(BufWriter::new(UnixStream::connect(path)?)),
+        })
+    }
+}
+
+/// Streams the records of steps in batches of `batch_size`.
+pub struct StepStream<W: Write> {
+    writer: StreamWriter<W>,
+    batch_size: usize,
+    steps: Vec<i64>,
+    nodes: Vec<i64>,
+    edges: Vec<i64>,
+    edges_added: Vec<i64>,
+    edges_removed: Vec<i64>,
+    activations: Vec<i64>,
+    mean_myelination: Vec<f64>,
+}
+
+impl<W: Write> StepStream<W> {
+    /// Starts a stream, writing its schema.
+    pub fn new(writer: W, batch_size: usize) -> io::Result<Self> {
+        Ok(Self {
+            writer: StreamWriter::new(writer, &COLUMNS)?,
+            batch_size,
+            steps: Vec::with_capacity(batch_size),
+            nodes: Vec::with_capacity(batch_size),
+            edges: Vec::with_capacity(batch_size),
+            edges_added: Vec::with_capacity(batch_size),
+            edges_removed: Vec::with_capacity(batch_size),
+            activations: Vec::with_capacity(batch_size),
+            mean_myelination: Vec::with_capacity(batch_size),
+        })
+    }
+
+    /// Records the step which ended at `simulation.timestep`, with its
+    /// `step_result`, writing a batch if it's full.
+    pub fn record<R: Rng>(&mut self, simulation: &Simulation<R>, step_result: &StepResult) -> io::Result<()> {
+        let num_edges = simulation.graph.edge_count();
+        let total_myelination = simulation
+            .graph
+            .edge_indices()
+            .map(|edge| simulation.graph[edge].myelination)
+            .sum::<usize>();
+
+        self.steps.push(simulation.timestep as i64);
+        self.nodes.push(simulation.graph.node_count() as i64);
+        self.edges.push(num_edges as i64);
+        self.edges_added.push(step_result.added_edges.len() as i64);
+        self.edges_removed.push(step_result.removed_edges.len() as i64);
+        self.activations.push(step_result.activated_nodes.len() as i64);
+        self.mean_myelination
+            .push(if num_edges > 0 { total_myelination as f64 / num_edges as f64 } else { 0. });
+
+        if self.steps.len() >= self.batch_size {
+            self.write_batch()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the steps recorded since the last batch as a batch, if there
+    /// are any, and flushes it through to the reader.
+    pub fn write_batch(&mut self) -> io::Result<()> {
+        if self.steps.is_empty() {
+            return Ok(());
+        }
+
+        let columns = [
+            Column::Int64(std::mem::take(&mut self.steps)),
+            Column::Int64(std::mem::take(&mut self.nodes)),
+            Column::Int64(std::mem::take(&mut self.edges)),
+            Column::Int64(std::mem::take(&mut self.edges_added)),
+            Column::Int64(std::mem::take(&mut self.edges_removed)),
+            Column::Int64(std::mem::take(&mut self.activations)),
+            Column::Float64(std::mem::take(&mut self.mean_myelination)),
+        ];
+        self.writer.write(&columns)?;
+        self.writer.flush()
+    }
+
+    /// Writes the last batch and ends the stream, returning the underlying
+    /// writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_batch()?;
+        self.writer.finish()
+    }
+}
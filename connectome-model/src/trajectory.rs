@@ -0,0 +1,97 @@
+//! A trajectory of a few key metrics of a run kept in memory rather than
+//! written as it goes, for exploratory runs which don't want files, or for
+//! showing live in the dashboard. A point is kept every `stride` steps, and
+//! only the last `capacity` of them, so memory stays bounded however long the
+//! run goes on.
+
+use std::collections::VecDeque;
+use std::io::{self, Write};
+
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::sim::{Simulation, StepResult};
+
+/// The metrics of a run at one step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TrajectoryPoint {
+    pub step: usize,
+    pub num_nodes: usize,
+    pub num_edges: usize,
+    /// The number of times nodes fired since the point before.
+    pub activations: usize,
+    /// The mean myelination of the edges, or 0 if there are none.
+    pub mean_myelination: f64,
+}
+
+/// Keeps the last `capacity` points of a run's trajectory, one every `stride`
+/// steps.
+pub struct TrajectoryBuffer {
+    points: VecDeque<TrajectoryPoint>,
+    capacity: usize,
+    stride: usize,
+    activations: usize,
+}
+
+impl TrajectoryBuffer {
+    pub fn new(capacity: usize, stride: usize) -> Self {
+        Self {
+            points: VecDeque::with_capacity(capacity),
+            capacity,
+            stride,
+            activations: 0,
+        }
+    }
+
+    /// Records the step which ended at `simulation.timestep`, with its
+    /// `step_result`, keeping a point if one is due and dropping the oldest if
+    /// the buffer is full.
+    pub fn record<R: Rng>(&mut self, simulation: &Simulation<R>, step_result: &StepResult) {
+        self.activations += step_result.activated_nodes.len();
+        if self.stride == 0 || !simulation.timestep.is_multiple_of(self.stride) || self.capacity == 0 {
+            return;
+        }
+
+        let num_edges = simulation.graph.edge_count();
+        let total_myelination = simulation
+            .graph
+            .edge_indices()
+            .map(|edge| simulation.graph[edge].myelination)
+            .sum::<usize>();
+        if self.points.len() == self.capacity {
+            self.points.pop_front();
+        }
+        self.points.push_back(TrajectoryPoint {
+            step: simulation.timestep,
+            num_nodes: simulation.graph.node_count(),
+            num_edges,
+            activations: std::mem::take(&mut self.activations),
+            mean_myelination: if num_edges > 0 { total_myelination as f64 / num_edges as f64 } else { 0. },
+        });
+    }
+
+    /// Returns the points kept, oldest first.
+    pub fn points(&self) -> impl Iterator<Item = &TrajectoryPoint> {
+        self.points.iter()
+    }
+
+    /// Returns the points kept, oldest first, emptying the buffer.
+    pub fn drain(&mut self) -> Vec<TrajectoryPoint> {
+        self.points.drain(..).collect()
+    }
+
+    /// Writes the points kept as CSV, oldest first.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "step,nodes,edges,activations,mean_myelination")?;
+        for point in &self.points {
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                point.step, point.num_nodes, point.num_edges, point.activations, point.mean_myelination
+            )?;
+        }
+        Ok(())
+    }
+}
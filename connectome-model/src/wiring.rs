@@ -0,0 +1,108 @@
+//! The wiring cost of the connectome: how long its edges are, as axons laid
+//! between the positions of their nodes, and how that compares with the
+//! cheapest wiring with as many edges, since wiring economy is what the grown
+//! network is most often compared with real connectomes on.
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use nalgebra::distance;
+use rand::Rng;
+
+use crate::sim::Simulation;
+
+/// The wiring cost of the graph at one timestep.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct WiringCost {
+    /// The total length of the edges.
+    pub total_length: f64,
+    pub mean_length: f64,
+    pub max_length: f64,
+    /// The number of edges of each length, in bins a unit wide: the `k`th
+    /// counts lengths in `[k, k + 1)`.
+    pub length_histogram: Vec<usize>,
+    /// The total length relative to that of the shortest edges the nodes
+    /// could have, as many as the graph has, or `None` if it has none. Paired
+    /// with the mean path length reported alongside it, this traces how the
+    /// network trades wiring cost for topology as it grows.
+    pub normalized_cost: Option<f64>,
+}
+
+/// The lengths of every pair of nodes of a graph, shortest first, to find the
+/// cheapest wiring of any number of edges. These only change when nodes are
+/// lesioned, or move.
+pub struct MinimalWiring {
+    num_nodes: usize,
+    moves: usize,
+    /// The total length of the shortest `k` pairs, at index `k`.
+    prefix_lengths: Vec<f64>,
+}
+
+impl MinimalWiring {
+    pub fn new<R: Rng>(simulation: &Simulation<R>) -> Self {
+        let positions = simulation
+            .graph
+            .node_indices()
+            .map(|id| simulation.graph[id].position)
+            .collect::<Vec<_>>();
+
+        let mut lengths = Vec::with_capacity(positions.len() * positions.len().saturating_sub(1) / 2);
+        for (i, a) in positions.iter().enumerate() {
+            lengths.extend(positions[i + 1..].iter().map(|b| distance(a, b)));
+        }
+        lengths.sort_by(f64::total_cmp);
+
+        let mut prefix_lengths = Vec::with_capacity(lengths.len() + 1);
+        prefix_lengths.push(0.);
+        for length in lengths {
+            prefix_lengths.push(prefix_lengths.last().unwrap() + length);
+        }
+
+        Self {
+            num_nodes: positions.len(),
+            moves: simulation.moves,
+            prefix_lengths,
+        }
+    }
+
+    /// The total length of the shortest `num_edges` edges between distinct
+    /// pairs of nodes, or of every pair if there aren't that many.
+    pub fn cost(&self, num_edges: usize) -> f64 {
+        self.prefix_lengths[num_edges.min(self.prefix_lengths.len() - 1)]
+    }
+
+    /// Whether these are still the lengths of the nodes of `simulation`.
+    pub fn is_current<R: Rng>(&self, simulation: &Simulation<R>) -> bool {
+        self.num_nodes == simulation.graph.node_count() && self.moves == simulation.moves
+    }
+}
+
+/// Measures the wiring cost of the graph, against the cheapest wiring of
+/// `minimal`, which has to be [current](MinimalWiring::is_current).
+pub fn wiring_cost<R: Rng>(simulation: &Simulation<R>, minimal: &MinimalWiring) -> WiringCost {
+    let graph = &simulation.graph;
+    let lengths = graph
+        .edge_indices()
+        .map(|id| {
+            let (source, target) = graph.edge_endpoints(id).unwrap();
+            distance(&graph[source].position, &graph[target].position)
+        })
+        .collect::<Vec<_>>();
+
+    let total_length = lengths.iter().sum::<f64>();
+    let max_length = lengths.iter().copied().fold(0., f64::max);
+    let mut length_histogram = vec![0; max_length as usize + 1];
+    for &length in &lengths {
+        length_histogram[length as usize] += 1;
+    }
+    let minimal_length = minimal.cost(lengths.len());
+
+    WiringCost {
+        total_length,
+        mean_length: if lengths.is_empty() { 0. } else { total_length / lengths.len() as f64 },
+        max_length,
+        length_histogram: if lengths.is_empty() { Vec::new() } else { length_histogram },
+        normalized_cost: if minimal_length > 0. { Some(total_length / minimal_length) } else { None },
+    }
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "driver")]
+
+use std::fs;
+
+use connectome_model::driver::{self, Config};
+use serde_json::Value;
+
+#[test]
+fn budgeted_betti_numbers_are_flagged_in_the_metrics() {
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(40),
+        seed: Some(435),
+        ..Config::default()
+    };
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-betti-budget");
+    let graph = driver::run(&config, out_dir.join("plain"), None).unwrap();
+    let budgeted = Config {
+        betti_budget_ms: Some(60_000),
+        ..config.clone()
+    };
+    assert_eq!(driver::run(&budgeted, out_dir.join("budgeted"), None).unwrap(), graph);
+
+    // With time to spare, the betti curves are exact, and the same as without
+    // a budget.
+    let betti_curve = |run: &str| fs::read_to_string(out_dir.join(run).join("betti_curve.csv")).unwrap();
+    assert_eq!(betti_curve("budgeted"), betti_curve("plain"));
+
+    let metrics = |run: &str| {
+        fs::read_to_string(out_dir.join(run).join("metrics.jsonl"))
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert!(metrics("plain").iter().all(|row| row.get("betti").is_none()));
+    let rows = metrics("budgeted");
+    assert_eq!(rows.len(), 4);
+    for row in &rows {
+        assert_eq!(row["betti"]["timed_out"], false);
+        assert!(row["betti"]["exact_dims"].as_u64().unwrap() > 0);
+    }
+
+    let invalid = Config {
+        betti_budget_ms: Some(0),
+        ..config
+    };
+    assert!(driver::run(&invalid, out_dir.join("invalid"), None).is_err());
+}
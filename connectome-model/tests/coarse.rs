@@ -0,0 +1,107 @@
+use connectome_model::coarse::{CoarseGraph, Partition};
+use connectome_model::sim::EdgeWeight;
+use nalgebra::Point3;
+use petgraph::graph::NodeIndex;
+
+mod common;
+
+#[test]
+fn boxes_split_the_grid_evenly() {
+    let mut simulation = common::simulation(435, 1, 4);
+    for step in 0..200 {
+        simulation.step(&[step * 7 % 64]);
+    }
+    let coarse_graph = CoarseGraph::new(&simulation, Partition::Boxes { divisions: 2 });
+
+    assert_eq!(coarse_graph.blocks.len(), 8);
+    assert!(coarse_graph.blocks.iter().all(|block| block.size == 8));
+    // The first box is the lowest corner of the grid, and x varies slowest.
+    assert_eq!(coarse_graph.blocks[0].centroid, Some(Point3::new(-1., -1., -1.)));
+    assert_eq!(coarse_graph.blocks[4].centroid, Some(Point3::new(1., -1., -1.)));
+    assert_eq!(coarse_graph.membership[0], Some(0));
+    assert_eq!(coarse_graph.membership[63], Some(7));
+
+    let num_edges = coarse_graph.connections.values().map(|connection| connection.num_edges).sum::<usize>();
+    assert_eq!(num_edges, simulation.graph.edge_count());
+    let myelination = coarse_graph.connections.values().map(|connection| connection.myelination).sum::<usize>();
+    let edges = simulation.graph.edge_indices();
+    assert_eq!(myelination, edges.map(|edge| simulation.graph[edge].myelination).sum::<usize>());
+}
+
+#[test]
+fn communities_follow_the_components() {
+    let mut simulation = common::simulation(435, 1, 4);
+    // Two triangles, joined by nothing.
+    for &(a, b) in &[(0, 1), (1, 2), (2, 0), (60, 61), (61, 62), (62, 60)] {
+        simulation.graph.add_edge(NodeIndex::new(a), NodeIndex::new(b), EdgeWeight::default());
+    }
+    let coarse_graph = CoarseGraph::new(&simulation, Partition::Communities);
+
+    // Every other node is a block of its own.
+    assert_eq!(coarse_graph.blocks.len(), 64 - 4);
+    let block = |node: usize| coarse_graph.membership[node].unwrap();
+    assert_eq!((block(0), block(1), block(2)), (0, 0, 0));
+    assert_eq!(block(3), 1);
+    assert_eq!((block(61), block(62)), (block(60), block(60)));
+    assert_eq!(coarse_graph.blocks[block(60)].size, 3);
+    assert_eq!(coarse_graph.connections.len(), 2);
+    assert_eq!(coarse_graph.connections[&(0, 0)].num_edges, 3);
+}
+
+#[cfg(feature = "driver")]
+#[test]
+fn runs_export_their_coarse_graphs_with_each_snapshot() {
+    use std::fs;
+
+    use connectome_model::driver::{self, CoarseGraining, Config, COARSE_GRAINED_DIR};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-coarse");
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(40),
+        seed: Some(435),
+        coarse_graining: Some(CoarseGraining {
+            partition: Partition::Boxes { divisions: 3 },
+            format: "csv".to_string(),
+        }),
+        ..Config::default()
+    };
+    let graph = driver::run(&config, &out_dir, None).unwrap();
+
+    let dir = out_dir.join(COARSE_GRAINED_DIR);
+    let mut files = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    files.sort();
+    assert_eq!(files.len(), 8);
+    assert_eq!(files[0], "step_000010.csv");
+    assert_eq!(files[7], "step_000040_membership.csv");
+
+    // Each node of a 3x3x3 grid is a box of its own, so the last coarse graph
+    // is the final graph.
+    let last = fs::read_to_string(dir.join("step_000040.csv")).unwrap();
+    let mut edges = last
+        .lines()
+        .skip(1)
+        .map(|line| {
+            let fields = line.split(',').collect::<Vec<_>>();
+            assert_eq!(fields[3], "1");
+            (fields[0].parse().unwrap(), fields[1].parse().unwrap())
+        })
+        .collect::<Vec<(usize, usize)>>();
+    edges.sort();
+    let mut final_edges = graph.edges.iter().map(|&(source, target, _)| (source, target)).collect::<Vec<_>>();
+    final_edges.sort();
+    assert_eq!(edges, final_edges);
+
+    let invalid = Config {
+        coarse_graining: Some(CoarseGraining {
+            partition: Partition::Communities,
+            format: "xlsx".to_string(),
+        }),
+        ..config
+    };
+    assert!(driver::run(&invalid, out_dir.join("invalid"), None).is_err());
+}
@@ -0,0 +1,24 @@
+//! Fixtures shared by the integration tests.
+
+#![allow(dead_code)]
+
+use connectome_model::sim::Simulation;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Returns a simulation with the default rates, seeded with `seed`, on a grid
+/// of `grid_size` nodes along each side, `spacing` apart.
+pub fn simulation(seed: u64, spacing: u32, grid_size: u32) -> Simulation<StdRng> {
+    let mut simulation = Simulation::new(1., 0.5, 0.01, 5, 4, 3, StdRng::seed_from_u64(seed));
+    simulation.init_uniform(spacing, grid_size);
+    simulation
+}
+
+/// Returns a simulation as [`simulation`] does, but in which nothing attaches,
+/// myelinates or decays, so that its edges stay as they're added.
+pub fn still_simulation(seed: u64, spacing: u32, grid_size: u32) -> Simulation<StdRng> {
+    let mut simulation = simulation(seed, spacing, grid_size);
+    simulation.connectivity_rate = 0.;
+    simulation.myelination_rate = 0.;
+    simulation.decay_rate = 0.;
+    simulation
+}
@@ -0,0 +1,53 @@
+use connectome_model::conduction::{self, delay};
+use connectome_model::sim::{EdgeWeight, Simulation};
+use petgraph::graph::NodeIndex;
+use rand::rngs::StdRng;
+
+mod common;
+
+/// A 2x2x2 grid with a path of edges 0 -> 1 -> 3, the first of them fully
+/// myelinated.
+fn simulation() -> Simulation<StdRng> {
+    let mut simulation = common::simulation(0, 2, 2);
+
+    for &(source, target, myelination) in &[(0, 1, 5), (1, 3, 0)] {
+        simulation.graph.add_edge(
+            NodeIndex::new(source),
+            NodeIndex::new(target),
+            EdgeWeight {
+                myelination,
+                ..EdgeWeight::default()
+            },
+        );
+    }
+
+    simulation
+}
+
+#[test]
+fn delays_match_the_activation_queue() {
+    assert_eq!(delay(0, 5), 6);
+    assert_eq!(delay(5, 5), 1);
+}
+
+#[test]
+fn myelination_shortens_paths() {
+    let conduction = conduction::conduction(&simulation());
+
+    // 0 -> 1 takes 1, 1 -> 3 takes 6, and 0 -> 3 takes 7, among 56 ordered
+    // pairs; unmyelinated, they'd take 6, 6 and 12.
+    assert!((conduction.mean_delay - 14. / 3.).abs() < 1e-12);
+    assert!((conduction.global_efficiency - (1. + 1. / 6. + 1. / 7.) / 56.).abs() < 1e-12);
+    assert!((conduction.unmyelinated_efficiency - (2. / 6. + 1. / 12.) / 56.).abs() < 1e-12);
+    assert!(conduction.myelination_gain.unwrap() > 3.);
+}
+
+#[test]
+fn edgeless_graphs_have_no_gain() {
+    let simulation = common::simulation(0, 1, 2);
+    let conduction = conduction::conduction(&simulation);
+
+    assert_eq!(conduction.mean_delay, 0.);
+    assert_eq!(conduction.global_efficiency, 0.);
+    assert_eq!(conduction.myelination_gain, None);
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "driver")]
+
+use std::fs;
+
+use connectome_model::driver::{self, Config, SHUFFLED_CONTROL_FILE};
+
+#[test]
+fn controls_are_recorded_with_the_complexes_without_changing_the_run() {
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(60),
+        seed: Some(435),
+        connectivity_rate: 0.5,
+        ..Config::default()
+    };
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-controls");
+    let graph = driver::run(&config, out_dir.join("plain"), None).unwrap();
+    let controlled = Config {
+        shuffled_controls: true,
+        ..config
+    };
+    assert_eq!(driver::run(&controlled, out_dir.join("controlled"), None).unwrap(), graph);
+
+    let controls = fs::read_to_string(out_dir.join("controlled").join(SHUFFLED_CONTROL_FILE)).unwrap();
+    let betti_curve = fs::read_to_string(out_dir.join("controlled").join("betti_curve.csv")).unwrap();
+    let steps = |csv: &str| {
+        csv.lines()
+            .skip(1)
+            .map(|line| line.split(',').next().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(steps(&controls), steps(&betti_curve));
+
+    // The controls have as many vertices and edges as the graph.
+    let header = controls.lines().next().unwrap().split(',').collect::<Vec<_>>();
+    let column = |name: &str| header.iter().position(|&column| column == name).unwrap();
+    for line in controls.lines().skip(1) {
+        let row = line.split(',').collect::<Vec<_>>();
+        for name in &["simplices_ratio_0", "simplices_ratio_1"] {
+            assert!(row[column(name)].is_empty() || row[column(name)] == "1", "{}", line);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+#![cfg(feature = "driver")]
+
+use std::fs;
+
+use connectome_model::driver::{self, Config, Lesion};
+use connectome_model::experiment::{self, Action, Event, Parameter};
+use connectome_model::stimulus::Selector;
+use graph_io::AttributeValue;
+
+fn config() -> Config {
+    Config {
+        grid_size: 3,
+        num_steps: Some(60),
+        seed: Some(435),
+        ..Config::default()
+    }
+}
+
+#[test]
+fn scripts_are_lists_of_events() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("connectome-script.json");
+    fs::write(
+        &path,
+        r#"[
+            { "step": 5, "action": "stimulate", "nodes": [0, 1] },
+            { "step": 8, "action": "stimulate-selected", "selector": { "kind": "hubs", "count": 2 } },
+            { "step": 10, "action": "lesion", "radius": 1.5 },
+            { "step": 10, "action": "set", "parameter": "decay-rate", "value": 0.02 }
+        ]"#,
+    )
+    .unwrap();
+
+    assert_eq!(experiment::load(&path).unwrap(), vec![
+        Event {
+            step: 5,
+            action: Action::Stimulate { nodes: vec![0, 1] },
+        },
+        Event {
+            step: 8,
+            action: Action::StimulateSelected {
+                selector: Selector::Hubs { count: 2 },
+            },
+        },
+        Event {
+            step: 10,
+            action: Action::Lesion {
+                center: [0.; 3],
+                radius: 1.5,
+            },
+        },
+        Event {
+            step: 10,
+            action: Action::Set {
+                parameter: Parameter::DecayRate,
+                value: 0.02,
+            },
+        },
+    ]);
+}
+
+#[test]
+fn scripted_lesions_match_the_built_in_one() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let built_in_dir = temp_dir.path().join("connectome-experiment-built-in");
+    let scripted_dir = temp_dir.path().join("connectome-experiment-scripted");
+    let built_in = Config {
+        lesion: Some(Lesion { step: 30, radius: 1.0 }),
+        ..config()
+    };
+    let scripted = Config {
+        events: vec![Event {
+            step: 30,
+            action: Action::Lesion {
+                center: [0.; 3],
+                radius: 1.0,
+            },
+        }],
+        ..config()
+    };
+
+    driver::run(&built_in, &built_in_dir, None).unwrap();
+    driver::run(&scripted, &scripted_dir, None).unwrap();
+
+    for name in &["lesion_recovery.csv", "metrics.jsonl", "betti_curve.csv"] {
+        assert_eq!(
+            fs::read_to_string(built_in_dir.join(name)).unwrap(),
+            fs::read_to_string(scripted_dir.join(name)).unwrap(),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn parameters_change_from_their_step_on() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-experiment-set");
+    let config = Config {
+        num_steps: Some(200),
+        myelination_rate: 1.,
+        events: vec![
+            Event {
+                step: 150,
+                action: Action::Set {
+                    parameter: Parameter::MaxMyelination,
+                    value: 1.,
+                },
+            },
+            Event {
+                step: 150,
+                action: Action::Stimulate { nodes: vec![13] },
+            },
+        ],
+        ..config()
+    };
+
+    let graph = driver::run(&config, &out_dir, None).unwrap();
+
+    assert!(!graph.edges.is_empty());
+    for (_, _, attributes) in &graph.edges {
+        assert!(matches!(attributes["myelination"], AttributeValue::Int(0) | AttributeValue::Int(1)));
+    }
+}
+
+#[test]
+fn invalid_events_are_rejected() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-experiment-invalid");
+    let invalid = [
+        Event {
+            step: 0,
+            action: Action::Stimulate { nodes: vec![0] },
+        },
+        Event {
+            step: 5,
+            action: Action::Stimulate { nodes: vec![27] },
+        },
+        Event {
+            step: 5,
+            action: Action::Set {
+                parameter: Parameter::DecayRate,
+                value: 2.,
+            },
+        },
+        Event {
+            step: 5,
+            action: Action::Set {
+                parameter: Parameter::RefractoryPeriod,
+                value: 1.5,
+            },
+        },
+        Event {
+            step: 5,
+            action: Action::StimulateSelected {
+                selector: Selector::Periphery { count: 0 },
+            },
+        },
+    ];
+
+    for event in invalid.iter().cloned() {
+        let config = Config {
+            events: vec![event],
+            ..config()
+        };
+        let err = driver::run(&config, &out_dir, None).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
+
+#[test]
+fn parameter_changes_are_logged_in_the_manifest() {
+    use std::sync::Mutex;
+
+    use provenance::{Manifest, MANIFEST_FILE};
+    use serde_json::{json, Value};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-experiment-logged");
+    let config = Config {
+        events: vec![Event {
+            step: 20,
+            action: Action::Set {
+                parameter: Parameter::DecayRate,
+                value: 0.,
+            },
+        }],
+        ..config()
+    };
+
+    let manifest = Mutex::new(Manifest::start(&out_dir, "connectome run", "0.1.0", &config, &[435]).unwrap());
+    driver::run_logged(&config, &out_dir, None, &manifest).unwrap();
+    manifest.into_inner().unwrap().finish().unwrap();
+
+    let manifest: Value = serde_json::from_str(&fs::read_to_string(out_dir.join(MANIFEST_FILE)).unwrap()).unwrap();
+    assert_eq!(
+        manifest["log"],
+        json!([{ "run": 0, "step": 20, "parameter": "decay-rate", "from": 0.01, "to": 0.0 }])
+    );
+}
+
+#[test]
+fn runs_can_be_driven_through_their_hubs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-experiment-hubs");
+    let driven = Config {
+        input: Selector::Hubs { count: 2 },
+        events: vec![Event {
+            step: 30,
+            action: Action::StimulateSelected {
+                selector: Selector::Regions { divisions: 2 },
+            },
+        }],
+        ..config()
+    };
+    let graph = driver::run(&driven, &out_dir, None).unwrap();
+    assert_eq!(driver::run(&driven, &out_dir, None).unwrap(), graph);
+    assert_ne!(driver::run(&config(), &out_dir, None).unwrap(), graph);
+
+    let no_input = Config {
+        input: Selector::Hubs { count: 0 },
+        ..driven
+    };
+    assert!(driver::run(&no_input, &out_dir, None).is_err());
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "driver")]
+
+use std::collections::BTreeSet;
+
+use connectome_model::driver::{self, Config, Frames};
+use connectome_model::frames::{self, FrameFormat};
+use connectome_model::snapshots::Schedule;
+
+/// Returns the `pos` of each node of a DOT frame.
+fn positions(dot: &str) -> Vec<String> {
+    dot.lines()
+        .filter(|line| !line.contains("->"))
+        .filter_map(|line| line.split("\"pos\"=\"").nth(1))
+        .map(|rest| rest.split('"').next().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn frames_have_stable_positions() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-frames");
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(20),
+        seed: Some(435),
+        frames: Some(Frames {
+            interval: 10,
+            format: FrameFormat::Dot,
+            schedule: Schedule::Interval,
+        }),
+        ..Config::default()
+    };
+
+    driver::run(&config, &out_dir, None).unwrap();
+
+    let frames_dir = out_dir.join(frames::FRAMES_DIR);
+    let names = std::fs::read_dir(&frames_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<BTreeSet<_>>();
+    assert_eq!(
+        names.into_iter().collect::<Vec<_>>(),
+        vec!["frame_000000.dot", "frame_000001.dot", "frame_000002.dot"]
+    );
+
+    let first = std::fs::read_to_string(frames_dir.join("frame_000000.dot")).unwrap();
+    let last = std::fs::read_to_string(frames_dir.join("frame_000002.dot")).unwrap();
+    assert!(!first.contains("->"));
+    assert!(last.contains("->"));
+    assert_eq!(positions(&first).len(), 27);
+    assert_eq!(positions(&first), positions(&last));
+    // Nodes without edges are in no core but the 0-core.
+    assert_eq!(first.matches("\"core\"=\"0\"").count(), 27);
+
+    // No two nodes of the grid are drawn in the same place.
+    assert_eq!(positions(&first).into_iter().collect::<BTreeSet<_>>().len(), 27);
+}
+
@@ -0,0 +1,114 @@
+use connectome_model::functional::{self, SpikeRecorder};
+use nalgebra::DMatrix;
+use petgraph::graph::NodeIndex;
+use petgraph::stable_graph::StableDiGraph;
+
+#[test]
+fn old_spikes_leave_the_window() {
+    let mut spikes = SpikeRecorder::new(3);
+    spikes.record(1, &[0, 1]);
+    spikes.record(2, &[2]);
+    assert_eq!(spikes.num_spikes(), 3);
+
+    spikes.record(4, &[]);
+    assert_eq!(spikes.num_spikes(), 1);
+}
+
+#[test]
+fn coincident_spikes_pair_up() {
+    let mut spikes = SpikeRecorder::new(10);
+    spikes.record(1, &[0, 1]);
+    spikes.record(2, &[2]);
+    spikes.record(5, &[3]);
+
+    let mut pairs = spikes.co_active_pairs(1).into_iter().collect::<Vec<_>>();
+    pairs.sort_unstable();
+    assert_eq!(pairs, vec![(0, 1), (0, 2), (1, 2)]);
+
+    // Nodes 0, 1 and 2 fired together, so they fill in a triangle, and node 3
+    // fired alone.
+    let complex = spikes.functional_complex(4, 1, 3);
+    assert_eq!(complex.f_vector(), vec![4, 3, 1]);
+    assert_eq!(complex.betti_numbers_full(), vec![2, 0, 0]);
+}
+
+#[test]
+fn connectivity_matrices_of_spike_trains() {
+    let mut spikes = SpikeRecorder::new(4);
+    spikes.record(1, &[0, 1]);
+    spikes.record(2, &[2]);
+    spikes.record(3, &[0, 1]);
+    spikes.record(4, &[]);
+    assert_eq!(spikes.num_bins(), 4);
+
+    // Nodes 0 and 1 always fire together, and never with node 2.
+    let correlation = spikes.correlation_matrix(4);
+    assert!((correlation[(0, 1)] - 1.).abs() < 1e-12);
+    assert!((correlation[(0, 2)] + 1. / 3f64.sqrt()).abs() < 1e-12);
+    assert_eq!(correlation[(0, 0)], 0.);
+    assert_eq!(correlation[(3, 0)], 0.);
+
+    let coincidence = spikes.coincidence_matrix(4, 1);
+    assert_eq!(coincidence[(0, 1)], 2.);
+    assert_eq!(coincidence[(1, 0)], 2.);
+    assert_eq!(coincidence[(0, 2)], 2.);
+    assert_eq!(coincidence[(0, 0)], 0.);
+
+    let mut csv = Vec::new();
+    functional::write_matrix(&coincidence, &mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().next(), Some("node,0,1,2,3"));
+    assert_eq!(csv.lines().nth(1), Some("0,0,2,2,0"));
+}
+
+#[test]
+fn structure_and_function_correlate() {
+    let mut graph = StableDiGraph::<(), ()>::new();
+    for _ in 0..3 {
+        graph.add_node(());
+    }
+    graph.add_edge(NodeIndex::new(0), NodeIndex::new(1), ());
+
+    let adjacency = functional::adjacency_matrix(&graph, 3, false);
+    assert_eq!(adjacency[(1, 0)], 1.);
+    assert_eq!(functional::adjacency_matrix(&graph, 3, true)[(1, 0)], 0.);
+
+    assert!((functional::matrix_correlation(&adjacency, &(adjacency.clone() * 2.)).unwrap() - 1.).abs() < 1e-12);
+    assert_eq!(functional::matrix_correlation(&adjacency, &DMatrix::zeros(3, 3)), None);
+}
+
+#[test]
+fn transfer_entropy_follows_the_direction_of_influence() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let source = (0..4000).map(|_| rng.gen_bool(0.5)).collect::<Vec<_>>();
+
+    // The target repeats the source a step later.
+    let target = std::iter::once(false).chain(source[..3999].iter().copied()).collect::<Vec<_>>();
+    assert!((functional::transfer_entropy(&source, &target, 1, 1) - 1.).abs() < 0.01);
+    assert!(functional::transfer_entropy(&target, &source, 1, 1) < 0.01);
+
+    // Looking at the source two steps back misses the copy.
+    assert!(functional::transfer_entropy(&source, &target, 1, 2) < 0.01);
+
+    // A node which fires a step after another gets information from it.
+    let mut spikes = SpikeRecorder::new(100);
+    for timestep in 1..=100 {
+        let mut nodes = Vec::new();
+        if source[timestep] {
+            nodes.push(0);
+        }
+        if source[timestep - 1] {
+            nodes.push(1);
+        }
+        spikes.record(timestep, &nodes);
+    }
+
+    let trains = spikes.spike_trains(3);
+    assert_eq!(trains[0], source[1..=100].to_vec());
+    let entropy = spikes.transfer_entropy_matrix(3, 2, 1);
+    assert!(entropy[(0, 1)] > 0.9, "{}", entropy);
+    assert!(entropy[(1, 0)] < 0.1, "{}", entropy);
+    assert_eq!(entropy[(2, 0)], 0.);
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "driver")]
+
+use std::collections::BTreeSet;
+
+use connectome_model::driver::{self, Config, Lesion, HISTORY_FILE};
+
+#[test]
+fn history_ends_with_the_final_graph() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-history");
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(40),
+        seed: Some(435),
+        lesion: Some(Lesion { step: 20, radius: 1.0 }),
+        history: true,
+        ..Config::default()
+    };
+
+    let graph = driver::run(&config, &out_dir, None).unwrap();
+    let gexf = std::fs::read_to_string(out_dir.join(HISTORY_FILE)).unwrap();
+
+    // The edges whose last lifespan is still going are those of the graph.
+    let mut open_edges = BTreeSet::new();
+    let mut edge = None;
+    let mut last_open = false;
+    for line in gexf.lines().map(str::trim) {
+        if line.starts_with("<edge ") {
+            let attribute = |name: &str| {
+                line.split(&format!(r#" {}=""#, name))
+                    .nth(1)
+                    .map(|rest| rest.split('"').next().unwrap().parse::<usize>().unwrap())
+            };
+            edge = Some((attribute("source").unwrap(), attribute("target").unwrap()));
+            last_open = attribute("start").is_some() && attribute("end").is_none();
+        } else if line.starts_with("<spell ") {
+            last_open = !line.contains("end=");
+        } else if line == "</edge>" && last_open {
+            open_edges.insert(edge.unwrap());
+        }
+    }
+
+    let final_edges = graph.edges.iter().map(|&(source, target, _)| (source, target)).collect::<BTreeSet<_>>();
+    assert!(!final_edges.is_empty());
+    assert_eq!(open_edges, final_edges);
+
+    // The lesioned center of the grid last existed at the lesion.
+    assert!(gexf.contains(r#"<node id="13" label="13" start="0" end="20">"#));
+    assert!(gexf.contains(r#"<node id="0" label="0" start="0">"#));
+}
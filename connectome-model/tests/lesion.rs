@@ -0,0 +1,63 @@
+use connectome_model::{lesion::RecoveryRecorder, sim::{EdgeWeight, Simulation}};
+use nalgebra::Point3;
+use petgraph::graph::NodeIndex;
+use rand::rngs::StdRng;
+
+mod common;
+
+/// A 2x2x2 grid with a path of edges 0 -> 1 -> 3 -> 7.
+fn simulation() -> Simulation<StdRng> {
+    let mut simulation = common::simulation(0, 2, 2);
+
+    for &(source, target) in &[(0, 1), (1, 3), (3, 7)] {
+        simulation
+            .graph
+            .add_edge(NodeIndex::new(source), NodeIndex::new(target), EdgeWeight::default());
+    }
+
+    simulation
+}
+
+#[test]
+fn lesioning_a_node_reports_its_edges() {
+    let mut simulation = simulation();
+    let result = simulation.lesion_nodes(&[1, 1]);
+
+    assert_eq!(result.removed_nodes, vec![1]);
+    let mut removed_edges = result.removed_edges;
+    removed_edges.sort_unstable();
+    assert_eq!(removed_edges, vec![(0, 1), (1, 3)]);
+    assert_eq!(simulation.graph.node_count(), 7);
+    assert_eq!(simulation.graph.edge_count(), 1);
+
+    // Lesioned nodes can no longer be activated.
+    simulation.step(&[1]);
+}
+
+#[test]
+fn lesioning_a_region() {
+    let mut simulation = simulation();
+
+    // Node 0 is at (-1, -1, -1), and its neighbors along each axis are 2 away.
+    let result = simulation.lesion_region(Point3::new(-1.0, -1.0, -1.0), 2.0);
+
+    let mut removed_nodes = result.removed_nodes;
+    removed_nodes.sort_unstable();
+    assert_eq!(removed_nodes, vec![0, 1, 2, 4]);
+    assert_eq!(simulation.graph.edge_count(), 1);
+}
+
+#[test]
+fn recovery_is_measured_against_the_lesion() {
+    let mut simulation = simulation();
+    let complex = connectome_model::simplex::SimplicialComplex::new((0..8).collect(), None);
+    let mut recorder = RecoveryRecorder::new(Vec::new(), &simulation, 2).unwrap();
+
+    simulation.lesion_nodes(&[7]);
+    recorder.record(&simulation, &complex).unwrap();
+
+    let output = String::from_utf8(recorder.into_inner()).unwrap();
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "steps_since_lesion,nodes,edges,edge_recovery,mean_myelination,betti_0,betti_1");
+    assert_eq!(lines[1], format!("0,7,2,{},0,8,0", 2.0 / 3.0));
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "driver")]
+
+use connectome_model::driver::Config;
+use connectome_model::sim::Simulation;
+use model::Model;
+use nalgebra::Point3;
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn checkpoints_restore_the_graph_and_queued_activations() {
+    let config = Config {
+        grid_size: 3,
+        connectivity_rate: 0.2,
+        ..Config::default()
+    };
+    let mut simulation = Simulation::init(&config, StdRng::seed_from_u64(435)).unwrap();
+    model::run(&mut simulation, 100, 0, None, |_| Ok(())).unwrap();
+    simulation.lesion_region(Point3::new(1., 1., 1.), 0.5);
+    assert!(simulation.graph.edge_count() > 0);
+
+    let json = serde_json::to_string(&simulation.checkpoint()).unwrap();
+    let checkpoint = serde_json::from_str(&json).unwrap();
+    let restored = Simulation::restore(&config, checkpoint, StdRng::seed_from_u64(0)).unwrap();
+
+    assert_eq!(restored.timestep, 100);
+    assert_eq!(restored.graph.node_count(), 26);
+    assert_eq!(restored.checkpoint(), simulation.checkpoint());
+    assert_eq!(Model::graph(&restored), Model::graph(&simulation));
+}
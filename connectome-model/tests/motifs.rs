@@ -0,0 +1,68 @@
+use connectome_model::motifs::MotifRecorder;
+use connectome_model::sim::StepResult;
+
+fn adding(added_edges: &[(usize, usize)]) -> StepResult {
+    StepResult {
+        added_edges: added_edges.to_vec(),
+        ..StepResult::default()
+    }
+}
+
+fn removing(removed_edges: &[(usize, usize)]) -> StepResult {
+    StepResult {
+        removed_edges: removed_edges.to_vec(),
+        ..StepResult::default()
+    }
+}
+
+#[test]
+fn closures_and_reciprocations_are_counted_by_window() {
+    let mut recorder = MotifRecorder::new(Vec::new(), 5, 2).unwrap();
+
+    // A wedge closed quickly, and another closed long after it opened.
+    recorder.record(1, &adding(&[(0, 1), (1, 2)])).unwrap();
+    recorder.record(2, &adding(&[(2, 0)])).unwrap();
+    recorder.record(3, &adding(&[(3, 4), (4, 5)])).unwrap();
+    recorder.record(9, &adding(&[(5, 3)])).unwrap();
+    // An edge decays and comes back the other way.
+    recorder.record(10, &removing(&[(0, 1)])).unwrap();
+    recorder.record(14, &adding(&[(1, 0)])).unwrap();
+
+    let csv = String::from_utf8(recorder.finish().unwrap()).unwrap();
+    assert_eq!(
+        csv,
+        "step,edges_added,triangle_closures,recent_closures,reciprocations,mean_reciprocation_latency\n\
+         5,5,1,1,0,\n\
+         10,1,1,0,0,\n\
+         15,1,1,0,1,13\n"
+    );
+}
+
+#[cfg(feature = "driver")]
+#[test]
+fn runs_write_their_temporal_motifs() {
+    use connectome_model::driver::{self, Config, TemporalMotifs, MOTIFS_FILE};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-motifs");
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(95),
+        seed: Some(435),
+        temporal_motifs: Some(TemporalMotifs { window: 10, delta: 5 }),
+        ..Config::default()
+    };
+
+    let graph = driver::run(&config, &out_dir, None).unwrap();
+    let table = std::fs::read_to_string(out_dir.join(MOTIFS_FILE)).unwrap();
+    let rows = table.lines().skip(1).map(|line| line.split(',').collect::<Vec<_>>()).collect::<Vec<_>>();
+
+    // Nine whole windows and a last half one.
+    assert_eq!(rows.len(), 10);
+    assert_eq!(rows[9][0], "100");
+    let edges_added = rows.iter().map(|row| row[1].parse::<usize>().unwrap()).sum::<usize>();
+    assert!(edges_added >= graph.edges.len());
+    for row in &rows {
+        assert!(row[3].parse::<usize>().unwrap() <= row[2].parse::<usize>().unwrap());
+    }
+}
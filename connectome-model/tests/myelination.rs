@@ -0,0 +1,94 @@
+#![cfg(feature = "driver")]
+
+use std::collections::BTreeMap;
+
+use connectome_model::driver::{self, Config, MYELINATION_FILE};
+use graph_io::AttributeValue;
+
+#[test]
+fn trajectories_end_at_the_final_myelination() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-myelination");
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(100),
+        seed: Some(435),
+        myelination_trajectories: true,
+        ..Config::default()
+    };
+
+    let graph = driver::run(&config, &out_dir, None).unwrap();
+    let table = std::fs::read_to_string(out_dir.join(MYELINATION_FILE)).unwrap();
+
+    let mut lines = table.lines();
+    assert_eq!(lines.next(), Some("step,source,target,myelination"));
+
+    let mut last = BTreeMap::new();
+    let mut last_step = 0;
+    for line in lines {
+        let row = line.split(',').map(|value| value.parse::<usize>().unwrap()).collect::<Vec<_>>();
+        assert!(row[0] >= last_step);
+        last_step = row[0];
+
+        // Myelination changes a level at a time, and a removed edge comes
+        // back unmyelinated.
+        let previous = last.insert((row[1], row[2]), row[3]).unwrap_or(0);
+        assert_eq!((previous as i64 - row[3] as i64).abs(), 1, "{}", line);
+    }
+    assert!(!last.is_empty());
+
+    for (source, target, attributes) in &graph.edges {
+        let myelination = match attributes["myelination"] {
+            AttributeValue::Int(myelination) => myelination as usize,
+            ref value => panic!("unexpected myelination {:?}", value),
+        };
+        assert_eq!(last.get(&(*source, *target)).copied().unwrap_or(0), myelination);
+    }
+}
+
+#[test]
+fn saturation_is_when_trajectories_first_reach_the_maximum() {
+    use connectome_model::driver::{MYELINATION_HISTOGRAM_FILE, SATURATION_FILE};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-myelination-statistics");
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(200),
+        record_interval: 50,
+        seed: Some(435),
+        myelination_trajectories: true,
+        myelination_statistics: true,
+        ..Config::default()
+    };
+
+    let graph = driver::run(&config, &out_dir, None).unwrap();
+    let rows = |name| {
+        std::fs::read_to_string(out_dir.join(name))
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').map(|value| value.parse::<usize>().unwrap()).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+
+    let mut first_saturated = BTreeMap::new();
+    for row in rows(MYELINATION_FILE) {
+        if row[3] == config.max_myelination {
+            first_saturated.entry((row[1], row[2])).or_insert(row[0]);
+        }
+    }
+    let saturated = rows(SATURATION_FILE)
+        .into_iter()
+        .map(|row| ((row[0], row[1]), row[2]))
+        .collect::<BTreeMap<_, _>>();
+    assert!(!saturated.is_empty());
+    assert_eq!(saturated, first_saturated);
+
+    // A row for each level at each of the 4 records, the last counting every
+    // edge of the final graph.
+    let histogram = rows(MYELINATION_HISTOGRAM_FILE);
+    assert_eq!(histogram.len(), 4 * (config.max_myelination + 1));
+    let last = histogram.iter().filter(|row| row[0] == 200).map(|row| row[2]).sum::<usize>();
+    assert_eq!(last, graph.edges.len());
+}
@@ -0,0 +1,90 @@
+#[cfg(feature = "driver")]
+use connectome_model::driver::{self, Config};
+use connectome_model::noise::{self, PositionNoise};
+use connectome_model::sim::Simulation;
+use connectome_model::wiring::MinimalWiring;
+use nalgebra::{distance, Point3};
+use rand::rngs::StdRng;
+
+mod common;
+
+fn positions(simulation: &Simulation<StdRng>) -> Vec<Point3<f64>> {
+    simulation.graph.node_indices().map(|id| simulation.graph[id].position).collect()
+}
+
+#[test]
+fn jitter_offsets_nodes_from_the_grid() {
+    let grid = positions(&common::simulation(435, 1, 3));
+    let mut jittered = common::simulation(435, 1, 3);
+    noise::displace(&mut jittered, 0.1);
+    assert_eq!(jittered.moves, 1);
+
+    let offsets = grid
+        .iter()
+        .zip(&positions(&jittered))
+        .map(|(a, b)| distance(a, b))
+        .collect::<Vec<_>>();
+    assert!(offsets.iter().all(|&offset| offset > 0. && offset < 1.));
+    // The mean square offset is 3σ², over three coordinates.
+    let mean_square = offsets.iter().map(|offset| offset * offset).sum::<f64>() / offsets.len() as f64;
+    assert!((mean_square / 0.03 - 1.).abs() < 0.5);
+
+    // No noise draws nothing, and moves nothing.
+    let mut still = common::simulation(435, 1, 3);
+    noise::displace(&mut still, 0.);
+    assert_eq!((positions(&still), still.moves), (grid, 0));
+    assert!(!PositionNoise { jitter: -1., drift: 0. }.is_valid());
+    assert!(PositionNoise::default().is_valid());
+}
+
+#[test]
+fn drifting_nodes_wander_as_the_run_goes_on() {
+    let mut drifting = common::simulation(435, 1, 3);
+    drifting.position_drift = 0.05;
+    let minimal_wiring = MinimalWiring::new(&drifting);
+    let start = positions(&drifting);
+
+    let mut spread = Vec::new();
+    for step in 0..100 {
+        drifting.step(&[step % 27]);
+        if step % 50 == 49 {
+            let now = positions(&drifting);
+            spread.push(start.iter().zip(&now).map(|(a, b)| distance(a, b)).sum::<f64>());
+        }
+    }
+    assert_eq!(drifting.moves, 100);
+    assert!(spread[1] > spread[0]);
+    assert!(!minimal_wiring.is_current(&drifting));
+    assert!(MinimalWiring::new(&drifting).is_current(&drifting));
+}
+
+#[cfg(feature = "driver")]
+#[test]
+fn runs_without_noise_are_unchanged() {
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(40),
+        seed: Some(435),
+        ..Config::default()
+    };
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-noise");
+    let graph = driver::run(&config, out_dir.join("plain"), None).unwrap();
+    let noiseless = Config {
+        position_noise: Some(PositionNoise::default()),
+        ..config.clone()
+    };
+    assert_eq!(driver::run(&noiseless, out_dir.join("noiseless"), None).unwrap(), graph);
+
+    let noisy = Config {
+        position_noise: Some(PositionNoise { jitter: 0.2, drift: 0.01 }),
+        ..config.clone()
+    };
+    assert!(driver::run(&noisy, out_dir.join("noisy"), None).is_ok());
+
+    let invalid = Config {
+        position_noise: Some(PositionNoise { jitter: -0.2, drift: 0. }),
+        ..config
+    };
+    assert!(driver::run(&invalid, out_dir.join("invalid"), None).is_err());
+}
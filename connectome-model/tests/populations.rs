@@ -0,0 +1,74 @@
+#![cfg(feature = "driver")]
+
+use connectome_model::driver::{self, Config, Population, HISTORY_FILE};
+use graph_io::AttributeValue;
+
+fn population(graph: &graph_io::AttributedGraph, id: usize) -> Option<&str> {
+    let (_, attributes) = graph.nodes.iter().find(|(node, _)| *node == id).unwrap();
+    match attributes.get("population") {
+        Some(AttributeValue::String(population)) => Some(population),
+        None => None,
+        Some(value) => panic!("unexpected population {:?}", value),
+    }
+}
+
+#[test]
+fn populations_are_exported_with_their_nodes() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-populations");
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(20),
+        seed: Some(435),
+        history: true,
+        populations: vec![
+            Population {
+                name: "sensory".to_string(),
+                nodes: vec![0, 1, 2],
+                center: [0.; 3],
+                radius: None,
+            },
+            // The center of the grid, and its six neighbors.
+            Population {
+                name: "internal".to_string(),
+                nodes: Vec::new(),
+                center: [0.; 3],
+                radius: Some(1.),
+            },
+        ],
+        ..Config::default()
+    };
+
+    let graph = driver::run(&config, &out_dir, None).unwrap();
+
+    assert_eq!(population(&graph, 0), Some("sensory"));
+    assert_eq!(population(&graph, 13), Some("internal"));
+    assert_eq!(population(&graph, 26), None);
+    let num_internal = (0..27).filter(|&id| population(&graph, id) == Some("internal")).count();
+    assert_eq!(num_internal, 7);
+
+    let mut graphml = Vec::new();
+    graph_io::exporter("graphml").unwrap().export(&graph, &mut graphml).unwrap();
+    assert!(String::from_utf8(graphml).unwrap().contains(">sensory<"));
+    let history = std::fs::read_to_string(out_dir.join(HISTORY_FILE)).unwrap();
+    assert!(history.contains(r#"value="internal""#));
+}
+
+#[test]
+fn populations_have_to_be_in_the_grid() {
+    let config = Config {
+        grid_size: 2,
+        num_steps: Some(1),
+        populations: vec![Population {
+            name: "sensory".to_string(),
+            nodes: vec![8],
+            center: [0.; 3],
+            radius: None,
+        }],
+        ..Config::default()
+    };
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let err = driver::run(&config, temp_dir.path().join("connectome-populations-invalid"), None).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,137 @@
+use connectome_model::protocol::{self, Protocol};
+use connectome_model::sim::{EdgeWeight, Simulation};
+use petgraph::graph::NodeIndex;
+use rand::rngs::StdRng;
+
+mod common;
+
+/// A 2x2x2 grid with a path of edges 0 -> 1 -> 3 -> 7 which neither grows,
+/// decays nor myelinates.
+fn simulation() -> Simulation<StdRng> {
+    myelinated_simulation(0)
+}
+
+/// The same path with every edge myelinated to `myelination`.
+fn myelinated_simulation(myelination: usize) -> Simulation<StdRng> {
+    let mut simulation = common::still_simulation(0, 2, 2);
+
+    for &(source, target) in &[(0, 1), (1, 3), (3, 7)] {
+        simulation
+            .graph
+            .add_edge(
+                NodeIndex::new(source),
+                NodeIndex::new(target),
+                EdgeWeight {
+                    myelination,
+                    ..EdgeWeight::default()
+                },
+            );
+    }
+
+    simulation
+}
+
+#[test]
+fn responses_travel_down_the_path() {
+    let mut simulation = simulation();
+    let protocol = Protocol {
+        stimulus: vec![0],
+        readouts: Vec::new(),
+        num_trials: 2,
+        trial_length: 20,
+    };
+
+    let mut num_steps = 0;
+    let trials = protocol.run(&mut simulation, |_, _| num_steps += 1);
+
+    assert_eq!(num_steps, 40);
+    assert_eq!(trials[0].onset, 1);
+    assert_eq!(trials[1].onset, 21);
+    for trial in &trials {
+        // An unmyelinated edge delivers 6 timesteps after its source fires.
+        let latencies = trial.latencies.iter().map(|(&node, &latency)| (node, latency)).collect::<Vec<_>>();
+        assert_eq!(latencies, vec![(0, 0), (1, 6), (3, 12), (7, 18)]);
+    }
+}
+
+#[test]
+fn myelination_speeds_activity_to_the_readouts() {
+    let protocol = Protocol {
+        stimulus: vec![0, 2],
+        readouts: vec![3, 7],
+        num_trials: 1,
+        trial_length: 20,
+    };
+
+    let trials = protocol.run(&mut simulation(), |_, _| {});
+    let latencies = trials[0].readout_latencies.iter().map(|(&pair, &latency)| (pair, latency)).collect::<Vec<_>>();
+    // Node 2 fires too, but nothing it fires reaches the readouts.
+    assert_eq!(latencies, vec![((0, 3), 12), ((0, 7), 18)]);
+
+    let trials = protocol.run(&mut myelinated_simulation(5), |_, _| {});
+    let latencies = trials[0].readout_latencies.iter().map(|(&pair, &latency)| (pair, latency)).collect::<Vec<_>>();
+    assert_eq!(latencies, vec![((0, 3), 2), ((0, 7), 3)]);
+
+    let mut csv = Vec::new();
+    protocol::write_readout_latencies(&trials, &mut csv).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(), "trial,stimulated,readout,latency\n0,0,3,2\n0,0,7,3\n");
+}
+
+#[test]
+fn latencies_are_tabulated_by_trial_and_node() {
+    let mut simulation = simulation();
+    let protocol = Protocol {
+        stimulus: vec![0],
+        readouts: Vec::new(),
+        num_trials: 1,
+        trial_length: 8,
+    };
+    let trials = protocol.run(&mut simulation, |_, _| {});
+
+    let mut csv = Vec::new();
+    protocol::write_latencies(&trials, 4, &mut csv).unwrap();
+
+    assert_eq!(String::from_utf8(csv).unwrap(), "trial,onset,0,1,2,3\n0,1,0,6,,\n");
+}
+
+#[cfg(feature = "driver")]
+#[test]
+fn runs_write_their_stimulus_responses() {
+    use connectome_model::driver::{self, Config, Stimulation, READOUT_LATENCY_FILE, STIMULUS_RESPONSE_FILE};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("math435-connectome-protocol");
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(20),
+        stimulation: Some(Stimulation {
+            nodes: vec![0, 13],
+            readouts: vec![26],
+            num_trials: 3,
+            trial_length: 10,
+        }),
+        seed: Some(435),
+        ..Config::default()
+    };
+
+    driver::run(&config, &out_dir, None).unwrap();
+
+    let table = std::fs::read_to_string(out_dir.join(STIMULUS_RESPONSE_FILE)).unwrap();
+    let rows = table.lines().collect::<Vec<_>>();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0].split(',').count(), 2 + 27);
+    assert!(rows[1].starts_with("0,21,"));
+    let table = std::fs::read_to_string(out_dir.join(READOUT_LATENCY_FILE)).unwrap();
+    assert!(table.starts_with("trial,stimulated,readout,latency\n"));
+
+    let invalid = Config {
+        stimulation: Some(Stimulation {
+            nodes: vec![27],
+            readouts: Vec::new(),
+            num_trials: 1,
+            trial_length: 1,
+        }),
+        ..config
+    };
+    assert!(driver::run(&invalid, &out_dir, None).is_err());
+}
@@ -0,0 +1,66 @@
+use std::collections::BinaryHeap;
+
+use connectome_model::queue::{self, Overflow, QueueLimit};
+use connectome_model::sim::{Activation, Simulation};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn activation(at: usize, queued_at: usize) -> Activation {
+    Activation { at, queued_at }
+}
+
+/// Fills a queue of capacity 3, then queues one more activation.
+fn overflow(overflow: Overflow) -> Vec<Activation> {
+    let limit = QueueLimit { capacity: 3, overflow };
+    let mut queue = BinaryHeap::new();
+    for &(at, queued_at) in &[(10, 4), (6, 5), (7, 6)] {
+        assert!(!limit.push(&mut queue, activation(at, queued_at)));
+    }
+    assert!(limit.push(&mut queue, activation(12, 7)));
+
+    let mut activations = queue.into_sorted_vec();
+    activations.reverse();
+    activations
+}
+
+#[test]
+fn full_queues_follow_their_overflow_policy() {
+    assert_eq!(
+        overflow(Overflow::DropOldest),
+        vec![activation(6, 5), activation(7, 6), activation(12, 7)]
+    );
+    // The activations due at steps 6 and 7 are the closest together.
+    assert_eq!(
+        overflow(Overflow::Coalesce),
+        vec![activation(6, 5), activation(10, 4), activation(12, 7)]
+    );
+    assert_eq!(
+        overflow(Overflow::Saturate),
+        vec![activation(6, 5), activation(7, 6), activation(10, 4)]
+    );
+}
+
+#[test]
+fn queues_stay_within_their_capacity() {
+    let run = |queue_limit| {
+        let mut simulation = Simulation::new(1., 0., 0., 5, 4, 0, StdRng::seed_from_u64(435));
+        simulation.init_uniform(1, 3);
+        simulation.queue_limit = queue_limit;
+        // Node 0 fires every step, faster than its unmyelinated edges deliver.
+        for step in 0..40 {
+            simulation.step(&[0, 1 + step % 26]);
+        }
+        queue::queue_stats(&simulation)
+    };
+
+    let unlimited = run(None);
+    assert!(unlimited.max_length > 2);
+    assert_eq!(unlimited.overflowed, 0);
+
+    let limited = run(Some(QueueLimit {
+        capacity: 2,
+        overflow: Overflow::DropOldest,
+    }));
+    assert!(limited.max_length <= 2);
+    assert!(limited.overflowed > 0);
+    assert!(limited.mean_length <= 2.);
+}
@@ -0,0 +1,95 @@
+use connectome_model::reservoir::{Evaluation, Task};
+
+mod common;
+
+#[test]
+fn readouts_recall_the_signal_without_changing_the_network() {
+    let mut simulation = common::simulation(435, 1, 4);
+    for step in 0..300 {
+        simulation.step(&[step * 7 % 64]);
+    }
+    let params = simulation.params();
+    let edges = simulation
+        .graph
+        .edge_indices()
+        .map(|edge| (simulation.graph.edge_endpoints(edge), simulation.graph[edge].myelination))
+        .collect::<Vec<_>>();
+
+    let evaluation = Evaluation {
+        input: vec![0, 1, 2],
+        num_symbols: 200,
+        symbol_length: 4,
+        washout: 10,
+        max_delay: 3,
+        ridge: 1.,
+    };
+    assert!(evaluation.is_valid());
+    let report = evaluation.run(&mut simulation);
+
+    assert_eq!(simulation.params(), params);
+    assert_eq!(
+        simulation
+            .graph
+            .edge_indices()
+            .map(|edge| (simulation.graph.edge_endpoints(edge), simulation.graph[edge].myelination))
+            .collect::<Vec<_>>(),
+        edges
+    );
+
+    assert_eq!((report.num_train, report.num_test), (93, 93));
+    assert_eq!(report.scores.len(), 8);
+    // The input nodes themselves fire with the current symbol.
+    let current = &report.scores[0];
+    assert_eq!((current.task, current.delay), (Task::Memory, 0));
+    assert_eq!(current.accuracy, 1.);
+    for score in &report.scores {
+        assert!((0. ..=1.).contains(&score.accuracy));
+        assert!((0. ..=1.).contains(&score.r_squared));
+    }
+    assert!(report.memory_capacity >= current.r_squared && report.memory_capacity <= 4.);
+
+    assert!(!Evaluation { num_symbols: 17, ..evaluation.clone() }.is_valid());
+    assert!(!Evaluation { ridge: 0., ..evaluation }.is_valid());
+}
+
+#[cfg(feature = "driver")]
+#[test]
+fn runs_write_their_reservoir_scores() {
+    use std::fs;
+
+    use connectome_model::driver::{self, Config, Reservoir, RESERVOIR_FILE};
+    use serde_json::Value;
+
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(100),
+        seed: Some(435),
+        ..Config::default()
+    };
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-reservoir");
+    let graph = driver::run(&config, out_dir.join("plain"), None).unwrap();
+    let evaluated = Config {
+        reservoir: Some(Reservoir {
+            num_symbols: 100,
+            ..Reservoir::default()
+        }),
+        ..config.clone()
+    };
+    assert_eq!(driver::run(&evaluated, out_dir.join("evaluated"), None).unwrap(), graph);
+    assert!(!out_dir.join("plain").join(RESERVOIR_FILE).exists());
+
+    let report: Value =
+        serde_json::from_str(&fs::read_to_string(out_dir.join("evaluated").join(RESERVOIR_FILE)).unwrap()).unwrap();
+    assert_eq!(report["scores"].as_array().unwrap().len(), 12);
+    assert_eq!(report["scores"][6]["task"], "xor");
+
+    let invalid = Config {
+        reservoir: Some(Reservoir {
+            washout: 400,
+            ..Reservoir::default()
+        }),
+        ..config
+    };
+    assert!(driver::run(&invalid, out_dir.join("invalid"), None).is_err());
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "driver")]
+
+use std::fs;
+
+use connectome_model::driver::{self, Config, Lesion, SIMPLEX_EVENTS_FILE};
+use topology::simplex::SimplicialComplex;
+
+#[test]
+fn replaying_the_events_gives_the_final_complex() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-simplex-events");
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(40),
+        seed: Some(435),
+        lesion: Some(Lesion { step: 20, radius: 1.0 }),
+        simplex_events: true,
+        ..Config::default()
+    };
+    driver::run(&config, &out_dir, None).unwrap();
+
+    let events = fs::read_to_string(out_dir.join(SIMPLEX_EVENTS_FILE)).unwrap();
+    let mut complex = SimplicialComplex::new((0..27).collect(), None);
+    let mut last_timestep = 0;
+    for line in events.lines().skip(1) {
+        let fields = line.split(',').collect::<Vec<_>>();
+        let timestep = fields[0].parse::<usize>().unwrap();
+        let vertices = fields[3].split(' ').map(|vertex| vertex.parse().unwrap()).collect::<Vec<usize>>();
+        assert!(timestep >= last_timestep && timestep <= 40);
+        assert_eq!(fields[2].parse::<usize>().unwrap(), vertices.len() - 1);
+        match fields[1] {
+            "birth" => complex.add_with_closure(vertices).unwrap(),
+            "death" => complex.remove(vertices).unwrap(),
+            event => panic!("unknown event {}", event),
+        }
+        last_timestep = timestep;
+    }
+    // The center of the grid was lesioned.
+    assert!(events.contains("20,death,0,13\n"));
+
+    let betti_curve = fs::read_to_string(out_dir.join("betti_curve.csv")).unwrap();
+    let last = betti_curve.lines().last().unwrap().split(',').collect::<Vec<_>>();
+    assert_eq!(last[0], "40");
+    for dim in 0..3 {
+        assert_eq!(last[4 + dim].parse::<usize>().unwrap(), complex.num_simplices(dim));
+    }
+}
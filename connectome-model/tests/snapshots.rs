@@ -0,0 +1,68 @@
+use connectome_model::snapshots::{Schedule, Scheduler};
+
+/// Returns the steps up to `num_steps` a snapshot is due after, with the
+/// graph having `edges(step)` edges after each.
+fn due_steps(schedule: Schedule, num_steps: usize, edges: impl Fn(usize) -> usize) -> Vec<usize> {
+    let mut scheduler = Scheduler::new(schedule, 10, 0);
+    (1..=num_steps).filter(|&step| scheduler.is_due(step, edges(step))).collect()
+}
+
+#[test]
+fn intervals_are_fixed() {
+    assert_eq!(due_steps(Schedule::Interval, 35, |_| 0), vec![10, 20, 30]);
+}
+
+#[test]
+fn logarithmic_snapshots_thin_out() {
+    let steps = due_steps(Schedule::Logarithmic { per_decade: 3 }, 1000, |_| 0);
+    assert_eq!(steps, vec![1, 3, 5, 10, 22, 47, 100, 216, 465, 1000]);
+}
+
+#[test]
+fn adaptive_snapshots_follow_changes_in_the_edges() {
+    let schedule = Schedule::Adaptive {
+        change: 0.5,
+        min_interval: 2,
+    };
+    // The edges double every step up to 64 at step 6, then hold, then drop to
+    // a quarter at step 20.
+    let edges = |step: usize| match step {
+        1..=6 => 1 << step,
+        7..=19 => 64,
+        _ => 16,
+    };
+
+    assert_eq!(due_steps(schedule, 30, edges), vec![2, 4, 6, 20]);
+}
+
+#[test]
+fn schedules_need_to_take_snapshots() {
+    assert!(Schedule::Interval.is_valid());
+    assert!(!Schedule::Logarithmic { per_decade: 0 }.is_valid());
+    assert!(!Schedule::Adaptive {
+        change: 0.1,
+        min_interval: 0
+    }
+    .is_valid());
+}
+
+#[cfg(feature = "driver")]
+#[test]
+fn runs_record_complexes_on_their_schedule() {
+    use connectome_model::driver::{self, Config};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-snapshots");
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(100),
+        seed: Some(435),
+        snapshots: Schedule::Logarithmic { per_decade: 2 },
+        ..Config::default()
+    };
+    driver::run(&config, &out_dir, None).unwrap();
+
+    let betti_curve = std::fs::read_to_string(out_dir.join("betti_curve.csv")).unwrap();
+    let steps = betti_curve.lines().skip(1).map(|line| line.split(',').next().unwrap()).collect::<Vec<_>>();
+    assert_eq!(steps, vec!["1", "4", "10", "32", "100"]);
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "driver")]
+
+use std::time::Duration;
+
+use connectome_model::{
+    sim::StepTimings,
+    simplex::SimplicialComplex,
+    stats::{self, StatsCollector},
+};
+mod common;
+
+fn timings(millis: u64) -> StepTimings {
+    StepTimings {
+        decay: Duration::from_millis(millis),
+        attachment: Duration::from_millis(2 * millis),
+        activation: Duration::ZERO,
+    }
+}
+
+#[test]
+fn reports_mean_timings_every_interval() {
+    let simulation = common::simulation(0, 1, 2);
+    let mut complex = SimplicialComplex::new((0..8).collect(), None);
+    complex.add(vec![0, 1]).unwrap();
+
+    let mut collector = StatsCollector::new(2, Vec::new());
+    collector.record_step(timings(1), Duration::from_millis(4));
+    assert!(collector.sample(1, &simulation.graph, &complex).unwrap().is_none());
+    collector.record_step(timings(3), Duration::from_millis(6));
+
+    let report = collector.sample(2, &simulation.graph, &complex).unwrap().unwrap();
+    assert_eq!(report.num_nodes, 8);
+    assert_eq!(report.boundary_shapes, complex.boundary_matrices.iter().map(|m| m.shape()).collect::<Vec<_>>());
+    assert!((report.decay_ms - 2.).abs() < 1e-9);
+    assert!((report.attachment_ms - 4.).abs() < 1e-9);
+    assert!((report.topology_ms - 5.).abs() < 1e-9);
+
+    // The timings are averaged afresh after each report.
+    collector.record_step(timings(10), Duration::ZERO);
+    let report = collector.sample(4, &simulation.graph, &complex).unwrap().unwrap();
+    assert!((report.decay_ms - 10.).abs() < 1e-9);
+
+    let lines = String::from_utf8(collector.into_inner()).unwrap();
+    let timesteps = lines
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["timestep"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(timesteps, vec![2, 4]);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn reads_resident_set_size() {
+    assert!(stats::resident_set_size().unwrap() > 0);
+}
@@ -0,0 +1,70 @@
+use connectome_model::sim::Simulation;
+use connectome_model::stimulus::Selector;
+use petgraph::{graph::NodeIndex, Incoming, Outgoing};
+use rand::{rngs::StdRng, SeedableRng};
+
+mod common;
+
+fn simulation() -> Simulation<StdRng> {
+    let mut simulation = common::simulation(435, 1, 3);
+    for step in 0..60 {
+        simulation.step(&[step % 27]);
+    }
+    simulation
+}
+
+#[test]
+fn hubs_and_the_periphery_are_chosen_by_degree() {
+    let simulation = simulation();
+    let graph = &simulation.graph;
+    let in_degree = |id: usize| graph.neighbors_directed(NodeIndex::new(id), Incoming).count();
+    let degree = |id: usize| in_degree(id) + graph.neighbors_directed(NodeIndex::new(id), Outgoing).count();
+    let mut rng = StdRng::seed_from_u64(1);
+
+    let hubs = Selector::Hubs { count: 3 }.select(&simulation, 27, &mut rng);
+    assert_eq!(hubs.len(), 3);
+    let max_in_degree = (0..27).map(in_degree).max().unwrap();
+    assert_eq!(in_degree(hubs[0]), max_in_degree);
+    assert!(hubs.windows(2).all(|pair| in_degree(pair[0]) >= in_degree(pair[1])));
+    let others = (0..27).filter(|node| !hubs.contains(node));
+    assert!(others.map(in_degree).all(|other| other <= in_degree(hubs[2])));
+
+    let periphery = Selector::Periphery { count: 4 }.select(&simulation, 27, &mut rng);
+    assert_eq!(periphery.len(), 4);
+    let min_degree = (0..27).map(degree).min().unwrap();
+    assert_eq!(degree(periphery[0]), min_degree);
+    let others = (0..27).filter(|node| !periphery.contains(node));
+    assert!(others.map(degree).all(|other| other >= degree(periphery[3])));
+}
+
+#[test]
+fn regions_each_get_a_node() {
+    let mut simulation = simulation();
+    let mut rng = StdRng::seed_from_u64(1);
+
+    // The grid is 3 nodes wide, so each of 3 divisions holds one layer.
+    let nodes = Selector::Regions { divisions: 3 }.select(&simulation, 27, &mut rng);
+    assert_eq!(nodes, (0..27).collect::<Vec<_>>());
+    let nodes = Selector::Regions { divisions: 2 }.select(&simulation, 27, &mut rng);
+    assert_eq!(nodes.len(), 8);
+    let mut sorted = nodes.clone();
+    sorted.dedup();
+    assert_eq!(sorted.len(), 8);
+
+    simulation.lesion_nodes(&(0..13).collect::<Vec<_>>());
+    let nodes = Selector::Regions { divisions: 1 }.select(&simulation, 27, &mut rng);
+    assert_eq!(nodes.len(), 1);
+    assert!(nodes[0] >= 13);
+}
+
+#[test]
+fn uniform_selection_draws_a_single_node_of_the_grid() {
+    let simulation = simulation();
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..20 {
+        let nodes = Selector::Uniform.select(&simulation, 27, &mut rng);
+        assert!(nodes.len() == 1 && nodes[0] < 27);
+    }
+    assert!(!Selector::Hubs { count: 0 }.is_valid());
+    assert!(!Selector::Regions { divisions: 0 }.is_valid());
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "driver")]
+
+use std::io::Read;
+use std::os::unix::net::UnixListener;
+
+use connectome_model::driver::{self, Config, Stream};
+use connectome_model::stream::{StepStream, StreamTarget, COLUMNS};
+mod common;
+
+/// Walks the messages of a stream whose batches have the given numbers of
+/// rows, returning the steps of each batch's rows and checking the stream
+/// ends after them.
+fn steps(stream: &[u8], batch_sizes: &[usize]) -> Vec<i64> {
+    let word = |position: usize| {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(&stream[position..position + 4]);
+        u32::from_le_bytes(bytes)
+    };
+
+    let mut steps = Vec::new();
+    let mut position = 0;
+    // The schema has no body.
+    for (i, &num_rows) in std::iter::once(&0).chain(batch_sizes).enumerate() {
+        assert_eq!(word(position), 0xFFFF_FFFF);
+        let length = word(position + 4) as usize;
+        assert!(length > 0 && length.is_multiple_of(8));
+        position += 8 + length;
+        if i > 0 {
+            // The steps are the first column of the body.
+            steps.extend(stream[position..position + num_rows * 8].chunks(8).map(|bytes| {
+                let mut step = [0; 8];
+                step.copy_from_slice(bytes);
+                i64::from_le_bytes(step)
+            }));
+            position += num_rows * 8 * COLUMNS.len();
+        }
+    }
+    assert_eq!(&stream[position..], &[0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0]);
+    steps
+}
+
+#[test]
+fn steps_are_streamed_in_batches() {
+    let mut simulation = common::simulation(435, 1, 3);
+    let mut stream = StepStream::new(Vec::new(), 4).unwrap();
+
+    for step in 1..=10 {
+        let step_result = simulation.step(&[step % 27]);
+        stream.record(&simulation, &step_result).unwrap();
+    }
+
+    let stream = stream.finish().unwrap();
+    assert_eq!(steps(&stream, &[4, 4, 2]), (1..=10).collect::<Vec<_>>());
+}
+
+#[test]
+fn runs_stream_to_unix_sockets() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().join("connectome-stream");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("steps.sock");
+    let listener = UnixListener::bind(&path).unwrap();
+    let reader = std::thread::spawn(move || {
+        let mut stream = Vec::new();
+        listener.accept().unwrap().0.read_to_end(&mut stream).unwrap();
+        stream
+    });
+
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(30),
+        seed: Some(435),
+        stream: Some(Stream {
+            target: StreamTarget::UnixSocket { path },
+            batch_size: 8,
+        }),
+        ..Config::default()
+    };
+    let graph = driver::run(&config, dir.join("run"), None).unwrap();
+    let unstreamed = Config { stream: None, ..config };
+    assert_eq!(driver::run(&unstreamed, dir.join("unstreamed"), None).unwrap(), graph);
+
+    let stream = reader.join().unwrap();
+    assert_eq!(steps(&stream, &[8, 8, 8, 6]), (1..=30).collect::<Vec<_>>());
+}
+
+#[test]
+fn streams_need_nonempty_batches_and_a_single_run() {
+    let config = Config {
+        num_steps: Some(1),
+        seed: Some(435),
+        stream: Some(Stream {
+            target: StreamTarget::Stdout,
+            batch_size: 0,
+        }),
+        ..Config::default()
+    };
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-stream-invalid");
+    assert!(driver::run(&config, &out_dir, None).is_err());
+
+    let sweep = Config {
+        stream: Some(Stream {
+            target: StreamTarget::Stdout,
+            batch_size: 1,
+        }),
+        num_runs: 2,
+        ..config
+    };
+    assert!(driver::sweep(&sweep, &out_dir, None).is_err());
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "driver")]
+
+use std::fs;
+
+use connectome_model::driver::{self, Config, PersistenceSummaries, PERSISTENCE_SUMMARIES_FILE};
+use serde_json::Value;
+
+#[test]
+fn persistence_is_summarized_whenever_the_complex_is_recorded() {
+    let config = Config {
+        grid_size: 3,
+        num_steps: Some(60),
+        seed: Some(435),
+        ..Config::default()
+    };
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("connectome-summaries");
+    let graph = driver::run(&config, out_dir.join("plain"), None).unwrap();
+    let summarized = Config {
+        persistence_summaries: Some(PersistenceSummaries {
+            num_landscapes: 2,
+            resolution: 11,
+            sigma: 0.5,
+        }),
+        ..config.clone()
+    };
+    assert_eq!(driver::run(&summarized, out_dir.join("summarized"), None).unwrap(), graph);
+
+    let lines = fs::read_to_string(out_dir.join("summarized").join(PERSISTENCE_SUMMARIES_FILE)).unwrap();
+    let summaries = lines
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    // Three dimensions after each of the 6 recordings.
+    assert_eq!(summaries.len(), 18);
+    for (i, summary) in summaries.iter().enumerate() {
+        assert_eq!(summary["timestep"], 10 * (i / 3 + 1));
+        assert_eq!(summary["dim"], i % 3);
+        assert_eq!(summary["landscapes"].as_array().unwrap().len(), 22);
+        assert_eq!(summary["image"].as_array().unwrap().len(), 121);
+    }
+    // By the end, some components are joined by myelinated edges, which enter
+    // before the rest.
+    let values = |summary: &Value, key: &str| {
+        summary[key].as_array().unwrap().iter().map(|value| value.as_f64().unwrap()).collect::<Vec<_>>()
+    };
+    assert!(values(&summaries[15], "landscapes").iter().any(|&value| value > 0.));
+    assert!(values(&summaries[15], "image").iter().all(|&value| value >= 0.));
+
+    let invalid = Config {
+        persistence_summaries: Some(PersistenceSummaries {
+            num_landscapes: 2,
+            resolution: 6,
+            sigma: 0.,
+        }),
+        ..config
+    };
+    assert!(driver::run(&invalid, out_dir.join("invalid"), None).is_err());
+}
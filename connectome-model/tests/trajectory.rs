@@ -0,0 +1,33 @@
+use connectome_model::trajectory::TrajectoryBuffer;
+
+mod common;
+
+#[test]
+fn buffers_keep_the_last_points_of_every_stride() {
+    let mut simulation = common::simulation(435, 1, 3);
+    let mut trajectory = TrajectoryBuffer::new(3, 5);
+
+    let mut activations = 0;
+    for step in 1..=32 {
+        let step_result = simulation.step(&[step % 27]);
+        if step > 15 && step <= 30 {
+            activations += step_result.activated_nodes.len();
+        }
+        trajectory.record(&simulation, &step_result);
+    }
+
+    let steps = trajectory.points().map(|point| point.step).collect::<Vec<_>>();
+    assert_eq!(steps, vec![20, 25, 30]);
+    let points = trajectory.points().collect::<Vec<_>>();
+    assert_eq!(points.iter().map(|point| point.activations).sum::<usize>(), activations);
+    assert!(points.iter().all(|point| point.num_nodes == 27));
+
+    let mut csv = Vec::new();
+    trajectory.write(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().next(), Some("step,nodes,edges,activations,mean_myelination"));
+    assert_eq!(csv.lines().count(), 4);
+
+    assert_eq!(trajectory.drain().len(), 3);
+    assert_eq!(trajectory.points().count(), 0);
+}
@@ -0,0 +1,48 @@
+use connectome_model::sim::{EdgeWeight, Transmission};
+use petgraph::graph::NodeIndex;
+
+mod common;
+
+/// Activates node 0 of a 2x2x2 grid with the one edge 0 -> 1, which never
+/// decays and nothing attaches to, and returns the steps node 1 fired at.
+fn firings_of_target(transmission: Transmission) -> Vec<usize> {
+    let mut simulation = common::still_simulation(0, 1, 2);
+    simulation.transmission = transmission;
+    simulation
+        .graph
+        .add_edge(NodeIndex::new(0), NodeIndex::new(1), EdgeWeight::default());
+
+    let mut firings = Vec::new();
+    for step in 1..=20 {
+        let activations = if step == 1 { vec![0] } else { Vec::new() };
+        if simulation.step(&activations).activated_nodes.contains(&1) {
+            firings.push(step);
+        }
+    }
+
+    firings
+}
+
+#[test]
+fn unreliable_edges_lose_activations() {
+    assert_eq!(firings_of_target(Transmission::default()), vec![7]);
+
+    let lossy = Transmission {
+        reliability: 0.,
+        myelinated: false,
+    };
+    assert_eq!(firings_of_target(lossy), Vec::<usize>::new());
+}
+
+#[test]
+fn myelination_makes_delivery_more_reliable() {
+    let transmission = Transmission {
+        reliability: 0.5,
+        myelinated: true,
+    };
+
+    assert_eq!(transmission.delivery_prob(0, 5), 0.5);
+    assert_eq!(transmission.delivery_prob(5, 5), 1.);
+    assert!((transmission.delivery_prob(2, 5) - 0.7).abs() < 1e-12);
+    assert_eq!(Transmission { myelinated: false, ..transmission }.delivery_prob(5, 5), 0.5);
+}
@@ -0,0 +1,44 @@
+use connectome_model::sim::EdgeWeight;
+use connectome_model::wiring::{self, MinimalWiring};
+use petgraph::graph::NodeIndex;
+
+mod common;
+
+#[test]
+fn minimal_wiring_takes_the_shortest_pairs() {
+    let simulation = common::simulation(0, 2, 2);
+    let minimal = MinimalWiring::new(&simulation);
+
+    // 12 pairs along the edges of the cube, 12 across its faces and 4 across
+    // it.
+    assert_eq!(minimal.cost(0), 0.);
+    assert_eq!(minimal.cost(12), 24.);
+    assert!((minimal.cost(24) - (24. + 12. * 8f64.sqrt())).abs() < 1e-9);
+    assert_eq!(minimal.cost(100), minimal.cost(28));
+    assert!(minimal.is_current(&simulation));
+}
+
+#[test]
+fn edges_cost_their_lengths() {
+    let mut simulation = common::simulation(0, 2, 2);
+    let minimal = MinimalWiring::new(&simulation);
+    assert_eq!(wiring::wiring_cost(&simulation, &minimal).normalized_cost, None);
+
+    // Along an edge of the cube, and across it.
+    for &(source, target) in &[(0, 1), (0, 7)] {
+        simulation
+            .graph
+            .add_edge(NodeIndex::new(source), NodeIndex::new(target), EdgeWeight::default());
+    }
+    let cost = wiring::wiring_cost(&simulation, &minimal);
+
+    let across = 12f64.sqrt();
+    assert!((cost.total_length - (2. + across)).abs() < 1e-9);
+    assert!((cost.mean_length - (2. + across) / 2.).abs() < 1e-9);
+    assert_eq!(cost.max_length, across);
+    assert_eq!(cost.length_histogram, vec![0, 0, 1, 1]);
+    assert!((cost.normalized_cost.unwrap() - (2. + across) / 4.).abs() < 1e-9);
+
+    simulation.lesion_nodes(&[7]);
+    assert!(!minimal.is_current(&simulation));
+}
@@ -22,10 +22,7 @@ use kiss3d::scene::SceneNode;
 use kiss3d::window::{State, Window};
 use nalgebra::{distance, Point3, Rotation3, Translation3, UnitQuaternion, Vector3, VectorN};
 
-use connectome_model::{
-    sim::Simulation,
-    simplex::{faces, SimplicialComplex},
-};
+use connectome_model::{sim::Simulation, simplex::SimplicialComplex};
 use rand::{distributions::Uniform, rngs::ThreadRng, seq::IteratorRandom, Rng};
 
 struct AppState {
@@ -251,7 +248,7 @@ impl DemoApp {
         }
 
         Text::new(&format!(
-            "simplex sizes: {:?}\nbetti numbers: {:?}",
+            "f-vector: {:?}\nbetti numbers: {:?}",
             self.sim_state.as_ref().unwrap().cached_outputs.0,
             self.sim_state.as_ref().unwrap().cached_outputs.1,
         ))
@@ -330,7 +327,7 @@ impl SimState {
         sim.init_uniform(3, Self::NUM_NODES);
 
         let simplicial_complex =
-            SimplicialComplex::new((0..Self::NUM_NODES.pow(3) as usize).collect());
+            SimplicialComplex::new((0..Self::NUM_NODES.pow(3) as usize).collect(), None);
 
         let mut neuron_nodes = HashMap::with_capacity(sim.graph.node_count());
 
@@ -392,13 +389,17 @@ impl State for SimState {
         }
 
         for pair in result.removed_edges {
-            self.simplicial_complex.remove(vec![pair.0, pair.1]);
+            self.simplicial_complex
+                .remove(vec![pair.0, pair.1])
+                .expect("removed edge should be in the complex");
 
             window.remove_node(&mut self.synapse_nodes.remove(&pair).unwrap());
         }
 
         for (source_id, target_id) in result.added_edges {
-            self.simplicial_complex.add(vec![source_id, target_id]);
+            self.simplicial_complex
+                .add(vec![source_id, target_id])
+                .expect("failed to add edge to the complex");
 
             let source = self
                 .sim
@@ -480,16 +481,10 @@ impl State for SimState {
         }
 
         if self.sim.timestep % 10 == 0 {
-            let lengths = self
-                .simplicial_complex
-                .simplices
-                .iter()
-                .map(|simplex| simplex.len())
-                .collect();
-
-            let betti_numbers = self.simplicial_complex.betti_numbers();
+            let f_vector = self.simplicial_complex.f_vector();
+            let betti_numbers = self.simplicial_complex.betti_numbers_full();
 
-            self.cached_outputs = (lengths, betti_numbers);
+            self.cached_outputs = (f_vector, betti_numbers);
         }
     }
 }
@@ -0,0 +1,192 @@
+//! Distributions to draw fitnesses and parameters from, beyond those of
+//! `rand_distr`: [`Truncated`] versions of them, confined to an interval, and
+//! finite [`Mixture`]s of them.
+//!
+//! A [`Spec`] describes a distribution in a configuration file, and builds
+//! the [`Sampler`] it describes, checking its parameters.
+
+use std::fmt;
+
+use rand::distributions::{Uniform, WeightedIndex};
+use rand::Rng;
+use rand_distr::{Distribution, Exp, Gamma, InverseGaussian, LogNormal, Pareto};
+use serde::{Deserialize, Serialize};
+
+/// The most draws in a row [`Truncated`] rejects before giving up, which only
+/// happens when almost none of the distribution lies in the interval.
+pub const MAX_REJECTIONS: usize = 1_000_000;
+
+/// A distribution confined to `[low, high]`, by drawing from it until a value
+/// lands there. The interval has to hold a fair share of the distribution for
+/// this to be quick; sampling panics after [`MAX_REJECTIONS`] misses in a row.
+#[derive(Clone, Debug)]
+pub struct Truncated<D> {
+    inner: D,
+    low: f64,
+    high: f64,
+}
+
+impl<D: Distribution<f64>> Truncated<D> {
+    pub fn new(inner: D, low: f64, high: f64) -> Result<Self, InvalidDistribution> {
+        if !is_interval(low, high) {
+            return Err(InvalidDistribution(format!(
+                "a truncation needs its low end {} below its high end {}",
+                low, high
+            )));
+        }
+
+        Ok(Self { inner, low, high })
+    }
+}
+
+impl<D: Distribution<f64>> Distribution<f64> for Truncated<D> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        for _ in 0..MAX_REJECTIONS {
+            let value = self.inner.sample(rng);
+            if self.low <= value && value <= self.high {
+                return value;
+            }
+        }
+
+        panic!(
+            "no value in [{}, {}] after {} draws; the truncation leaves almost nothing of the distribution",
+            self.low, self.high, MAX_REJECTIONS
+        )
+    }
+}
+
+/// A finite mixture: each draw comes from one of the components, picked with
+/// probability proportional to its weight.
+#[derive(Clone, Debug)]
+pub struct Mixture<D> {
+    components: Vec<D>,
+    index: WeightedIndex<f64>,
+}
+
+impl<D: Distribution<f64>> Mixture<D> {
+    /// Makes a mixture of components with the given weights, which needn't
+    /// sum to 1.
+    pub fn new<I: IntoIterator<Item = (f64, D)>>(components: I) -> Result<Self, InvalidDistribution> {
+        let (weights, components): (Vec<_>, Vec<_>) = components.into_iter().unzip();
+        let index = WeightedIndex::new(&weights)
+            .map_err(|err| InvalidDistribution(format!("invalid mixture weights {:?}: {}", weights, err)))?;
+
+        Ok(Self { components, index })
+    }
+}
+
+impl<D: Distribution<f64>> Distribution<f64> for Mixture<D> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.components[self.index.sample(rng)].sample(rng)
+    }
+}
+
+/// A distribution as given in a configuration, tagged with its `kind`, e.g.
+/// `{ "kind": "truncated", "low": 0, "high": 2, "inner": { "kind":
+/// "inverse-gaussian", "mean": 1, "shape": 10 } }`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Spec {
+    InverseGaussian { mean: f64, shape: f64 },
+    LogNormal { mu: f64, sigma: f64 },
+    Exponential { rate: f64 },
+    Gamma { shape: f64, scale: f64 },
+    Pareto { scale: f64, shape: f64 },
+    Uniform { low: f64, high: f64 },
+    Truncated { inner: Box<Spec>, low: f64, high: f64 },
+    /// Components with their weights.
+    Mixture { components: Vec<(f64, Spec)> },
+}
+
+impl Spec {
+    /// Builds the distribution, or says what's wrong with its parameters.
+    pub fn build(&self) -> Result<Sampler, InvalidDistribution> {
+        let invalid = |err: &dyn fmt::Debug| InvalidDistribution(format!("invalid {:?}: {:?}", self, err));
+
+        Ok(match self {
+            Spec::InverseGaussian { mean, shape } => {
+                Sampler::InverseGaussian(InverseGaussian::new(*mean, *shape).map_err(|err| invalid(&err))?)
+            }
+            Spec::LogNormal { mu, sigma } => Sampler::LogNormal(LogNormal::new(*mu, *sigma).map_err(|err| invalid(&err))?),
+            Spec::Exponential { rate } => Sampler::Exponential(Exp::new(*rate).map_err(|err| invalid(&err))?),
+            Spec::Gamma { shape, scale } => Sampler::Gamma(Gamma::new(*shape, *scale).map_err(|err| invalid(&err))?),
+            Spec::Pareto { scale, shape } => Sampler::Pareto(Pareto::new(*scale, *shape).map_err(|err| invalid(&err))?),
+            Spec::Uniform { low, high } => {
+                if !is_interval(*low, *high) {
+                    return Err(invalid(&"the low end has to be below the high end"));
+                }
+                Sampler::Uniform(Uniform::new_inclusive(*low, *high))
+            }
+            Spec::Truncated { inner, low, high } => Sampler::Truncated(Box::new(Truncated::new(inner.build()?, *low, *high)?)),
+            Spec::Mixture { components } => Sampler::Mixture(Mixture::new(
+                components
+                    .iter()
+                    .map(|(weight, spec)| Ok((*weight, spec.build()?)))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )?),
+        })
+    }
+
+    /// Returns whether every draw is positive, as fitnesses and temperatures
+    /// have to be: the continuous distributions on the positive reals draw 0
+    /// with probability 0, but a uniform or truncated one can reach 0 or below,
+    /// and a mixture does if any component does.
+    pub fn is_positive(&self) -> bool {
+        match self {
+            Spec::InverseGaussian { .. }
+            | Spec::LogNormal { .. }
+            | Spec::Exponential { .. }
+            | Spec::Gamma { .. }
+            | Spec::Pareto { .. } => true,
+            Spec::Uniform { low, .. } => *low > 0.,
+            Spec::Truncated { inner, low, .. } => *low > 0. || inner.is_positive(),
+            Spec::Mixture { components } => components.iter().all(|(_, spec)| spec.is_positive()),
+        }
+    }
+}
+
+/// A distribution built from a [`Spec`].
+#[derive(Debug)]
+pub enum Sampler {
+    InverseGaussian(InverseGaussian<f64>),
+    LogNormal(LogNormal<f64>),
+    Exponential(Exp<f64>),
+    Gamma(Gamma<f64>),
+    Pareto(Pareto<f64>),
+    Uniform(Uniform<f64>),
+    Truncated(Box<Truncated<Sampler>>),
+    Mixture(Mixture<Sampler>),
+}
+
+impl Distribution<f64> for Sampler {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match self {
+            Sampler::InverseGaussian(dist) => dist.sample(rng),
+            Sampler::LogNormal(dist) => dist.sample(rng),
+            Sampler::Exponential(dist) => dist.sample(rng),
+            Sampler::Gamma(dist) => dist.sample(rng),
+            Sampler::Pareto(dist) => dist.sample(rng),
+            Sampler::Uniform(dist) => dist.sample(rng),
+            Sampler::Truncated(dist) => dist.sample(rng),
+            Sampler::Mixture(dist) => dist.sample(rng),
+        }
+    }
+}
+
+/// Whether `[low, high]` is an interval with room in it, which it isn't if
+/// either end is NaN.
+fn is_interval(low: f64, high: f64) -> bool {
+    low < high
+}
+
+/// The parameters of a distribution don't describe one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidDistribution(pub String);
+
+impl fmt::Display for InvalidDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for InvalidDistribution {}
@@ -0,0 +1,69 @@
+use distributions::{Mixture, Spec, Truncated};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, InverseGaussian, Uniform};
+
+#[test]
+fn truncated_values_stay_in_range() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let dist = Truncated::new(InverseGaussian::new(1.0, 10.0).unwrap(), 0.0, 1.2).unwrap();
+
+    assert!(dist.sample_iter(&mut rng).take(10_000).all(|value| (0.0..=1.2).contains(&value)));
+    assert!(Truncated::new(InverseGaussian::new(1.0, 10.0).unwrap(), 2.0, 1.0).is_err());
+}
+
+#[test]
+fn mixtures_draw_components_by_weight() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let dist = Mixture::new(vec![(3.0, Uniform::new(0.0, 1.0)), (1.0, Uniform::new(10.0, 11.0))]).unwrap();
+
+    let high = dist.sample_iter(&mut rng).take(10_000).filter(|&value| value >= 10.0).count();
+    assert!((high as f64 / 10_000.0 - 0.25).abs() < 0.02, "{}", high);
+    assert!(Mixture::<Uniform<f64>>::new(vec![]).is_err());
+}
+
+#[test]
+fn specs_build_from_configuration() {
+    let spec: Spec = serde_json::from_str(
+        r#"{
+            "kind": "mixture",
+            "components": [
+                [1, { "kind": "truncated", "low": 0, "high": 1.5, "inner": { "kind": "inverse-gaussian", "mean": 1, "shape": 10 } }],
+                [1, { "kind": "uniform", "low": 2, "high": 3 }]
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let dist = spec.build().unwrap();
+    assert!(dist
+        .sample_iter(&mut rng)
+        .take(1000)
+        .all(|value| (0.0..=1.5).contains(&value) || (2.0..=3.0).contains(&value)));
+
+    let invalid = Spec::Truncated {
+        inner: Box::new(Spec::Exponential { rate: -1.0 }),
+        low: 0.0,
+        high: 1.0,
+    };
+    assert!(invalid.build().is_err());
+}
+
+#[test]
+fn positive_specs() {
+    let inverse_gaussian = Spec::InverseGaussian { mean: 1.0, shape: 10.0 };
+    let straddling = Spec::Uniform { low: -0.9, high: 1.0 };
+
+    assert!(inverse_gaussian.is_positive());
+    assert!(!straddling.is_positive());
+    assert!(Spec::Truncated {
+        inner: Box::new(straddling.clone()),
+        low: 0.5,
+        high: 1.0
+    }
+    .is_positive());
+    assert!(!Spec::Mixture {
+        components: vec![(1.0, inverse_gaussian), (1.0, straddling)]
+    }
+    .is_positive());
+}
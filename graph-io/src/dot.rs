@@ -0,0 +1,49 @@
+//! Graphviz's DOT language.
+
+use std::io::{self, Write};
+
+use crate::{Attributes, AttributedGraph, GraphExporter};
+
+/// Writes a `digraph` or `graph`, with each node and edge's attributes in
+/// its attribute list.
+pub struct Dot;
+
+impl GraphExporter for Dot {
+    fn extension(&self) -> &'static str {
+        "dot"
+    }
+
+    fn export(&self, graph: &AttributedGraph, writer: &mut dyn Write) -> io::Result<()> {
+        let (keyword, edge_op) = if graph.directed { ("digraph", "->") } else { ("graph", "--") };
+
+        writeln!(writer, "{} {{", keyword)?;
+
+        for (id, attributes) in &graph.nodes {
+            writeln!(writer, "    {}{};", id, attribute_list(attributes))?;
+        }
+
+        for (source, target, attributes) in &graph.edges {
+            writeln!(writer, "    {} {} {}{};", source, edge_op, target, attribute_list(attributes))?;
+        }
+
+        writeln!(writer, "}}")
+    }
+}
+
+/// Formats attributes as a DOT attribute list, or nothing if there are none.
+fn attribute_list(attributes: &Attributes) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+
+    let attributes = attributes
+        .iter()
+        .map(|(name, value)| format!("{}={}", quote(name), quote(&value.to_string())))
+        .collect::<Vec<_>>();
+
+    format!(" [{}]", attributes.join(", "))
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,87 @@
+//! Graphs whose nodes and edges come and go, recorded as the timesteps over
+//! which each of them existed, for formats with a timeline such as dynamic
+//! GEXF.
+
+use std::collections::BTreeMap;
+
+use crate::Attributes;
+
+/// The timesteps over which a node or edge existed, both inclusive. An `end`
+/// of `None` means it still exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lifespan {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+/// The history of a graph: each node, with its attributes, and each edge,
+/// with every lifespan it's had, since an edge may be removed and added again.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DynamicGraph {
+    pub directed: bool,
+    pub nodes: BTreeMap<usize, (Attributes, Vec<Lifespan>)>,
+    pub edges: BTreeMap<(usize, usize), Vec<Lifespan>>,
+}
+
+impl DynamicGraph {
+    pub fn new(directed: bool) -> Self {
+        DynamicGraph {
+            directed,
+            ..Default::default()
+        }
+    }
+
+    /// Records a node existing from `start` on.
+    pub fn add_node(&mut self, start: usize, id: usize, attributes: Attributes) {
+        let (node_attributes, lifespans) = self.nodes.entry(id).or_default();
+        *node_attributes = attributes;
+        open(lifespans, start);
+    }
+
+    /// Records a node existing no later than `end`.
+    pub fn remove_node(&mut self, end: usize, id: usize) {
+        if let Some((_, lifespans)) = self.nodes.get_mut(&id) {
+            close(lifespans, end);
+        }
+    }
+
+    /// Records an edge existing from `start` on.
+    pub fn add_edge(&mut self, start: usize, source: usize, target: usize) {
+        let key = self.key(source, target);
+        open(self.edges.entry(key).or_default(), start);
+    }
+
+    /// Records an edge existing no later than `end`.
+    pub fn remove_edge(&mut self, end: usize, source: usize, target: usize) {
+        let key = self.key(source, target);
+        if let Some(lifespans) = self.edges.get_mut(&key) {
+            close(lifespans, end);
+        }
+    }
+
+    /// The key of an edge, which in an undirected graph is the same either
+    /// way round.
+    fn key(&self, source: usize, target: usize) -> (usize, usize) {
+        if self.directed {
+            (source, target)
+        } else {
+            (source.min(target), source.max(target))
+        }
+    }
+}
+
+/// Starts a lifespan, unless one is already going.
+fn open(lifespans: &mut Vec<Lifespan>, start: usize) {
+    if !matches!(lifespans.last(), Some(Lifespan { end: None, .. })) {
+        lifespans.push(Lifespan { start, end: None });
+    }
+}
+
+/// Ends the lifespan that's going, if there is one.
+fn close(lifespans: &mut [Lifespan], end: usize) {
+    if let Some(lifespan) = lifespans.last_mut() {
+        if lifespan.end.is_none() {
+            lifespan.end = Some(end);
+        }
+    }
+}
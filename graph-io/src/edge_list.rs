@@ -0,0 +1,36 @@
+//! Edge lists as CSV, with a column for each edge attribute.
+
+use std::io::{self, Write};
+
+use crate::{AttributedGraph, GraphExporter};
+
+/// Writes one row per edge, `source,target` followed by the edge's attributes
+/// in order of name. Attributes an edge doesn't have are left empty. Nodes
+/// only appear through their edges.
+pub struct CsvEdgeList;
+
+impl GraphExporter for CsvEdgeList {
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+
+    fn export(&self, graph: &AttributedGraph, writer: &mut dyn Write) -> io::Result<()> {
+        let names = graph.edge_attribute_names();
+        let mut csv = csv::Writer::from_writer(writer);
+
+        csv.write_record(["source", "target"].iter().cloned().chain(names.iter().cloned()))?;
+
+        for (source, target, attributes) in &graph.edges {
+            let record = [source.to_string(), target.to_string()]
+                .iter()
+                .cloned()
+                .chain(names.iter().map(|&name| {
+                    attributes.get(name).map_or_else(String::new, |value| value.to_string())
+                }))
+                .collect::<Vec<_>>();
+            csv.write_record(&record)?;
+        }
+
+        csv.flush()
+    }
+}
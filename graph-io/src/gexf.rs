@@ -0,0 +1,179 @@
+//! GEXF, Gephi's native format.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::dynamic::{DynamicGraph, Lifespan};
+use crate::{attribute_types, escape_xml, AttributeValue, Attributes, AttributedGraph, GraphExporter};
+
+/// Writes GEXF 1.2, declaring a typed attribute for each node and edge
+/// attribute.
+pub struct Gexf;
+
+impl GraphExporter for Gexf {
+    fn extension(&self) -> &'static str {
+        "gexf"
+    }
+
+    fn export(&self, graph: &AttributedGraph, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(writer, r#"<gexf xmlns="http://www.gexf.net/1.2draft" version="1.2">"#)?;
+
+        let edge_type = if graph.directed { "directed" } else { "undirected" };
+        writeln!(writer, r#"  <graph defaultedgetype="{}">"#, edge_type)?;
+
+        let node_types = attribute_types(graph.nodes.iter().map(|(_, attributes)| attributes));
+        let edge_types = attribute_types(graph.edges.iter().map(|(_, _, attributes)| attributes));
+        write_attribute_declarations(writer, "node", &node_types)?;
+        write_attribute_declarations(writer, "edge", &edge_types)?;
+
+        writeln!(writer, "    <nodes>")?;
+        for (id, attributes) in &graph.nodes {
+            writeln!(writer, r#"      <node id="{}" label="{}">"#, id, id)?;
+            write_attribute_values(writer, attributes)?;
+            writeln!(writer, "      </node>")?;
+        }
+        writeln!(writer, "    </nodes>")?;
+
+        writeln!(writer, "    <edges>")?;
+        for (i, (source, target, attributes)) in graph.edges.iter().enumerate() {
+            writeln!(writer, r#"      <edge id="{}" source="{}" target="{}">"#, i, source, target)?;
+            write_attribute_values(writer, attributes)?;
+            writeln!(writer, "      </edge>")?;
+        }
+        writeln!(writer, "    </edges>")?;
+
+        writeln!(writer, "  </graph>")?;
+        writeln!(writer, "</gexf>")
+    }
+}
+
+/// Writes a [`DynamicGraph`] as a dynamic GEXF 1.2 graph, with the timesteps
+/// each node and edge existed at as its `start` and `end`, or as a `spell` for
+/// each lifespan of one that existed more than once, so that its history can
+/// be played back on Gephi's timeline.
+pub struct DynamicGexf;
+
+impl DynamicGexf {
+    pub fn extension(&self) -> &'static str {
+        "gexf"
+    }
+
+    pub fn export(&self, graph: &DynamicGraph, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(writer, r#"<gexf xmlns="http://www.gexf.net/1.2draft" version="1.2">"#)?;
+
+        let edge_type = if graph.directed { "directed" } else { "undirected" };
+        writeln!(
+            writer,
+            r#"  <graph mode="dynamic" defaultedgetype="{}" timeformat="double">"#,
+            edge_type
+        )?;
+
+        let node_types = attribute_types(graph.nodes.values().map(|(attributes, _)| attributes));
+        write_attribute_declarations(writer, "node", &node_types)?;
+
+        writeln!(writer, "    <nodes>")?;
+        for (id, (attributes, lifespans)) in &graph.nodes {
+            writeln!(writer, r#"      <node id="{}" label="{}"{}>"#, id, id, lifespan_attributes(lifespans))?;
+            write_attribute_values(writer, attributes)?;
+            write_spells(writer, lifespans)?;
+            writeln!(writer, "      </node>")?;
+        }
+        writeln!(writer, "    </nodes>")?;
+
+        writeln!(writer, "    <edges>")?;
+        for (i, ((source, target), lifespans)) in graph.edges.iter().enumerate() {
+            writeln!(
+                writer,
+                r#"      <edge id="{}" source="{}" target="{}"{}>"#,
+                i,
+                source,
+                target,
+                lifespan_attributes(lifespans)
+            )?;
+            write_spells(writer, lifespans)?;
+            writeln!(writer, "      </edge>")?;
+        }
+        writeln!(writer, "    </edges>")?;
+
+        writeln!(writer, "  </graph>")?;
+        writeln!(writer, "</gexf>")
+    }
+
+    /// Writes a graph to a new file at `path`.
+    pub fn export_to_path(&self, graph: &DynamicGraph, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.export(graph, &mut writer)?;
+        writer.flush()
+    }
+}
+
+/// Formats a single lifespan as `start` and `end` attributes, leaving out an
+/// end which hasn't come. Several lifespans are written as spells instead.
+fn lifespan_attributes(lifespans: &[Lifespan]) -> String {
+    match lifespans {
+        [lifespan] => format!(r#" start="{}"{}"#, lifespan.start, end_attribute(lifespan)),
+        _ => String::new(),
+    }
+}
+
+fn end_attribute(lifespan: &Lifespan) -> String {
+    match lifespan.end {
+        Some(end) => format!(r#" end="{}""#, end),
+        None => String::new(),
+    }
+}
+
+fn write_spells(writer: &mut dyn Write, lifespans: &[Lifespan]) -> io::Result<()> {
+    if lifespans.len() < 2 {
+        return Ok(());
+    }
+
+    writeln!(writer, "        <spells>")?;
+    for lifespan in lifespans {
+        writeln!(writer, r#"          <spell start="{}"{}/>"#, lifespan.start, end_attribute(lifespan))?;
+    }
+    writeln!(writer, "        </spells>")
+}
+
+fn write_attribute_declarations(
+    writer: &mut dyn Write,
+    class: &str,
+    types: &BTreeMap<&str, &AttributeValue>,
+) -> io::Result<()> {
+    if types.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, r#"    <attributes class="{}">"#, class)?;
+    for (name, value) in types {
+        writeln!(
+            writer,
+            r#"      <attribute id="{}" title="{}" type="{}"/>"#,
+            escape_xml(name),
+            escape_xml(name),
+            value.xml_type()
+        )?;
+    }
+    writeln!(writer, "    </attributes>")
+}
+
+fn write_attribute_values(writer: &mut dyn Write, attributes: &Attributes) -> io::Result<()> {
+    if attributes.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, "        <attvalues>")?;
+    for (name, value) in attributes {
+        writeln!(
+            writer,
+            r#"          <attvalue for="{}" value="{}"/>"#,
+            escape_xml(name),
+            escape_xml(&value.to_string())
+        )?;
+    }
+    writeln!(writer, "        </attvalues>")
+}
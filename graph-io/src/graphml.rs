@@ -0,0 +1,68 @@
+//! GraphML, as read by Gephi, Cytoscape, NetworkX and igraph.
+
+use std::io::{self, Write};
+
+use crate::{attribute_types, escape_xml, Attributes, AttributedGraph, GraphExporter};
+
+/// Writes GraphML, declaring a typed key for each node and edge attribute.
+pub struct GraphMl;
+
+impl GraphExporter for GraphMl {
+    fn extension(&self) -> &'static str {
+        "graphml"
+    }
+
+    fn export(&self, graph: &AttributedGraph, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(writer, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#)?;
+
+        let node_types = attribute_types(graph.nodes.iter().map(|(_, attributes)| attributes));
+        let edge_types = attribute_types(graph.edges.iter().map(|(_, _, attributes)| attributes));
+
+        for (domain, types) in &[("node", &node_types), ("edge", &edge_types)] {
+            for (name, value) in types.iter() {
+                writeln!(
+                    writer,
+                    r#"  <key id="{}_{}" for="{}" attr.name="{}" attr.type="{}"/>"#,
+                    domain,
+                    escape_xml(name),
+                    domain,
+                    escape_xml(name),
+                    value.xml_type()
+                )?;
+            }
+        }
+
+        let edge_default = if graph.directed { "directed" } else { "undirected" };
+        writeln!(writer, r#"  <graph edgedefault="{}">"#, edge_default)?;
+
+        for (id, attributes) in &graph.nodes {
+            writeln!(writer, r#"    <node id="n{}">"#, id)?;
+            write_data(writer, "node", attributes)?;
+            writeln!(writer, "    </node>")?;
+        }
+
+        for (source, target, attributes) in &graph.edges {
+            writeln!(writer, r#"    <edge source="n{}" target="n{}">"#, source, target)?;
+            write_data(writer, "edge", attributes)?;
+            writeln!(writer, "    </edge>")?;
+        }
+
+        writeln!(writer, "  </graph>")?;
+        writeln!(writer, "</graphml>")
+    }
+}
+
+fn write_data(writer: &mut dyn Write, domain: &str, attributes: &Attributes) -> io::Result<()> {
+    for (name, value) in attributes {
+        writeln!(
+            writer,
+            r#"      <data key="{}_{}">{}</data>"#,
+            domain,
+            escape_xml(name),
+            escape_xml(&value.to_string())
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,222 @@
+//! Exporting petgraph graphs, along with attributes on their nodes and edges,
+//! to the file formats other network tools read.
+//!
+//! A graph is first flattened into an [`AttributedGraph`], with a map of
+//! attributes for each node and edge, and then written out by one of the
+//! [`GraphExporter`]s. The history of a graph whose edges come and go is
+//! recorded in a [`DynamicGraph`] instead, and written by
+//! [`DynamicGexf`](gexf::DynamicGexf).
+
+pub mod dot;
+pub mod dynamic;
+pub mod edge_list;
+pub mod gexf;
+pub mod graphml;
+pub mod node_link;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use petgraph::visit::{EdgeRef, GraphProp, IntoEdgeReferences, IntoNodeReferences, NodeIndexable, NodeRef};
+
+pub use dynamic::{DynamicGraph, Lifespan};
+
+/// The value of an attribute of a node or edge.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl AttributeValue {
+    /// Returns the name of this value's type in GraphML and GEXF, which share
+    /// their type names.
+    fn xml_type(&self) -> &'static str {
+        match self {
+            AttributeValue::Bool(_) => "boolean",
+            AttributeValue::Int(_) => "long",
+            AttributeValue::Float(_) => "double",
+            AttributeValue::String(_) => "string",
+        }
+    }
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AttributeValue::Bool(value) => write!(f, "{}", value),
+            AttributeValue::Int(value) => write!(f, "{}", value),
+            AttributeValue::Float(value) => write!(f, "{}", value),
+            AttributeValue::String(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(value: bool) -> Self {
+        AttributeValue::Bool(value)
+    }
+}
+
+impl From<i64> for AttributeValue {
+    fn from(value: i64) -> Self {
+        AttributeValue::Int(value)
+    }
+}
+
+impl From<usize> for AttributeValue {
+    fn from(value: usize) -> Self {
+        AttributeValue::Int(value as i64)
+    }
+}
+
+impl From<f64> for AttributeValue {
+    fn from(value: f64) -> Self {
+        AttributeValue::Float(value)
+    }
+}
+
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        AttributeValue::String(value.to_string())
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        AttributeValue::String(value)
+    }
+}
+
+/// The attributes of a node or edge, by name.
+pub type Attributes = BTreeMap<String, AttributeValue>;
+
+/// A graph flattened for export: its nodes, identified by their petgraph
+/// index, and its edges, each with a map of attributes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AttributedGraph {
+    pub directed: bool,
+    pub nodes: Vec<(usize, Attributes)>,
+    pub edges: Vec<(usize, usize, Attributes)>,
+}
+
+impl AttributedGraph {
+    /// Flattens a petgraph graph, mapping the weight of each node and edge to
+    /// its attributes.
+    pub fn from_graph<G, FN, FE>(graph: G, mut node_attributes: FN, mut edge_attributes: FE) -> Self
+    where
+        G: IntoNodeReferences + IntoEdgeReferences + NodeIndexable + GraphProp,
+        FN: FnMut(&G::NodeWeight) -> Attributes,
+        FE: FnMut(&G::EdgeWeight) -> Attributes,
+    {
+        let nodes = graph
+            .node_references()
+            .map(|node| (graph.to_index(node.id()), node_attributes(node.weight())))
+            .collect();
+        let edges = graph
+            .edge_references()
+            .map(|edge| {
+                (
+                    graph.to_index(edge.source()),
+                    graph.to_index(edge.target()),
+                    edge_attributes(edge.weight()),
+                )
+            })
+            .collect();
+
+        AttributedGraph {
+            directed: graph.is_directed(),
+            nodes,
+            edges,
+        }
+    }
+
+    /// Flattens a petgraph graph without any attributes.
+    pub fn from_structure<G>(graph: G) -> Self
+    where
+        G: IntoNodeReferences + IntoEdgeReferences + NodeIndexable + GraphProp,
+    {
+        Self::from_graph(graph, |_| Attributes::new(), |_| Attributes::new())
+    }
+
+    /// Returns the names of the attributes of any node, in sorted order.
+    pub fn node_attribute_names(&self) -> BTreeSet<&str> {
+        attribute_names(self.nodes.iter().map(|(_, attributes)| attributes))
+    }
+
+    /// Returns the names of the attributes of any edge, in sorted order.
+    pub fn edge_attribute_names(&self) -> BTreeSet<&str> {
+        attribute_names(self.edges.iter().map(|(_, _, attributes)| attributes))
+    }
+}
+
+fn attribute_names<'a, I: Iterator<Item = &'a Attributes>>(attributes: I) -> BTreeSet<&'a str> {
+    attributes
+        .flat_map(|attributes| attributes.keys().map(|name| name.as_str()))
+        .collect()
+}
+
+/// Returns the first value given for each attribute, which formats with
+/// declared attribute types take the type from.
+fn attribute_types<'a, I: Iterator<Item = &'a Attributes>>(attributes: I) -> BTreeMap<&'a str, &'a AttributeValue> {
+    let mut types = BTreeMap::new();
+
+    for attributes in attributes {
+        for (name, value) in attributes {
+            types.entry(name.as_str()).or_insert(value);
+        }
+    }
+
+    types
+}
+
+/// Escapes text for use in XML attribute values and content.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Writes an [`AttributedGraph`] in some file format.
+pub trait GraphExporter {
+    /// The extension of files in this format, without the dot.
+    fn extension(&self) -> &'static str;
+
+    fn export(&self, graph: &AttributedGraph, writer: &mut dyn Write) -> io::Result<()>;
+
+    /// Writes a graph to a new file at `path`.
+    fn export_to_path(&self, graph: &AttributedGraph, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.export(graph, &mut writer)?;
+        writer.flush()
+    }
+}
+
+/// Returns the exporter for a format, given by name or file extension: one of
+/// `csv`, `dot` (or `gv`), `gexf`, `graphml` and `json`.
+pub fn exporter(format: &str) -> Option<Box<dyn GraphExporter>> {
+    match format {
+        "csv" => Some(Box::new(edge_list::CsvEdgeList)),
+        "dot" | "gv" => Some(Box::new(dot::Dot)),
+        "gexf" => Some(Box::new(gexf::Gexf)),
+        "graphml" => Some(Box::new(graphml::GraphMl)),
+        "json" => Some(Box::new(node_link::NodeLinkJson)),
+        _ => None,
+    }
+}
@@ -0,0 +1,68 @@
+//! Node-link JSON, as read by NetworkX's `node_link_graph` and by d3.
+
+use std::io::{self, Write};
+
+use serde_json::{json, Map, Value};
+
+use crate::{AttributeValue, Attributes, AttributedGraph, GraphExporter};
+
+/// Writes a JSON object with a `nodes` array and a `links` array, where each
+/// node and link object holds its attributes alongside its `id`, or its
+/// `source` and `target`.
+pub struct NodeLinkJson;
+
+impl GraphExporter for NodeLinkJson {
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn export(&self, graph: &AttributedGraph, writer: &mut dyn Write) -> io::Result<()> {
+        let nodes = graph
+            .nodes
+            .iter()
+            .map(|(id, attributes)| object(attributes, &[("id", json!(id))]))
+            .collect::<Vec<_>>();
+        let links = graph
+            .edges
+            .iter()
+            .map(|(source, target, attributes)| {
+                object(attributes, &[("source", json!(source)), ("target", json!(target))])
+            })
+            .collect::<Vec<_>>();
+
+        let document = json!({
+            "directed": graph.directed,
+            "multigraph": false,
+            "graph": {},
+            "nodes": nodes,
+            "links": links,
+        });
+
+        serde_json::to_writer(&mut *writer, &document)?;
+        writeln!(writer)
+    }
+}
+
+/// Builds a JSON object from attributes, along with the given fields, which
+/// take precedence over attributes of the same name.
+fn object(attributes: &Attributes, fields: &[(&str, Value)]) -> Value {
+    let mut object = attributes
+        .iter()
+        .map(|(name, value)| (name.clone(), to_json(value)))
+        .collect::<Map<_, _>>();
+
+    for (name, value) in fields {
+        object.insert(name.to_string(), value.clone());
+    }
+
+    Value::Object(object)
+}
+
+fn to_json(value: &AttributeValue) -> Value {
+    match value {
+        AttributeValue::Bool(value) => json!(value),
+        AttributeValue::Int(value) => json!(value),
+        AttributeValue::Float(value) => json!(value),
+        AttributeValue::String(value) => json!(value),
+    }
+}
@@ -0,0 +1,131 @@
+use graph_io::gexf::DynamicGexf;
+use graph_io::{exporter, Attributes, AttributedGraph, DynamicGraph, GraphExporter, Lifespan};
+use petgraph::graph::{DiGraph, UnGraph};
+
+/// A directed path 0 -> 1 -> 2 with a name on each node, and a weight on each
+/// edge.
+fn path() -> AttributedGraph {
+    let mut graph = DiGraph::<&str, f64>::new();
+    let a = graph.add_node("a");
+    let b = graph.add_node("b & c");
+    let c = graph.add_node("\"d\"");
+    graph.add_edge(a, b, 0.5);
+    graph.add_edge(b, c, 2.0);
+
+    AttributedGraph::from_graph(
+        &graph,
+        |&name| {
+            let mut attributes = Attributes::new();
+            attributes.insert("name".to_string(), name.into());
+            attributes
+        },
+        |&weight| {
+            let mut attributes = Attributes::new();
+            attributes.insert("weight".to_string(), weight.into());
+            attributes
+        },
+    )
+}
+
+fn export_to_string(exporter: &dyn GraphExporter, graph: &AttributedGraph) -> String {
+    let mut output = Vec::new();
+    exporter.export(graph, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn flattening_keeps_indices_and_direction() {
+    let graph = path();
+
+    assert!(graph.directed);
+    assert_eq!(graph.nodes.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert_eq!(graph.edges.iter().map(|&(a, b, _)| (a, b)).collect::<Vec<_>>(), vec![(0, 1), (1, 2)]);
+    assert_eq!(graph.edge_attribute_names().into_iter().collect::<Vec<_>>(), vec!["weight"]);
+
+    let undirected = AttributedGraph::from_structure(&UnGraph::<(), ()>::from_edges([(0, 1)]));
+    assert!(!undirected.directed);
+}
+
+#[test]
+fn csv_edge_list() {
+    let output = export_to_string(&*exporter("csv").unwrap(), &path());
+
+    assert_eq!(output, "source,target,weight\n0,1,0.5\n1,2,2\n");
+}
+
+#[test]
+fn dot_quotes_attributes() {
+    let output = export_to_string(&*exporter("dot").unwrap(), &path());
+
+    assert!(output.starts_with("digraph {\n"));
+    assert!(output.contains("    1 [\"name\"=\"b & c\"];\n"));
+    assert!(output.contains("    2 [\"name\"=\"\\\"d\\\"\"];\n"));
+    assert!(output.contains("    0 -> 1 [\"weight\"=\"0.5\"];\n"));
+}
+
+#[test]
+fn graphml_declares_typed_keys() {
+    let output = export_to_string(&*exporter("graphml").unwrap(), &path());
+
+    assert!(output.contains(r#"<key id="node_name" for="node" attr.name="name" attr.type="string"/>"#));
+    assert!(output.contains(r#"<key id="edge_weight" for="edge" attr.name="weight" attr.type="double"/>"#));
+    assert!(output.contains(r#"<graph edgedefault="directed">"#));
+    assert!(output.contains(r#"<data key="node_name">b &amp; c</data>"#));
+    assert!(output.contains(r#"<edge source="n1" target="n2">"#));
+}
+
+#[test]
+fn gexf_declares_attributes() {
+    let output = export_to_string(&*exporter("gexf").unwrap(), &path());
+
+    assert!(output.contains(r#"<attributes class="edge">"#));
+    assert!(output.contains(r#"<attribute id="weight" title="weight" type="double"/>"#));
+    assert!(output.contains(r#"<attvalue for="name" value="&quot;d&quot;"/>"#));
+    assert!(output.contains(r#"<edge id="1" source="1" target="2">"#));
+}
+
+#[test]
+fn node_link_json() {
+    let output = export_to_string(&*exporter("json").unwrap(), &path());
+    let document: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+    assert_eq!(document["directed"], true);
+    assert_eq!(document["nodes"][1], serde_json::json!({ "id": 1, "name": "b & c" }));
+    assert_eq!(document["links"][0], serde_json::json!({ "source": 0, "target": 1, "weight": 0.5 }));
+}
+
+#[test]
+fn unknown_format() {
+    assert!(exporter("xlsx").is_none());
+}
+
+#[test]
+fn dynamic_gexf_writes_lifespans() {
+    let mut graph = DynamicGraph::new(true);
+    let mut attributes = Attributes::new();
+    attributes.insert("x".to_string(), 1.5.into());
+    graph.add_node(0, 0, attributes);
+    graph.add_node(0, 1, Attributes::new());
+    graph.add_edge(1, 0, 1);
+    graph.add_edge(1, 1, 0);
+    graph.remove_edge(3, 0, 1);
+    graph.add_edge(6, 0, 1);
+    graph.remove_node(7, 1);
+
+    assert_eq!(
+        graph.edges[&(0, 1)],
+        vec![Lifespan { start: 1, end: Some(3) }, Lifespan { start: 6, end: None }]
+    );
+
+    let mut output = Vec::new();
+    DynamicGexf.export(&graph, &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains(r#"<graph mode="dynamic" defaultedgetype="directed" timeformat="double">"#));
+    assert!(output.contains(r#"<node id="0" label="0" start="0">"#));
+    assert!(output.contains(r#"<node id="1" label="1" start="0" end="7">"#));
+    assert!(output.contains(r#"<edge id="0" source="0" target="1">"#));
+    assert!(output.contains(r#"<spell start="1" end="3"/>"#));
+    assert!(output.contains(r#"<spell start="6"/>"#));
+    assert!(output.contains(r#"<edge id="1" source="1" target="0" start="1">"#));
+}
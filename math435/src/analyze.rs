@@ -0,0 +1,279 @@
+//! Recomputing the summary statistics of output written by earlier runs, so
+//! that old datasets pick up metrics added since without simulating again.
+//!
+//! Two kinds of table are read, either of them compressed: tables of nodes
+//! with an `in_degree` column, as the Bose-Einstein runs and sweeps write, and
+//! edge lists with `source` and `target` columns, as graphs are exported.
+//! Either may have a `run` column, which splits it into runs.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use compression::Compression;
+use net_metrics::PowerLawFit;
+use serde::Serialize;
+
+/// The kinds of table an analysis can be of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableKind {
+    Nodes,
+    Edges,
+}
+
+/// The statistics of the in-degrees of one run.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RunSummary {
+    /// The run, if the table has more than one.
+    pub run: Option<u64>,
+    pub num_nodes: usize,
+    pub num_edges: usize,
+    pub mean_degree: f64,
+    pub max_degree: usize,
+    pub degree_gini: f64,
+    pub condensation_fraction: f64,
+    /// The power law fit to the tail of the degrees, if there are enough of
+    /// them to fit one.
+    pub power_law: Option<PowerLawFit>,
+}
+
+/// The mean, standard deviation and range of a statistic across runs.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Aggregate {
+    pub num_runs: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// The statistics of each run of a table, and of them all together.
+#[derive(Clone, Debug, Serialize)]
+pub struct Analysis {
+    pub kind: TableKind,
+    pub runs: Vec<RunSummary>,
+    pub aggregates: BTreeMap<&'static str, Aggregate>,
+}
+
+/// Reads a table of nodes or edges and computes the statistics of each run.
+pub fn analyze<P: AsRef<Path>>(path: P) -> io::Result<Analysis> {
+    let (kind, degrees) = read_degrees(path)?;
+    let runs = degrees
+        .into_iter()
+        .map(|(run, degrees)| summarize(run, &degrees))
+        .collect::<Vec<_>>();
+    let aggregates = aggregate(&runs);
+
+    Ok(Analysis { kind, runs, aggregates })
+}
+
+/// The in-degrees of each run of a table, by run if it has a `run` column.
+pub type Degrees = BTreeMap<Option<u64>, Vec<usize>>;
+
+/// Reads the in-degrees of each run of a table of nodes or edges.
+pub fn read_degrees<P: AsRef<Path>>(path: P) -> io::Result<(TableKind, Degrees)> {
+    let path = path.as_ref();
+    if table_name(path).ends_with(".parquet") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "parquet files aren't supported; analyze the CSV output instead",
+        ));
+    }
+
+    let mut reader = csv::Reader::from_reader(compression::open(path)?);
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| headers.iter().position(|header| header == name);
+
+    let run_column = column("run");
+    let (kind, degrees) = match (column("in_degree"), column("source"), column("target")) {
+        (Some(in_degree), _, _) => (TableKind::Nodes, node_degrees(&mut reader, run_column, in_degree)?),
+        (None, Some(source), Some(target)) => (TableKind::Edges, edge_degrees(&mut reader, run_column, source, target)?),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "expected an `in_degree` column, or `source` and `target` columns",
+            ))
+        }
+    };
+
+    let degrees = degrees
+        .into_iter()
+        .map(|(run, degrees)| (run_column.map(|_| run), degrees))
+        .collect();
+
+    Ok((kind, degrees))
+}
+
+/// The name of a table's file without any compression extension, such as
+/// `bose_einstein_sweep.csv` for `bose_einstein_sweep.csv.zst`.
+pub fn table_name(path: &Path) -> String {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    match Compression::of_path(path).extension() {
+        Some(extension) => name.strip_suffix(&format!(".{}", extension)).unwrap_or(&name).to_string(),
+        None => name.into_owned(),
+    }
+}
+
+/// Collects the `in_degree` column of each run.
+fn node_degrees<R: io::Read>(
+    reader: &mut csv::Reader<R>,
+    run_column: Option<usize>,
+    in_degree: usize,
+) -> io::Result<BTreeMap<u64, Vec<usize>>> {
+    let mut degrees = BTreeMap::<u64, Vec<usize>>::new();
+
+    for record in reader.records() {
+        let record = record?;
+        let run = parse_run(&record, run_column)?;
+        degrees.entry(run).or_default().push(parse(&record, in_degree)?);
+    }
+
+    Ok(degrees)
+}
+
+/// Counts the edges into each node of each run. Nodes are only known by their
+/// edges, so isolated nodes aren't counted.
+fn edge_degrees<R: io::Read>(
+    reader: &mut csv::Reader<R>,
+    run_column: Option<usize>,
+    source: usize,
+    target: usize,
+) -> io::Result<BTreeMap<u64, Vec<usize>>> {
+    let mut degrees = BTreeMap::<u64, BTreeMap<String, usize>>::new();
+
+    for record in reader.records() {
+        let record = record?;
+        let run = degrees.entry(parse_run(&record, run_column)?).or_default();
+        run.entry(record[source].to_string()).or_insert(0);
+        *run.entry(record[target].to_string()).or_insert(0) += 1;
+    }
+
+    Ok(degrees
+        .into_iter()
+        .map(|(run, degrees)| (run, degrees.into_values().collect()))
+        .collect())
+}
+
+fn parse_run(record: &csv::StringRecord, run_column: Option<usize>) -> io::Result<u64> {
+    match run_column {
+        Some(column) => parse(record, column),
+        None => Ok(0),
+    }
+}
+
+fn parse<T: std::str::FromStr>(record: &csv::StringRecord, column: usize) -> io::Result<T> {
+    record[column].trim().parse().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("malformed value `{}` on line {}", &record[column], record.position().map_or(0, |p| p.line())),
+        )
+    })
+}
+
+/// Computes the statistics of the in-degrees of a run.
+pub fn summarize(run: Option<u64>, degrees: &[usize]) -> RunSummary {
+    let num_edges = degrees.iter().sum::<usize>();
+    let as_f64 = degrees.iter().map(|&degree| degree as f64).collect::<Vec<_>>();
+
+    RunSummary {
+        run,
+        num_nodes: degrees.len(),
+        num_edges,
+        mean_degree: num_edges as f64 / degrees.len().max(1) as f64,
+        max_degree: degrees.iter().copied().max().unwrap_or(0),
+        degree_gini: net_metrics::gini(&as_f64),
+        condensation_fraction: net_metrics::condensation_fraction(degrees),
+        power_law: net_metrics::best_power_law_fit(degrees),
+    }
+}
+
+/// A statistic of a run, which not every run need have.
+type Statistic = fn(&RunSummary) -> Option<f64>;
+
+/// Aggregates each statistic over the runs which have it.
+fn aggregate(runs: &[RunSummary]) -> BTreeMap<&'static str, Aggregate> {
+    let statistics: [(&'static str, Statistic); 7] = [
+        ("num_nodes", |run| Some(run.num_nodes as f64)),
+        ("num_edges", |run| Some(run.num_edges as f64)),
+        ("mean_degree", |run| Some(run.mean_degree)),
+        ("max_degree", |run| Some(run.max_degree as f64)),
+        ("degree_gini", |run| Some(run.degree_gini)),
+        ("condensation_fraction", |run| Some(run.condensation_fraction)),
+        ("power_law_exponent", |run| run.power_law.as_ref().map(|fit| fit.exponent)),
+    ];
+
+    statistics
+        .iter()
+        .filter_map(|(name, statistic)| {
+            let values = runs.iter().filter_map(statistic).collect::<Vec<_>>();
+            if values.is_empty() {
+                return None;
+            }
+
+            let n = values.len() as f64;
+            let mean = values.iter().sum::<f64>() / n;
+            let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / n;
+
+            Some((
+                *name,
+                Aggregate {
+                    num_runs: values.len(),
+                    mean,
+                    std_dev: variance.sqrt(),
+                    min: values.iter().copied().fold(f64::INFINITY, f64::min),
+                    max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                },
+            ))
+        })
+        .collect()
+}
+
+impl fmt::Display for RunSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(run) = self.run {
+            write!(f, "run {}: ", run)?;
+        }
+        write!(
+            f,
+            "{} nodes, {} edges, mean degree {:.3}, max degree {}, gini {:.4}, condensation {:.4}",
+            self.num_nodes,
+            self.num_edges,
+            self.mean_degree,
+            self.max_degree,
+            self.degree_gini,
+            self.condensation_fraction
+        )?;
+        match &self.power_law {
+            Some(fit) => write!(
+                f,
+                ", power law exponent {:.3} ± {:.3} from degree {}",
+                fit.exponent, fit.std_error, fit.k_min
+            ),
+            None => write!(f, ", too few degrees for a power law fit"),
+        }
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for run in &self.runs {
+            writeln!(f, "{}", run)?;
+        }
+
+        if self.runs.len() > 1 {
+            writeln!(f, "across {} runs:", self.runs.len())?;
+            for (name, aggregate) in &self.aggregates {
+                writeln!(
+                    f,
+                    "  {}: {:.4} ± {:.4} (min {:.4}, max {:.4})",
+                    name, aggregate.mean, aggregate.std_dev, aggregate.min, aggregate.max
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
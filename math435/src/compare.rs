@@ -0,0 +1,380 @@
+//! Comparing two datasets of runs, such as sweeps at two temperatures or with
+//! two sets of parameters, by the key observables of their runs: whether
+//! their degree distributions differ, by a two-sample Kolmogorov-Smirnov
+//! test, how far apart the statistics of their runs are, as effect sizes, and
+//! how their mean betti curves differ.
+//!
+//! A dataset is either a table of nodes or edges, as read by
+//! [`analyze`], or a directory of connectome output: a single
+//! run, with its nodes in `nodes.csv` and its betti curve in
+//! `betti_curve.csv`, or a sweep, with a `run_<n>` directory for each run.
+//! Tables don't have betti curves, so those are only compared between
+//! directories.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::analyze::{self, RunSummary};
+
+/// The betti curve file of a connectome run.
+const BETTI_CURVE_FILE: &str = "betti_curve.csv";
+/// The nodes file of a connectome run.
+const NODES_FILE: &str = "nodes.csv";
+
+/// The runs of a dataset: the in-degrees of each, and its betti curve if it
+/// has one.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dataset {
+    pub degrees: Vec<Vec<usize>>,
+    pub betti_curves: Vec<BettiCurve>,
+}
+
+/// The betti numbers of a complex at each timestep it was recorded.
+pub type BettiCurve = BTreeMap<usize, Vec<i64>>;
+
+impl Dataset {
+    /// Reads a table or a directory of connectome output.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        if !path.is_dir() {
+            let (_, degrees) = analyze::read_degrees(path)?;
+            return Ok(Dataset {
+                degrees: degrees.into_values().collect(),
+                betti_curves: Vec::new(),
+            });
+        }
+
+        let mut run_dirs = fs::read_dir(path)?
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let index = entry.file_name().to_str()?.strip_prefix("run_")?.parse::<usize>().ok()?;
+                Some((index, entry.path()))
+            })
+            .filter(|(_, dir)| dir.is_dir())
+            .collect::<Vec<_>>();
+        run_dirs.sort();
+        if run_dirs.is_empty() {
+            run_dirs.push((0, path.to_path_buf()));
+        }
+
+        let mut dataset = Dataset::default();
+        for (_, dir) in run_dirs {
+            let nodes = find_table(&dir, NODES_FILE)?;
+            let (_, degrees) = analyze::read_degrees(nodes)?;
+            dataset.degrees.extend(degrees.into_values());
+            if let Ok(betti_curve) = find_table(&dir, BETTI_CURVE_FILE) {
+                dataset.betti_curves.push(read_betti_curve(&betti_curve)?);
+            }
+        }
+
+        Ok(dataset)
+    }
+}
+
+/// Returns the path of the table `name` in `dir`, which may be compressed.
+fn find_table(dir: &Path, name: &str) -> io::Result<PathBuf> {
+    fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| analyze::table_name(path) == name)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} has no {}", dir.display(), name),
+            )
+        })
+}
+
+/// Reads the `betti_<k>` columns of a betti curve, by timestep.
+fn read_betti_curve(path: &Path) -> io::Result<BettiCurve> {
+    let mut reader = csv::Reader::from_reader(compression::open(path)?);
+    let headers = reader.headers()?.clone();
+    let betti_columns = headers
+        .iter()
+        .enumerate()
+        .filter(|(_, header)| header.starts_with("betti_"))
+        .map(|(column, _)| column)
+        .collect::<Vec<_>>();
+
+    let mut curve = BettiCurve::new();
+    for record in reader.records() {
+        let record = record?;
+        let parse = |column: usize| -> io::Result<i64> {
+            record[column].trim().parse().map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("malformed value `{}` in {}", &record[column], path.display()),
+                )
+            })
+        };
+        let timestep = parse(0)? as usize;
+        let betti_numbers = betti_columns.iter().map(|&column| parse(column)).collect::<io::Result<_>>()?;
+        curve.insert(timestep, betti_numbers);
+    }
+
+    Ok(curve)
+}
+
+/// A two-sample Kolmogorov-Smirnov test.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct KsTest {
+    /// The largest distance between the empirical distribution functions.
+    pub statistic: f64,
+    /// The asymptotic probability of a distance at least as large if both
+    /// samples came from the same distribution.
+    pub p_value: f64,
+    pub sizes: (usize, usize),
+}
+
+/// Tests whether two samples come from the same distribution, or returns
+/// `None` if either is empty.
+pub fn ks_test(a: &[f64], b: &[f64]) -> Option<KsTest> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+
+    let sorted = |sample: &[f64]| {
+        let mut sample = sample.to_vec();
+        sample.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        sample
+    };
+    let (a, b) = (sorted(a), sorted(b));
+    let (n, m) = (a.len() as f64, b.len() as f64);
+
+    // Step through the values of both in order, taking every copy of a value
+    // at once, so that ties don't count as a distance.
+    let (mut i, mut j, mut statistic) = (0, 0, 0f64);
+    while i < a.len() && j < b.len() {
+        let value = a[i].min(b[j]);
+        while i < a.len() && a[i] <= value {
+            i += 1;
+        }
+        while j < b.len() && b[j] <= value {
+            j += 1;
+        }
+        statistic = statistic.max((i as f64 / n - j as f64 / m).abs());
+    }
+
+    let effective = (n * m / (n + m)).sqrt();
+    let lambda = (effective + 0.12 + 0.11 / effective) * statistic;
+
+    Some(KsTest {
+        statistic,
+        p_value: kolmogorov_survival(lambda),
+        sizes: (a.len(), b.len()),
+    })
+}
+
+/// The survival function of the Kolmogorov distribution,
+/// `2 Σ (-1)^(k-1) exp(-2 k² λ²)`.
+fn kolmogorov_survival(lambda: f64) -> f64 {
+    if lambda < 1e-3 {
+        return 1.;
+    }
+
+    let mut sum = 0.;
+    for k in 1..=100 {
+        let term = (-2. * (k * k) as f64 * lambda * lambda).exp();
+        sum += if k % 2 == 1 { term } else { -term };
+        if term < 1e-12 {
+            break;
+        }
+    }
+    (2. * sum).clamp(0., 1.)
+}
+
+/// How far apart a statistic of the runs of two datasets is.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EffectSize {
+    pub mean_a: f64,
+    pub mean_b: f64,
+    /// The mean of the second less that of the first.
+    pub difference: f64,
+    /// Cohen's d: the difference over the pooled standard deviation, if
+    /// either dataset has more than one run and the runs vary at all.
+    pub cohens_d: Option<f64>,
+}
+
+/// Measures how far apart two samples of a statistic are, or returns `None`
+/// if either is empty.
+pub fn effect_size(a: &[f64], b: &[f64]) -> Option<EffectSize> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+
+    let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+    let squares = |values: &[f64], mean: f64| values.iter().map(|value| (value - mean).powi(2)).sum::<f64>();
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    let degrees_of_freedom = a.len() + b.len() - 2;
+    let pooled = ((squares(a, mean_a) + squares(b, mean_b)) / degrees_of_freedom.max(1) as f64).sqrt();
+
+    Some(EffectSize {
+        mean_a,
+        mean_b,
+        difference: mean_b - mean_a,
+        cohens_d: if degrees_of_freedom > 0 && pooled > 0. {
+            Some((mean_b - mean_a) / pooled)
+        } else {
+            None
+        },
+    })
+}
+
+/// How the mean betti curves of two datasets differ in one dimension, at the
+/// timesteps both were recorded at.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BettiDifference {
+    pub dim: usize,
+    pub timesteps: Vec<usize>,
+    /// The mean betti number of the second dataset less that of the first, at
+    /// each timestep.
+    pub differences: Vec<f64>,
+    pub mean_difference: f64,
+    pub max_abs_difference: f64,
+}
+
+/// The comparison of two datasets.
+#[derive(Clone, Debug, Serialize)]
+pub struct Comparison {
+    pub num_runs: (usize, usize),
+    /// The test of the in-degrees of every run of each, pooled.
+    pub degree_distribution: Option<KsTest>,
+    /// The effect sizes of the statistics of their runs, as in
+    /// [`analyze`].
+    pub statistics: BTreeMap<&'static str, EffectSize>,
+    pub betti_curves: Vec<BettiDifference>,
+}
+
+/// A statistic of a run, which not every run need have.
+type Statistic = fn(&RunSummary) -> Option<f64>;
+
+/// Compares two datasets.
+pub fn compare(a: &Dataset, b: &Dataset) -> Comparison {
+    let pooled = |dataset: &Dataset| {
+        dataset
+            .degrees
+            .iter()
+            .flatten()
+            .map(|&degree| degree as f64)
+            .collect::<Vec<_>>()
+    };
+    let summaries = |dataset: &Dataset| {
+        dataset
+            .degrees
+            .iter()
+            .map(|degrees| analyze::summarize(None, degrees))
+            .collect::<Vec<_>>()
+    };
+    let (summaries_a, summaries_b) = (summaries(a), summaries(b));
+
+    let statistics: [(&'static str, Statistic); 6] = [
+        ("num_edges", |run| Some(run.num_edges as f64)),
+        ("mean_degree", |run| Some(run.mean_degree)),
+        ("max_degree", |run| Some(run.max_degree as f64)),
+        ("degree_gini", |run| Some(run.degree_gini)),
+        ("condensation_fraction", |run| Some(run.condensation_fraction)),
+        ("power_law_exponent", |run| run.power_law.as_ref().map(|fit| fit.exponent)),
+    ];
+    let statistics = statistics
+        .iter()
+        .filter_map(|&(name, statistic)| {
+            let values_a = summaries_a.iter().filter_map(statistic).collect::<Vec<_>>();
+            let values_b = summaries_b.iter().filter_map(statistic).collect::<Vec<_>>();
+            Some((name, effect_size(&values_a, &values_b)?))
+        })
+        .collect();
+
+    Comparison {
+        num_runs: (a.degrees.len(), b.degrees.len()),
+        degree_distribution: ks_test(&pooled(a), &pooled(b)),
+        statistics,
+        betti_curves: betti_differences(&a.betti_curves, &b.betti_curves),
+    }
+}
+
+/// Returns the mean betti number of the curves in each dimension at each
+/// timestep any of them were recorded at.
+fn mean_curve(curves: &[BettiCurve]) -> BTreeMap<usize, Vec<f64>> {
+    let mut sums = BTreeMap::<usize, (Vec<f64>, usize)>::new();
+    for (&timestep, betti_numbers) in curves.iter().flatten() {
+        let (sum, count) = sums.entry(timestep).or_default();
+        if sum.len() < betti_numbers.len() {
+            sum.resize(betti_numbers.len(), 0.);
+        }
+        for (total, &betti_number) in sum.iter_mut().zip(betti_numbers) {
+            *total += betti_number as f64;
+        }
+        *count += 1;
+    }
+
+    sums.into_iter()
+        .map(|(timestep, (sum, count))| (timestep, sum.into_iter().map(|total| total / count as f64).collect()))
+        .collect()
+}
+
+/// Compares the mean betti curves of two sets of runs in each dimension both
+/// record.
+fn betti_differences(a: &[BettiCurve], b: &[BettiCurve]) -> Vec<BettiDifference> {
+    let (mean_a, mean_b) = (mean_curve(a), mean_curve(b));
+    let shared = mean_a
+        .iter()
+        .filter_map(|(timestep, a)| Some((*timestep, a, mean_b.get(timestep)?)))
+        .collect::<Vec<_>>();
+    let num_dims = shared.iter().map(|(_, a, b)| a.len().min(b.len())).min().unwrap_or(0);
+
+    (0..num_dims)
+        .map(|dim| {
+            let differences = shared.iter().map(|(_, a, b)| b[dim] - a[dim]).collect::<Vec<_>>();
+            BettiDifference {
+                dim,
+                timesteps: shared.iter().map(|&(timestep, _, _)| timestep).collect(),
+                mean_difference: differences.iter().sum::<f64>() / differences.len() as f64,
+                max_abs_difference: differences.iter().fold(0., |max, difference| difference.abs().max(max)),
+                differences,
+            }
+        })
+        .collect()
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} runs against {} runs", self.num_runs.0, self.num_runs.1)?;
+        match &self.degree_distribution {
+            Some(test) => writeln!(
+                f,
+                "degree distributions: KS statistic {:.4}, p-value {:.4} ({} against {} nodes)",
+                test.statistic, test.p_value, test.sizes.0, test.sizes.1
+            )?,
+            None => writeln!(f, "degree distributions: no nodes to compare")?,
+        }
+
+        for (name, effect) in &self.statistics {
+            write!(
+                f,
+                "  {}: {:.4} against {:.4}, difference {:.4}",
+                name, effect.mean_a, effect.mean_b, effect.difference
+            )?;
+            match effect.cohens_d {
+                Some(d) => writeln!(f, ", Cohen's d {:.3}", d)?,
+                None => writeln!(f)?,
+            }
+        }
+
+        for difference in &self.betti_curves {
+            writeln!(
+                f,
+                "  betti_{}: mean difference {:.4}, largest {:.4} over {} timesteps",
+                difference.dim,
+                difference.mean_difference,
+                difference.max_abs_difference,
+                difference.timesteps.len()
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// The configuration of every subcommand. Each section, and each field within
+/// it, falls back to its default when left out of a configuration file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub bose_einstein: bose_einstein::Config,
+    pub connectome: connectome_model::driver::Config,
+    pub topology: TopologyConfig,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TopologyConfig {
+    /// The dimension above which cofaces of the edges aren't completed.
+    pub max_dim: usize,
+}
+
+impl Default for TopologyConfig {
+    fn default() -> Self {
+        Self { max_dim: 3 }
+    }
+}
+
+impl Config {
+    /// Loads a configuration from a JSON file.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
@@ -0,0 +1,119 @@
+//! Just enough HTTP/1.1 for the [`serve`](crate::serve) API: one request per
+//! connection, with a body of a given length, answered with JSON or a file.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use serde::Serialize;
+use serde_json::json;
+
+/// The largest request body accepted, which is plenty for a configuration.
+pub const MAX_BODY_LEN: usize = 1 << 20;
+
+/// The most header lines read before a request is given up on.
+const MAX_HEADERS: usize = 100;
+
+pub struct Request {
+    pub method: String,
+    /// The path of the request, without any query string.
+    pub path: String,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Reads a request, as far as the end of its body.
+    pub fn read<R: BufRead>(mut reader: R) -> io::Result<Self> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+
+        let mut parts = line.split_whitespace();
+        let (method, target) = match (parts.next(), parts.next()) {
+            (Some(method), Some(target)) => (method.to_string(), target),
+            _ => return Err(invalid("malformed request line")),
+        };
+        let path = target.split('?').next().unwrap_or_default().to_string();
+
+        let mut content_len = 0;
+        for _ in 0..MAX_HEADERS {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 {
+                return Err(invalid("connection closed in the headers"));
+            }
+
+            let header = header.trim_end();
+            if header.is_empty() {
+                let mut body = vec![0; content_len];
+                reader.read_exact(&mut body)?;
+                return Ok(Request { method, path, body });
+            }
+
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    content_len = value.trim().parse().map_err(|_| invalid("malformed content length"))?;
+                    if content_len > MAX_BODY_LEN {
+                        return Err(invalid("request body too large"));
+                    }
+                }
+            }
+        }
+
+        Err(invalid("too many headers"))
+    }
+}
+
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn json<S: Serialize>(status: u16, value: &S) -> Self {
+        Response {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_vec_pretty(value).unwrap(),
+        }
+    }
+
+    /// An error, with its message in a JSON object.
+    pub fn error<M: fmt::Display>(status: u16, message: M) -> Self {
+        Response::json(status, &json!({ "error": message.to_string() }))
+    }
+
+    pub fn file(body: Vec<u8>) -> Self {
+        Response {
+            status: 200,
+            content_type: "application/octet-stream",
+            body,
+        }
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status,
+            reason(self.status),
+            self.content_type,
+            self.body.len()
+        )?;
+        writer.write_all(&self.body)?;
+        writer.flush()
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
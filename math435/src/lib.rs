@@ -0,0 +1,10 @@
+//! The shared pieces of the `math435` command line: loading a configuration
+//! for every subcommand, placing their output, serving an HTTP API to launch
+//! them, and analyzing and comparing the output of earlier runs.
+
+pub mod analyze;
+pub mod compare;
+pub mod config;
+pub mod http;
+pub mod output;
+pub mod serve;
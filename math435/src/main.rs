@@ -0,0 +1,390 @@
+use std::io;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use compression::Compression;
+use connectome_model::driver::Frames;
+use connectome_model::frames::FrameFormat;
+use connectome_model::snapshots::Schedule;
+use graph_io::AttributedGraph;
+use math435::compare::Dataset;
+use math435::config::Config;
+use math435::output::{Output, BOSE_EINSTEIN_RUN_FILE, BOSE_EINSTEIN_SWEEP_FILE};
+use math435::serve::Server;
+use provenance::Manifest;
+use results_db::ResultsDb;
+use serde_json::json;
+use topology::simplex::SimplicialComplex;
+
+fn cli() -> Command {
+    let run_or_sweep = |name: &'static str, about: &'static str| {
+        Command::new(name)
+            .about(about)
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("run").about("Runs a single simulation").arg(
+                    Arg::new("export")
+                        .long("export")
+                        .value_parser(["csv", "dot", "gexf", "graphml", "json"])
+                        .help("Also writes the final graph in this format"),
+                ),
+            )
+            .subcommand(Command::new("sweep").about("Runs many simulations in parallel"))
+    };
+
+    Command::new("math435")
+        .about("Runs the simulations and analyses of the math435 project")
+        .subcommand_required(true)
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .global(true)
+                .value_parser(value_parser!(PathBuf))
+                .help("A JSON file of parameters; any left out take their defaults"),
+        )
+        .arg(
+            Arg::new("out")
+                .long("out")
+                .global(true)
+                .default_value("out")
+                .value_parser(value_parser!(PathBuf))
+                .help("The directory to write output files to"),
+        )
+        .arg(
+            Arg::new("compress")
+                .long("compress")
+                .global(true)
+                .value_parser(Compression::NAMES)
+                .help("Compresses the CSV and JSON lines output of the simulations"),
+        )
+        .arg(
+            Arg::new("progress")
+                .long("progress")
+                .global(true)
+                .default_value("auto")
+                .value_parser(progress::Mode::NAMES)
+                .help("How progress is reported: a bar, plain text, or JSON events; auto picks a bar on a terminal"),
+        )
+        .arg(
+            Arg::new("stats")
+                .long("stats")
+                .global(true)
+                .value_name("STEPS")
+                .value_parser(value_parser!(usize))
+                .help("Reports memory use, graph size and step timings of connectome runs every this many steps"),
+        )
+        .arg(
+            Arg::new("frames")
+                .long("frames")
+                .global(true)
+                .value_name("STEPS")
+                .value_parser(value_parser!(usize))
+                .help("Writes a frame of the connectome graph every this many steps, for animating its growth"),
+        )
+        .arg(
+            Arg::new("frame-format")
+                .long("frame-format")
+                .global(true)
+                .default_value("dot")
+                .value_parser(FrameFormat::NAMES)
+                .help("The format of the frames; png renders them with Graphviz's neato"),
+        )
+        .arg(
+            Arg::new("history")
+                .long("history")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Writes the history of each connectome graph as a dynamic GEXF file, for Gephi's timeline"),
+        )
+        .arg(
+            Arg::new("script")
+                .long("script")
+                .global(true)
+                .value_parser(value_parser!(PathBuf))
+                .help("A JSON script of events at which connectome runs stimulate, lesion or change parameters"),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .global(true)
+                .value_name("sqlite:PATH")
+                .help("Also adds the runs of the simulations to a results database"),
+        )
+        .subcommand(run_or_sweep(
+            "bose-einstein",
+            "The Bose-Einstein condensation model of a growing network",
+        ))
+        .subcommand(run_or_sweep("connectome", "The connectome model"))
+        .subcommand(
+            Command::new("serve")
+                .about("Serves an HTTP API to launch simulations, follow them and download their output")
+                .arg(
+                    Arg::new("addr")
+                        .long("addr")
+                        .default_value("127.0.0.1:8435")
+                        .help("The address to listen on"),
+                ),
+        )
+        .subcommand(
+            Command::new("analyze")
+                .about("Recomputes the degree statistics of the nodes or edges written by earlier runs")
+                .arg(
+                    Arg::new("table")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf))
+                        .help("A CSV of nodes with an in_degree column, or of edges, possibly compressed"),
+                ),
+        )
+        .subcommand(
+            Command::new("compare")
+                .about("Compares the degree distributions, run statistics and betti curves of two datasets of runs")
+                .arg(
+                    Arg::new("a")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf))
+                        .help("A table of nodes or edges, or a directory of connectome output"),
+                )
+                .arg(
+                    Arg::new("b")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf))
+                        .help("The dataset to compare it with, of either kind"),
+                ),
+        )
+        .subcommand(
+            Command::new("topology")
+                .about("Analyses of simplicial complexes")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("analyze")
+                        .about("Reports the homology of the clique complex of an edge list")
+                        .arg(
+                            Arg::new("edgelist")
+                                .required(true)
+                                .value_parser(value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            Arg::new("max-dim")
+                                .long("max-dim")
+                                .value_parser(value_parser!(usize))
+                                .help("Overrides the maximum dimension from the configuration"),
+                        ),
+                ),
+        )
+}
+
+fn main() {
+    let matches = cli().get_matches();
+
+    if let Err(err) = run(&matches) {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+/// Runs a subcommand, recording its parameters and the files it writes in a
+/// manifest in the output directory.
+fn run(matches: &ArgMatches) -> io::Result<()> {
+    progress::set_mode(matches.get_one::<String>("progress").unwrap().parse().unwrap());
+
+    let mut config = match matches.get_one::<PathBuf>("config") {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    if let Some(compression) = matches.get_one::<String>("compress") {
+        let compression = compression.parse::<Compression>().unwrap();
+        config.bose_einstein.compression = compression;
+        config.connectome.compression = compression;
+    }
+    if let Some(&interval) = matches.get_one::<usize>("stats") {
+        config.connectome.stats_interval = Some(interval);
+    }
+    if let Some(&interval) = matches.get_one::<usize>("frames") {
+        let format = match matches.get_one::<String>("frame-format").unwrap().as_str() {
+            "png" => FrameFormat::Png,
+            _ => FrameFormat::Dot,
+        };
+        config.connectome.frames = Some(Frames {
+            interval,
+            format,
+            schedule: Schedule::Interval,
+        });
+    }
+    if matches.get_flag("history") {
+        config.connectome.history = true;
+    }
+    if let Some(path) = matches.get_one::<PathBuf>("script") {
+        config.connectome.events = connectome_model::experiment::load(path)?;
+    }
+    let output = Output::create(matches.get_one::<PathBuf>("out").unwrap())?;
+
+    // The server records each job it runs in a manifest of its own.
+    if let Some(("serve", matches)) = matches.subcommand() {
+        let listener = TcpListener::bind(matches.get_one::<String>("addr").unwrap())?;
+        eprintln!("listening on http://{}", listener.local_addr()?);
+        return Server::start(output, config)?.listen(listener);
+    }
+
+    let mut program = String::from("math435");
+    let mut subcommand = matches.subcommand();
+    while let Some((name, matches)) = subcommand {
+        program.push(' ');
+        program.push_str(name);
+        subcommand = matches.subcommand();
+    }
+
+    // A sweep picks up the seed of the interrupted sweep it resumes.
+    config.bose_einstein = match program.as_str() {
+        "math435 bose-einstein sweep" => {
+            bose_einstein::sweep_config(&config.bose_einstein, output.path(BOSE_EINSTEIN_SWEEP_FILE))?
+        }
+        _ => config.bose_einstein.seeded(),
+    };
+    config.connectome = match program.as_str() {
+        "math435 connectome sweep" => connectome_model::driver::sweep_config(&config.connectome, output.dir())?,
+        _ => config.connectome.seeded(),
+    };
+
+    let seeds = match matches.subcommand_name() {
+        Some("bose-einstein") => config.bose_einstein.seed.into_iter().collect(),
+        Some("connectome") => config.connectome.seed.into_iter().collect(),
+        _ => Vec::new(),
+    };
+
+    let db = match matches.get_one::<String>("output") {
+        Some(output) => Some(results_db(output)?),
+        None => None,
+    };
+
+    let manifest = Mutex::new(Manifest::start(output.dir(), &program, env!("CARGO_PKG_VERSION"), &config, &seeds)?);
+    dispatch(matches, &config, &output, db.as_ref(), &manifest)?;
+    manifest.into_inner().unwrap().finish()
+}
+
+/// Opens the results database named by `--output`.
+fn results_db(output: &str) -> io::Result<ResultsDb> {
+    match output.strip_prefix("sqlite:") {
+        Some(path) => ResultsDb::open(path),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unknown output `{}`; expected `sqlite:<path>`", output),
+        )),
+    }
+}
+
+/// Runs the subcommand, logging anything its run does partway in `manifest`.
+fn dispatch(
+    matches: &ArgMatches,
+    config: &Config,
+    output: &Output,
+    db: Option<&ResultsDb>,
+    manifest: &Mutex<Manifest>,
+) -> io::Result<()> {
+    match matches.subcommand() {
+        Some(("bose-einstein", matches)) => match matches.subcommand() {
+            Some(("run", matches)) => {
+                let graph = bose_einstein::run(&config.bose_einstein, output.path(BOSE_EINSTEIN_RUN_FILE), db)?;
+                export(matches, &graph, output, "bose_einstein_graph")
+            }
+            Some(("sweep", _)) => bose_einstein::sweep(&config.bose_einstein, output.path(BOSE_EINSTEIN_SWEEP_FILE), db),
+            _ => unreachable!(),
+        },
+        Some(("connectome", matches)) => match matches.subcommand() {
+            Some(("run", matches)) => {
+                let graph = connectome_model::driver::run_logged(&config.connectome, output.dir(), db, manifest)?;
+                export(matches, &graph, output, "connectome_graph")
+            }
+            Some(("sweep", _)) => {
+                connectome_model::driver::sweep_logged(&config.connectome, output.dir(), db, manifest)
+            }
+            _ => unreachable!(),
+        },
+        Some(("analyze", matches)) => analyze_table(matches.get_one::<PathBuf>("table").unwrap(), output),
+        Some(("compare", matches)) => compare(
+            matches.get_one::<PathBuf>("a").unwrap(),
+            matches.get_one::<PathBuf>("b").unwrap(),
+            output,
+        ),
+        Some(("topology", matches)) => match matches.subcommand() {
+            Some(("analyze", matches)) => {
+                let max_dim = matches
+                    .get_one::<usize>("max-dim")
+                    .cloned()
+                    .unwrap_or(config.topology.max_dim);
+
+                analyze(matches.get_one::<PathBuf>("edgelist").unwrap(), max_dim, output)
+            }
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// Writes a graph to `<name>.<extension>` if an export format was asked for.
+fn export(matches: &ArgMatches, graph: &AttributedGraph, output: &Output, name: &str) -> io::Result<()> {
+    let exporter = match matches.get_one::<String>("export") {
+        Some(format) => graph_io::exporter(format).unwrap(),
+        None => return Ok(()),
+    };
+
+    exporter.export_to_path(graph, &output.path(format!("{}.{}", name, exporter.extension())))
+}
+
+/// Prints the degree statistics of a table of nodes or edges, and writes them
+/// to a JSON file named after it.
+fn analyze_table(path: &Path, output: &Output) -> io::Result<()> {
+    let analysis = math435::analyze::analyze(path)?;
+    print!("{}", analysis);
+
+    let name = math435::analyze::table_name(path);
+    let stem = Path::new(&name).file_stem().unwrap_or_default().to_string_lossy();
+    std::fs::write(
+        output.path(format!("{}_summary.json", stem)),
+        serde_json::to_string_pretty(&analysis)?,
+    )
+}
+
+/// Prints the comparison of two datasets of runs, and writes it to a JSON
+/// file named after them both.
+fn compare(a: &Path, b: &Path, output: &Output) -> io::Result<()> {
+    let comparison = math435::compare::compare(&Dataset::load(a)?, &Dataset::load(b)?);
+    print!("{}", comparison);
+
+    let stem = |path: &Path| {
+        let name = math435::analyze::table_name(path);
+        Path::new(&name).file_stem().unwrap_or_default().to_string_lossy().into_owned()
+    };
+    std::fs::write(
+        output.path(format!("{}_vs_{}_comparison.json", stem(a), stem(b))),
+        serde_json::to_string_pretty(&comparison)?,
+    )
+}
+
+/// Prints the f-vector and betti numbers of the clique complex of an edge
+/// list, and writes them to a JSON file named after it.
+fn analyze(path: &Path, max_dim: usize, output: &Output) -> io::Result<()> {
+    let complex = SimplicialComplex::from_edge_list(path, max_dim)?;
+
+    let f_vector = complex.f_vector();
+    let betti_numbers = complex.betti_numbers_full();
+    let euler_characteristic = complex.euler_characteristic();
+
+    println!("f-vector: {:?}", f_vector);
+    println!("betti numbers: {:?}", betti_numbers);
+    println!("euler characteristic: {}", euler_characteristic);
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let report = json!({
+        "edgelist": path,
+        "max_dim": max_dim,
+        "f_vector": f_vector,
+        "betti_numbers": betti_numbers,
+        "euler_characteristic": euler_characteristic,
+    });
+    std::fs::write(
+        output.path(format!("{}_analysis.json", stem)),
+        serde_json::to_string_pretty(&report)?,
+    )
+}
@@ -0,0 +1,33 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The file a Bose-Einstein run is written to in the output directory.
+pub const BOSE_EINSTEIN_RUN_FILE: &str = "bose_einstein.csv";
+
+/// The file a Bose-Einstein sweep is written to in the output directory.
+pub const BOSE_EINSTEIN_SWEEP_FILE: &str = "bose_einstein_sweep.csv";
+
+/// The directory a subcommand writes its output files to.
+pub struct Output {
+    dir: PathBuf,
+}
+
+impl Output {
+    /// Uses `dir` for output, creating it if it doesn't exist yet.
+    pub fn create<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+
+        Ok(Output {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path of an output file.
+    pub fn path<P: AsRef<Path>>(&self, name: P) -> PathBuf {
+        self.dir.join(name)
+    }
+}
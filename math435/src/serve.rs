@@ -0,0 +1,426 @@
+//! `math435 serve`: a small HTTP API for launching simulations and following
+//! them, so that a web frontend or a scheduler can drive experiments without
+//! shelling out to the binary.
+//!
+//! - `POST /jobs` with `{"program": "connectome", "mode": "sweep", "config":
+//!   {...}}` queues a simulation, returning its id. The configuration takes
+//!   the same form as a `--config` file, and is layered over the server's own.
+//! - `GET /jobs` lists every job, and `GET /jobs/<id>` reports one: whether
+//!   it's queued, running, finished or failed, and for a sweep, how many of its
+//!   runs are done.
+//! - `GET /jobs/<id>/files` lists the files a job wrote, and
+//!   `GET /jobs/<id>/files/<path>` downloads one.
+//!
+//! Jobs run one at a time, in the order they were queued, each writing to
+//! `jobs/<id>` in the output directory as the equivalent subcommand would.
+
+use std::io::{self, BufReader};
+use std::net::{TcpListener, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex, Weak};
+use std::thread;
+use std::time::SystemTime;
+
+use provenance::{Ledger, Manifest};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::http::{Request, Response};
+use crate::output::{Output, BOSE_EINSTEIN_RUN_FILE, BOSE_EINSTEIN_SWEEP_FILE};
+
+/// The directory jobs write to, within the output directory.
+pub const JOBS_DIR: &str = "jobs";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Program {
+    BoseEinstein,
+    Connectome,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    Run,
+    Sweep,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Queued,
+    Running,
+    Finished,
+    Failed,
+}
+
+/// The body of a request to launch a job.
+#[derive(Deserialize)]
+struct Submission {
+    program: Program,
+    mode: Mode,
+    #[serde(default)]
+    config: Value,
+}
+
+/// A simulation launched through the API.
+#[derive(Clone, Debug, Serialize)]
+pub struct Job {
+    pub id: u64,
+    pub program: Program,
+    pub mode: Mode,
+    pub status: Status,
+    /// Why the job failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub queued_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    /// The configuration the job runs with, seeded.
+    pub config: Config,
+}
+
+impl Job {
+    /// The name of the subcommand the job is equivalent to.
+    fn subcommand(&self) -> String {
+        let program = match self.program {
+            Program::BoseEinstein => "bose-einstein",
+            Program::Connectome => "connectome",
+        };
+        let mode = match self.mode {
+            Mode::Run => "run",
+            Mode::Sweep => "sweep",
+        };
+
+        format!("math435 {} {}", program, mode)
+    }
+}
+
+/// How many of the runs of a sweep are done.
+#[derive(Serialize)]
+struct SweepProgress {
+    runs_done: u64,
+    num_runs: u64,
+}
+
+#[derive(Serialize)]
+struct JobReport<'a> {
+    #[serde(flatten)]
+    job: &'a Job,
+    progress: Option<SweepProgress>,
+}
+
+/// The jobs launched through the API, and the queue of those waiting to run.
+pub struct Server {
+    out: Output,
+    /// The configuration jobs' configurations are layered over.
+    base: Config,
+    /// The id of the first job, which follows on from those of earlier
+    /// servers writing to the same directory.
+    first_id: u64,
+    jobs: Mutex<Vec<Job>>,
+    queue: Mutex<mpsc::Sender<u64>>,
+}
+
+impl Server {
+    /// Starts a server writing jobs to `out`, along with the worker thread
+    /// which runs them, and which stops once the server is dropped.
+    pub fn start(out: Output, base: Config) -> io::Result<Arc<Self>> {
+        let first_id = last_job_id(&out.path(JOBS_DIR))?.map_or(0, |id| id + 1);
+        let (sender, receiver) = mpsc::channel();
+        let server = Arc::new(Server {
+            out,
+            base,
+            first_id,
+            jobs: Mutex::new(Vec::new()),
+            queue: Mutex::new(sender),
+        });
+
+        let worker: Weak<Server> = Arc::downgrade(&server);
+        thread::spawn(move || {
+            for id in receiver {
+                match worker.upgrade() {
+                    Some(server) => server.execute(id),
+                    None => break,
+                }
+            }
+        });
+
+        Ok(server)
+    }
+
+    /// Answers requests on `listener`, each on its own thread, until it
+    /// fails.
+    pub fn listen(self: &Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let server = Arc::clone(self);
+            let stream = stream?;
+            thread::spawn(move || server.respond(stream));
+        }
+
+        Ok(())
+    }
+
+    fn respond(&self, stream: TcpStream) {
+        let response = match Request::read(BufReader::new(&stream)) {
+            Ok(request) => self.handle(&request),
+            Err(err) => Response::error(400, err),
+        };
+
+        // The client may be gone, which is no concern of the server's.
+        let _ = response.write(&stream);
+    }
+
+    /// Answers a request.
+    pub fn handle(&self, request: &Request) -> Response {
+        let segments = request
+            .path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+
+        let result = match (request.method.as_str(), segments.as_slice()) {
+            ("POST", ["jobs"]) => self.submit(&request.body),
+            ("GET", ["jobs"]) => {
+                let jobs = self.jobs.lock().unwrap().clone();
+                Ok(Response::json(200, &jobs.iter().map(|job| self.report(job)).collect::<Vec<_>>()))
+            }
+            ("GET", ["jobs", id]) => self.job(id).map(|job| Response::json(200, &self.report(&job))),
+            ("GET", ["jobs", id, "files"]) => self.job(id).and_then(|job| {
+                files(&self.job_dir(job.id))
+                    .map(|files| Response::json(200, &files))
+                    .map_err(|err| Response::error(500, err))
+            }),
+            ("GET", ["jobs", id, "files", path @ ..]) => self.job(id).and_then(|job| self.download(&job, path)),
+            (_, ["jobs"]) | (_, ["jobs", _]) => Err(Response::error(405, "method not allowed")),
+            _ => Err(Response::error(404, "not found")),
+        };
+
+        result.unwrap_or_else(|response| response)
+    }
+
+    /// Queues a job, with its configuration layered over the server's and
+    /// seeded, so that the job can be reproduced.
+    fn submit(&self, body: &[u8]) -> Result<Response, Response> {
+        let submission: Submission = serde_json::from_slice(body).map_err(|err| Response::error(400, err))?;
+
+        let mut config = serde_json::to_value(&self.base).unwrap();
+        if !submission.config.is_null() {
+            merge(&mut config, submission.config);
+        }
+        let config: Config = serde_json::from_value(config).map_err(|err| Response::error(400, err))?;
+
+        if submission.program == Program::Connectome && config.connectome.num_steps.is_none() {
+            return Err(Response::error(400, "a connectome job needs a number of steps to stop at"));
+        }
+
+        let config = Config {
+            bose_einstein: config.bose_einstein.seeded(),
+            connectome: config.connectome.seeded(),
+            ..config
+        };
+
+        let mut jobs = self.jobs.lock().unwrap();
+        let job = Job {
+            id: self.first_id + jobs.len() as u64,
+            program: submission.program,
+            mode: submission.mode,
+            status: Status::Queued,
+            error: None,
+            queued_at: now(),
+            started_at: None,
+            finished_at: None,
+            config,
+        };
+        jobs.push(job.clone());
+        drop(jobs);
+
+        self.queue
+            .lock()
+            .unwrap()
+            .send(job.id)
+            .map_err(|_| Response::error(500, "the worker has stopped"))?;
+
+        Ok(Response::json(202, &self.report(&job)))
+    }
+
+    fn job(&self, id: &str) -> Result<Job, Response> {
+        let not_found = || Response::error(404, format!("no job {}", id));
+        let index = id
+            .parse::<u64>()
+            .ok()
+            .and_then(|id| id.checked_sub(self.first_id))
+            .ok_or_else(not_found)?;
+
+        self.jobs.lock().unwrap().get(index as usize).cloned().ok_or_else(not_found)
+    }
+
+    fn report<'a>(&self, job: &'a Job) -> JobReport<'a> {
+        let progress = match job.mode {
+            Mode::Run => None,
+            Mode::Sweep => {
+                let dir = self.job_dir(job.id);
+                let (ledger, num_runs) = match job.program {
+                    Program::BoseEinstein => (
+                        dir.join(BOSE_EINSTEIN_SWEEP_FILE)
+                            .with_extension(bose_einstein::LEDGER_EXTENSION),
+                        job.config.bose_einstein.num_runs,
+                    ),
+                    Program::Connectome => (
+                        dir.join(connectome_model::driver::LEDGER_FILE),
+                        job.config.connectome.num_runs as u64,
+                    ),
+                };
+
+                // A finished sweep has removed its ledger.
+                let runs_done = match job.status {
+                    Status::Finished => num_runs,
+                    _ => Ledger::completed(ledger).map_or(0, |runs| runs.len() as u64),
+                };
+
+                Some(SweepProgress { runs_done, num_runs })
+            }
+        };
+
+        JobReport { job, progress }
+    }
+
+    /// Returns a file a job wrote, given the segments of its path within the
+    /// job's directory, which can't lead out of it.
+    fn download(&self, job: &Job, segments: &[&str]) -> Result<Response, Response> {
+        let path = segments.iter().collect::<PathBuf>();
+        if !path.components().all(|component| matches!(component, Component::Normal(_))) {
+            return Err(Response::error(400, "file paths must be within the job's directory"));
+        }
+
+        std::fs::read(self.job_dir(job.id).join(&path))
+            .map(Response::file)
+            .map_err(|_| Response::error(404, format!("no file {}", path.display())))
+    }
+
+    fn job_dir(&self, id: u64) -> PathBuf {
+        self.out.path(JOBS_DIR).join(id.to_string())
+    }
+
+    /// Runs a queued job, recording whether it finished or failed. A job
+    /// which panics fails without taking the worker down with it.
+    fn execute(&self, id: u64) {
+        let job = self.update(id, |job| {
+            job.status = Status::Running;
+            job.started_at = Some(now());
+        });
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| run(&job, &self.job_dir(id))));
+        let error = match result {
+            Ok(Ok(())) => None,
+            Ok(Err(err)) => Some(err.to_string()),
+            Err(panic) => Some(
+                panic
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "the job panicked".to_string()),
+            ),
+        };
+
+        self.update(id, |job| {
+            job.status = if error.is_none() { Status::Finished } else { Status::Failed };
+            job.error = error;
+            job.finished_at = Some(now());
+        });
+    }
+
+    /// Changes a job, returning it as changed.
+    fn update<F: FnOnce(&mut Job)>(&self, id: u64, change: F) -> Job {
+        let mut jobs = self.jobs.lock().unwrap();
+        let job = &mut jobs[(id - self.first_id) as usize];
+        change(job);
+        job.clone()
+    }
+}
+
+/// Runs a job as its subcommand would, recording it in a manifest in `dir`.
+fn run(job: &Job, dir: &Path) -> io::Result<()> {
+    let output = Output::create(dir)?;
+    let config = &job.config;
+    let seeds = match job.program {
+        Program::BoseEinstein => config.bose_einstein.seed.into_iter().collect::<Vec<_>>(),
+        Program::Connectome => config.connectome.seed.into_iter().collect(),
+    };
+
+    let manifest = Mutex::new(Manifest::start(dir, &job.subcommand(), env!("CARGO_PKG_VERSION"), config, &seeds)?);
+    match (job.program, job.mode) {
+        (Program::BoseEinstein, Mode::Run) => {
+            bose_einstein::run(&config.bose_einstein, output.path(BOSE_EINSTEIN_RUN_FILE), None).map(drop)
+        }
+        (Program::BoseEinstein, Mode::Sweep) => {
+            bose_einstein::sweep(&config.bose_einstein, output.path(BOSE_EINSTEIN_SWEEP_FILE), None)
+        }
+        (Program::Connectome, Mode::Run) => {
+            connectome_model::driver::run_logged(&config.connectome, dir, None, &manifest).map(drop)
+        }
+        (Program::Connectome, Mode::Sweep) => {
+            connectome_model::driver::sweep_logged(&config.connectome, dir, None, &manifest)
+        }
+    }?;
+    manifest.into_inner().unwrap().finish()
+}
+
+/// Overrides the fields of `base` with those given in `overrides`, object by
+/// object, so that a partial configuration keeps the rest of the base.
+fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                merge(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+/// Lists the files in a directory and those within it, relative to it.
+fn files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(current) = dirs.pop() {
+        if !current.exists() {
+            continue;
+        }
+
+        for entry in std::fs::read_dir(&current)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                files.push(path.strip_prefix(dir).unwrap().to_path_buf());
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the largest id of the jobs already in `dir`, if there are any.
+fn last_job_id(dir: &Path) -> io::Result<Option<u64>> {
+    if !dir.exists() {
+        return Ok(None);
+    }
+
+    let mut max = None;
+    for entry in std::fs::read_dir(dir)? {
+        if let Some(id) = entry?.file_name().to_str().and_then(|name| name.parse::<u64>().ok()) {
+            max = max.max(Some(id));
+        }
+    }
+
+    Ok(max)
+}
+
+fn now() -> String {
+    provenance::utc_timestamp(SystemTime::now())
+}
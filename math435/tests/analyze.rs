@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+
+use math435::analyze::{analyze, table_name, TableKind};
+
+fn write(name: &str, contents: &str) -> PathBuf {
+    // Kept, as it outlives this function.
+    let path = tempfile::tempdir().unwrap().keep().join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn node_tables_are_split_into_runs() {
+    let path = write(
+        "math435-analyze-sweep.csv",
+        "id,run,in_degree,fitness\n0,0,3,0.5\n1,0,1,0.2\n2,0,0,0.1\n0,1,2,0.4\n1,1,2,0.3\n",
+    );
+
+    let analysis = analyze(&path).unwrap();
+
+    assert_eq!(analysis.kind, TableKind::Nodes);
+    assert_eq!(analysis.runs.len(), 2);
+    assert_eq!(analysis.runs[0].run, Some(0));
+    assert_eq!(analysis.runs[0].num_edges, 4);
+    assert_eq!(analysis.runs[0].condensation_fraction, 0.75);
+    assert_eq!(analysis.runs[1].degree_gini, 0.0);
+    assert_eq!(analysis.aggregates["condensation_fraction"].mean, 0.625);
+    assert!(!analysis.aggregates.contains_key("power_law_exponent"));
+}
+
+#[test]
+fn edge_lists_count_in_degrees() {
+    let path = write("math435-analyze-edges.csv", "source,target,weight\n0,1,1.0\n0,2,1.0\n1,2,1.0\n");
+
+    let analysis = analyze(&path).unwrap();
+
+    assert_eq!(analysis.kind, TableKind::Edges);
+    assert_eq!(analysis.runs.len(), 1);
+    assert_eq!(analysis.runs[0].run, None);
+    assert_eq!(analysis.runs[0].num_nodes, 3);
+    assert_eq!(analysis.runs[0].max_degree, 2);
+}
+
+#[test]
+fn unknown_tables_are_errors() {
+    let path = write("math435-analyze-other.csv", "a,b\n1,2\n");
+    assert!(analyze(&path).is_err());
+
+    let path = write("math435-analyze.parquet", "");
+    assert_eq!(analyze(&path).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+
+    assert_eq!(table_name(&PathBuf::from("out/bose_einstein_sweep.csv.zst")), "bose_einstein_sweep.csv");
+}
@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::Path;
+
+use math435::compare::{compare, effect_size, ks_test, Dataset};
+
+/// Writes a connectome run's nodes and betti curve to `run_<index>` in `dir`.
+fn write_run(dir: &Path, index: usize, in_degrees: &[usize], betti_curve: &str) {
+    let run_dir = dir.join(format!("run_{}", index));
+    fs::create_dir_all(&run_dir).unwrap();
+    let nodes = in_degrees
+        .iter()
+        .enumerate()
+        .map(|(id, in_degree)| format!("{},{},1,1\n", id, in_degree))
+        .collect::<String>();
+    fs::write(run_dir.join("nodes.csv"), format!("id,in_degree,out_degree,core\n{}", nodes)).unwrap();
+    fs::write(run_dir.join("betti_curve.csv"), betti_curve).unwrap();
+}
+
+#[test]
+fn ks_tests_tell_apart_shifted_samples() {
+    let a = (0..200).map(f64::from).collect::<Vec<_>>();
+    let same = ks_test(&a, &a).unwrap();
+    assert_eq!(same.statistic, 0.);
+    assert_eq!(same.p_value, 1.);
+
+    let shifted = a.iter().map(|value| value + 100.).collect::<Vec<_>>();
+    let test = ks_test(&a, &shifted).unwrap();
+    assert_eq!(test.statistic, 0.5);
+    assert!(test.p_value < 1e-6);
+    assert_eq!(test.sizes, (200, 200));
+    assert!(ks_test(&a, &[]).is_none());
+
+    let effect = effect_size(&[1., 2., 3.], &[3., 4., 5.]).unwrap();
+    assert_eq!(effect.difference, 2.);
+    assert_eq!(effect.cohens_d, Some(2.));
+    assert_eq!(effect_size(&[1.], &[2.]).unwrap().cohens_d, None);
+}
+
+#[test]
+fn datasets_of_connectome_runs_are_compared() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().join("math435-compare");
+    let (hot, cold) = (dir.join("hot"), dir.join("cold"));
+    let header = "timestep,betti_0,betti_1,simplices_0,simplices_1\n";
+    write_run(&hot, 0, &[1, 1, 1, 1], &format!("{}10,4,0,4,0\n20,2,1,4,3\n", header));
+    write_run(&hot, 1, &[2, 1, 1, 0], &format!("{}10,4,0,4,0\n20,2,0,4,3\n", header));
+    write_run(&cold, 0, &[4, 0, 0, 0], &format!("{}10,3,0,4,1\n20,1,0,4,3\n30,1,0,4,3\n", header));
+
+    let (hot, cold) = (Dataset::load(&hot).unwrap(), Dataset::load(&cold).unwrap());
+    assert_eq!(hot.degrees.len(), 2);
+    assert_eq!(hot.betti_curves.len(), 2);
+
+    let comparison = compare(&hot, &cold);
+    assert_eq!(comparison.num_runs, (2, 1));
+    assert!(comparison.degree_distribution.as_ref().unwrap().statistic > 0.);
+    let max_degree = &comparison.statistics["max_degree"];
+    assert_eq!((max_degree.mean_a, max_degree.mean_b), (1.5, 4.));
+
+    // Only timesteps 10 and 20 are shared.
+    assert_eq!(comparison.betti_curves.len(), 2);
+    assert_eq!(comparison.betti_curves[0].timesteps, vec![10, 20]);
+    assert_eq!(comparison.betti_curves[0].differences, vec![-1., -1.]);
+    assert_eq!(comparison.betti_curves[1].differences, vec![0., -0.5]);
+    assert_eq!(comparison.betti_curves[1].max_abs_difference, 0.5);
+
+    // A table has no betti curves to compare.
+    let table = dir.join("table.csv");
+    fs::write(&table, "id,run,in_degree\n0,0,1\n1,0,1\n").unwrap();
+    let comparison = compare(&Dataset::load(&table).unwrap(), &cold);
+    assert!(comparison.betti_curves.is_empty());
+    assert!(Dataset::load(dir.join("missing")).is_err());
+}
@@ -0,0 +1,24 @@
+use math435::config::Config;
+
+#[test]
+fn missing_fields_take_their_defaults() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("math435-partial-config.json");
+    std::fs::write(&path, r#"{ "connectome": { "num_steps": 100, "lesion": { "step": 50, "radius": 1.5 } } }"#).unwrap();
+
+    let config = Config::load(&path).unwrap();
+
+    assert_eq!(config.bose_einstein, bose_einstein::Config::default());
+    assert_eq!(config.connectome.num_steps, Some(100));
+    assert_eq!(config.connectome.lesion.as_ref().map(|lesion| lesion.step), Some(50));
+    assert_eq!(config.connectome.grid_size, connectome_model::driver::Config::default().grid_size);
+}
+
+#[test]
+fn malformed_config_is_an_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("math435-malformed-config.json");
+    std::fs::write(&path, r#"{ "bose_einstein": { "num_runs": "many" } }"#).unwrap();
+
+    assert!(Config::load(&path).is_err());
+}
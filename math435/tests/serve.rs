@@ -0,0 +1,124 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use math435::{config::Config, output::Output, serve::Server};
+use serde_json::{json, Value};
+
+/// Starts a server writing to a fresh directory, listening on a free port.
+fn start(name: &str) -> SocketAddr {
+    // Kept, as it outlives this function.
+    let dir = tempfile::tempdir().unwrap().keep().join(name);
+    let server = Server::start(Output::create(&dir).unwrap(), Config::default()).unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || server.listen(listener));
+    addr
+}
+
+/// Makes a request, returning the status and body of the response.
+fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Vec<u8>) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        body.len(),
+        body
+    )
+    .unwrap();
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).unwrap();
+    let head_len = response.windows(4).position(|window| window == b"\r\n\r\n").unwrap();
+    let status = String::from_utf8_lossy(&response[9..12]).parse().unwrap();
+
+    (status, response[head_len + 4..].to_vec())
+}
+
+fn request_json(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+    let (status, body) = request(addr, method, path, body);
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+/// Polls a job until it's no longer queued or running.
+fn wait_for(addr: SocketAddr, id: u64) -> Value {
+    let start = Instant::now();
+
+    loop {
+        let (_, job) = request_json(addr, "GET", &format!("/jobs/{}", id), "");
+        if job["status"] != "queued" && job["status"] != "running" {
+            return job;
+        }
+
+        assert!(start.elapsed() < Duration::from_secs(60), "{}", job);
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn job_output_can_be_downloaded() {
+    let addr = start("math435-serve-run");
+    let submission = json!({ "program": "bose-einstein", "mode": "run", "config": { "bose_einstein": { "num_steps": 20 } } });
+
+    let (status, job) = request_json(addr, "POST", "/jobs", &submission.to_string());
+    assert_eq!(status, 202);
+    assert_eq!(job["config"]["bose_einstein"]["num_steps"], 20);
+    assert!(job["config"]["bose_einstein"]["seed"].is_u64());
+
+    let job = wait_for(addr, job["id"].as_u64().unwrap());
+    assert_eq!(job["status"], "finished", "{}", job);
+
+    let (_, files) = request_json(addr, "GET", "/jobs/0/files", "");
+    for file in &["bose_einstein.csv", "bose_einstein.metrics.json", "manifest.json"] {
+        assert!(files.as_array().unwrap().contains(&json!(file)), "{}", files);
+    }
+
+    let (status, csv) = request(addr, "GET", "/jobs/0/files/bose_einstein.csv", "");
+    assert_eq!(status, 200);
+    assert!(csv.starts_with(b"id,in_degree,out_degree,fitness,temperature,core\n"));
+
+    let (_, manifest) = request_json(addr, "GET", "/jobs/0/files/manifest.json", "");
+    assert_eq!(manifest["program"], "math435 bose-einstein run");
+
+    assert_eq!(request(addr, "GET", "/jobs/0/files/../../secret", "").0, 400);
+    assert_eq!(request(addr, "GET", "/jobs/0/files/missing.csv", "").0, 404);
+    assert_eq!(request(addr, "GET", "/jobs/7", "").0, 404);
+}
+
+#[test]
+fn sweep_reports_its_progress() {
+    let addr = start("math435-serve-sweep");
+    let submission = json!({
+        "program": "bose-einstein",
+        "mode": "sweep",
+        "config": { "bose_einstein": { "num_steps": 10, "num_runs": 3 } }
+    });
+
+    let (_, job) = request_json(addr, "POST", "/jobs", &submission.to_string());
+    let job = wait_for(addr, job["id"].as_u64().unwrap());
+    assert_eq!(job["status"], "finished", "{}", job);
+    assert_eq!(job["progress"], json!({ "runs_done": 3, "num_runs": 3 }));
+
+    let (_, jobs) = request_json(addr, "GET", "/jobs", "");
+    assert_eq!(jobs.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn bad_submissions_are_rejected() {
+    let addr = start("math435-serve-bad");
+
+    assert_eq!(request(addr, "POST", "/jobs", "{").0, 400);
+    assert_eq!(request(addr, "POST", "/jobs", r#"{ "program": "ising", "mode": "run" }"#).0, 400);
+
+    // A connectome run would otherwise go on until the server is stopped.
+    let (status, body) = request_json(addr, "POST", "/jobs", r#"{ "program": "connectome", "mode": "run" }"#);
+    assert_eq!(status, 400);
+    assert!(body["error"].as_str().unwrap().contains("number of steps"));
+
+    assert_eq!(request(addr, "DELETE", "/jobs", "").0, 405);
+    assert_eq!(request(addr, "GET", "/", "").0, 404);
+}
@@ -0,0 +1,86 @@
+//! The interface the simulations share, so that running them, recording them
+//! as they go, reporting progress and saving their state can be written once
+//! against it rather than once for each.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+
+use graph_io::AttributedGraph;
+use net_metrics::Summary;
+use progress::Progress;
+use rand::{rngs::StdRng, Rng};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A simulation of a growing network.
+pub trait Model: Sized {
+    /// What a simulation is set up from.
+    type Config;
+    /// The state of a simulation, which it can be saved as and restored from.
+    type Checkpoint: Serialize + DeserializeOwned;
+
+    /// Sets up a simulation from `config`, drawing from `rng` as it runs.
+    fn init(config: &Self::Config, rng: StdRng) -> io::Result<Self>;
+
+    /// Runs a step of the simulation, with whatever input it takes by default.
+    fn step(&mut self);
+
+    /// The number of steps the simulation has run.
+    fn timestep(&self) -> usize;
+
+    /// Returns the graph for export, with the attributes of its nodes and
+    /// edges.
+    fn graph(&self) -> AttributedGraph;
+
+    /// Computes the summary metrics of the graph as it is now, drawing any
+    /// samples they take from `rng`.
+    fn metrics<R: Rng>(&self, rng: &mut R) -> Summary;
+
+    /// Returns the state of the simulation.
+    fn checkpoint(&self) -> Self::Checkpoint;
+
+    /// Restores a simulation set up from `config` to the state of
+    /// `checkpoint`, drawing from `rng` from then on.
+    fn restore(config: &Self::Config, checkpoint: Self::Checkpoint, rng: StdRng) -> io::Result<Self>;
+}
+
+/// Runs `num_steps` steps of `model`, advancing `progress` with each if given,
+/// and passing it to `record` after every `record_interval` steps, counting
+/// from the timestep it started at.
+pub fn run<M, F>(
+    model: &mut M,
+    num_steps: usize,
+    record_interval: usize,
+    progress: Option<&Progress>,
+    mut record: F,
+) -> io::Result<()>
+where
+    M: Model,
+    F: FnMut(&M) -> io::Result<()>,
+{
+    for step in 1..=num_steps {
+        model.step();
+        if let Some(progress) = progress {
+            progress.inc(1);
+        }
+        if record_interval > 0 && step % record_interval == 0 {
+            record(model)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Saves the state of `model` to a JSON file at `path`.
+pub fn save<M: Model, P: AsRef<Path>>(model: &M, path: P) -> io::Result<()> {
+    let writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer(writer, &model.checkpoint())?;
+    Ok(())
+}
+
+/// Restores a simulation set up from `config` to the state saved at `path`
+/// by [`save`].
+pub fn load<M: Model, P: AsRef<Path>>(config: &M::Config, path: P, rng: StdRng) -> io::Result<M> {
+    let reader = BufReader::new(File::open(path)?);
+    M::restore(config, serde_json::from_reader(reader)?, rng)
+}
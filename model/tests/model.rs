@@ -0,0 +1,85 @@
+use std::io;
+
+use graph_io::AttributedGraph;
+use model::Model;
+use net_metrics::Summary;
+use petgraph::graph::UnGraph;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// A path which grows by a node each step.
+struct Path {
+    length: usize,
+}
+
+impl Path {
+    fn graph(&self) -> UnGraph<(), ()> {
+        let mut graph = UnGraph::new_undirected();
+        let nodes = (0..=self.length).map(|_| graph.add_node(())).collect::<Vec<_>>();
+        for pair in nodes.windows(2) {
+            graph.add_edge(pair[0], pair[1], ());
+        }
+        graph
+    }
+}
+
+impl Model for Path {
+    type Config = ();
+    type Checkpoint = usize;
+
+    fn init(_: &(), _: StdRng) -> io::Result<Self> {
+        Ok(Path { length: 0 })
+    }
+
+    fn step(&mut self) {
+        self.length += 1;
+    }
+
+    fn timestep(&self) -> usize {
+        self.length
+    }
+
+    fn graph(&self) -> AttributedGraph {
+        AttributedGraph::from_graph(&Path::graph(self), |_| Default::default(), |_| Default::default())
+    }
+
+    fn metrics<R: Rng>(&self, rng: &mut R) -> Summary {
+        net_metrics::summarize(&Path::graph(self), rng)
+    }
+
+    fn checkpoint(&self) -> usize {
+        self.length
+    }
+
+    fn restore(_: &(), length: usize, _: StdRng) -> io::Result<Self> {
+        Ok(Path { length })
+    }
+}
+
+#[test]
+fn run_records_every_interval() {
+    let mut path = Path::init(&(), StdRng::seed_from_u64(0)).unwrap();
+    let mut recorded = Vec::new();
+    model::run(&mut path, 10, 3, None, |path| {
+        recorded.push(path.timestep());
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(path.timestep(), 10);
+    assert_eq!(recorded, vec![3, 6, 9]);
+    assert_eq!(Model::graph(&path).edges.len(), 10);
+}
+
+#[test]
+fn save_and_load_round_trip() {
+    let mut path = Path::init(&(), StdRng::seed_from_u64(0)).unwrap();
+    model::run(&mut path, 4, 0, None, |_| Ok(())).unwrap();
+
+    let file = std::env::temp_dir().join(format!("model-checkpoint-{}.json", std::process::id()));
+    model::save(&path, &file).unwrap();
+    let loaded: Path = model::load(&(), &file, StdRng::seed_from_u64(1)).unwrap();
+    std::fs::remove_file(&file).unwrap();
+
+    assert_eq!(loaded.timestep(), 4);
+    assert_eq!(loaded.metrics(&mut StdRng::seed_from_u64(2)), path.metrics(&mut StdRng::seed_from_u64(2)));
+}
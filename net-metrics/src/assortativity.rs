@@ -0,0 +1,43 @@
+use petgraph::visit::{IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+
+use crate::SimpleGraph;
+
+/// Returns Newman's degree assortativity: the Pearson correlation between the
+/// degrees at either end of an edge. Positive values mean that nodes tend to
+/// connect to nodes of similar degree. `None` if there are no edges, or every
+/// edge joins nodes of the same degree, where the correlation is undefined.
+pub fn degree_assortativity<G>(graph: G) -> Option<f64>
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable,
+{
+    let graph = SimpleGraph::new(graph);
+
+    let mut num_edges = 0.0;
+    let mut product_sum = 0.0;
+    let mut sum = 0.0;
+    let mut square_sum = 0.0;
+
+    for (a, b) in graph.edges() {
+        let j = graph.degree(a) as f64;
+        let k = graph.degree(b) as f64;
+
+        num_edges += 1.0;
+        product_sum += j * k;
+        sum += 0.5 * (j + k);
+        square_sum += 0.5 * (j * j + k * k);
+    }
+
+    if num_edges == 0.0 {
+        return None;
+    }
+
+    let mean = sum / num_edges;
+    let numerator = product_sum / num_edges - mean * mean;
+    let denominator = square_sum / num_edges - mean * mean;
+
+    if denominator.abs() < 1e-12 {
+        None
+    } else {
+        Some(numerator / denominator)
+    }
+}
@@ -0,0 +1,78 @@
+use petgraph::visit::{IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+
+use crate::SimpleGraph;
+
+/// Returns the number of triangles through each node.
+fn triangles(graph: &SimpleGraph) -> Vec<usize> {
+    (0..graph.num_nodes())
+        .map(|node| {
+            let neighbors = &graph.neighbors[node];
+
+            neighbors
+                .iter()
+                .map(|&a| neighbors.range(a + 1..).filter(|b| graph.neighbors[a].contains(b)).count())
+                .sum()
+        })
+        .collect()
+}
+
+/// Returns the local clustering coefficient of each node, in the order the
+/// graph lists them: the fraction of pairs of its neighbors which are
+/// themselves adjacent, or 0 for nodes with fewer than two neighbors.
+pub fn local_clustering<G>(graph: G) -> Vec<f64>
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable,
+{
+    let graph = SimpleGraph::new(graph);
+
+    triangles(&graph)
+        .into_iter()
+        .enumerate()
+        .map(|(node, triangles)| {
+            let degree = graph.degree(node);
+
+            if degree < 2 {
+                0.0
+            } else {
+                2.0 * triangles as f64 / (degree * (degree - 1)) as f64
+            }
+        })
+        .collect()
+}
+
+/// Returns the mean local clustering coefficient, or 0 for an empty graph.
+pub fn average_clustering<G>(graph: G) -> f64
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable,
+{
+    let clustering = local_clustering(graph);
+
+    if clustering.is_empty() {
+        0.0
+    } else {
+        clustering.iter().sum::<f64>() / clustering.len() as f64
+    }
+}
+
+/// Returns the global clustering coefficient: the fraction of paths of length
+/// two which are closed into a triangle, or 0 if there are none.
+pub fn transitivity<G>(graph: G) -> f64
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable,
+{
+    let graph = SimpleGraph::new(graph);
+
+    let closed = triangles(&graph).into_iter().sum::<usize>();
+    let paths = (0..graph.num_nodes())
+        .map(|node| {
+            let degree = graph.degree(node);
+            degree * degree.saturating_sub(1) / 2
+        })
+        .sum::<usize>();
+
+    if paths == 0 {
+        0.0
+    } else {
+        closed as f64 / paths as f64
+    }
+}
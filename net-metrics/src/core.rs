@@ -0,0 +1,59 @@
+use petgraph::visit::{IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+
+use crate::SimpleGraph;
+
+/// Returns the core number of each node, in the order the graph lists them:
+/// the largest `k` for which the node is in the `k`-core, the largest
+/// subgraph in which every node has degree at least `k`.
+///
+/// Nodes are peeled off in order of their degree among the nodes left, as in
+/// Batagelj and Zaversnik's algorithm, in time linear in the size of the
+/// graph.
+pub fn core_numbers<G>(graph: G) -> Vec<usize>
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable,
+{
+    let graph = SimpleGraph::new(graph);
+    let num_nodes = graph.num_nodes();
+    let mut degrees = (0..num_nodes).map(|node| graph.degree(node)).collect::<Vec<_>>();
+    let max_degree = degrees.iter().cloned().max().unwrap_or(0);
+
+    // The nodes sorted by degree, where each node is in it, and where the
+    // nodes of each degree start.
+    let mut starts = vec![0; max_degree + 2];
+    for &degree in &degrees {
+        starts[degree + 1] += 1;
+    }
+    for degree in 1..starts.len() {
+        starts[degree] += starts[degree - 1];
+    }
+    let mut order = vec![0; num_nodes];
+    let mut positions = vec![0; num_nodes];
+    let mut next = starts.clone();
+    for node in 0..num_nodes {
+        positions[node] = next[degrees[node]];
+        order[positions[node]] = node;
+        next[degrees[node]] += 1;
+    }
+
+    for i in 0..num_nodes {
+        let node = order[i];
+
+        for &neighbor in &graph.neighbors[node] {
+            if degrees[neighbor] > degrees[node] {
+                // Moves the neighbor to the start of the nodes of its degree,
+                // which then start one later, leaving it with one less.
+                let degree = degrees[neighbor];
+                let first = order[starts[degree]];
+                if first != neighbor {
+                    order.swap(positions[neighbor], starts[degree]);
+                    positions.swap(neighbor, first);
+                }
+                starts[degree] += 1;
+                degrees[neighbor] -= 1;
+            }
+        }
+    }
+
+    degrees
+}
@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use petgraph::visit::{EdgeRef, GraphProp, IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+
+use crate::node_positions;
+
+/// Which of a node's edges its degree counts. In an undirected graph every
+/// edge is both incoming and outgoing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    In,
+    Out,
+    Total,
+}
+
+/// Returns the degree of each node, in the order the graph lists them.
+/// Parallel edges each count, and a self-loop counts once in each direction.
+pub fn degrees<G>(graph: G, direction: Direction) -> Vec<usize>
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable + GraphProp,
+{
+    let positions = node_positions(graph);
+    let mut degrees = vec![0; graph.node_identifiers().count()];
+
+    for edge in graph.edge_references() {
+        let source = positions[graph.to_index(edge.source())].unwrap();
+        let target = positions[graph.to_index(edge.target())].unwrap();
+
+        let (counts_source, counts_target) = match direction {
+            _ if !graph.is_directed() => (true, true),
+            Direction::In => (false, true),
+            Direction::Out => (true, false),
+            Direction::Total => (true, true),
+        };
+
+        if counts_source {
+            degrees[source] += 1;
+        }
+        if counts_target {
+            degrees[target] += 1;
+        }
+    }
+
+    degrees
+}
+
+/// Returns the number of nodes with each degree.
+pub fn degree_distribution<G>(graph: G, direction: Direction) -> BTreeMap<usize, usize>
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable + GraphProp,
+{
+    let mut distribution = BTreeMap::new();
+
+    for degree in degrees(graph, direction) {
+        *distribution.entry(degree).or_insert(0) += 1;
+    }
+
+    distribution
+}
+
+/// Returns the Gini coefficient of some nonnegative values: 0 when they're
+/// all equal, approaching 1 as they concentrate in a single value. Empty or
+/// all-zero values give 0.
+pub fn gini(values: &[f64]) -> f64 {
+    let total: f64 = values.iter().sum();
+
+    if values.is_empty() || total == 0.0 {
+        return 0.0;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let n = sorted.len() as f64;
+    let weighted: f64 = sorted
+        .iter()
+        .enumerate()
+        .map(|(i, value)| (i + 1) as f64 * value)
+        .sum();
+
+    2.0 * weighted / (n * total) - (n + 1.0) / n
+}
+
+/// Returns the share of all the edges held by the node of highest degree, the
+/// order parameter of condensation: it stays finite as a network grows only
+/// when a single node keeps a fixed fraction of the links. No edges give 0.
+pub fn condensation_fraction(degrees: &[usize]) -> f64 {
+    let total: usize = degrees.iter().sum();
+
+    match degrees.iter().max() {
+        Some(&max) if total > 0 => max as f64 / total as f64,
+        _ => 0.0,
+    }
+}
+
+/// Returns the Gini coefficient of the degrees of a graph.
+pub fn degree_gini<G>(graph: G, direction: Direction) -> f64
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable + GraphProp,
+{
+    let degrees = degrees(graph, direction)
+        .into_iter()
+        .map(|degree| degree as f64)
+        .collect::<Vec<_>>();
+
+    gini(&degrees)
+}
@@ -0,0 +1,93 @@
+use std::collections::BTreeSet;
+
+use petgraph::visit::{EdgeRef, GraphProp, IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+use serde::Serialize;
+
+use crate::{degrees, node_positions, Direction};
+
+/// How the directions of a graph's edges are arranged.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Directedness {
+    /// The fraction of pairs of nodes joined by an edge which are joined in
+    /// both directions. `None` if no two nodes are joined.
+    pub reciprocity: Option<f64>,
+    /// The Pearson correlation between the in-degree and out-degree of each
+    /// node. `None` if either is the same for every node, where the
+    /// correlation is undefined.
+    pub in_out_degree_correlation: Option<f64>,
+}
+
+/// Computes the reciprocity and in/out-degree correlation of a graph, in which
+/// every edge of an undirected graph goes both ways.
+pub fn directedness<G>(graph: G) -> Directedness
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable + GraphProp,
+{
+    Directedness {
+        reciprocity: reciprocity(graph),
+        in_out_degree_correlation: in_out_degree_correlation(graph),
+    }
+}
+
+/// Returns the Pearson correlation between the in-degree and out-degree of
+/// each node of a graph, or `None` if either is the same for every node.
+pub fn in_out_degree_correlation<G>(graph: G) -> Option<f64>
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable + GraphProp,
+{
+    correlation(&degrees(graph, Direction::In), &degrees(graph, Direction::Out))
+}
+
+/// Returns the fraction of pairs of distinct nodes joined by an edge which are
+/// joined in both directions, ignoring self-loops and counting parallel edges
+/// once.
+pub fn reciprocity<G>(graph: G) -> Option<f64>
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable + GraphProp,
+{
+    if !graph.is_directed() {
+        return if graph.edge_references().any(|edge| edge.source() != edge.target()) { Some(1.) } else { None };
+    }
+
+    let positions = node_positions(graph);
+    let edges = graph
+        .edge_references()
+        .map(|edge| {
+            let source = positions[graph.to_index(edge.source())].unwrap();
+            let target = positions[graph.to_index(edge.target())].unwrap();
+            (source, target)
+        })
+        .filter(|(source, target)| source != target)
+        .collect::<BTreeSet<_>>();
+
+    let num_reciprocated = edges.iter().filter(|&&(source, target)| edges.contains(&(target, source))).count();
+    // Each reciprocated pair is two of the edges, and each other pair one.
+    let num_pairs = edges.len() - num_reciprocated / 2;
+
+    if num_pairs == 0 {
+        None
+    } else {
+        Some((num_reciprocated / 2) as f64 / num_pairs as f64)
+    }
+}
+
+/// Returns the Pearson correlation of two equally long lists of counts.
+fn correlation(xs: &[usize], ys: &[usize]) -> Option<f64> {
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<usize>() as f64 / n;
+    let mean_y = ys.iter().sum::<usize>() as f64 / n;
+
+    let (mut covariance, mut variance_x, mut variance_y) = (0., 0., 0.);
+    for (&x, &y) in xs.iter().zip(ys) {
+        let (dx, dy) = (x as f64 - mean_x, y as f64 - mean_y);
+        covariance += dx * dy;
+        variance_x += dx * dx;
+        variance_y += dy * dy;
+    }
+
+    if variance_x < 1e-12 || variance_y < 1e-12 {
+        None
+    } else {
+        Some(covariance / (variance_x * variance_y).sqrt())
+    }
+}
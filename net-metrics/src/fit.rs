@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+/// The fewest degrees a tail is fit to, below which the exponent means
+/// little.
+pub const MIN_TAIL_LEN: usize = 10;
+
+/// A power law `p(k) ∝ k^-exponent` fit to the tail of a degree sequence,
+/// from `k_min` on.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PowerLawFit {
+    pub exponent: f64,
+    /// The standard error of the exponent, `(exponent - 1) / √n`.
+    pub std_error: f64,
+    pub k_min: usize,
+    /// How many of the degrees are in the tail, at least `k_min`.
+    pub tail_len: usize,
+    /// The Kolmogorov-Smirnov distance between the tail and the fit.
+    pub ks_distance: f64,
+}
+
+/// Fits a power law to the degrees of at least `k_min` by maximum
+/// likelihood, with the discrete approximation of Clauset, Shalizi and
+/// Newman (2009): `1 + n / Σ ln(k / (k_min - ½))`. Returns `None` if fewer
+/// than [`MIN_TAIL_LEN`] degrees are in the tail, or they're all `k_min`.
+pub fn power_law_fit(degrees: &[usize], k_min: usize) -> Option<PowerLawFit> {
+    let k_min = k_min.max(1);
+    let mut tail = degrees.iter().copied().filter(|&k| k >= k_min).collect::<Vec<_>>();
+    tail.sort_unstable();
+
+    let n = tail.len();
+    let shift = k_min as f64 - 0.5;
+    let log_sum: f64 = tail.iter().map(|&k| (k as f64 / shift).ln()).sum();
+    if n < MIN_TAIL_LEN || tail[n - 1] == k_min {
+        return None;
+    }
+
+    let exponent = 1.0 + n as f64 / log_sum;
+
+    // The fit's complementary CDF, P(K >= k) = ((k - ½) / (k_min - ½))^(1 - α),
+    // against the fraction of the tail at each distinct degree or above.
+    let mut ks_distance: f64 = 0.0;
+    let mut start = 0;
+    while start < n {
+        let k = tail[start];
+        let empirical = (n - start) as f64 / n as f64;
+        let model = ((k as f64 - 0.5) / shift).powf(1.0 - exponent);
+        ks_distance = ks_distance.max((empirical - model).abs());
+
+        start += tail[start..].iter().take_while(|&&other| other == k).count();
+    }
+
+    Some(PowerLawFit {
+        exponent,
+        std_error: (exponent - 1.0) / (n as f64).sqrt(),
+        k_min,
+        tail_len: n,
+        ks_distance,
+    })
+}
+
+/// Fits a power law to the tail of the degrees, choosing `k_min` as the
+/// degree whose fit is closest to the tail it's fit to, by Kolmogorov-Smirnov
+/// distance.
+pub fn best_power_law_fit(degrees: &[usize]) -> Option<PowerLawFit> {
+    let mut candidates = degrees.iter().copied().filter(|&k| k > 0).collect::<Vec<_>>();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    candidates
+        .into_iter()
+        .filter_map(|k_min| power_law_fit(degrees, k_min))
+        .min_by(|a, b| a.ks_distance.partial_cmp(&b.ks_distance).unwrap())
+}
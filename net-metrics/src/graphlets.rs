@@ -0,0 +1,231 @@
+use petgraph::visit::{IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+use rand::{seq::index, Rng};
+use serde::Serialize;
+
+use crate::SimpleGraph;
+
+/// The number of nodes [`sample_graphlet_counts`] is usually given, above
+/// which graphlets are estimated rather than counted.
+pub const GRAPHLET_SAMPLE_NODES: usize = 2048;
+
+/// The number of connected induced subgraphs of each shape with two to four
+/// nodes, the graphlets G0 to G8 of Pržulj's numbering.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Graphlets {
+    /// The number of nodes the counts were estimated from, every node when
+    /// they're exact.
+    pub num_sampled: usize,
+    pub edges: f64,
+    /// Paths of three nodes.
+    pub wedges: f64,
+    pub triangles: f64,
+    /// Paths of four nodes.
+    pub paths: f64,
+    /// A node joined to three others.
+    pub stars: f64,
+    /// Cycles of four nodes.
+    pub cycles: f64,
+    /// A triangle with a fourth node joined to one of its corners.
+    pub tailed_triangles: f64,
+    /// Four nodes with every edge but one.
+    pub diamonds: f64,
+    /// Four nodes with every edge.
+    pub cliques: f64,
+    /// The number of times a node is in each orbit of the graphlets, summed
+    /// over the nodes, in Pržulj's numbering: the nodes of a graphlet in the
+    /// same orbit are those an automorphism of it can swap.
+    pub orbits: Vec<f64>,
+}
+
+/// Counts the graphlets of every node exactly.
+pub fn graphlet_counts<G>(graph: G) -> Graphlets
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable,
+{
+    let graph = SimpleGraph::new(graph);
+    let nodes = (0..graph.num_nodes()).collect::<Vec<_>>();
+    count(&graph, &nodes)
+}
+
+/// Estimates the graphlet counts from up to `num_samples` distinct nodes
+/// chosen at random, or counts them exactly if there are no more than that.
+///
+/// Every count is a sum over the nodes of terms found from each node's
+/// neighborhood out to two steps, so summing the terms of a uniform sample of
+/// nodes and scaling up estimates each without bias. The counts of the
+/// graphlets with four nodes subtract those of denser ones, so they're
+/// clamped to be no less than 0.
+pub fn sample_graphlet_counts<G, R>(graph: G, num_samples: usize, rng: &mut R) -> Graphlets
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable,
+    R: Rng,
+{
+    let graph = SimpleGraph::new(graph);
+    let num_nodes = graph.num_nodes();
+
+    let nodes = if num_samples >= num_nodes {
+        (0..num_nodes).collect::<Vec<_>>()
+    } else {
+        index::sample(rng, num_nodes, num_samples).into_vec()
+    };
+
+    count(&graph, &nodes)
+}
+
+/// Sums the terms of `nodes` towards the counts of subgraphs which needn't be
+/// induced, scales them up to the whole graph, and takes out the subgraphs of
+/// each shape found in denser ones to leave the induced counts.
+fn count(graph: &SimpleGraph, nodes: &[usize]) -> Graphlets {
+    let num_nodes = graph.num_nodes();
+    // The number of common neighbors of the node being looked at with every
+    // other, and the nodes with any.
+    let mut common = vec![0usize; num_nodes];
+    let mut reached = Vec::new();
+    let mut sums = NodeSums::default();
+
+    for &node in nodes {
+        let degree = graph.degree(node) as f64;
+
+        for &neighbor in &graph.neighbors[node] {
+            for &other in &graph.neighbors[neighbor] {
+                if other != node {
+                    if common[other] == 0 {
+                        reached.push(other);
+                    }
+                    common[other] += 1;
+                }
+            }
+        }
+
+        let triangles = graph.neighbors[node].iter().map(|&neighbor| common[neighbor]).sum::<usize>() as f64 / 2.;
+        sums.degrees += degree;
+        sums.wedges += pairs(graph.degree(node));
+        sums.triangles += triangles;
+        sums.extended_paths += graph.neighbors[node]
+            .iter()
+            .map(|&neighbor| (degree - 1.) * (graph.degree(neighbor) as f64 - 1.))
+            .sum::<f64>();
+        sums.stars += degree * (degree - 1.) * (degree - 2.) / 6.;
+        sums.opposite_corners += reached.iter().map(|&other| pairs(common[other])).sum::<f64>();
+        sums.tails += triangles * (degree - 2.);
+        sums.diamond_edges += graph.neighbors[node].iter().map(|&neighbor| pairs(common[neighbor])).sum::<f64>();
+        sums.cliques += neighborhood_triangles(graph, node) as f64;
+
+        for other in reached.drain(..) {
+            common[other] = 0;
+        }
+    }
+
+    let scale = if nodes.is_empty() { 0. } else { num_nodes as f64 / nodes.len() as f64 };
+    let sums = sums.scaled(scale);
+
+    // Each clique has four corners, each cycle two pairs of opposite corners
+    // seen from both, and each edge two ends.
+    let triangles = sums.triangles / 3.;
+    let cliques = sums.cliques / 4.;
+    let diamonds = (sums.diamond_edges / 2. - 6. * cliques).max(0.);
+    let cycles = (sums.opposite_corners / 4. - diamonds - 3. * cliques).max(0.);
+    let tailed_triangles = (sums.tails - 4. * diamonds - 12. * cliques).max(0.);
+    let stars = (sums.stars - tailed_triangles - 2. * diamonds - 4. * cliques).max(0.);
+    let paths = (sums.extended_paths / 2.
+        - 3. * triangles
+        - 4. * cycles
+        - 2. * tailed_triangles
+        - 6. * diamonds
+        - 12. * cliques)
+        .max(0.);
+    let edges = sums.degrees / 2.;
+    let wedges = (sums.wedges - 3. * triangles).max(0.);
+
+    Graphlets {
+        num_sampled: nodes.len(),
+        edges,
+        wedges,
+        triangles,
+        paths,
+        stars,
+        cycles,
+        tailed_triangles,
+        diamonds,
+        cliques,
+        orbits: vec![
+            2. * edges,
+            2. * wedges,
+            wedges,
+            3. * triangles,
+            2. * paths,
+            2. * paths,
+            3. * stars,
+            stars,
+            4. * cycles,
+            tailed_triangles,
+            2. * tailed_triangles,
+            tailed_triangles,
+            2. * diamonds,
+            2. * diamonds,
+            4. * cliques,
+        ],
+    }
+}
+
+/// The sums over nodes of how many subgraphs of each shape, not necessarily
+/// induced, a node is in, each in its own way.
+#[derive(Default)]
+struct NodeSums {
+    degrees: f64,
+    /// Paths of three nodes centered on the node.
+    wedges: f64,
+    triangles: f64,
+    /// Paths of four nodes, or triangles, through an edge of the node with the
+    /// node in the middle.
+    extended_paths: f64,
+    /// Stars centered on the node.
+    stars: f64,
+    /// Cycles of four nodes with the node at a corner, once for each node at
+    /// the opposite corner.
+    opposite_corners: f64,
+    /// Triangles of the node with a tail from it.
+    tails: f64,
+    /// Pairs of triangles on each edge of the node.
+    diamond_edges: f64,
+    cliques: f64,
+}
+
+impl NodeSums {
+    fn scaled(self, scale: f64) -> Self {
+        Self {
+            degrees: self.degrees * scale,
+            wedges: self.wedges * scale,
+            triangles: self.triangles * scale,
+            extended_paths: self.extended_paths * scale,
+            stars: self.stars * scale,
+            opposite_corners: self.opposite_corners * scale,
+            tails: self.tails * scale,
+            diamond_edges: self.diamond_edges * scale,
+            cliques: self.cliques * scale,
+        }
+    }
+}
+
+/// Returns the number of pairs of `n` things.
+fn pairs(n: usize) -> f64 {
+    (n * n.saturating_sub(1)) as f64 / 2.
+}
+
+/// Returns the number of triangles among the neighbors of a node, which is the
+/// number of cliques of four nodes it's in.
+fn neighborhood_triangles(graph: &SimpleGraph, node: usize) -> usize {
+    let neighbors = &graph.neighbors[node];
+    let mut count = 0;
+
+    for &a in neighbors {
+        for &b in graph.neighbors[a].range(a + 1..).filter(|b| neighbors.contains(b)) {
+            count += graph.neighbors[b]
+                .range(b + 1..)
+                .filter(|c| neighbors.contains(c) && graph.neighbors[a].contains(c))
+                .count();
+        }
+    }
+
+    count
+}
@@ -0,0 +1,94 @@
+//! Network metrics over petgraph graphs, shared by the simulations so that
+//! they all report the same quantities, computed the same way.
+//!
+//! Degrees count every edge, respecting direction where asked to, and the
+//! directed metrics look at which way edges go. The other metrics are defined
+//! on simple undirected graphs, so they look at the underlying simple graph:
+//! edge directions are ignored, parallel edges count once, and self-loops are
+//! dropped.
+
+pub mod assortativity;
+pub mod clustering;
+pub mod core;
+pub mod degree;
+pub mod directed;
+pub mod fit;
+pub mod graphlets;
+pub mod paths;
+pub mod rich_club;
+pub mod robustness;
+pub mod summary;
+
+pub use assortativity::degree_assortativity;
+pub use clustering::{average_clustering, local_clustering, transitivity};
+pub use core::core_numbers;
+pub use degree::{condensation_fraction, degree_distribution, degree_gini, degrees, gini, Direction};
+pub use directed::{directedness, in_out_degree_correlation, reciprocity, Directedness};
+pub use fit::{best_power_law_fit, power_law_fit, PowerLawFit};
+pub use graphlets::{graphlet_counts, sample_graphlet_counts, Graphlets};
+pub use paths::{sample_path_lengths, PathLengths};
+pub use rich_club::rich_club_coefficients;
+pub use robustness::{robustness_curve, Removal, RobustnessCurve};
+pub use summary::{summarize, Summary};
+
+use std::collections::BTreeSet;
+
+use petgraph::visit::{EdgeRef, IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+
+/// The simple undirected graph underlying a graph, with its nodes numbered
+/// densely in the order the graph lists them.
+pub(crate) struct SimpleGraph {
+    pub neighbors: Vec<BTreeSet<usize>>,
+}
+
+impl SimpleGraph {
+    pub fn new<G>(graph: G) -> Self
+    where
+        G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable,
+    {
+        let positions = node_positions(graph);
+        let mut neighbors = vec![BTreeSet::new(); graph.node_identifiers().count()];
+
+        for edge in graph.edge_references() {
+            let source = positions[graph.to_index(edge.source())].unwrap();
+            let target = positions[graph.to_index(edge.target())].unwrap();
+
+            if source != target {
+                neighbors[source].insert(target);
+                neighbors[target].insert(source);
+            }
+        }
+
+        SimpleGraph { neighbors }
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    pub fn degree(&self, node: usize) -> usize {
+        self.neighbors[node].len()
+    }
+
+    /// Iterates over the edges, each once, as `(a, b)` with `a < b`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors.iter().enumerate().flat_map(|(a, neighbors)| {
+            neighbors.range(a + 1..).map(move |&b| (a, b))
+        })
+    }
+}
+
+/// Maps each node's index to its position in the order the graph lists its
+/// nodes, leaving gaps for the indices of removed nodes.
+pub(crate) fn node_positions<G>(graph: G) -> Vec<Option<usize>>
+where
+    G: IntoNodeIdentifiers + NodeIndexable,
+{
+    let mut positions = vec![None; graph.node_bound()];
+
+    for (position, node) in graph.node_identifiers().enumerate() {
+        positions[graph.to_index(node)] = Some(position);
+    }
+
+    positions
+}
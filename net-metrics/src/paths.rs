@@ -0,0 +1,76 @@
+use std::collections::VecDeque;
+
+use petgraph::visit::{IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+use rand::{seq::index, Rng};
+use serde::Serialize;
+
+use crate::SimpleGraph;
+
+/// Shortest path lengths measured from a sample of source nodes.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PathLengths {
+    /// The number of source nodes searched from.
+    pub num_sources: usize,
+    /// The mean length of the shortest paths to every node reachable from a
+    /// source, or 0 if none are.
+    pub mean: f64,
+    /// The longest shortest path found, a lower bound on the diameter of the
+    /// largest component.
+    pub max: usize,
+    /// The fraction of pairs of a source and another node which are joined by
+    /// a path.
+    pub reachable_fraction: f64,
+}
+
+/// Measures shortest path lengths by breadth-first search from up to
+/// `num_sources` distinct nodes chosen at random, or from every node if
+/// there are no more than that.
+pub fn sample_path_lengths<G, R>(graph: G, num_sources: usize, rng: &mut R) -> PathLengths
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable,
+    R: Rng,
+{
+    let graph = SimpleGraph::new(graph);
+    let num_nodes = graph.num_nodes();
+
+    let sources = if num_sources >= num_nodes {
+        (0..num_nodes).collect::<Vec<_>>()
+    } else {
+        index::sample(rng, num_nodes, num_sources).into_vec()
+    };
+
+    let mut total_length = 0;
+    let mut num_paths = 0;
+    let mut max = 0;
+
+    for &source in &sources {
+        let mut distances = vec![None; num_nodes];
+        let mut queue = VecDeque::new();
+        distances[source] = Some(0);
+        queue.push_back(source);
+
+        while let Some(node) = queue.pop_front() {
+            let distance = distances[node].unwrap();
+
+            for &neighbor in &graph.neighbors[node] {
+                if distances[neighbor].is_none() {
+                    distances[neighbor] = Some(distance + 1);
+                    queue.push_back(neighbor);
+
+                    total_length += distance + 1;
+                    num_paths += 1;
+                    max = max.max(distance + 1);
+                }
+            }
+        }
+    }
+
+    let num_pairs = sources.len() * num_nodes.saturating_sub(1);
+
+    PathLengths {
+        num_sources: sources.len(),
+        mean: if num_paths > 0 { total_length as f64 / num_paths as f64 } else { 0.0 },
+        max,
+        reachable_fraction: if num_pairs > 0 { num_paths as f64 / num_pairs as f64 } else { 0.0 },
+    }
+}
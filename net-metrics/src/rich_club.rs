@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+
+use petgraph::visit::{IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+
+use crate::SimpleGraph;
+
+/// Returns the rich-club coefficient φ(k) for each degree `k`: the density of
+/// the subgraph induced by the nodes of degree greater than `k`, i.e.
+/// 2E_k / (N_k (N_k - 1)). Degrees with fewer than two such nodes are left
+/// out.
+pub fn rich_club_coefficients<G>(graph: G) -> BTreeMap<usize, f64>
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable,
+{
+    let graph = SimpleGraph::new(graph);
+    let max_degree = (0..graph.num_nodes()).map(|node| graph.degree(node)).max().unwrap_or(0);
+
+    // Each edge is in the rich club of every degree below both of its ends'.
+    let mut nodes_above = vec![0; max_degree + 1];
+    let mut edges_above = vec![0; max_degree + 1];
+
+    for node in 0..graph.num_nodes() {
+        for count in &mut nodes_above[..graph.degree(node)] {
+            *count += 1;
+        }
+    }
+
+    for (a, b) in graph.edges() {
+        for count in &mut edges_above[..graph.degree(a).min(graph.degree(b))] {
+            *count += 1;
+        }
+    }
+
+    (0..=max_degree)
+        .filter(|&k| nodes_above[k] >= 2)
+        .map(|k| {
+            let n = nodes_above[k] as f64;
+            (k, 2.0 * edges_above[k] as f64 / (n * (n - 1.0)))
+        })
+        .collect()
+}
@@ -0,0 +1,110 @@
+use petgraph::visit::{IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+use rand::{seq::SliceRandom, Rng};
+use serde::Serialize;
+
+use crate::SimpleGraph;
+
+/// The number of fractions of nodes removed a robustness curve is usually
+/// sampled at, past none.
+pub const ROBUSTNESS_POINTS: usize = 100;
+
+/// The order nodes are removed from a graph in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Removal {
+    /// The nodes of highest degree in the whole graph first, as in a targeted
+    /// attack.
+    Degree,
+    /// A uniformly random order, as in random failures.
+    Random,
+}
+
+/// The size of the largest component of a graph as more and more of its nodes
+/// are removed.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RobustnessCurve {
+    /// The fractions of nodes removed, evenly spaced from 0 to 1.
+    pub fractions_removed: Vec<f64>,
+    /// The size of the largest component with each fraction removed, as a
+    /// fraction of the number of nodes of the whole graph.
+    pub giant_component: Vec<f64>,
+    /// The mean size of the largest component over removing every node, one
+    /// at a time, Schneider et al.'s robustness R: near 1/2 for a graph which
+    /// stays connected until the end, near 0 for one which falls apart.
+    pub robustness: f64,
+}
+
+/// Removes the nodes of a graph one at a time in the given order, with ties
+/// in degree broken by the order the graph lists the nodes in, and records
+/// the size of the largest component at `num_points + 1` evenly spaced
+/// fractions of the nodes removed.
+pub fn robustness_curve<G, R>(graph: G, removal: Removal, num_points: usize, rng: &mut R) -> RobustnessCurve
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable,
+    R: Rng,
+{
+    let graph = SimpleGraph::new(graph);
+    let num_nodes = graph.num_nodes();
+
+    let mut order = (0..num_nodes).collect::<Vec<_>>();
+    match removal {
+        Removal::Degree => order.sort_by_key(|&node| std::cmp::Reverse(graph.degree(node))),
+        Removal::Random => order.shuffle(rng),
+    }
+
+    let giant = giant_components(&graph, &order);
+    let size = num_nodes.max(1) as f64;
+    let fractions_removed = (0..=num_points)
+        .map(|point| if num_points > 0 { point as f64 / num_points as f64 } else { 0.0 })
+        .collect::<Vec<_>>();
+
+    RobustnessCurve {
+        giant_component: fractions_removed
+            .iter()
+            .map(|&fraction| giant[(fraction * num_nodes as f64).round() as usize] as f64 / size)
+            .collect(),
+        fractions_removed,
+        robustness: giant[1..].iter().sum::<usize>() as f64 / (size * size),
+    }
+}
+
+/// Returns the size of the largest component after removing the first `k`
+/// nodes of `order`, at index `k`, by adding the nodes back in reverse and
+/// merging the components they join.
+fn giant_components(graph: &SimpleGraph, order: &[usize]) -> Vec<usize> {
+    let num_nodes = graph.num_nodes();
+    let mut parents = (0..num_nodes).collect::<Vec<_>>();
+    let mut sizes = vec![1; num_nodes];
+    let mut present = vec![false; num_nodes];
+    let mut giant = vec![0; num_nodes + 1];
+    let mut largest = 0;
+
+    for (k, &node) in order.iter().enumerate().rev() {
+        present[node] = true;
+        largest = largest.max(1);
+
+        for &neighbor in &graph.neighbors[node] {
+            if present[neighbor] {
+                let (a, b) = (root(&mut parents, node), root(&mut parents, neighbor));
+                if a != b {
+                    let (small, large) = if sizes[a] < sizes[b] { (a, b) } else { (b, a) };
+                    parents[small] = large;
+                    sizes[large] += sizes[small];
+                    largest = largest.max(sizes[large]);
+                }
+            }
+        }
+
+        giant[k] = largest;
+    }
+
+    giant
+}
+
+/// Returns the root of a node's component, halving the path to it.
+fn root(parents: &mut [usize], mut node: usize) -> usize {
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
+    }
+    node
+}
@@ -0,0 +1,49 @@
+use petgraph::visit::{GraphProp, IntoEdgeReferences, IntoNodeIdentifiers, NodeIndexable};
+use rand::Rng;
+use serde::Serialize;
+
+use crate::{
+    average_clustering, degree_assortativity, degree_gini, degrees, sample_path_lengths, transitivity, Direction,
+    PathLengths,
+};
+
+/// The number of sources [`summarize`] samples path lengths from.
+pub const PATH_LENGTH_SOURCES: usize = 32;
+
+/// The metrics every simulation reports about its graph.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Summary {
+    pub num_nodes: usize,
+    pub num_edges: usize,
+    pub mean_degree: f64,
+    pub max_degree: usize,
+    pub degree_gini: f64,
+    pub average_clustering: f64,
+    pub transitivity: f64,
+    pub degree_assortativity: Option<f64>,
+    pub path_lengths: PathLengths,
+}
+
+/// Computes the summary metrics of a graph, sampling path lengths from
+/// [`PATH_LENGTH_SOURCES`] nodes.
+pub fn summarize<G, R>(graph: G, rng: &mut R) -> Summary
+where
+    G: IntoNodeIdentifiers + IntoEdgeReferences + NodeIndexable + GraphProp,
+    R: Rng,
+{
+    let degrees = degrees(graph, Direction::Total);
+    let num_nodes = degrees.len();
+    let total_degree = degrees.iter().sum::<usize>();
+
+    Summary {
+        num_nodes,
+        num_edges: graph.edge_references().count(),
+        mean_degree: if num_nodes > 0 { total_degree as f64 / num_nodes as f64 } else { 0.0 },
+        max_degree: degrees.iter().cloned().max().unwrap_or(0),
+        degree_gini: degree_gini(graph, Direction::Total),
+        average_clustering: average_clustering(graph),
+        transitivity: transitivity(graph),
+        degree_assortativity: degree_assortativity(graph),
+        path_lengths: sample_path_lengths(graph, PATH_LENGTH_SOURCES, rng),
+    }
+}
@@ -0,0 +1,249 @@
+use net_metrics::*;
+use petgraph::graph::{DiGraph, UnGraph};
+use petgraph::stable_graph::StableDiGraph;
+use rand::{rngs::StdRng, SeedableRng};
+
+fn triangle_with_pendant() -> UnGraph<(), ()> {
+    UnGraph::from_edges([(0, 1), (1, 2), (0, 2), (2, 3)])
+}
+
+fn star() -> UnGraph<(), ()> {
+    UnGraph::from_edges([(0, 1), (0, 2), (0, 3), (0, 4)])
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+}
+
+#[test]
+fn directed_degrees() {
+    let graph = DiGraph::<(), ()>::from_edges([(0, 1), (0, 2), (1, 2)]);
+
+    assert_eq!(degrees(&graph, Direction::In), vec![0, 1, 2]);
+    assert_eq!(degrees(&graph, Direction::Out), vec![2, 1, 0]);
+    assert_eq!(degrees(&graph, Direction::Total), vec![2, 2, 2]);
+    assert_eq!(
+        degree_distribution(&graph, Direction::In).into_iter().collect::<Vec<_>>(),
+        vec![(0, 1), (1, 1), (2, 1)]
+    );
+}
+
+#[test]
+fn reciprocity_and_degree_correlation() {
+    // A self-loop, and a parallel edge, which don't change the pairs joined.
+    let graph = DiGraph::<(), ()>::from_edges([(0, 1), (1, 0), (0, 2), (1, 2), (2, 2), (1, 2)]);
+    let directedness = directedness(&graph);
+
+    assert_close(directedness.reciprocity.unwrap(), 1. / 3.);
+    assert_close(directedness.in_out_degree_correlation.unwrap(), -3. / 12f64.sqrt());
+    assert_eq!(reciprocity(&triangle_with_pendant()), Some(1.));
+    assert_eq!(reciprocity(&DiGraph::<(), ()>::from_edges([(0, 0)])), None);
+}
+
+#[test]
+fn removed_nodes_are_skipped() {
+    let mut graph = StableDiGraph::<(), ()>::from_edges([(0, 1), (1, 2), (2, 3)]);
+    graph.remove_node(1.into());
+
+    assert_eq!(degrees(&graph, Direction::Total), vec![0, 1, 1]);
+    assert_close(average_clustering(&graph), 0.0);
+}
+
+#[test]
+fn clustering_of_a_triangle_with_a_pendant() {
+    let graph = triangle_with_pendant();
+
+    let local = local_clustering(&graph);
+    for (actual, expected) in local.into_iter().zip(&[1.0, 1.0, 1.0 / 3.0, 0.0]) {
+        assert_close(actual, *expected);
+    }
+    assert_close(average_clustering(&graph), 7.0 / 12.0);
+    assert_close(transitivity(&graph), 0.6);
+}
+
+#[test]
+fn stars_are_disassortative_and_unequal() {
+    let graph = star();
+
+    assert_close(degree_assortativity(&graph).unwrap(), -1.0);
+    assert_close(degree_gini(&graph, Direction::Total), 0.3);
+    assert_eq!(degree_assortativity(&UnGraph::<(), ()>::from_edges([(0, 1)])), None);
+}
+
+#[test]
+fn gini_extremes() {
+    assert_close(gini(&[2.0, 2.0, 2.0]), 0.0);
+    assert_close(gini(&[0.0, 0.0, 0.0, 1.0]), 0.75);
+    assert_close(gini(&[]), 0.0);
+}
+
+#[test]
+fn path_lengths_of_a_path() {
+    let graph = UnGraph::<(), ()>::from_edges([(0, 1), (1, 2), (2, 3)]);
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let lengths = sample_path_lengths(&graph, 10, &mut rng);
+    assert_eq!(lengths.num_sources, 4);
+    assert_close(lengths.mean, 5.0 / 3.0);
+    assert_eq!(lengths.max, 3);
+    assert_close(lengths.reachable_fraction, 1.0);
+
+    let sampled = sample_path_lengths(&graph, 2, &mut rng);
+    assert_eq!(sampled.num_sources, 2);
+}
+
+#[test]
+fn disconnected_pairs_are_unreachable() {
+    let mut graph = UnGraph::<(), ()>::from_edges([(0, 1)]);
+    graph.add_node(());
+
+    let lengths = sample_path_lengths(&graph, 3, &mut StdRng::seed_from_u64(0));
+    assert_close(lengths.reachable_fraction, 2.0 / 6.0);
+}
+
+#[test]
+fn rich_club_of_a_triangle_with_a_pendant() {
+    let coefficients = rich_club_coefficients(&triangle_with_pendant());
+
+    // Above degree 0 every node is in the club, with 4 of 6 possible edges;
+    // above degree 1 the triangle remains.
+    assert_eq!(coefficients.keys().cloned().collect::<Vec<_>>(), vec![0, 1]);
+    assert_close(coefficients[&0], 4.0 / 6.0);
+    assert_close(coefficients[&1], 1.0);
+}
+
+#[test]
+fn summary_of_a_star() {
+    let summary = summarize(&star(), &mut StdRng::seed_from_u64(0));
+
+    assert_eq!(summary.num_nodes, 5);
+    assert_eq!(summary.num_edges, 4);
+    assert_close(summary.mean_degree, 1.6);
+    assert_eq!(summary.max_degree, 4);
+    assert_eq!(summary.path_lengths.max, 2);
+}
+
+#[test]
+fn condensation_of_a_star() {
+    assert_close(condensation_fraction(&degrees(&star(), Direction::Total)), 0.5);
+    assert_close(condensation_fraction(&[0, 0, 0]), 0.0);
+}
+
+#[test]
+fn power_law_fit_recovers_the_exponent() {
+    // The quantiles of a discrete power law of exponent 2.5 from degree 1.
+    let degrees = (0..10_000)
+        .map(|i| {
+            let u = (i as f64 + 0.5) / 10_000.0;
+            (0.5 * (1.0 - u).powf(-1.0 / 1.5) + 0.5).floor() as usize
+        })
+        .collect::<Vec<_>>();
+
+    // The approximation is poor in the smallest degrees, so the fit from
+    // degree 1 is off, but from a few degrees up it's close.
+    let fit = power_law_fit(&degrees, 6).unwrap();
+    assert!((fit.exponent - 2.5).abs() < 0.02, "{:?}", fit);
+    assert_eq!(fit.tail_len, 274);
+
+    let best = best_power_law_fit(&degrees).unwrap();
+    assert!((best.exponent - 2.5).abs() < 0.1, "{:?}", best);
+    assert!(best.ks_distance <= power_law_fit(&degrees, 1).unwrap().ks_distance);
+
+    assert_eq!(power_law_fit(&[3; 20], 3), None);
+    assert_eq!(power_law_fit(&[1, 2, 3], 1), None);
+}
+
+#[test]
+fn graphlets_of_a_triangle_with_a_pendant() {
+    let graphlets = graphlet_counts(&triangle_with_pendant());
+
+    assert_eq!(graphlets.num_sampled, 4);
+    assert_close(graphlets.edges, 4.0);
+    assert_close(graphlets.wedges, 2.0);
+    assert_close(graphlets.triangles, 1.0);
+    assert_close(graphlets.tailed_triangles, 1.0);
+    assert_close(graphlets.paths + graphlets.stars + graphlets.cycles + graphlets.diamonds + graphlets.cliques, 0.0);
+    assert_eq!(graphlets.orbits[9..12], [1.0, 2.0, 1.0]);
+}
+
+#[test]
+fn graphlets_match_a_brute_force_count() {
+    use rand::Rng;
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let num_nodes = 12;
+    let mut graph = UnGraph::<(), ()>::default();
+    let nodes = (0..num_nodes).map(|_| graph.add_node(())).collect::<Vec<_>>();
+    for a in 0..num_nodes {
+        for b in a + 1..num_nodes {
+            if rng.gen_bool(0.4) {
+                graph.add_edge(nodes[a], nodes[b], ());
+            }
+        }
+    }
+
+    // The connected sets of four nodes, by their number of edges and
+    // greatest degree among themselves.
+    let mut shapes = std::collections::BTreeMap::new();
+    for a in 0..num_nodes {
+        for b in a + 1..num_nodes {
+            for c in b + 1..num_nodes {
+                for d in c + 1..num_nodes {
+                    let set = [a, b, c, d];
+                    let degrees = set
+                        .iter()
+                        .map(|&x| set.iter().filter(|&&y| graph.contains_edge(nodes[x], nodes[y])).count())
+                        .collect::<Vec<_>>();
+                    let num_edges = degrees.iter().sum::<usize>() / 2;
+                    // A set of four nodes with no isolated node is connected
+                    // unless it's just two disjoint edges.
+                    if num_edges >= 3 && degrees.iter().all(|&degree| degree > 0) {
+                        *shapes.entry((num_edges, *degrees.iter().max().unwrap())).or_insert(0.0) += 1.0;
+                    }
+                }
+            }
+        }
+    }
+    let shape = |num_edges, max_degree| shapes.get(&(num_edges, max_degree)).cloned().unwrap_or(0.0);
+
+    let graphlets = graphlet_counts(&graph);
+    assert_close(graphlets.paths, shape(3, 2));
+    assert_close(graphlets.stars, shape(3, 3));
+    assert_close(graphlets.cycles, shape(4, 2));
+    assert_close(graphlets.tailed_triangles, shape(4, 3));
+    assert_close(graphlets.diamonds, shape(5, 3));
+    assert_close(graphlets.cliques, shape(6, 3));
+
+    assert_eq!(sample_graphlet_counts(&graph, num_nodes, &mut rng), graphlets);
+    let sampled = sample_graphlet_counts(&graph, 6, &mut rng);
+    assert_eq!(sampled.num_sampled, 6);
+    assert!(sampled.cliques >= 0.0 && sampled.paths >= 0.0);
+}
+
+#[test]
+fn core_numbers_peel_off_the_periphery() {
+    assert_eq!(core_numbers(&triangle_with_pendant()), vec![2, 2, 2, 1]);
+    assert_eq!(core_numbers(&star()), vec![1, 1, 1, 1, 1]);
+
+    // A clique of four with a path hanging off it, and an isolated node.
+    let mut graph = UnGraph::<(), ()>::from_edges([(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3), (3, 4), (4, 5)]);
+    graph.add_node(());
+    assert_eq!(core_numbers(&graph), vec![3, 3, 3, 3, 1, 1, 0]);
+}
+
+#[test]
+fn stars_fall_apart_under_attack_but_not_failure() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let attack = robustness_curve(&star(), Removal::Degree, 5, &mut rng);
+    assert_eq!(attack.fractions_removed, vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
+    assert_eq!(attack.giant_component, vec![1.0, 0.2, 0.2, 0.2, 0.2, 0.0]);
+    assert_close(attack.robustness, 4.0 / 25.0);
+
+    // Removing the hub at random does no more harm than removing it first.
+    let failure = robustness_curve(&star(), Removal::Random, 5, &mut rng);
+    assert_eq!(failure.giant_component[0], 1.0);
+    assert_eq!(failure.giant_component[5], 0.0);
+    assert!(failure.giant_component.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert!(failure.robustness >= attack.robustness);
+}
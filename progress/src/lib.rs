@@ -0,0 +1,217 @@
+//! Progress reporting for long batches of runs, which suits a terminal as
+//! well as the log of a cluster job: an interactive bar, lines of plain text
+//! every so often, or JSON events, one per line.
+//!
+//! The mode is set once for the whole program with [`set_mode`], and picked
+//! up by every [`Progress`] made after.
+
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+
+/// How often plain-text lines and JSON events are written at most.
+pub const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How progress is reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// A bar if stderr is a terminal, and plain text otherwise.
+    Auto,
+    Bar,
+    Plain,
+    Json,
+}
+
+impl Mode {
+    /// The names the modes are parsed from.
+    pub const NAMES: [&'static str; 4] = ["auto", "bar", "plain", "json"];
+
+    /// Decides what [`Mode::Auto`] means here.
+    fn resolve(self) -> Self {
+        match self {
+            Mode::Auto if io::stderr().is_terminal() => Mode::Bar,
+            Mode::Auto => Mode::Plain,
+            mode => mode,
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(Mode::NAMES[*self as usize])
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "auto" => Ok(Mode::Auto),
+            "bar" => Ok(Mode::Bar),
+            "plain" => Ok(Mode::Plain),
+            "json" => Ok(Mode::Json),
+            _ => Err(format!("unknown progress mode: {}", name)),
+        }
+    }
+}
+
+static MODE: AtomicU8 = AtomicU8::new(Mode::Auto as u8);
+
+/// Sets how progress is reported from now on.
+pub fn set_mode(mode: Mode) {
+    MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+pub fn mode() -> Mode {
+    match MODE.load(Ordering::Relaxed) {
+        1 => Mode::Bar,
+        2 => Mode::Plain,
+        3 => Mode::Json,
+        _ => Mode::Auto,
+    }
+}
+
+/// The progress through a batch of `len` items, or an open-ended one. It can
+/// be advanced from many threads at once.
+pub struct Progress {
+    label: String,
+    len: Option<u64>,
+    done: AtomicU64,
+    start: Instant,
+    reporter: Reporter,
+}
+
+enum Reporter {
+    Bar(ProgressBar),
+    Lines {
+        json: bool,
+        state: Mutex<LineState>,
+    },
+}
+
+struct LineState {
+    writer: Box<dyn Write + Send>,
+    last_report: Instant,
+}
+
+/// A JSON progress event.
+#[derive(Serialize)]
+struct Event<'a> {
+    event: &'a str,
+    label: &'a str,
+    done: u64,
+    total: Option<u64>,
+    elapsed_secs: f64,
+}
+
+impl Progress {
+    /// Starts reporting progress to stderr, in the mode set with
+    /// [`set_mode`].
+    pub fn new(label: &str, len: Option<u64>) -> Self {
+        match mode().resolve() {
+            Mode::Bar => {
+                let bar = match len {
+                    Some(len) => ProgressBar::new(len).with_style(ProgressStyle::default_bar().template(
+                        "{spinner:.green} [{elapsed_precise}] [{wide_bar}] {pos}/{len} ({per_sec}, eta {eta})",
+                    )),
+                    None => ProgressBar::new_spinner().with_style(
+                        ProgressStyle::default_spinner().template("{spinner:.green} [{elapsed_precise}] {pos} ({per_sec})"),
+                    ),
+                };
+
+                Progress::with_reporter(label, len, Reporter::Bar(bar))
+            }
+            mode => Progress::with_writer(mode == Mode::Json, label, len, io::stderr()),
+        }
+    }
+
+    /// Starts reporting progress to `writer`, as JSON events if `json` is
+    /// set, or as plain text otherwise.
+    pub fn with_writer<W: Write + Send + 'static>(json: bool, label: &str, len: Option<u64>, writer: W) -> Self {
+        let reporter = Reporter::Lines {
+            json,
+            state: Mutex::new(LineState {
+                writer: Box::new(writer),
+                last_report: Instant::now(),
+            }),
+        };
+
+        let progress = Progress::with_reporter(label, len, reporter);
+        progress.report("start", 0);
+        progress
+    }
+
+    fn with_reporter(label: &str, len: Option<u64>, reporter: Reporter) -> Self {
+        Progress {
+            label: label.to_string(),
+            len,
+            done: AtomicU64::new(0),
+            start: Instant::now(),
+            reporter,
+        }
+    }
+
+    /// Advances the progress by `n` items.
+    pub fn inc(&self, n: u64) {
+        let done = self.done.fetch_add(n, Ordering::Relaxed) + n;
+
+        match &self.reporter {
+            Reporter::Bar(bar) => bar.inc(n),
+            Reporter::Lines { state, .. } => {
+                let due = state.lock().unwrap().last_report.elapsed() >= REPORT_INTERVAL;
+                if due {
+                    self.report("progress", done);
+                }
+            }
+        }
+    }
+
+    /// Reports that the batch is done.
+    pub fn finish(self) {
+        match &self.reporter {
+            Reporter::Bar(bar) => bar.finish(),
+            Reporter::Lines { .. } => self.report("finish", self.done.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Writes a line for an event. Errors are ignored, as progress is no
+    /// reason to stop a run.
+    fn report(&self, event: &str, done: u64) {
+        let (json, state) = match &self.reporter {
+            Reporter::Lines { json, state } => (*json, state),
+            Reporter::Bar(_) => return,
+        };
+
+        let mut state = state.lock().unwrap();
+        state.last_report = Instant::now();
+        let elapsed = self.start.elapsed().as_secs_f64();
+
+        let line = if json {
+            serde_json::to_string(&Event {
+                event,
+                label: &self.label,
+                done,
+                total: self.len,
+                elapsed_secs: elapsed,
+            })
+            .unwrap()
+        } else {
+            let count = match self.len {
+                Some(len) if len > 0 => format!("{}/{} ({:.0}%)", done, len, 100.0 * done as f64 / len as f64),
+                Some(len) => format!("{}/{}", done, len),
+                None => done.to_string(),
+            };
+            format!("{}: {} {}, {:.1}s elapsed", self.label, event, count, elapsed)
+        };
+
+        let _ = writeln!(state.writer, "{}", line);
+        let _ = state.writer.flush();
+    }
+}
@@ -0,0 +1,85 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use progress::{Mode, Progress};
+use serde_json::Value;
+
+/// A writer whose output can be read back after it's moved into a
+/// [`Progress`].
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Buffer {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+#[test]
+fn json_events_bracket_the_batch() {
+    let buffer = Buffer::default();
+    let progress = Progress::with_writer(true, "sweep", Some(40), buffer.clone());
+    for _ in 0..40 {
+        progress.inc(1);
+    }
+    progress.finish();
+
+    let events = buffer
+        .lines()
+        .iter()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    let first = events.first().unwrap();
+    assert_eq!(first["event"], "start");
+    assert_eq!(first["label"], "sweep");
+    assert_eq!(first["total"], 40);
+
+    let last = events.last().unwrap();
+    assert_eq!(last["event"], "finish");
+    assert_eq!(last["done"], 40);
+}
+
+#[test]
+fn plain_lines_give_a_percentage() {
+    let buffer = Buffer::default();
+    let progress = Progress::with_writer(false, "sweep", Some(8), buffer.clone());
+    progress.inc(8);
+    progress.finish();
+
+    let lines = buffer.lines();
+    assert!(lines[0].starts_with("sweep: start 0/8 (0%)"), "{}", lines[0]);
+    assert!(lines.last().unwrap().starts_with("sweep: finish 8/8 (100%)"));
+
+    let buffer = Buffer::default();
+    let progress = Progress::with_writer(false, "steps", None, buffer.clone());
+    progress.inc(3);
+    progress.finish();
+    assert!(buffer.lines().last().unwrap().starts_with("steps: finish 3,"));
+}
+
+#[test]
+fn modes() {
+    for &name in &Mode::NAMES {
+        assert_eq!(name.parse::<Mode>().unwrap().to_string(), name);
+    }
+
+    assert_eq!(progress::mode(), Mode::Auto);
+    progress::set_mode(Mode::Json);
+    assert_eq!(progress::mode(), Mode::Json);
+}
@@ -0,0 +1,186 @@
+//! A completion ledger for batches of independent runs: a journal recording
+//! which runs finished and the output shards each wrote, so that a batch
+//! interrupted by a crash or preemption can resume with only the runs it's
+//! missing.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+/// The first line of a ledger, identifying the batch it's for.
+#[derive(Serialize, Deserialize)]
+struct Header {
+    parameters: Value,
+}
+
+/// A line recording that a run finished.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    run: u64,
+    /// The files the run wrote, relative to the ledger's directory.
+    shards: Vec<PathBuf>,
+}
+
+/// An append-only journal of the runs of a batch which have finished. It can
+/// be shared between the threads doing the runs.
+#[derive(Debug)]
+pub struct Ledger {
+    path: PathBuf,
+    dir: PathBuf,
+    completed: BTreeMap<u64, Vec<PathBuf>>,
+    file: Mutex<File>,
+}
+
+impl Ledger {
+    /// Opens the ledger at `path` for a batch with the given parameters,
+    /// creating it if it doesn't exist. A ledger left by a batch with other
+    /// parameters is an error, since its runs can't be mixed with these. A
+    /// line cut short by a crash is dropped.
+    pub fn open<P: AsRef<Path>, S: Serialize>(path: P, parameters: &S) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let parameters = serde_json::to_value(parameters)?;
+        let mut completed = BTreeMap::new();
+
+        if path.exists() {
+            let (header, entries, complete_len) = read(&path)?;
+            OpenOptions::new().write(true).open(&path)?.set_len(complete_len)?;
+
+            if let Some(header) = header {
+                if header.parameters != parameters {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "the ledger at {} is from a batch with other parameters; \
+                             remove it to start over",
+                            path.display()
+                        ),
+                    ));
+                }
+
+                for entry in entries {
+                    completed.insert(entry.run, entry.shards);
+                }
+            } else {
+                fs::remove_file(&path)?;
+            }
+        }
+
+        let new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        if new {
+            serde_json::to_writer(&mut file, &Header { parameters })?;
+            writeln!(file)?;
+            file.sync_data()?;
+        }
+
+        Ok(Ledger {
+            path,
+            dir,
+            completed,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Reads the parameters of the batch the ledger at `path` is for, or
+    /// `None` if there's no ledger there.
+    pub fn parameters<P: AsRef<Path>, T: DeserializeOwned>(path: P) -> io::Result<Option<T>> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        match read(path)?.0 {
+            Some(header) => Ok(Some(serde_json::from_value(header.parameters)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads which runs the ledger at `path` records as finished, in order,
+    /// without opening it for the batch; none if there's no ledger there.
+    pub fn completed<P: AsRef<Path>>(path: P) -> io::Result<Vec<u64>> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut runs = read(path)?.1.into_iter().map(|entry| entry.run).collect::<Vec<_>>();
+        runs.sort_unstable();
+        runs.dedup();
+        Ok(runs)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns whether a run finished in an earlier invocation, with every
+    /// one of its shards still in place.
+    pub fn is_complete(&self, run: u64) -> bool {
+        match self.completed.get(&run) {
+            Some(shards) => shards.iter().all(|shard| self.dir.join(shard).exists()),
+            None => false,
+        }
+    }
+
+    /// Records that a run finished, having written `shards`. The entry is
+    /// synced to disk before returning, so the shards should be complete by
+    /// then.
+    pub fn complete(&self, run: u64, shards: &[PathBuf]) -> io::Result<()> {
+        let shards = shards
+            .iter()
+            .map(|shard| shard.strip_prefix(&self.dir).unwrap_or(shard).to_path_buf())
+            .collect();
+
+        let mut line = serde_json::to_vec(&Entry { run, shards })?;
+        line.push(b'\n');
+
+        let mut file = self.file.lock().unwrap();
+        file.write_all(&line)?;
+        file.sync_data()
+    }
+
+    /// Removes the ledger, once its batch has been dealt with.
+    pub fn remove(self) -> io::Result<()> {
+        drop(self.file);
+        fs::remove_file(&self.path)
+    }
+}
+
+/// Reads the header and entries of a ledger, along with the length of the
+/// complete lines they're read from.
+fn read(path: &Path) -> io::Result<(Option<Header>, Vec<Entry>, u64)> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut header = None;
+    let mut entries = Vec::new();
+    let mut complete_len = 0;
+    let mut line = String::new();
+
+    while reader.read_line(&mut line)? > 0 {
+        if !line.ends_with('\n') {
+            break;
+        }
+
+        if header.is_none() {
+            match serde_json::from_str(&line) {
+                Ok(parsed) => header = Some(parsed),
+                Err(_) => break,
+            }
+        } else {
+            match serde_json::from_str(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(_) => break,
+            }
+        }
+
+        complete_len += line.len() as u64;
+        line.clear();
+    }
+
+    Ok((header, entries, complete_len))
+}
@@ -0,0 +1,202 @@
+//! Provenance for experiment output: a `manifest.json` written alongside the
+//! output files, recording how they were produced, so that results stay
+//! attributable long after the fact, and a [`Ledger`] of the runs of a batch
+//! which have finished, so that it can be resumed.
+
+pub mod ledger;
+
+pub use ledger::Ledger;
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+/// The name of the manifest file in an output directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// What produced the files in an output directory.
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    /// The program and subcommand that was run.
+    pub program: String,
+    /// The version of the crate the program is from.
+    pub version: String,
+    /// Every parameter of the run, as it was configured.
+    pub parameters: serde_json::Value,
+    /// The seeds the run's random number generators were seeded from.
+    pub seeds: Vec<u64>,
+    /// The commit the program was built from, if it was built from a git
+    /// checkout.
+    pub git_commit: Option<String>,
+    /// Whether the checkout had uncommitted changes.
+    pub git_dirty: Option<bool>,
+    pub hostname: Option<String>,
+    pub started_at: String,
+    /// When the run finished, or `None` while it's still running.
+    pub finished_at: Option<String>,
+    /// The files written during the run, relative to the output directory.
+    pub files: Vec<PathBuf>,
+    /// What happened during the run that its parameters alone don't say,
+    /// such as parameters changed partway through, in the order it happened.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub log: Vec<serde_json::Value>,
+    #[serde(skip)]
+    out_dir: PathBuf,
+    /// The files already in the output directory at the start, with the time
+    /// they were last modified.
+    #[serde(skip)]
+    existing: HashMap<PathBuf, SystemTime>,
+}
+
+impl Manifest {
+    /// Starts a manifest for a run writing to `out_dir`, recording the
+    /// environment and the start time now. The manifest is written right
+    /// away, so that a run which never finishes still has one.
+    pub fn start<P, S>(out_dir: P, program: &str, version: &str, parameters: &S, seeds: &[u64]) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+        S: Serialize,
+    {
+        let start = SystemTime::now();
+        let (git_commit, git_dirty) = match git_commit() {
+            Some((commit, dirty)) => (Some(commit), Some(dirty)),
+            None => (None, None),
+        };
+
+        let mut manifest = Manifest {
+            program: program.to_string(),
+            version: version.to_string(),
+            parameters: serde_json::to_value(parameters)?,
+            seeds: seeds.to_vec(),
+            git_commit,
+            git_dirty,
+            hostname: hostname(),
+            started_at: utc_timestamp(start),
+            finished_at: None,
+            files: Vec::new(),
+            log: Vec::new(),
+            out_dir: out_dir.as_ref().to_path_buf(),
+            existing: HashMap::new(),
+        };
+
+        fs::create_dir_all(&manifest.out_dir)?;
+        collect_files(&manifest.out_dir, &mut manifest.existing)?;
+        manifest.write()?;
+        Ok(manifest)
+    }
+
+    /// Records the end time along with every file in the output directory
+    /// which was created or modified since the start, and writes the manifest
+    /// again.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.finished_at = Some(utc_timestamp(SystemTime::now()));
+
+        let mut files = HashMap::new();
+        collect_files(&self.out_dir, &mut files)?;
+        self.files = files
+            .into_iter()
+            .filter(|(file, modified)| self.existing.get(file) != Some(modified))
+            .filter_map(|(file, _)| file.strip_prefix(&self.out_dir).ok().map(Path::to_path_buf))
+            .filter(|file| file != Path::new(MANIFEST_FILE))
+            .collect();
+        self.files.sort();
+
+        self.write()
+    }
+
+    /// Adds an entry to the log, and writes the manifest again, so that it's
+    /// there even if the run never finishes.
+    pub fn log<S: Serialize>(&mut self, entry: &S) -> io::Result<()> {
+        self.log.push(serde_json::to_value(entry)?);
+        self.write()
+    }
+
+    fn write(&self) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(self.out_dir.join(MANIFEST_FILE))?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+}
+
+/// Adds the files under `dir` to `files`, with the time each was last
+/// modified.
+fn collect_files(dir: &Path, files: &mut HashMap<PathBuf, SystemTime>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+
+        if metadata.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else {
+            files.insert(entry.path(), metadata.modified()?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the commit of the checkout this crate was built from, and whether
+/// it has uncommitted changes, if git can tell.
+fn git_commit() -> Option<(String, bool)> {
+    let git = |args: &[&str]| {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(env!("CARGO_MANIFEST_DIR"))
+            .args(args)
+            .output()
+            .ok()?;
+
+        if output.status.success() {
+            String::from_utf8(output.stdout).ok()
+        } else {
+            None
+        }
+    };
+
+    let commit = git(&["rev-parse", "HEAD"])?.trim().to_string();
+    let dirty = !git(&["status", "--porcelain"])?.trim().is_empty();
+    Some((commit, dirty))
+}
+
+fn hostname() -> Option<String> {
+    fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .map(|hostname| hostname.trim().to_string())
+        .filter(|hostname| !hostname.is_empty())
+}
+
+/// Formats a time as an RFC 3339 timestamp in UTC, to the second.
+pub fn utc_timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+    let (days, secs_of_day) = (secs / 86400, secs % 86400);
+
+    // Converts days since the epoch to a proleptic Gregorian date, after
+    // Howard Hinnant's `civil_from_days`, with eras of 400 years.
+    let z = days + 719468;
+    let era = z / 146097;
+    let day_of_era = z % 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as u64;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
@@ -0,0 +1,57 @@
+use std::io::Write;
+
+use provenance::Ledger;
+use serde_json::json;
+
+#[test]
+fn reopened_ledger_resumes_the_missing_runs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().join("math435-ledger-resume");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("ledger.jsonl");
+    let parameters = json!({ "num_runs": 4, "seed": 7 });
+
+    let ledger = Ledger::open(&path, &parameters).unwrap();
+    for run in 0..3 {
+        let shard = dir.join(format!("run_{}.csv", run));
+        std::fs::write(&shard, "1,2\n").unwrap();
+        ledger.complete(run, &[shard]).unwrap();
+    }
+    drop(ledger);
+
+    // A crash partway through writing an entry leaves half a line, and a run
+    // whose shard is gone has to be done again.
+    let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+    write!(file, r#"{{"run": 3, "sha"#).unwrap();
+    drop(file);
+    std::fs::remove_file(dir.join("run_1.csv")).unwrap();
+
+    let ledger = Ledger::open(&path, &parameters).unwrap();
+    let complete = (0..4).filter(|&run| ledger.is_complete(run)).collect::<Vec<_>>();
+    assert_eq!(complete, vec![0, 2]);
+
+    ledger.complete(3, &[]).unwrap();
+    drop(ledger);
+
+    let ledger = Ledger::open(&path, &parameters).unwrap();
+    assert!(ledger.is_complete(3));
+    assert_eq!(Ledger::parameters(&path).unwrap(), Some(parameters));
+    assert_eq!(Ledger::completed(&path).unwrap(), vec![0, 1, 2, 3]);
+
+    ledger.remove().unwrap();
+    assert!(!path.exists());
+    assert!(Ledger::completed(&path).unwrap().is_empty());
+}
+
+#[test]
+fn ledger_of_other_parameters_is_an_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().join("math435-ledger-mismatch");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("ledger.jsonl");
+
+    Ledger::open(&path, &json!({ "seed": 1 })).unwrap();
+
+    assert!(Ledger::open(&path, &json!({ "seed": 2 })).is_err());
+    assert!(Ledger::open(&path, &json!({ "seed": 1 })).is_ok());
+}
@@ -0,0 +1,57 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use provenance::{utc_timestamp, Manifest, MANIFEST_FILE};
+use serde_json::{json, Value};
+
+#[test]
+fn timestamps() {
+    assert_eq!(utc_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+    assert_eq!(utc_timestamp(UNIX_EPOCH + Duration::from_secs(951_782_400)), "2000-02-29T00:00:00Z");
+    assert_eq!(utc_timestamp(UNIX_EPOCH + Duration::from_secs(1_700_000_000)), "2023-11-14T22:13:20Z");
+}
+
+#[test]
+fn manifest_lists_the_files_written() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("math435-provenance");
+    std::fs::create_dir_all(&out_dir).unwrap();
+
+    // Files from before the run aren't its output.
+    std::fs::write(out_dir.join("stale.csv"), "").unwrap();
+
+    let manifest = Manifest::start(&out_dir, "test run", "0.1.0", &json!({ "rate": 0.5 }), &[7]).unwrap();
+
+    let started: Value = serde_json::from_str(&std::fs::read_to_string(out_dir.join(MANIFEST_FILE)).unwrap()).unwrap();
+    assert_eq!(started["finished_at"], Value::Null);
+
+    std::fs::create_dir_all(out_dir.join("run_0")).unwrap();
+    std::fs::write(out_dir.join("run_0").join("curve.csv"), "a,b\n").unwrap();
+    std::fs::write(out_dir.join("metrics.jsonl"), "{}\n").unwrap();
+    manifest.finish().unwrap();
+
+    let finished: Value = serde_json::from_str(&std::fs::read_to_string(out_dir.join(MANIFEST_FILE)).unwrap()).unwrap();
+    assert_eq!(finished["program"], "test run");
+    assert_eq!(finished["parameters"], json!({ "rate": 0.5 }));
+    assert_eq!(finished["seeds"], json!([7]));
+    assert!(finished["finished_at"].is_string());
+    assert_eq!(finished["files"], json!(["metrics.jsonl", "run_0/curve.csv"]));
+}
+
+#[test]
+fn logged_entries_are_written_right_away() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_dir = temp_dir.path().join("math435-provenance-log");
+
+    let mut manifest = Manifest::start(&out_dir, "test run", "0.1.0", &json!({}), &[]).unwrap();
+    let read = || -> Value {
+        let manifest = std::fs::read_to_string(out_dir.join(MANIFEST_FILE)).unwrap();
+        serde_json::from_str(&manifest).unwrap()
+    };
+    assert_eq!(read().get("log"), None);
+
+    manifest.log(&json!({ "step": 5, "decay_rate": 0.02 })).unwrap();
+    assert_eq!(read()["log"], json!([{ "step": 5, "decay_rate": 0.02 }]));
+
+    manifest.finish().unwrap();
+    assert_eq!(read()["log"], json!([{ "step": 5, "decay_rate": 0.02 }]));
+}
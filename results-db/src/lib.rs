@@ -0,0 +1,264 @@
+//! A SQLite database of simulation results: the runs, with their parameters,
+//! and the nodes, edges and metrics of their graphs, in indexed tables, so
+//! that sweeps can be queried with SQL rather than loaded from flat files.
+//!
+//! Parameters are stored as JSON, which SQLite's JSON functions can pick
+//! apart, e.g. for the largest degree at each temperature of a sweep:
+//!
+//! ```sql
+//! SELECT json_extract(runs.parameters, '$.initial_temperature') AS temperature,
+//!        MAX(metrics.max_degree)
+//! FROM runs JOIN metrics ON metrics.run_id = runs.id
+//! GROUP BY temperature;
+//! ```
+
+pub mod sqlite;
+
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+
+use graph_io::{AttributeValue, AttributedGraph, Attributes};
+use net_metrics::Summary;
+use serde::Serialize;
+
+use crate::sqlite::{Connection, Value};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS runs (
+        id INTEGER PRIMARY KEY,
+        program TEXT NOT NULL,
+        parameters TEXT NOT NULL,
+        run_index INTEGER NOT NULL,
+        seed TEXT,
+        UNIQUE (program, parameters, run_index)
+    );
+    CREATE TABLE IF NOT EXISTS nodes (
+        run_id INTEGER NOT NULL REFERENCES runs (id),
+        node INTEGER NOT NULL,
+        in_degree INTEGER NOT NULL,
+        out_degree INTEGER NOT NULL,
+        attributes TEXT NOT NULL,
+        PRIMARY KEY (run_id, node)
+    );
+    CREATE TABLE IF NOT EXISTS edges (
+        run_id INTEGER NOT NULL REFERENCES runs (id),
+        source INTEGER NOT NULL,
+        target INTEGER NOT NULL,
+        attributes TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS edges_by_run ON edges (run_id, source);
+    CREATE TABLE IF NOT EXISTS metrics (
+        run_id INTEGER NOT NULL REFERENCES runs (id),
+        timestep INTEGER,
+        num_nodes INTEGER NOT NULL,
+        num_edges INTEGER NOT NULL,
+        mean_degree REAL NOT NULL,
+        max_degree INTEGER NOT NULL,
+        degree_gini REAL NOT NULL,
+        average_clustering REAL NOT NULL,
+        transitivity REAL NOT NULL,
+        degree_assortativity REAL,
+        mean_path_length REAL NOT NULL,
+        max_path_length INTEGER NOT NULL,
+        reachable_fraction REAL NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS metrics_by_run ON metrics (run_id, timestep);
+";
+
+/// A database of results, which can be written to from many threads at once.
+pub struct ResultsDb {
+    connection: Mutex<Connection>,
+}
+
+impl ResultsDb {
+    /// Opens the database at `path`, creating it and its tables if they don't
+    /// exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+
+        Ok(ResultsDb {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Adds a run, returning its id. A run of the same program with the same
+    /// parameters and index, left by an interrupted sweep, is replaced along
+    /// with everything recorded about it.
+    pub fn insert_run<S: Serialize>(
+        &self,
+        program: &str,
+        parameters: &S,
+        run_index: u64,
+        seed: Option<u64>,
+    ) -> io::Result<i64> {
+        let parameters = serde_json::to_string(parameters)?;
+        self.connection
+            .lock()
+            .unwrap()
+            .transaction(|connection| insert_run(connection, program, parameters, run_index, seed))
+    }
+
+    /// Adds the nodes and edges of a run's graph, with their attributes as
+    /// JSON objects.
+    pub fn insert_graph(&self, run_id: i64, graph: &AttributedGraph) -> io::Result<()> {
+        self.connection
+            .lock()
+            .unwrap()
+            .transaction(|connection| insert_graph(connection, run_id, graph))
+    }
+
+    /// Adds the metrics of a run's graph, at a timestep if it's one of many.
+    pub fn insert_metrics(&self, run_id: i64, timestep: Option<usize>, summary: &Summary) -> io::Result<()> {
+        insert_metrics(&self.connection.lock().unwrap(), run_id, timestep, summary)
+    }
+
+    /// Adds a run as [`insert_run`](Self::insert_run) does, with its final
+    /// graph and its metrics at each of their timesteps, all in one
+    /// transaction, so that a run which is interrupted leaves none of its
+    /// rows behind to be counted twice when it's run again.
+    pub fn insert_results<S: Serialize>(
+        &self,
+        program: &str,
+        parameters: &S,
+        run_index: u64,
+        seed: Option<u64>,
+        graph: &AttributedGraph,
+        metrics: &[(Option<usize>, Summary)],
+    ) -> io::Result<()> {
+        let parameters = serde_json::to_string(parameters)?;
+        self.connection.lock().unwrap().transaction(|connection| {
+            let run_id = insert_run(connection, program, parameters, run_index, seed)?;
+            insert_graph(connection, run_id, graph)?;
+            for (timestep, summary) in metrics {
+                insert_metrics(connection, run_id, *timestep, summary)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Runs a query, returning every row.
+    pub fn query(&self, sql: &str, params: &[Value]) -> io::Result<Vec<Vec<Value>>> {
+        self.connection.lock().unwrap().prepare(sql)?.query(params)
+    }
+}
+
+fn insert_run(
+    connection: &Connection,
+    program: &str,
+    parameters: String,
+    run_index: u64,
+    seed: Option<u64>,
+) -> io::Result<i64> {
+    let key = [program.into(), parameters.into(), Value::Integer(run_index as i64)];
+
+    let existing = connection
+        .prepare("SELECT id FROM runs WHERE program = ?1 AND parameters = ?2 AND run_index = ?3")?
+        .query(&key)?;
+
+    if let Some(row) = existing.first() {
+        for table in &["nodes", "edges", "metrics"] {
+            connection
+                .prepare(&format!("DELETE FROM {} WHERE run_id = ?1", table))?
+                .execute(&row[..1])?;
+        }
+        connection.prepare("DELETE FROM runs WHERE id = ?1")?.execute(&row[..1])?;
+    }
+
+    connection
+        .prepare("INSERT INTO runs (program, parameters, run_index, seed) VALUES (?1, ?2, ?3, ?4)")?
+        .execute(&[
+            key[0].clone(),
+            key[1].clone(),
+            key[2].clone(),
+            seed.map(|seed| seed.to_string()).into(),
+        ])?;
+
+    Ok(connection.last_insert_rowid())
+}
+
+fn insert_graph(connection: &Connection, run_id: i64, graph: &AttributedGraph) -> io::Result<()> {
+    let num_nodes = graph.nodes.iter().map(|&(node, _)| node + 1).max().unwrap_or(0);
+    let mut in_degrees = vec![0usize; num_nodes];
+    let mut out_degrees = vec![0usize; num_nodes];
+
+    for &(source, target, _) in &graph.edges {
+        out_degrees[source] += 1;
+        in_degrees[target] += 1;
+
+        if !graph.directed {
+            in_degrees[source] += 1;
+            out_degrees[target] += 1;
+        }
+    }
+
+    let mut insert_node = connection
+        .prepare("INSERT INTO nodes (run_id, node, in_degree, out_degree, attributes) VALUES (?1, ?2, ?3, ?4, ?5)")?;
+    for (node, attributes) in &graph.nodes {
+        insert_node.execute(&[
+            run_id.into(),
+            (*node).into(),
+            in_degrees[*node].into(),
+            out_degrees[*node].into(),
+            to_json(attributes).into(),
+        ])?;
+    }
+
+    let mut insert_edge =
+        connection.prepare("INSERT INTO edges (run_id, source, target, attributes) VALUES (?1, ?2, ?3, ?4)")?;
+    for (source, target, attributes) in &graph.edges {
+        insert_edge.execute(&[
+            run_id.into(),
+            (*source).into(),
+            (*target).into(),
+            to_json(attributes).into(),
+        ])?;
+    }
+
+    Ok(())
+}
+
+fn insert_metrics(connection: &Connection, run_id: i64, timestep: Option<usize>, summary: &Summary) -> io::Result<()> {
+    connection
+        .prepare(
+            "INSERT INTO metrics (run_id, timestep, num_nodes, num_edges, mean_degree, max_degree, degree_gini, \
+             average_clustering, transitivity, degree_assortativity, mean_path_length, max_path_length, \
+             reachable_fraction) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        )?
+        .execute(&[
+            run_id.into(),
+            timestep.into(),
+            summary.num_nodes.into(),
+            summary.num_edges.into(),
+            summary.mean_degree.into(),
+            summary.max_degree.into(),
+            summary.degree_gini.into(),
+            summary.average_clustering.into(),
+            summary.transitivity.into(),
+            summary.degree_assortativity.into(),
+            summary.path_lengths.mean.into(),
+            summary.path_lengths.max.into(),
+            summary.path_lengths.reachable_fraction.into(),
+        ])
+}
+
+/// Writes attributes as a JSON object. Non-finite floats, which JSON can't
+/// represent, become nulls.
+fn to_json(attributes: &Attributes) -> String {
+    let object = attributes
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                AttributeValue::Bool(value) => serde_json::Value::from(*value),
+                AttributeValue::Int(value) => serde_json::Value::from(*value),
+                AttributeValue::Float(value) => serde_json::Value::from(*value),
+                AttributeValue::String(value) => serde_json::Value::from(value.as_str()),
+            };
+            (name.clone(), value)
+        })
+        .collect::<serde_json::Map<_, _>>();
+
+    serde_json::Value::Object(object).to_string()
+}
@@ -0,0 +1,303 @@
+//! A minimal binding to the system's SQLite library: connections, and
+//! prepared statements with positional parameters.
+
+use std::ffi::{CStr, CString};
+use std::io;
+use std::os::raw::{c_char, c_int, c_uchar, c_void};
+use std::path::Path;
+use std::ptr;
+
+#[allow(non_camel_case_types)]
+enum sqlite3 {}
+#[allow(non_camel_case_types)]
+enum sqlite3_stmt {}
+
+const SQLITE_OK: c_int = 0;
+const SQLITE_ROW: c_int = 100;
+const SQLITE_DONE: c_int = 101;
+
+const SQLITE_INTEGER: c_int = 1;
+const SQLITE_FLOAT: c_int = 2;
+const SQLITE_TEXT: c_int = 3;
+
+const SQLITE_OPEN_READWRITE: c_int = 0x2;
+const SQLITE_OPEN_CREATE: c_int = 0x4;
+
+#[link(name = "sqlite3")]
+extern "C" {
+    fn sqlite3_open_v2(filename: *const c_char, db: *mut *mut sqlite3, flags: c_int, vfs: *const c_char) -> c_int;
+    fn sqlite3_close(db: *mut sqlite3) -> c_int;
+    fn sqlite3_errmsg(db: *mut sqlite3) -> *const c_char;
+    fn sqlite3_exec(
+        db: *mut sqlite3,
+        sql: *const c_char,
+        callback: *const c_void,
+        arg: *mut c_void,
+        errmsg: *mut *mut c_char,
+    ) -> c_int;
+    fn sqlite3_last_insert_rowid(db: *mut sqlite3) -> i64;
+    fn sqlite3_prepare_v2(
+        db: *mut sqlite3,
+        sql: *const c_char,
+        len: c_int,
+        stmt: *mut *mut sqlite3_stmt,
+        tail: *mut *const c_char,
+    ) -> c_int;
+    fn sqlite3_bind_null(stmt: *mut sqlite3_stmt, index: c_int) -> c_int;
+    fn sqlite3_bind_int64(stmt: *mut sqlite3_stmt, index: c_int, value: i64) -> c_int;
+    fn sqlite3_bind_double(stmt: *mut sqlite3_stmt, index: c_int, value: f64) -> c_int;
+    fn sqlite3_bind_text(
+        stmt: *mut sqlite3_stmt,
+        index: c_int,
+        text: *const c_char,
+        len: c_int,
+        destructor: isize,
+    ) -> c_int;
+    fn sqlite3_step(stmt: *mut sqlite3_stmt) -> c_int;
+    fn sqlite3_reset(stmt: *mut sqlite3_stmt) -> c_int;
+    fn sqlite3_clear_bindings(stmt: *mut sqlite3_stmt) -> c_int;
+    fn sqlite3_finalize(stmt: *mut sqlite3_stmt) -> c_int;
+    fn sqlite3_column_count(stmt: *mut sqlite3_stmt) -> c_int;
+    fn sqlite3_column_type(stmt: *mut sqlite3_stmt, index: c_int) -> c_int;
+    fn sqlite3_column_int64(stmt: *mut sqlite3_stmt, index: c_int) -> i64;
+    fn sqlite3_column_double(stmt: *mut sqlite3_stmt, index: c_int) -> f64;
+    fn sqlite3_column_text(stmt: *mut sqlite3_stmt, index: c_int) -> *const c_uchar;
+}
+
+/// Tells SQLite to copy bound text, as it's freed before the statement is
+/// done with it.
+const SQLITE_TRANSIENT: isize = -1;
+
+/// A value bound to a parameter of a statement, or read from a column.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        Value::Integer(value as i64)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Real(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Text(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Text(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+/// A connection to a database file.
+pub struct Connection {
+    db: *mut sqlite3,
+}
+
+// A connection may be moved between threads, as long as it's used by one at a
+// time, which `&mut` and `Mutex` ensure for callers.
+unsafe impl Send for Connection {}
+
+impl Connection {
+    /// Opens the database at `path`, creating it if it doesn't exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = c_string(&path.as_ref().to_string_lossy())?;
+        let mut db = ptr::null_mut();
+        let code = unsafe {
+            sqlite3_open_v2(
+                path.as_ptr(),
+                &mut db,
+                SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                ptr::null(),
+            )
+        };
+
+        let connection = Connection { db };
+        if code != SQLITE_OK {
+            return Err(connection.error());
+        }
+
+        Ok(connection)
+    }
+
+    /// Runs one or more statements separated by semicolons, without
+    /// parameters.
+    pub fn execute_batch(&self, sql: &str) -> io::Result<()> {
+        let sql = c_string(sql)?;
+        let code = unsafe { sqlite3_exec(self.db, sql.as_ptr(), ptr::null(), ptr::null_mut(), ptr::null_mut()) };
+        self.check(code)
+    }
+
+    pub fn prepare(&self, sql: &str) -> io::Result<Statement<'_>> {
+        let sql = c_string(sql)?;
+        let mut stmt = ptr::null_mut();
+        let code = unsafe { sqlite3_prepare_v2(self.db, sql.as_ptr(), -1, &mut stmt, ptr::null_mut()) };
+        self.check(code)?;
+
+        Ok(Statement {
+            connection: self,
+            stmt,
+        })
+    }
+
+    /// Returns the rowid of the last row inserted.
+    pub fn last_insert_rowid(&self) -> i64 {
+        unsafe { sqlite3_last_insert_rowid(self.db) }
+    }
+
+    /// Runs `body` in a transaction, which is committed if it succeeds and
+    /// rolled back otherwise.
+    pub fn transaction<T, F>(&self, body: F) -> io::Result<T>
+    where
+        F: FnOnce(&Self) -> io::Result<T>,
+    {
+        self.execute_batch("BEGIN")?;
+
+        match body(self) {
+            Ok(value) => {
+                self.execute_batch("COMMIT")?;
+                Ok(value)
+            }
+            Err(err) => {
+                let _ = self.execute_batch("ROLLBACK");
+                Err(err)
+            }
+        }
+    }
+
+    fn check(&self, code: c_int) -> io::Result<()> {
+        if code == SQLITE_OK {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn error(&self) -> io::Error {
+        let message = unsafe { CStr::from_ptr(sqlite3_errmsg(self.db)) };
+        io::Error::other(format!("sqlite: {}", message.to_string_lossy()))
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        unsafe {
+            sqlite3_close(self.db);
+        }
+    }
+}
+
+/// A prepared statement, which can be run many times with different
+/// parameters.
+pub struct Statement<'c> {
+    connection: &'c Connection,
+    stmt: *mut sqlite3_stmt,
+}
+
+impl Statement<'_> {
+    /// Runs the statement with the given parameters, discarding any rows.
+    pub fn execute(&mut self, params: &[Value]) -> io::Result<()> {
+        self.query(params).map(drop)
+    }
+
+    /// Runs the statement with the given parameters, returning every row.
+    pub fn query(&mut self, params: &[Value]) -> io::Result<Vec<Vec<Value>>> {
+        let result = self.bind(params).and_then(|()| self.rows());
+
+        unsafe {
+            sqlite3_reset(self.stmt);
+            sqlite3_clear_bindings(self.stmt);
+        }
+
+        result
+    }
+
+    fn bind(&mut self, params: &[Value]) -> io::Result<()> {
+        for (i, param) in params.iter().enumerate() {
+            let index = i as c_int + 1;
+            let code = unsafe {
+                match param {
+                    Value::Null => sqlite3_bind_null(self.stmt, index),
+                    Value::Integer(value) => sqlite3_bind_int64(self.stmt, index, *value),
+                    Value::Real(value) => sqlite3_bind_double(self.stmt, index, *value),
+                    Value::Text(text) => sqlite3_bind_text(
+                        self.stmt,
+                        index,
+                        text.as_ptr() as *const c_char,
+                        text.len() as c_int,
+                        SQLITE_TRANSIENT,
+                    ),
+                }
+            };
+            self.connection.check(code)?;
+        }
+
+        Ok(())
+    }
+
+    fn rows(&mut self) -> io::Result<Vec<Vec<Value>>> {
+        let mut rows = Vec::new();
+
+        loop {
+            match unsafe { sqlite3_step(self.stmt) } {
+                SQLITE_ROW => rows.push(self.row()),
+                SQLITE_DONE => return Ok(rows),
+                _ => return Err(self.connection.error()),
+            }
+        }
+    }
+
+    fn row(&self) -> Vec<Value> {
+        let num_columns = unsafe { sqlite3_column_count(self.stmt) };
+
+        (0..num_columns)
+            .map(|index| unsafe {
+                match sqlite3_column_type(self.stmt, index) {
+                    SQLITE_INTEGER => Value::Integer(sqlite3_column_int64(self.stmt, index)),
+                    SQLITE_FLOAT => Value::Real(sqlite3_column_double(self.stmt, index)),
+                    SQLITE_TEXT => {
+                        let text = CStr::from_ptr(sqlite3_column_text(self.stmt, index) as *const c_char);
+                        Value::Text(text.to_string_lossy().into_owned())
+                    }
+                    _ => Value::Null,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Drop for Statement<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            sqlite3_finalize(self.stmt);
+        }
+    }
+}
+
+fn c_string(text: &str) -> io::Result<CString> {
+    CString::new(text).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
@@ -0,0 +1,127 @@
+use graph_io::{AttributedGraph, Attributes};
+use net_metrics::{PathLengths, Summary};
+use results_db::{sqlite::Value, ResultsDb};
+use serde_json::json;
+
+fn summary(max_degree: usize) -> Summary {
+    Summary {
+        num_nodes: 3,
+        num_edges: 2,
+        mean_degree: 4.0 / 3.0,
+        max_degree,
+        degree_gini: 0.2,
+        average_clustering: 0.0,
+        transitivity: 0.0,
+        degree_assortativity: None,
+        path_lengths: PathLengths {
+            num_sources: 3,
+            mean: 1.0,
+            max: 1,
+            reachable_fraction: 2.0 / 6.0,
+        },
+    }
+}
+
+/// A directed path 0 -> 1 -> 2, with a fitness on each node.
+fn graph() -> AttributedGraph {
+    let nodes = (0..3)
+        .map(|node| {
+            let mut attributes = Attributes::new();
+            attributes.insert("fitness".to_string(), (node as f64 / 2.0).into());
+            (node, attributes)
+        })
+        .collect();
+
+    AttributedGraph {
+        directed: true,
+        nodes,
+        edges: vec![(0, 1, Attributes::new()), (1, 2, Attributes::new())],
+    }
+}
+
+#[test]
+fn sweeps_can_be_queried_by_parameter() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("math435-results.db");
+    let db = ResultsDb::open(&path).unwrap();
+
+    for (run_index, &temperature) in [0.5, 1.0, 1.0].iter().enumerate() {
+        let parameters = json!({ "initial_temperature": temperature });
+        let run_id = db.insert_run("bose-einstein", &parameters, run_index as u64, Some(7)).unwrap();
+        db.insert_graph(run_id, &graph()).unwrap();
+        db.insert_metrics(run_id, None, &summary(run_index + 1)).unwrap();
+    }
+
+    let rows = db
+        .query(
+            "SELECT json_extract(runs.parameters, '$.initial_temperature') AS temperature, MAX(metrics.max_degree) \
+             FROM runs JOIN metrics ON metrics.run_id = runs.id GROUP BY temperature ORDER BY temperature",
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            vec![Value::Real(0.5), Value::Integer(1)],
+            vec![Value::Real(1.0), Value::Integer(3)],
+        ]
+    );
+
+    let rows = db
+        .query(
+            "SELECT node, in_degree, out_degree, json_extract(attributes, '$.fitness') FROM nodes \
+             WHERE run_id = ?1 ORDER BY node",
+            &[Value::Integer(1)],
+        )
+        .unwrap();
+    assert_eq!(rows[1], vec![Value::Integer(1), Value::Integer(1), Value::Integer(1), Value::Real(0.5)]);
+    assert_eq!(rows[2][2], Value::Integer(0));
+}
+
+#[test]
+fn rerun_replaces_the_earlier_run() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("math435-results-rerun.db");
+    let db = ResultsDb::open(&path).unwrap();
+    let parameters = json!({ "num_steps": 10 });
+
+    for _ in 0..2 {
+        let run_id = db.insert_run("connectome", &parameters, 0, None).unwrap();
+        db.insert_graph(run_id, &graph()).unwrap();
+        db.insert_metrics(run_id, Some(10), &summary(2)).unwrap();
+    }
+
+    for table in &["runs", "nodes", "edges", "metrics"] {
+        let count = db.query(&format!("SELECT COUNT(*) FROM {}", table), &[]).unwrap();
+        let expected = match *table {
+            "nodes" => 3,
+            "edges" => 2,
+            _ => 1,
+        };
+        assert_eq!(count, vec![vec![Value::Integer(expected)]], "{}", table);
+    }
+}
+
+#[test]
+fn results_of_a_run_are_added_together() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("math435-results-together.db");
+    let db = ResultsDb::open(&path).unwrap();
+    let parameters = json!({ "num_steps": 20 });
+
+    // A run left half-written by an interrupted sweep, then run again.
+    let run_id = db.insert_run("connectome", &parameters, 0, None).unwrap();
+    db.insert_metrics(run_id, Some(10), &summary(2)).unwrap();
+    let metrics = [(Some(10), summary(2)), (Some(20), summary(3))];
+    db.insert_results("connectome", &parameters, 0, None, &graph(), &metrics).unwrap();
+
+    let rows = db
+        .query(
+            "SELECT runs.run_index, COUNT(*) FROM runs JOIN metrics ON metrics.run_id = runs.id GROUP BY runs.id",
+            &[],
+        )
+        .unwrap();
+    assert_eq!(rows, vec![vec![Value::Integer(0), Value::Integer(2)]]);
+    let rows = db.query("SELECT COUNT(*) FROM nodes", &[]).unwrap();
+    assert_eq!(rows, vec![vec![Value::Integer(3)]]);
+}
@@ -0,0 +1,75 @@
+//! Benchmarks of building flag complexes edge by edge with `add`, and of the
+//! GF(2) rank computation on random boundary-like matrices.
+
+use topology::simplex::{rank, SimplicialComplex};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use nalgebra::DMatrix;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Returns the edges of a random directed graph, each present with
+/// probability `density` and oriented at random, in random order.
+fn random_edges(num_vertices: usize, density: f64, seed: u64) -> Vec<Vec<usize>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut edges = Vec::new();
+
+    for a in 0..num_vertices {
+        for b in a + 1..num_vertices {
+            if rng.gen_bool(density) {
+                edges.push(if rng.gen_bool(0.5) { vec![a, b] } else { vec![b, a] });
+            }
+        }
+    }
+
+    for i in (1..edges.len()).rev() {
+        edges.swap(i, rng.gen_range(0, i + 1));
+    }
+
+    edges
+}
+
+fn bench_add(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add");
+    group.sample_size(10);
+
+    for &num_vertices in &[20, 40, 80] {
+        let edges = random_edges(num_vertices, 0.2, 0);
+
+        group.bench_with_input(BenchmarkId::from_parameter(num_vertices), &edges, |b, edges| {
+            b.iter_batched(
+                || SimplicialComplex::new((0..num_vertices).collect(), None),
+                |mut complex| {
+                    for edge in edges {
+                        complex.add(edge.clone()).unwrap();
+                    }
+                    complex
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_rank(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rank");
+    group.sample_size(10);
+
+    for &size in &[64, 256, 1024] {
+        for &density in &[0.01, 0.1] {
+            let mut rng = StdRng::seed_from_u64(size as u64);
+            let matrix = DMatrix::<u64>::from_fn(size, size, |_, _| rng.gen_bool(density) as u64);
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("density {}", density), size),
+                &matrix,
+                |b, matrix| b.iter(|| rank(matrix)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_add, bench_rank);
+criterion_main!(benches);
@@ -0,0 +1,67 @@
+//! Feeds sequences of edge insertions and removals, decoded from the fuzzer's
+//! bytes, into a `SimplicialComplex`, checking after each one that nothing
+//! panicked and that the complex still passes `validate()`.
+//!
+//! Run from `topology` with `cargo fuzz run edge_ops`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use topology::simplex::SimplicialComplex;
+
+/// Few enough vertices that edges collide often and cliques form quickly.
+const NUM_VERTICES: usize = 8;
+
+fuzz_target!(|data: &[u8]| {
+    let (&first, mut bytes) = match data.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+
+    // The first byte caps the dimension of the complex, or leaves it uncapped.
+    let max_dim = match first % 5 {
+        0 => None,
+        dim => Some(dim as usize),
+    };
+    let mut complex = SimplicialComplex::new((0..NUM_VERTICES).collect(), max_dim);
+
+    while let Some((&op, rest)) = bytes.split_first() {
+        bytes = rest;
+
+        // Errors, e.g. from removing a missing edge, are expected; only
+        // panics and broken invariants are bugs.
+        let _ = match op % 5 {
+            0 => take_edges(&mut bytes, 1).pop().map(|edge| complex.add(edge)),
+            1 => take_edges(&mut bytes, 1).pop().map(|edge| complex.remove(edge)),
+            2 => Some(complex.add_batch(&take_edges(&mut bytes, 1 + op as usize / 5 % 4))),
+            3 => Some(complex.remove_batch(&take_edges(&mut bytes, 1 + op as usize / 5 % 4))),
+            _ => Some(complex.remove_vertex(op as usize / 5 % NUM_VERTICES)),
+        };
+
+        let report = complex.validate();
+        assert!(report.is_valid(), "{}", report);
+
+        let betti_numbers = complex.betti_numbers_full();
+        assert!(betti_numbers.iter().all(|&betti| betti >= 0), "{:?}", betti_numbers);
+
+        let alternating_sum = betti_numbers
+            .iter()
+            .enumerate()
+            .map(|(dim, &betti)| if dim % 2 == 0 { betti } else { -betti })
+            .sum::<i64>();
+        assert_eq!(alternating_sum, complex.euler_characteristic());
+    }
+});
+
+/// Decodes up to `count` edges from pairs of bytes. Self-loops are kept, as
+/// the complex must reject them rather than panic.
+fn take_edges(bytes: &mut &[u8], count: usize) -> Vec<Vec<usize>> {
+    let mut edges = Vec::with_capacity(count);
+
+    while edges.len() < count && bytes.len() >= 2 {
+        edges.push(vec![bytes[0] as usize % NUM_VERTICES, bytes[1] as usize % NUM_VERTICES]);
+        *bytes = &bytes[2..];
+    }
+
+    edges
+}
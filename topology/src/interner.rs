@@ -0,0 +1,90 @@
+//! Interning of simplices as compact integer ids.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::simplex::Simplex;
+
+/// A compact id standing for an interned simplex.
+pub type SimplexId = u32;
+
+/// Maps each simplex to a dense id, and back. Each simplex's vertices are
+/// stored once, and shared between both directions of the lookup. The ids of
+/// released simplices are handed out again, so ids stay dense as a complex
+/// changes.
+#[derive(Default)]
+pub struct SimplexInterner {
+    ids: HashMap<Arc<Simplex>, SimplexId>,
+    simplices: Vec<Option<Arc<Simplex>>>,
+    free: Vec<SimplexId>,
+}
+
+impl SimplexInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of interned simplices.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns the id of a simplex, if it's interned.
+    pub fn get(&self, simplex: &Simplex) -> Option<SimplexId> {
+        self.ids.get(simplex).cloned()
+    }
+
+    /// Returns the id of a simplex, interning it first if needed. A simplex
+    /// which is already interned keeps its original orientation.
+    pub fn intern(&mut self, simplex: Simplex) -> SimplexId {
+        if let Some(id) = self.get(&simplex) {
+            return id;
+        }
+
+        let simplex = Arc::new(simplex);
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.simplices[id as usize] = Some(simplex.clone());
+                id
+            }
+            None => {
+                self.simplices.push(Some(simplex.clone()));
+                (self.simplices.len() - 1) as SimplexId
+            }
+        };
+
+        self.ids.insert(simplex, id);
+        id
+    }
+
+    /// Returns the simplex with the given id.
+    ///
+    /// Panics if the id isn't in use.
+    pub fn resolve(&self, id: SimplexId) -> &Simplex {
+        self.simplices[id as usize]
+            .as_ref()
+            .expect("simplex id is not in use")
+    }
+
+    /// Replaces an interned simplex with the same one built from another
+    /// order of its vertices, keeping its id.
+    pub fn reorient(&mut self, id: SimplexId, simplex: Simplex) {
+        let simplex = Arc::new(simplex);
+        self.ids.remove(&simplex);
+        self.ids.insert(simplex.clone(), id);
+        self.simplices[id as usize] = Some(simplex);
+    }
+
+    /// Forgets a simplex, freeing its id to be reused, and returns it.
+    pub fn release(&mut self, id: SimplexId) -> Option<Simplex> {
+        let simplex = self.simplices.get_mut(id as usize)?.take()?;
+        self.ids.remove(&simplex);
+        self.free.push(id);
+
+        Some(Arc::try_unwrap(simplex).unwrap_or_else(|simplex| (*simplex).clone()))
+    }
+}
@@ -0,0 +1,98 @@
+//! Combinatorial Hodge Laplacians of a simplicial complex, over the reals.
+//!
+//! The `k`th Hodge Laplacian is L_k = ∂_kᵀ∂_k + ∂_(k+1)∂_(k+1)ᵀ, acting on
+//! `k`-chains. Its kernel is isomorphic to the `k`th real homology group, so
+//! the multiplicity of its zero eigenvalue is β_k, its kernel holds the
+//! harmonic representatives of the homology classes, and its smallest nonzero
+//! eigenvalue (the spectral gap) measures how close the complex is to gaining
+//! another hole.
+
+use nalgebra::{DMatrix, DVector, SymmetricEigen};
+
+use crate::simplex::SimplicialComplex;
+
+/// Eigenvalues below this are taken to be zero.
+const ZERO_EIGENVALUE: f64 = 1e-8;
+
+impl SimplicialComplex {
+    /// Returns the signed boundary matrix ∂_dim over the reals, taking
+    /// `dim`-chains to `(dim - 1)`-chains: the integer boundary matrix of the
+    /// complex without its dummy row and column, so that rows and columns are
+    /// ordered by simplex index.
+    pub fn real_boundary_matrix(&self, dim: usize) -> DMatrix<f64> {
+        let num_faces = if dim > 0 { self.num_simplices(dim - 1) } else { 0 };
+        let num_simplices = self.num_simplices(dim);
+
+        if dim == 0 || num_simplices == 0 {
+            return DMatrix::zeros(num_faces, num_simplices);
+        }
+
+        self.boundary_matrices[dim - 1]
+            .slice((1, 1), (num_faces, num_simplices))
+            .map(|e| e as f64)
+    }
+
+    /// Returns the `dim`th Hodge Laplacian, ordered by simplex index.
+    pub fn hodge_laplacian(&self, dim: usize) -> DMatrix<f64> {
+        let lower = self.real_boundary_matrix(dim);
+        let upper = self.real_boundary_matrix(dim + 1);
+
+        lower.transpose() * lower + &upper * upper.transpose()
+    }
+
+    /// Returns the `count` smallest eigenvalues of the `dim`th Hodge
+    /// Laplacian, in ascending order. Eigenvalues within rounding of zero are
+    /// reported as exactly zero, so that there are β_dim of them.
+    pub fn laplacian_spectrum(&self, dim: usize, count: usize) -> Vec<f64> {
+        self.laplacian_eigenpairs(dim)
+            .into_iter()
+            .take(count)
+            .map(|(eigenvalue, _)| eigenvalue)
+            .collect()
+    }
+
+    /// Returns the smallest nonzero eigenvalue of the `dim`th Hodge Laplacian,
+    /// or `None` if it has none.
+    pub fn spectral_gap(&self, dim: usize) -> Option<f64> {
+        self.laplacian_eigenpairs(dim)
+            .into_iter()
+            .map(|(eigenvalue, _)| eigenvalue)
+            .find(|&eigenvalue| eigenvalue > 0.0)
+    }
+
+    /// Returns an orthonormal basis of the harmonic `dim`-chains, the kernel of
+    /// the `dim`th Hodge Laplacian, with one entry per `dim`-simplex in index
+    /// order. Each harmonic chain is both a cycle and a cocycle, and they
+    /// represent the real homology classes.
+    pub fn harmonic_representatives(&self, dim: usize) -> Vec<DVector<f64>> {
+        self.laplacian_eigenpairs(dim)
+            .into_iter()
+            .take_while(|&(eigenvalue, _)| eigenvalue == 0.0)
+            .map(|(_, eigenvector)| eigenvector)
+            .collect()
+    }
+
+    /// Returns the eigenvalues and eigenvectors of the `dim`th Hodge
+    /// Laplacian, in ascending order of eigenvalue.
+    fn laplacian_eigenpairs(&self, dim: usize) -> Vec<(f64, DVector<f64>)> {
+        let laplacian = self.hodge_laplacian(dim);
+
+        if laplacian.is_empty() {
+            return Vec::new();
+        }
+
+        let eigen = SymmetricEigen::new(laplacian);
+        let mut eigenpairs = eigen
+            .eigenvalues
+            .iter()
+            .zip(eigen.eigenvectors.column_iter())
+            .map(|(&eigenvalue, eigenvector)| {
+                let eigenvalue = if eigenvalue.abs() < ZERO_EIGENVALUE { 0.0 } else { eigenvalue };
+                (eigenvalue, eigenvector.into_owned())
+            })
+            .collect::<Vec<_>>();
+
+        eigenpairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        eigenpairs
+    }
+}
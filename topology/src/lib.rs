@@ -0,0 +1,18 @@
+//! Simplicial complexes built from graphs, and their homology.
+//!
+//! A [`SimplicialComplex`](simplex::SimplicialComplex) can be built all at once
+//! (as a Vietoris–Rips, clique, or directed flag complex, or from a file), or
+//! kept up to date edge by edge as a graph changes. Betti numbers are computed
+//! from its boundary matrices over GF(2) or any other prime field, and the
+//! persistence diagram of a changing complex is kept up to date by
+//! [`UpdatingPersistence`](persistence::UpdatingPersistence), and summarized as
+//! fixed-size vectors by [`summaries`].
+
+pub mod interner;
+pub mod laplacian;
+pub mod null_models;
+mod par;
+pub mod persistence;
+pub mod recorder;
+pub mod simplex;
+pub mod summaries;
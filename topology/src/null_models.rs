@@ -0,0 +1,151 @@
+//! Random simplicial complexes to compare measured topology against.
+
+use std::collections::HashSet;
+
+use petgraph::graph::UnGraph;
+use rand::Rng;
+
+use crate::simplex::SimplicialComplex;
+
+/// Builds a Linial–Meshulam random 2-complex: the complete graph on
+/// `num_vertices` vertices, with each of its triangles filled in independently
+/// with probability `p`.
+pub fn linial_meshulam<R: Rng>(num_vertices: usize, p: f64, rng: &mut R) -> SimplicialComplex {
+    let mut complex = SimplicialComplex::new((0..num_vertices).collect(), Some(2));
+
+    for a in 0..num_vertices {
+        for b in a + 1..num_vertices {
+            complex.add_with_closure(vec![a, b]).unwrap();
+        }
+    }
+
+    for a in 0..num_vertices {
+        for b in a + 1..num_vertices {
+            for c in b + 1..num_vertices {
+                if rng.gen_bool(p) {
+                    complex.add_with_closure(vec![a, b, c]).unwrap();
+                }
+            }
+        }
+    }
+
+    complex
+}
+
+/// Builds the clique complex of an Erdős–Rényi random graph G(n, p), truncated
+/// at `max_dim`.
+pub fn erdos_renyi_flag<R: Rng>(
+    num_vertices: usize,
+    p: f64,
+    max_dim: usize,
+    rng: &mut R,
+) -> SimplicialComplex {
+    let mut graph = UnGraph::<(), ()>::new_undirected();
+    let nodes = (0..num_vertices)
+        .map(|_| graph.add_node(()))
+        .collect::<Vec<_>>();
+
+    for a in 0..num_vertices {
+        for b in a + 1..num_vertices {
+            if rng.gen_bool(p) {
+                graph.add_edge(nodes[a], nodes[b], ());
+            }
+        }
+    }
+
+    SimplicialComplex::clique_complex(&graph, max_dim)
+}
+
+/// Builds an Erdős–Rényi flag complex with as many vertices as `complex`, and
+/// the same expected number of edges.
+pub fn matched_erdos_renyi_flag<R: Rng>(
+    complex: &SimplicialComplex,
+    max_dim: usize,
+    rng: &mut R,
+) -> SimplicialComplex {
+    let num_vertices = complex.num_simplices(0);
+
+    erdos_renyi_flag(num_vertices, density(complex, 1), max_dim, rng)
+}
+
+/// Builds a Linial–Meshulam complex with as many vertices as `complex`, and
+/// the same expected number of triangles.
+pub fn matched_linial_meshulam<R: Rng>(
+    complex: &SimplicialComplex,
+    rng: &mut R,
+) -> SimplicialComplex {
+    let num_vertices = complex.num_simplices(0);
+
+    linial_meshulam(num_vertices, density(complex, 2), rng)
+}
+
+/// The number of swaps per edge [`degree_preserving_flag`] tries, enough for
+/// the edges to be well mixed.
+pub const SWAPS_PER_EDGE: usize = 10;
+
+/// Randomizes a directed graph, given by its edges, keeping the in-degree and
+/// out-degree of every vertex: `num_swaps` times, picks two edges `a -> b` and
+/// `c -> d` and swaps their targets to give `a -> d` and `c -> b`, unless that
+/// would make a self-loop or join two vertices already joined either way.
+pub fn rewire<R: Rng>(edges: &[(usize, usize)], num_swaps: usize, rng: &mut R) -> Vec<(usize, usize)> {
+    let mut edges = edges.to_vec();
+    if edges.len() < 2 {
+        return edges;
+    }
+    let mut joined = edges.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect::<HashSet<_>>();
+
+    for _ in 0..num_swaps {
+        let (i, j) = (rng.gen_range(0, edges.len()), rng.gen_range(0, edges.len()));
+        let ((a, b), (c, d)) = (edges[i], edges[j]);
+        if a == d || c == b || joined.contains(&(a.min(d), a.max(d))) || joined.contains(&(c.min(b), c.max(b))) {
+            continue;
+        }
+
+        joined.remove(&(a.min(b), a.max(b)));
+        joined.remove(&(c.min(d), c.max(d)));
+        joined.insert((a.min(d), a.max(d)));
+        joined.insert((c.min(b), c.max(b)));
+        edges[i] = (a, d);
+        edges[j] = (c, b);
+    }
+
+    edges
+}
+
+/// Builds the directed flag complex of a degree-preserving randomization of a
+/// directed graph with the given vertices and edges, as with [`rewire`], with
+/// [`SWAPS_PER_EDGE`] swaps per edge. No two of the edges may join the same
+/// vertices.
+pub fn degree_preserving_flag<R: Rng>(
+    vertices: Vec<usize>,
+    edges: &[(usize, usize)],
+    max_dim: Option<usize>,
+    rng: &mut R,
+) -> SimplicialComplex {
+    let edges = rewire(edges, SWAPS_PER_EDGE * edges.len(), rng);
+
+    let mut complex = SimplicialComplex::new(vertices, max_dim);
+    let edges = edges.into_iter().map(|(a, b)| vec![a, b]).collect::<Vec<_>>();
+    complex.add_batch(&edges).unwrap();
+    complex
+}
+
+/// Returns the fraction of the possible `dim`-simplices on the complex's
+/// vertices which are present.
+pub fn density(complex: &SimplicialComplex, dim: usize) -> f64 {
+    let possible = binomial(complex.num_simplices(0), dim + 1);
+
+    if possible == 0.0 {
+        0.0
+    } else {
+        complex.num_simplices(dim) as f64 / possible
+    }
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
@@ -0,0 +1,31 @@
+//! The parallel iterators used to compute ranks, which run serially when the
+//! `parallel` feature is off, e.g. for WebAssembly.
+
+#[cfg(feature = "parallel")]
+pub(crate) use rayon::prelude::*;
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) use self::serial::*;
+
+#[cfg(not(feature = "parallel"))]
+mod serial {
+    use std::slice::ChunksMut;
+
+    pub(crate) trait IntoParallelIterator: IntoIterator + Sized {
+        fn into_par_iter(self) -> Self::IntoIter {
+            self.into_iter()
+        }
+    }
+
+    impl<I: IntoIterator> IntoParallelIterator for I {}
+
+    pub(crate) trait ParallelSliceMut<T> {
+        fn par_chunks_mut(&mut self, chunk_size: usize) -> ChunksMut<'_, T>;
+    }
+
+    impl<T> ParallelSliceMut<T> for [T] {
+        fn par_chunks_mut(&mut self, chunk_size: usize) -> ChunksMut<'_, T> {
+            self.chunks_mut(chunk_size)
+        }
+    }
+}
@@ -0,0 +1,497 @@
+//! Persistent homology of a filtration that changes over time, kept up to date
+//! as simplices are inserted and removed, after Cohen-Steiner, Edelsbrunner and
+//! Morozov's vineyards.
+//!
+//! The filtration orders simplices by the time they were inserted at. Its
+//! boundary matrix D is kept reduced as R = DV over GF(2), with V upper
+//! triangular, so that the pairing can be read off of the lowest entries of
+//! the columns of R. Each update only touches the columns it affects:
+//!
+//! - an insertion appends a column and reduces it, then moves it back past any
+//!   later simplices by transpositions of adjacent simplices;
+//! - a removal first clears the simplex out of every column of V, after which
+//!   its row and column can be dropped.
+//!
+//! Columns and rows are identified by stable ids rather than by position, so
+//! that neither transpositions nor removals renumber anything.
+//!
+//! A static complex can also be filtered by weights on its edges, such as
+//! myelination or distance, with [`weight_persistence`].
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::simplex::{ComplexError, Simplex, SimplicialComplex};
+
+/// A point of a persistence diagram: a homology class of dimension `dim` born
+/// at `birth`, and killed at `death`, or never if `None`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PersistencePair {
+    pub dim: usize,
+    pub birth: f64,
+    pub death: Option<f64>,
+}
+
+/// The order in which edge weights enter a weight filtration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeightOrder {
+    /// Small weights come first, as for distances: a simplex enters at the
+    /// largest weight among its edges.
+    Ascending,
+    /// Large weights come first, as for myelination: a simplex enters at the
+    /// smallest weight among its edges.
+    Descending,
+}
+
+impl WeightOrder {
+    /// Maps a weight to a time which increases through the filtration, and
+    /// back again.
+    fn time(self, weight: f64) -> f64 {
+        match self {
+            WeightOrder::Ascending => weight,
+            WeightOrder::Descending => -weight,
+        }
+    }
+}
+
+struct Entry {
+    simplex: Simplex,
+    time: f64,
+    /// The simplex's position in the filtration; only the order matters.
+    key: u64,
+    /// This simplex's column of R, as the ids of its nonzero rows.
+    reduced: BTreeSet<usize>,
+    /// This simplex's column of V, as the ids of its nonzero rows.
+    operations: BTreeSet<usize>,
+    /// The row of the lowest entry of `reduced`, once it has been reduced.
+    low: Option<usize>,
+    cofacets: HashSet<usize>,
+}
+
+/// The persistence pairing of a dynamic complex, filtered by the time each of
+/// its simplices was inserted at.
+#[derive(Default)]
+pub struct UpdatingPersistence {
+    entries: HashMap<usize, Entry>,
+    ids: HashMap<Simplex, usize>,
+    order: BTreeMap<u64, usize>,
+    /// The column whose lowest entry is in each row, i.e. the pairing.
+    owners: HashMap<usize, usize>,
+    next_id: usize,
+    next_key: u64,
+}
+
+impl UpdatingPersistence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of simplices in the filtration.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the time a simplex was inserted at, if it's in the filtration.
+    pub fn time(&self, simplex: &[usize]) -> Option<f64> {
+        self.ids
+            .get(&Simplex::from(simplex))
+            .map(|id| self.entries[id].time)
+    }
+
+    /// Iterates over the simplices along with their times, in no particular
+    /// order.
+    pub fn simplices(&self) -> impl Iterator<Item = (&Simplex, f64)> {
+        self.entries.values().map(|entry| (&entry.simplex, entry.time))
+    }
+
+    /// Inserts a simplex at the given time, and returns whether it wasn't
+    /// already present. All of its faces must be present already, and since a
+    /// simplex can't be born before its faces, its time is raised to the
+    /// latest of theirs.
+    ///
+    /// Panics if `time` is NaN.
+    pub fn insert(&mut self, simplex: &[usize], time: f64) -> Result<bool, ComplexError> {
+        assert!(!time.is_nan(), "filtration times can't be NaN");
+
+        let simplex = Simplex::from(simplex);
+
+        if simplex.is_empty() {
+            return Err(ComplexError::EmptySimplex);
+        }
+
+        if simplex.has_repeated_vertex() {
+            return Err(ComplexError::RepeatedVertex(simplex));
+        }
+
+        if self.ids.contains_key(&simplex) {
+            return Ok(false);
+        }
+
+        let mut time = time;
+        let mut faces = BTreeSet::new();
+
+        if simplex.len() > 1 {
+            for face in simplex.faces() {
+                let &face_id = self
+                    .ids
+                    .get(&face)
+                    .ok_or(ComplexError::SimplexNotFound(face))?;
+
+                time = time.max(self.entries[&face_id].time);
+                faces.insert(face_id);
+            }
+        }
+
+        let id = self.next_id;
+        let key = self.next_key;
+        self.next_id += 1;
+        self.next_key += 1;
+
+        for face_id in &faces {
+            self.entries.get_mut(face_id).unwrap().cofacets.insert(id);
+        }
+
+        self.entries.insert(
+            id,
+            Entry {
+                simplex: simplex.clone(),
+                time,
+                key,
+                reduced: faces,
+                operations: std::iter::once(id).collect(),
+                low: None,
+                cofacets: HashSet::new(),
+            },
+        );
+        self.ids.insert(simplex, id);
+        self.order.insert(key, id);
+        self.reduce(vec![id]);
+
+        // None of the simplices after the new one can be its faces, since
+        // their times are later.
+        while let Some(previous) = self.previous(id) {
+            if self.entries[&previous].time <= time {
+                break;
+            }
+
+            self.transpose(previous, id);
+        }
+
+        Ok(true)
+    }
+
+    /// Removes a simplex along with all of its cofaces.
+    pub fn remove(&mut self, simplex: &[usize]) -> Result<(), ComplexError> {
+        let simplex = Simplex::from(simplex);
+
+        if simplex.is_empty() {
+            return Err(ComplexError::EmptySimplex);
+        }
+
+        let &id = self
+            .ids
+            .get(&simplex)
+            .ok_or(ComplexError::SimplexNotFound(simplex))?;
+
+        self.remove_id(id);
+        Ok(())
+    }
+
+    /// Brings the filtration in line with a complex: simplices which are no
+    /// longer in the complex are removed, and new ones are inserted at `time`.
+    pub fn update(&mut self, complex: &SimplicialComplex, time: f64) -> Result<(), ComplexError> {
+        let removed = self
+            .ids
+            .keys()
+            .filter(|simplex| !complex.contains(simplex))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for simplex in removed {
+            // Removing a face takes its cofaces with it.
+            if self.ids.contains_key(&simplex) {
+                self.remove(&simplex)?;
+            }
+        }
+
+        for dim in 0..=complex.dim() {
+            for simplex in complex.simplices_of_dim(dim) {
+                self.insert(simplex, time)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the persistence diagram, sorted by dimension and then by birth.
+    /// Pairs which are born and killed at the same time are left out.
+    pub fn diagram(&self) -> Vec<PersistencePair> {
+        let mut diagram = Vec::new();
+
+        for (id, entry) in &self.entries {
+            match entry.low {
+                Some(low) => {
+                    let birth = &self.entries[&low];
+
+                    if entry.time > birth.time {
+                        diagram.push(PersistencePair {
+                            dim: birth.simplex.dim(),
+                            birth: birth.time,
+                            death: Some(entry.time),
+                        });
+                    }
+                }
+                None if !self.owners.contains_key(id) => diagram.push(PersistencePair {
+                    dim: entry.simplex.dim(),
+                    birth: entry.time,
+                    death: None,
+                }),
+                None => {}
+            }
+        }
+
+        diagram.sort_by(|a, b| {
+            (a.dim, a.birth, a.death.unwrap_or(f64::INFINITY))
+                .partial_cmp(&(b.dim, b.birth, b.death.unwrap_or(f64::INFINITY)))
+                .unwrap()
+        });
+        diagram
+    }
+
+    /// Returns the betti numbers of the current complex, β₀ through the
+    /// dimension of its largest simplex, as the number of classes which
+    /// haven't been killed.
+    pub fn betti_numbers(&self) -> Vec<i64> {
+        let dim = self.entries.values().map(|entry| entry.simplex.dim()).max();
+        let mut betti_numbers = vec![0; dim.map_or(0, |dim| dim + 1)];
+
+        for pair in self.diagram() {
+            if pair.death.is_none() {
+                betti_numbers[pair.dim] += 1;
+            }
+        }
+
+        betti_numbers
+    }
+
+    fn remove_id(&mut self, id: usize) {
+        let cofacets = self.entries[&id].cofacets.iter().cloned().collect::<Vec<_>>();
+
+        for cofacet in cofacets {
+            self.remove_id(cofacet);
+        }
+
+        // With its cofaces gone, the simplex's row of D, and so of R, is zero.
+        // Once no other column of V uses its column, both can be dropped.
+        let affected = self
+            .entries
+            .iter()
+            .filter(|&(&j, entry)| j != id && entry.operations.contains(&id))
+            .map(|(&j, _)| j)
+            .collect::<Vec<_>>();
+
+        for &j in &affected {
+            self.add_column(id, j);
+        }
+
+        self.release(id);
+        let entry = self.entries.remove(&id).unwrap();
+
+        if entry.simplex.len() > 1 {
+            for face in entry.simplex.faces() {
+                let face_id = self.ids[&face];
+                self.entries.get_mut(&face_id).unwrap().cofacets.remove(&id);
+            }
+        }
+
+        self.order.remove(&entry.key);
+        self.ids.remove(&entry.simplex);
+        self.reduce(affected);
+    }
+
+    /// Returns the simplex immediately before the given one in the filtration.
+    fn previous(&self, id: usize) -> Option<usize> {
+        self.order
+            .range(..self.entries[&id].key)
+            .next_back()
+            .map(|(_, &previous)| previous)
+    }
+
+    /// Swaps two adjacent simplices in the filtration, where `a` comes first
+    /// and isn't a face of `b`.
+    fn transpose(&mut self, a: usize, b: usize) {
+        // Clear V's entry above the diagonal, which would end up below it.
+        if self.entries[&b].operations.contains(&a) {
+            self.add_column(a, b);
+        }
+
+        let key_a = self.entries[&a].key;
+        let key_b = self.entries[&b].key;
+        self.entries.get_mut(&a).unwrap().key = key_b;
+        self.entries.get_mut(&b).unwrap().key = key_a;
+        self.order.insert(key_a, b);
+        self.order.insert(key_b, a);
+
+        // Only the columns with their lowest entry in one of the two swapped
+        // rows can have a different lowest entry now.
+        let mut worklist = vec![a, b];
+        worklist.extend(self.owners.get(&a));
+        worklist.extend(self.owners.get(&b));
+        worklist.sort_unstable();
+        worklist.dedup();
+
+        self.reduce(worklist);
+    }
+
+    /// Adds column `source` of R and V to column `target`, where `source`
+    /// comes first in the filtration.
+    fn add_column(&mut self, source: usize, target: usize) {
+        let (reduced, operations) = {
+            let source = &self.entries[&source];
+            (source.reduced.clone(), source.operations.clone())
+        };
+
+        let target = self.entries.get_mut(&target).unwrap();
+        target.reduced = &target.reduced ^ &reduced;
+        target.operations = &target.operations ^ &operations;
+    }
+
+    /// Unpairs a column from the row of its lowest entry.
+    fn release(&mut self, id: usize) {
+        if let Some(low) = self.entries.get_mut(&id).unwrap().low.take() {
+            if self.owners.get(&low) == Some(&id) {
+                self.owners.remove(&low);
+            }
+        }
+    }
+
+    /// Returns the row of the lowest entry of a column of R, i.e. the entry
+    /// whose simplex comes last in the filtration.
+    fn lowest(&self, id: usize) -> Option<usize> {
+        self.entries[&id]
+            .reduced
+            .iter()
+            .max_by_key(|row| self.entries[row].key)
+            .cloned()
+    }
+
+    /// Reduces the given columns of R until no two columns share their lowest
+    /// entry, only ever adding a column to a later one.
+    fn reduce(&mut self, mut worklist: Vec<usize>) {
+        for &id in &worklist {
+            self.release(id);
+        }
+
+        while let Some(j) = worklist.pop() {
+            while let Some(low) = self.lowest(j) {
+                let k = match self.owners.get(&low) {
+                    Some(&k) => k,
+                    None => {
+                        self.owners.insert(low, j);
+                        self.entries.get_mut(&j).unwrap().low = Some(low);
+                        break;
+                    }
+                };
+
+                if self.entries[&k].key < self.entries[&j].key {
+                    self.add_column(k, j);
+                } else {
+                    // The later column gives up the row and is reduced again.
+                    self.add_column(j, k);
+                    self.entries.get_mut(&k).unwrap().low = None;
+                    self.owners.insert(low, j);
+                    self.entries.get_mut(&j).unwrap().low = Some(low);
+                    worklist.push(k);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Returns the simplices of a complex along with the weights they enter a
+/// filtration at, given the weight of each edge, in the order they enter.
+/// Each simplex enters with the last of its edges, and the vertices are all
+/// there from the start, at the first weight of any edge (or zero if there
+/// are none).
+///
+/// `edge_weight` is called once per edge, with its vertices in ascending
+/// order.
+///
+/// Panics if an edge's weight is NaN.
+pub fn weight_filtration<F>(
+    complex: &SimplicialComplex,
+    order: WeightOrder,
+    mut edge_weight: F,
+) -> Vec<(Simplex, f64)>
+where
+    F: FnMut(usize, usize) -> f64,
+{
+    let edge_times = complex
+        .simplices_of_dim(1)
+        .map(|edge| (Simplex::from(&edge[..]), order.time(edge_weight(edge[0], edge[1]))))
+        .collect::<HashMap<_, _>>();
+    let start = edge_times.values().cloned().fold(None, |start: Option<f64>, time| {
+        Some(start.map_or(time, |start| start.min(time)))
+    });
+    let start = start.unwrap_or(0.0);
+
+    let mut filtration = Vec::new();
+    for dim in 0..=complex.dim() {
+        for simplex in complex.simplices_of_dim(dim) {
+            let mut time = start;
+
+            for (i, &a) in simplex.iter().enumerate() {
+                for &b in &simplex[i + 1..] {
+                    time = time.max(edge_times[&Simplex::from(&[a, b][..])]);
+                }
+            }
+
+            filtration.push((simplex.clone(), time));
+        }
+    }
+
+    // Sorting stably keeps the faces of each simplex before it.
+    filtration.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+    filtration
+        .into_iter()
+        .map(|(simplex, time)| (simplex, order.time(time)))
+        .collect()
+}
+
+/// Returns the persistence diagram of a complex filtered by the weights of its
+/// edges, as in [`weight_filtration`]. Births and deaths are given as
+/// weights, so with [`WeightOrder::Descending`] each class dies at a smaller
+/// weight than it's born at, and the diagram is sorted by dimension and then
+/// by birth in filtration order.
+///
+/// Panics if an edge's weight is NaN.
+pub fn weight_persistence<F>(
+    complex: &SimplicialComplex,
+    order: WeightOrder,
+    edge_weight: F,
+) -> Vec<PersistencePair>
+where
+    F: FnMut(usize, usize) -> f64,
+{
+    let mut persistence = UpdatingPersistence::new();
+
+    for (simplex, weight) in weight_filtration(complex, order, edge_weight) {
+        persistence
+            .insert(&simplex, order.time(weight))
+            .expect("faces enter a filtration before their cofaces");
+    }
+
+    persistence
+        .diagram()
+        .into_iter()
+        .map(|pair| PersistencePair {
+            birth: order.time(pair.birth),
+            death: pair.death.map(|death| order.time(death)),
+            ..pair
+        })
+        .collect()
+}
@@ -0,0 +1,205 @@
+//! Recording the topology of a changing complex over time.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::simplex::{BudgetedBetti, EventKind, SimplicialComplex};
+
+/// Appends a row of betti numbers and simplex counts to a CSV file each time
+/// it's called, giving betti curves over the course of a simulation.
+///
+/// The columns are the timestep, β₀ through β_(n-1), and the number of
+/// simplices of dimension 0 through n - 1, for the `n` dimensions given when
+/// the recorder is created. Higher dimensions are left out, and dimensions
+/// without any simplices are recorded as zero. With a budget, betti numbers
+/// which aren't computed within it are recorded as estimates, as in
+/// [`SimplicialComplex::betti_numbers_within`].
+pub struct BettiRecorder<W: Write> {
+    writer: W,
+    num_dims: usize,
+    budget: Option<Duration>,
+}
+
+impl BettiRecorder<BufWriter<File>> {
+    /// Creates (or truncates) a CSV file to record to.
+    pub fn create<P: AsRef<Path>>(path: P, num_dims: usize) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), num_dims)
+    }
+}
+
+impl<W: Write> BettiRecorder<W> {
+    /// Creates a recorder writing to `writer`, and writes the header row.
+    pub fn new(mut writer: W, num_dims: usize) -> io::Result<Self> {
+        let header = std::iter::once("timestep".to_string())
+            .chain((0..num_dims).map(|dim| format!("betti_{}", dim)))
+            .chain((0..num_dims).map(|dim| format!("simplices_{}", dim)))
+            .collect::<Vec<_>>();
+        writeln!(writer, "{}", header.join(","))?;
+
+        Ok(BettiRecorder {
+            writer,
+            num_dims,
+            budget: None,
+        })
+    }
+
+    /// Gives each call to [`record`](Self::record) `budget` to compute the
+    /// betti numbers in, if given.
+    pub fn with_budget(self, budget: Option<Duration>) -> Self {
+        Self { budget, ..self }
+    }
+
+    /// Records the betti numbers and simplex counts of a complex, and returns
+    /// the betti numbers, with how many of them are exact.
+    pub fn record(&mut self, timestep: usize, complex: &SimplicialComplex) -> io::Result<BudgetedBetti> {
+        let betti = match self.budget {
+            Some(budget) => complex.betti_numbers_within(budget),
+            None => {
+                let betti_numbers = complex.betti_numbers_full();
+                BudgetedBetti {
+                    exact_dims: betti_numbers.len(),
+                    betti_numbers,
+                }
+            }
+        };
+        let betti_numbers = &betti.betti_numbers;
+        let f_vector = complex.f_vector();
+
+        let row = std::iter::once(timestep.to_string())
+            .chain((0..self.num_dims).map(|dim| betti_numbers.get(dim).unwrap_or(&0).to_string()))
+            .chain((0..self.num_dims).map(|dim| f_vector.get(dim).unwrap_or(&0).to_string()))
+            .collect::<Vec<_>>();
+        writeln!(self.writer, "{}", row.join(","))?;
+        Ok(betti)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Appends a row comparing a complex with a randomized control to a CSV file
+/// each time it's called, so that over-representation of its topology can be
+/// followed over the course of a simulation.
+///
+/// The columns are the timestep, the betti numbers and simplex counts of the
+/// control in dimensions 0 through n - 1, as in [`BettiRecorder`], and the
+/// ratios of those of the complex to those of the control, left empty where
+/// the control's are zero.
+pub struct ControlRecorder<W: Write> {
+    writer: W,
+    num_dims: usize,
+}
+
+impl<W: Write> ControlRecorder<W> {
+    /// Creates a recorder writing to `writer`, and writes the header row.
+    pub fn new(mut writer: W, num_dims: usize) -> io::Result<Self> {
+        let header = std::iter::once("timestep".to_string())
+            .chain((0..num_dims).map(|dim| format!("control_betti_{}", dim)))
+            .chain((0..num_dims).map(|dim| format!("control_simplices_{}", dim)))
+            .chain((0..num_dims).map(|dim| format!("betti_ratio_{}", dim)))
+            .chain((0..num_dims).map(|dim| format!("simplices_ratio_{}", dim)))
+            .collect::<Vec<_>>();
+        writeln!(writer, "{}", header.join(","))?;
+
+        Ok(ControlRecorder { writer, num_dims })
+    }
+
+    /// Records the betti numbers and simplex counts of `control`, and those of
+    /// `complex` relative to them.
+    pub fn record(
+        &mut self,
+        timestep: usize,
+        complex: &SimplicialComplex,
+        control: &SimplicialComplex,
+    ) -> io::Result<()> {
+        let counts = |complex: &SimplicialComplex| {
+            let betti_numbers = complex.betti_numbers_full();
+            let f_vector = complex.f_vector();
+            (0..self.num_dims)
+                .map(|dim| *betti_numbers.get(dim).unwrap_or(&0))
+                .chain((0..self.num_dims).map(|dim| *f_vector.get(dim).unwrap_or(&0) as i64))
+                .collect::<Vec<_>>()
+        };
+        let (counts, control_counts) = (counts(complex), counts(control));
+
+        let ratios = counts.iter().zip(&control_counts).map(|(&count, &control_count)| {
+            if control_count == 0 {
+                String::new()
+            } else {
+                (count as f64 / control_count as f64).to_string()
+            }
+        });
+        let row = std::iter::once(timestep.to_string())
+            .chain(control_counts.iter().map(|count| count.to_string()))
+            .chain(ratios)
+            .collect::<Vec<_>>();
+        writeln!(self.writer, "{}", row.join(","))
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Appends a row to a CSV file for every simplex born into or dying out of a
+/// complex, giving the raw stream of topological events behind its betti
+/// curves, which can be replayed to rebuild the complex at any timestep.
+///
+/// The columns are the timestep, `birth` or `death`, the dimension of the
+/// simplex and its vertices, from source to sink and separated by spaces. Rows
+/// are in the order the events happened, so faces are born before their
+/// cofaces and die after them.
+pub struct EventRecorder<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> EventRecorder<W> {
+    /// Creates a recorder writing to `writer`, and writes the header row.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writeln!(writer, "timestep,event,dim,vertices")?;
+        Ok(EventRecorder { writer })
+    }
+
+    /// Records the events the complex has recorded since they were last taken,
+    /// as happening at `timestep`, and takes them. The complex has to have
+    /// [started recording](SimplicialComplex::record_events) them.
+    pub fn record(&mut self, timestep: usize, complex: &mut SimplicialComplex) -> io::Result<()> {
+        for event in complex.take_events() {
+            let kind = match event.kind {
+                EventKind::Birth => "birth",
+                EventKind::Death => "death",
+            };
+            let vertices = event
+                .simplex
+                .oriented_vertices()
+                .iter()
+                .map(|vertex| vertex.to_string())
+                .collect::<Vec<_>>();
+            writeln!(self.writer, "{},{},{},{}", timestep, kind, event.simplex.dim(), vertices.join(" "))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
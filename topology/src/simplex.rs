@@ -0,0 +1,2212 @@
+use bimap::BiHashMap;
+use nalgebra::{distance, DMatrix, Dynamic, Matrix, Point3, Scalar, VecStorage};
+use petgraph::{
+    graph::UnGraph,
+    stable_graph::StableDiGraph,
+    visit::{EdgeRef, IntoEdgeReferences, NodeIndexable},
+};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::interner::{SimplexId, SimplexInterner};
+use crate::par::*;
+
+type GenericMatrix = Matrix<i64, Dynamic, Dynamic, VecStorage<i64, Dynamic, Dynamic>>;
+
+/// The entries of a matrix over the integers, which can be reduced into the
+/// field of any prime order to compute its rank there.
+pub trait Coefficient: Scalar + Copy {
+    /// Returns the entry mod `p`, in `0..p`.
+    fn reduce(self, p: u64) -> u64;
+}
+
+impl Coefficient for u64 {
+    fn reduce(self, p: u64) -> u64 {
+        self % p
+    }
+}
+
+impl Coefficient for i64 {
+    fn reduce(self, p: u64) -> u64 {
+        self.rem_euclid(p as i64) as u64
+    }
+}
+
+/// Returns the coefficient of the `i`th face of a simplex in its boundary,
+/// the face which omits its `i`th vertex in ascending order: (-1)^i.
+fn incidence(i: usize) -> i64 {
+    if i.is_multiple_of(2) {
+        1
+    } else {
+        -1
+    }
+}
+
+/// A simplex, identified by its vertices in ascending order.
+///
+/// The order of the vertices a simplex is built from is kept as well: for a
+/// directed clique, from source to sink. Its parity is the simplex's
+/// orientation: `[2, 1]` and `[1, 2]` are the same simplex with opposite
+/// orientations. The order takes no part in comparisons or hashing, but it
+/// records which way the edges of a directed complex point.
+#[derive(Clone)]
+pub struct Simplex {
+    vertices: Vec<usize>,
+    order: Vec<usize>,
+    orientation: i8,
+}
+
+impl Simplex {
+    /// Returns the vertices in ascending order.
+    pub fn vertices(&self) -> &[usize] {
+        &self.vertices
+    }
+
+    /// Returns 1 if the simplex was built from an even permutation of its
+    /// sorted vertices, and -1 otherwise.
+    pub fn orientation(&self) -> i8 {
+        self.orientation
+    }
+
+    /// Returns the vertices in the order the simplex was built from. For an
+    /// edge, this is its direction, and for a directed clique, the order of
+    /// its vertices from source to sink.
+    pub fn oriented_vertices(&self) -> Vec<usize> {
+        self.order.clone()
+    }
+
+    pub fn dim(&self) -> usize {
+        self.vertices.len() - 1
+    }
+
+    /// Returns the faces of the simplex, where the `i`th face omits the `i`th
+    /// vertex. Faces are positively oriented.
+    pub fn faces(&self) -> Vec<Simplex> {
+        (0..self.vertices.len())
+            .map(|i| {
+                let mut vertices = self.vertices.clone();
+                vertices.remove(i);
+
+                Simplex {
+                    order: vertices.clone(),
+                    vertices,
+                    orientation: 1,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns whether a vertex appears more than once, as in a self-loop,
+    /// which makes the simplex degenerate.
+    pub fn has_repeated_vertex(&self) -> bool {
+        self.vertices.windows(2).any(|pair| pair[0] == pair[1])
+    }
+}
+
+impl From<Vec<usize>> for Simplex {
+    fn from(order: Vec<usize>) -> Self {
+        let inversions = (0..order.len())
+            .map(|i| order[i + 1..].iter().filter(|&&v| v < order[i]).count())
+            .sum::<usize>();
+
+        let mut vertices = order.clone();
+        vertices.sort_unstable();
+
+        Simplex {
+            vertices,
+            order,
+            orientation: if inversions % 2 == 0 { 1 } else { -1 },
+        }
+    }
+}
+
+impl From<&[usize]> for Simplex {
+    fn from(vertices: &[usize]) -> Self {
+        Self::from(vertices.to_vec())
+    }
+}
+
+impl Deref for Simplex {
+    type Target = [usize];
+
+    fn deref(&self) -> &[usize] {
+        &self.vertices
+    }
+}
+
+impl PartialEq for Simplex {
+    fn eq(&self, other: &Self) -> bool {
+        self.vertices == other.vertices
+    }
+}
+
+impl Eq for Simplex {}
+
+impl Hash for Simplex {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.vertices.hash(state);
+    }
+}
+
+impl PartialOrd for Simplex {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Simplex {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.vertices.cmp(&other.vertices)
+    }
+}
+
+impl fmt::Debug for Simplex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.oriented_vertices()).finish()
+    }
+}
+
+/// The structural invariants violated by a complex, as found by
+/// [`SimplicialComplex::validate`].
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub violations: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    fn push(&mut self, violation: String) {
+        self.violations.push(violation);
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "no violations");
+        }
+
+        writeln!(f, "{} violations:", self.violations.len())?;
+
+        for violation in &self.violations {
+            writeln!(f, "  {}", violation)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An error from modifying a [`SimplicialComplex`].
+#[derive(Debug)]
+pub enum ComplexError {
+    /// The simplex has no vertices.
+    EmptySimplex,
+    /// The simplex has a vertex more than once.
+    RepeatedVertex(Simplex),
+    /// The simplex to remove isn't part of the complex.
+    SimplexNotFound(Simplex),
+    /// The simplex is above the complex's maximum dimension.
+    ExceedsMaxDim(Simplex),
+    /// The simplex maps, indices, and boundary matrices disagree about a
+    /// simplex; see [`SimplicialComplex::validate`].
+    InconsistentBoundary(String),
+}
+
+impl fmt::Display for ComplexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ComplexError::EmptySimplex => write!(f, "simplex has no vertices"),
+            ComplexError::RepeatedVertex(simplex) => {
+                write!(f, "simplex {:?} has a repeated vertex", simplex)
+            }
+            ComplexError::SimplexNotFound(simplex) => {
+                write!(f, "simplex {:?} is not in the complex", simplex)
+            }
+            ComplexError::ExceedsMaxDim(simplex) => {
+                write!(f, "simplex {:?} is above the maximum dimension", simplex)
+            }
+            ComplexError::InconsistentBoundary(message) => {
+                write!(f, "inconsistent boundary: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ComplexError {}
+
+/// Whether a simplex entered or left a complex.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    Birth,
+    Death,
+}
+
+/// A simplex entering or leaving a complex, as recorded once
+/// [`SimplicialComplex::record_events`] is called.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimplexEvent {
+    pub kind: EventKind,
+    pub simplex: Simplex,
+}
+
+/// Betti numbers computed within a time budget, as by
+/// [`SimplicialComplex::betti_numbers_within`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BudgetedBetti {
+    /// β₀ through β_dim, as in
+    /// [`betti_numbers_full`](SimplicialComplex::betti_numbers_full): exact
+    /// below `exact_dims`, and estimated from the simplex counts from there
+    /// on.
+    pub betti_numbers: Vec<i64>,
+    pub exact_dims: usize,
+}
+
+impl BudgetedBetti {
+    /// Returns whether the budget ran out before every betti number was
+    /// computed exactly.
+    pub fn timed_out(&self) -> bool {
+        self.exact_dims < self.betti_numbers.len()
+    }
+}
+
+/// A simplicial complex, along with its boundary matrices over the integers.
+///
+/// Simplices are interned, and referred to by id everywhere else: `simplices`
+/// maps each simplex to the vertices which complete it to a coface, and
+/// `simplex_indices` maps each simplex to its row in the boundary matrix of
+/// its dimension (and column in the one below). The boundary matrices are
+/// signed, oriented by the ascending order of the vertices of each simplex,
+/// so that reducing their entries mod `p` gives them over any prime field,
+/// GF(2) among them.
+pub struct SimplicialComplex {
+    pub interner: SimplexInterner,
+    pub simplices: Vec<HashMap<SimplexId, HashSet<usize>>>,
+    pub simplex_indices: Vec<BiHashMap<usize, SimplexId>>,
+    pub boundary_matrices: Vec<GenericMatrix>,
+    /// Boundary columns waiting to be appended to each matrix at the end of a
+    /// batch, or `None` outside of one.
+    deferred_columns: Option<Vec<Vec<Vec<usize>>>>,
+    /// The simplices of the pivot rows of each reduced boundary matrix as of
+    /// its last reduction, whose count is the matrix's rank, or `None` if a
+    /// column has been added or removed since.
+    pivots: Mutex<Vec<Option<HashSet<SimplexId>>>>,
+    /// The dimension above which cofaces are no longer completed, if any.
+    max_dim: Option<usize>,
+    /// The edges which were also added against their orientation, as the
+    /// reciprocal of an edge already in the complex.
+    reciprocal: HashSet<SimplexId>,
+    /// Every simplex added or removed since the events were last taken, in
+    /// order, if they're being recorded.
+    events: Option<Vec<SimplexEvent>>,
+}
+
+impl SimplicialComplex {
+    /// Creates a complex of isolated vertices. If `max_dim` is given, [`add`]
+    /// completes cofaces only up to that dimension, and refuses simplices above
+    /// it.
+    ///
+    /// [`add`]: Self::add
+    pub fn new(vertices: Vec<usize>, max_dim: Option<usize>) -> Self {
+        let mut complex = SimplicialComplex {
+            interner: SimplexInterner::new(),
+            simplices: vec![HashMap::new()],
+            simplex_indices: vec![BiHashMap::new()],
+            boundary_matrices: vec![GenericMatrix::from_iterator(1, 1, vec![0i64])],
+            deferred_columns: None,
+            pivots: Mutex::new(Vec::new()),
+            max_dim,
+            reciprocal: HashSet::new(),
+            events: None,
+        };
+
+        // Register the vertices up front so that isolated ones still count
+        // towards the connected components.
+        for vertex in vertices {
+            complex.insert(Simplex::from(vec![vertex]));
+        }
+
+        complex
+    }
+
+    /// Builds the Vietoris–Rips complex of a set of points: every subset of at
+    /// most `max_dim + 1` points that are pairwise within `radius` of each
+    /// other spans a simplex. Vertices are identified by their index in
+    /// `points`, so node positions can be passed directly in node order.
+    pub fn vietoris_rips(points: &[Point3<f64>], radius: f64, max_dim: usize) -> Self {
+        let vertices = (0..points.len()).collect::<Vec<_>>();
+
+        // Orienting every edge from the lower to the higher index makes each
+        // clique a directed clique with a unique vertex order.
+        let out_neighbors = points
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                points
+                    .iter()
+                    .enumerate()
+                    .skip(i + 1)
+                    .filter(|(_, b)| distance(a, b) <= radius)
+                    .map(|(j, _)| j)
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+
+        let mut complex = Self::new(vertices.clone(), Some(max_dim));
+        complex.insert_flag_cliques(&vertices, &out_neighbors, max_dim);
+        complex
+    }
+
+    /// Builds the directed flag complex of a graph: every directed clique (a
+    /// set of nodes whose edges form a transitive tournament, ordered from
+    /// source to sink) of at most `max_dim + 1` nodes spans a simplex. This is
+    /// the complex studied in flagser and the Blue Brain analyses. Vertices are
+    /// identified by node index.
+    ///
+    /// Each simplex keeps its vertices from source to sink, but a set of
+    /// vertices spans at most one simplex: a pair of reciprocal edges spans a
+    /// single edge, oriented like whichever was found first, and so do the
+    /// directed cliques through it. The complex is only the full directed flag
+    /// complex of a graph without reciprocal edges; use
+    /// [`count_directed_simplices`] to count every directed clique.
+    pub fn from_digraph<N, E>(graph: &StableDiGraph<N, E>, max_dim: usize) -> Self {
+        let (vertices, out_neighbors) = digraph_out_neighbors(graph);
+
+        let mut complex = Self::new(vertices.clone(), Some(max_dim));
+        complex.insert_flag_cliques(&vertices, &out_neighbors, max_dim);
+        complex
+    }
+
+    /// Builds the clique complex of an undirected graph, truncated at
+    /// `max_dim`: every clique of at most `max_dim + 1` nodes spans a simplex.
+    /// The maximal cliques are enumerated first (Bron–Kerbosch with pivoting),
+    /// and the complex is then populated from their subsets in a single pass.
+    /// Vertices are identified by node index, and simplices are ordered by
+    /// ascending index.
+    pub fn clique_complex<N, E>(graph: &UnGraph<N, E>, max_dim: usize) -> Self {
+        let neighbors = graph
+            .node_indices()
+            .map(|node| {
+                graph
+                    .neighbors(node)
+                    .filter(|&neighbor| neighbor != node)
+                    .map(|neighbor| neighbor.index())
+                    .collect()
+            })
+            .collect::<Vec<BTreeSet<usize>>>();
+
+        let mut layers = vec![BTreeSet::new(); max_dim + 1];
+
+        for clique in maximal_cliques(&neighbors) {
+            for (dim, layer) in layers.iter_mut().enumerate().take(clique.len()) {
+                layer.extend(combinations(&clique, dim + 1));
+            }
+        }
+
+        let mut complex = Self::new((0..graph.node_count()).collect(), Some(max_dim));
+
+        for simplex in layers.into_iter().flatten() {
+            complex.insert(Simplex::from(simplex));
+        }
+
+        complex
+    }
+
+    /// Reads a directed edge list from a file and builds its directed flag
+    /// complex, as in [`from_digraph`](Self::from_digraph). Each line holds the
+    /// source and target vertex of an edge, separated by commas or whitespace;
+    /// any further columns (e.g. weights) are ignored, as are blank lines,
+    /// `#` comments, and a header line. A `.gz` or `.zst` file is
+    /// decompressed.
+    pub fn from_edge_list<P: AsRef<Path>>(path: P, max_dim: usize) -> io::Result<Self> {
+        let edges = read_vertex_lists(path, Some(2))?;
+
+        let num_vertices = edges.iter().flatten().max().map_or(0, |&max| max + 1);
+        let mut out_neighbors = vec![BTreeSet::new(); num_vertices];
+
+        for (line, edge) in edges.iter().enumerate() {
+            if edge.len() < 2 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("edge {} has fewer than two vertices", line + 1),
+                ));
+            }
+
+            if edge[0] != edge[1] {
+                out_neighbors[edge[0]].insert(edge[1]);
+            }
+        }
+
+        let vertices = edges
+            .iter()
+            .flat_map(|edge| edge[..2].iter().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let mut complex = Self::new(vertices.clone(), Some(max_dim));
+        complex.insert_flag_cliques(&vertices, &out_neighbors, max_dim);
+        Ok(complex)
+    }
+
+    /// Reads a list of simplices (typically the maximal ones) from a file, one
+    /// per line with vertices separated by commas or whitespace, and builds the
+    /// smallest complex containing all of them and their faces.
+    pub fn from_simplex_list<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let simplices = read_vertex_lists(path, None)?;
+
+        let mut layers: Vec<BTreeSet<Simplex>> = Vec::new();
+
+        for simplex in simplices {
+            if layers.len() < simplex.len() {
+                layers.resize(simplex.len(), BTreeSet::new());
+            }
+
+            for (dim, layer) in layers.iter_mut().enumerate().take(simplex.len()) {
+                layer.extend(combinations(&simplex, dim + 1).into_iter().map(Simplex::from));
+            }
+        }
+
+        let mut complex = Self::new(Vec::new(), None);
+
+        for simplex in layers.into_iter().flatten() {
+            complex.insert(simplex);
+        }
+
+        Ok(complex)
+    }
+
+    /// Writes every simplex, one per line with space-separated vertices, in
+    /// order of dimension. Vertices are written in the order the simplex was
+    /// built from, so edge directions survive a round trip through
+    /// [`from_simplex_list`](Self::from_simplex_list).
+    pub fn write_simplex_list<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for dim in 0..self.simplex_indices.len() {
+            for simplex in self.sorted_simplices_of_dim(dim) {
+                let vertices = simplex
+                    .oriented_vertices()
+                    .iter()
+                    .map(|vertex| vertex.to_string())
+                    .collect::<Vec<_>>();
+                writeln!(writer, "{}", vertices.join(" "))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes only the maximal simplices, in the same format as
+    /// [`write_simplex_list`](Self::write_simplex_list). This is the most
+    /// compact description of the complex, and
+    /// [`from_simplex_list`](Self::from_simplex_list) fills the faces back in,
+    /// though the orientations of non-maximal edges are lost.
+    pub fn write_facet_list<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for simplex in self.maximal_simplices() {
+            let vertices = simplex
+                .oriented_vertices()
+                .iter()
+                .map(|vertex| vertex.to_string())
+                .collect::<Vec<_>>();
+            writeln!(writer, "{}", vertices.join(" "))?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the complex in Perseus' non-manifold simplicial toplex format.
+    /// Each line holds a simplex's dimension, its vertices, and its birth time,
+    /// which is 1 for every simplex since the complex isn't filtered. Perseus
+    /// expects positive vertex labels, so every vertex is shifted up by one.
+    pub fn write_perseus<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "1")?;
+
+        for dim in 0..self.simplex_indices.len() {
+            for simplex in self.sorted_simplices_of_dim(dim) {
+                let vertices = simplex
+                    .iter()
+                    .map(|vertex| (vertex + 1).to_string())
+                    .collect::<Vec<_>>();
+                writeln!(writer, "{} {} 1", dim, vertices.join(" "))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the 1-skeleton as a ripser lower-distance matrix over the
+    /// vertices in ascending order: adjacent vertices are at distance 1, and
+    /// all others at distance 2. Running ripser with a threshold between the
+    /// two reproduces the (undirected) flag complex of the 1-skeleton.
+    pub fn write_ripser_distance_matrix<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let vertices = self
+            .sorted_simplices_of_dim(0)
+            .into_iter()
+            .map(|simplex| simplex[0])
+            .collect::<Vec<_>>();
+
+        for (i, &a) in vertices.iter().enumerate().skip(1) {
+            let distances = vertices[..i]
+                .iter()
+                .map(|&b| if self.contains(&[a, b]) { "1" } else { "2" })
+                .collect::<Vec<_>>();
+            writeln!(writer, "{}", distances.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the simplices of a dimension sorted lexicographically, so that
+    /// output doesn't depend on insertion order.
+    fn sorted_simplices_of_dim(&self, dim: usize) -> Vec<&Simplex> {
+        let mut simplices = self.simplices_of_dim(dim).collect::<Vec<_>>();
+        simplices.sort();
+        simplices
+    }
+
+    /// Inserts every directed clique of up to `max_dim + 1` vertices, one
+    /// dimension at a time so that faces always precede their cofaces.
+    fn insert_flag_cliques(
+        &mut self,
+        vertices: &[usize],
+        out_neighbors: &[BTreeSet<usize>],
+        max_dim: usize,
+    ) {
+        for layer in directed_cliques(vertices, out_neighbors, max_dim) {
+            for simplex in layer {
+                self.insert(Simplex::from(simplex));
+            }
+        }
+    }
+
+    /// Inserts a single simplex without completing any of its cofaces, and
+    /// returns whether it wasn't already present. Faces which aren't present
+    /// yet are registered, but are not given boundaries of their own, so
+    /// simplices should be inserted in order of dimension.
+    fn insert(&mut self, simplex: Simplex) -> bool {
+        while self.simplices.len() < simplex.len() {
+            self.simplices.push(HashMap::new());
+            self.simplex_indices.push(BiHashMap::new());
+            self.boundary_matrices
+                .push(GenericMatrix::from_iterator(1, 1, vec![0i64]));
+        }
+
+        let dim = simplex.len() - 1;
+
+        if let Some(id) = self.interner.get(&simplex) {
+            // The reverse of an edge spans the same simplex, so that it's only
+            // recorded, to keep the edge until both directions are removed.
+            if dim == 1 && self.interner.resolve(id).orientation() != simplex.orientation() {
+                self.reciprocal.insert(id);
+            }
+            return false;
+        }
+
+        if dim > 0 {
+            let mut column_indices = Vec::new();
+
+            for (i, face) in simplex.faces().into_iter().enumerate() {
+                let face_id = self.interner.intern(face);
+                self.simplices[dim - 1]
+                    .entry(face_id)
+                    .or_default()
+                    .insert(simplex[i]);
+                column_indices.push(self.index_or_insert(dim - 1, face_id));
+            }
+
+            self.add_column(dim - 1, column_indices);
+        }
+
+        if let Some(events) = &mut self.events {
+            events.push(SimplexEvent {
+                kind: EventKind::Birth,
+                simplex: simplex.clone(),
+            });
+        }
+
+        let id = self.interner.intern(simplex);
+        self.index_or_insert(dim, id);
+        self.simplices[dim].entry(id).or_default();
+        true
+    }
+
+    /// Starts recording every simplex added to or removed from the complex
+    /// from now on, cofaces completed or removed along with it among them, to
+    /// be taken with [`take_events`](Self::take_events).
+    pub fn record_events(&mut self) {
+        self.events.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns the events recorded since they were last taken, in the order
+    /// they happened: faces are born before their cofaces, and die after
+    /// them.
+    pub fn take_events(&mut self) -> Vec<SimplexEvent> {
+        self.events.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Returns the row index of a simplex, registering it (and adding its row)
+    /// if it isn't indexed yet.
+    fn index_or_insert(&mut self, dim: usize, id: SimplexId) -> usize {
+        if let Some(&index) = self.simplex_indices[dim].get_by_right(&id) {
+            return index;
+        }
+
+        // Add one to the index because of the dummy element in the matrix to allow for the addition of rows and columns.
+        let index = self.simplex_indices[dim].len() + 1;
+        self.simplex_indices[dim].insert(index, id);
+        self.add_row(dim);
+        index
+    }
+
+    fn add_row(&mut self, mat_index: usize) {
+        // Rows are sized from the simplex indices when the batch is flushed.
+        if self.deferred_columns.is_some() {
+            return;
+        }
+
+        let n_rows = self.boundary_matrices[mat_index].nrows() + 1;
+        self.boundary_matrices[mat_index].resize_vertically_mut(n_rows, 0);
+    }
+
+    /// Appends the boundary of a simplex to a boundary matrix, given the rows
+    /// of its faces in the order of [`Simplex::faces`].
+    fn add_column(&mut self, mat_index: usize, indices: Vec<usize>) {
+        if let Some(deferred_columns) = &mut self.deferred_columns {
+            if deferred_columns.len() <= mat_index {
+                deferred_columns.resize(mat_index + 1, Vec::new());
+            }
+
+            deferred_columns[mat_index].push(indices);
+            return;
+        }
+
+        self.mark_dirty(mat_index);
+        let n_cols = self.boundary_matrices[mat_index].ncols() + 1;
+        self.boundary_matrices[mat_index].resize_horizontally_mut(n_cols, 0);
+        for (i, index) in indices.into_iter().enumerate() {
+            self.boundary_matrices[mat_index][(index, n_cols - 1)] = incidence(i);
+        }
+    }
+
+    /// Forgets the cached pivots of a boundary matrix whose columns changed.
+    /// Rows only ever come and go empty, which leaves the rank as it was, and
+    /// the pivots are kept by simplex rather than by row, so they survive the
+    /// rows being renumbered.
+    fn mark_dirty(&mut self, mat_index: usize) {
+        if let Some(pivots) = self.pivots.get_mut().unwrap().get_mut(mat_index) {
+            *pivots = None;
+        }
+    }
+
+    /// Returns the dimensions whose boundary matrices have changed since their
+    /// rank was last computed, and so will be reduced again by the next Betti
+    /// number computation.
+    pub fn dirty_dims(&self) -> Vec<usize> {
+        let pivots = self.pivots.lock().unwrap();
+
+        (0..self.boundary_matrices.len())
+            .filter(|&dim| !matches!(pivots.get(dim), Some(Some(_))))
+            .collect()
+    }
+
+    /// Returns the rank of every boundary matrix over GF(2), reducing only the
+    /// ones that changed since the last call, as [`pivots`](Self::pivots)
+    /// does. The bit-packed [`rank`] would reduce a whole matrix in parallel
+    /// row blocks, but it can't skip cleared columns, which saves more on flag
+    /// complexes; it's left to [`cohomology_betti_numbers`], which has nothing
+    /// to clear with.
+    ///
+    /// [`cohomology_betti_numbers`]: Self::cohomology_betti_numbers
+    fn ranks(&self) -> Vec<usize> {
+        self.pivots()
+            .iter()
+            .map(|pivots| pivots.as_ref().unwrap().len())
+            .collect()
+    }
+
+    /// Reduces the boundary matrices that changed since the last call, and
+    /// returns the pivots of every one of them.
+    ///
+    /// Each matrix is cleared using the one above it (the "twist" of Chen and
+    /// Kerber): a simplex in a pivot row of ∂_(k+1) is the lowest entry of a
+    /// boundary, so its own column of ∂_k would reduce to zero, and is skipped
+    /// outright. On flag complexes that leaves only a small fraction of the
+    /// columns to reduce. Clearing needs the pivots of the matrix above, so
+    /// the changed matrices are reduced in rounds: every one whose upper
+    /// neighbour is up to date, or which is the top one, is reduced in
+    /// parallel, which leaves a run of changed matrices to go one at a time
+    /// from the top down.
+    fn pivots(&self) -> MutexGuard<'_, Vec<Option<HashSet<SimplexId>>>> {
+        let mut pivots = self.pivots.lock().unwrap();
+        pivots.resize(self.boundary_matrices.len(), None);
+
+        let no_pivots = HashSet::new();
+        loop {
+            let ready = (0..pivots.len())
+                .filter(|&mat_index| pivots[mat_index].is_none() && !matches!(pivots.get(mat_index + 1), Some(None)))
+                .collect::<Vec<_>>();
+            if ready.is_empty() {
+                break;
+            }
+
+            let above = &*pivots;
+            let reduced = ready
+                .into_par_iter()
+                .map(|mat_index| {
+                    let cleared = match above.get(mat_index + 1) {
+                        Some(Some(above)) => above,
+                        _ => &no_pivots,
+                    };
+                    (mat_index, self.reduce_boundary(mat_index, cleared, None))
+                })
+                .collect::<Vec<_>>();
+
+            for (mat_index, reduced) in reduced {
+                pivots[mat_index] = reduced;
+            }
+        }
+
+        pivots
+    }
+
+    /// Returns the betti numbers as in
+    /// [`betti_numbers_full`](Self::betti_numbers_full), giving up on reducing
+    /// the boundary matrices once `budget` has passed, so that a dense complex
+    /// can't stall its caller.
+    ///
+    /// The matrices are reduced from the bottom dimension up, so that the
+    /// budget goes to the lower betti numbers first, and the ranks of those
+    /// it runs out on are taken to be the largest they could be given the
+    /// simplex counts and the ranks below. Dense complexes, which are the slow
+    /// ones to reduce, have few holes, so the estimates are close there. Ranks
+    /// which were computed are cached as usual.
+    pub fn betti_numbers_within(&self, budget: Duration) -> BudgetedBetti {
+        let deadline = Instant::now() + budget;
+        let mut pivots = self.pivots.lock().unwrap();
+        pivots.resize(self.boundary_matrices.len(), None);
+
+        let no_pivots = HashSet::new();
+        let mut num_exact = 0;
+        for mat_index in 0..self.boundary_matrices.len() {
+            if pivots[mat_index].is_none() {
+                let cleared = match pivots.get(mat_index + 1) {
+                    Some(Some(above)) => above,
+                    _ => &no_pivots,
+                };
+                pivots[mat_index] = self.reduce_boundary(mat_index, cleared, Some(deadline));
+            }
+            if pivots[mat_index].is_none() {
+                break;
+            }
+            num_exact += 1;
+        }
+
+        let mut ranks = pivots[..num_exact]
+            .iter()
+            .map(|pivots| pivots.as_ref().unwrap().len())
+            .collect::<Vec<_>>();
+        drop(pivots);
+
+        for mat_index in num_exact..self.boundary_matrices.len() {
+            let lower_rank = mat_index.checked_sub(1).map_or(0, |below| ranks[below]);
+            let nullity = self.num_simplices(mat_index) - lower_rank;
+            ranks.push(nullity.min(self.num_simplices(mat_index + 1)));
+        }
+
+        // β_k takes the ranks of ∂_k and ∂_(k+1), and the top one only that
+        // of ∂_k.
+        let betti_numbers = self.betti_numbers_from_ranks(&ranks);
+        let exact_dims = if num_exact + 1 >= betti_numbers.len() {
+            betti_numbers.len()
+        } else {
+            num_exact
+        };
+
+        BudgetedBetti {
+            betti_numbers,
+            exact_dims,
+        }
+    }
+
+    /// Reduces a boundary matrix over GF(2), skipping the columns of the
+    /// `cleared` simplices, and returns the simplices of its pivot rows, or
+    /// `None` if the `deadline` passes first.
+    fn reduce_boundary(
+        &self,
+        mat_index: usize,
+        cleared: &HashSet<SimplexId>,
+        deadline: Option<Instant>,
+    ) -> Option<HashSet<SimplexId>> {
+        let column_ids = self.simplex_indices.get(mat_index + 1);
+        let is_cleared = |column: usize| {
+            matches!(
+                column_ids.and_then(|ids| ids.get_by_left(&column)),
+                Some(id) if cleared.contains(id)
+            )
+        };
+
+        // The reduced column with its lowest entry in each row.
+        let mut reduced: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+
+        for (column, entries) in self.boundary_matrices[mat_index].column_iter().enumerate().skip(1) {
+            if is_cleared(column) {
+                continue;
+            }
+            if deadline.is_some_and(|deadline| column.is_multiple_of(DEADLINE_COLUMNS) && Instant::now() >= deadline) {
+                return None;
+            }
+
+            let mut entries = entries
+                .iter()
+                .enumerate()
+                .filter(|(_, &e)| e % 2 != 0)
+                .map(|(row, _)| row)
+                .collect::<BTreeSet<_>>();
+
+            while let Some(&low) = entries.iter().next_back() {
+                match reduced.get(&low) {
+                    Some(other) => entries = &entries ^ other,
+                    None => {
+                        reduced.insert(low, entries);
+                        break;
+                    }
+                }
+            }
+        }
+
+        Some(
+            reduced
+                .keys()
+                .map(|row| *self.simplex_indices[mat_index].get_by_left(row).unwrap())
+                .collect(),
+        )
+    }
+
+    /// Returns whether the given simplex is part of the complex, regardless of
+    /// the order its vertices are given in.
+    pub fn contains(&self, simplex: &[usize]) -> bool {
+        !simplex.is_empty() && self.interner.get(&Simplex::from(simplex)).is_some()
+    }
+
+    /// Returns the vertices which complete a simplex to one of its cofacets,
+    /// if the simplex is part of the complex.
+    fn completions(&self, simplex: &Simplex) -> Option<&HashSet<usize>> {
+        let id = self.interner.get(simplex)?;
+        self.simplices.get(simplex.dim())?.get(&id)
+    }
+
+    /// Returns the maximum dimension the complex was created with, if any.
+    pub fn max_dim(&self) -> Option<usize> {
+        self.max_dim
+    }
+
+    /// Returns the dimension of the largest simplex, or zero if the complex is
+    /// empty.
+    pub fn dim(&self) -> usize {
+        self.f_vector().len().saturating_sub(1)
+    }
+
+    /// Iterates over the simplices of the given dimension, in no particular
+    /// order.
+    pub fn simplices_of_dim(&self, dim: usize) -> impl Iterator<Item = &Simplex> {
+        self.simplex_indices
+            .get(dim)
+            .into_iter()
+            .flat_map(|indices| indices.right_values())
+            .map(move |&id| self.interner.resolve(id))
+    }
+
+    /// Returns the maximal simplices (facets), which aren't a face of any other
+    /// simplex, sorted by dimension and then lexicographically.
+    pub fn maximal_simplices(&self) -> Vec<&Simplex> {
+        let mut maximal = Vec::new();
+
+        for completions in &self.simplices {
+            let mut facets = completions
+                .iter()
+                .filter(|(_, completions)| completions.is_empty())
+                .map(|(&id, _)| self.interner.resolve(id))
+                .collect::<Vec<_>>();
+            facets.sort();
+            maximal.extend(facets);
+        }
+
+        maximal
+    }
+
+    /// Returns the number of simplices of the given dimension.
+    pub fn num_simplices(&self, dim: usize) -> usize {
+        self.simplex_indices.get(dim).map_or(0, |indices| indices.len())
+    }
+
+    /// Returns the cofaces of a simplex that are `codim` dimensions above it,
+    /// i.e. the simplices of that dimension which have it as a face. The
+    /// cofaces of codimension zero are just the simplex itself. Fails if the
+    /// boundary matrices disagree with the simplex maps about a coface.
+    pub fn cofaces(&self, simplex: &[usize], codim: usize) -> Result<Vec<Simplex>, ComplexError> {
+        if !self.contains(simplex) {
+            return Ok(Vec::new());
+        }
+
+        let mut cofaces: HashSet<Simplex> = HashSet::new();
+        cofaces.insert(Simplex::from(simplex));
+
+        for _ in 0..codim {
+            let mut cofacets = HashSet::new();
+
+            for coface in &cofaces {
+                cofacets.extend(self.cofacets(coface)?);
+            }
+
+            cofaces = cofacets;
+        }
+
+        Ok(cofaces.into_iter().collect())
+    }
+
+    /// Returns the cofaces of codimension one, read off of the simplex's row in
+    /// the boundary matrix.
+    fn cofacets(&self, simplex: &Simplex) -> Result<Vec<Simplex>, ComplexError> {
+        let dim = simplex.dim();
+
+        if dim + 1 >= self.simplex_indices.len() {
+            return Ok(Vec::new());
+        }
+
+        let &row = self
+            .interner
+            .get(simplex)
+            .and_then(|id| self.simplex_indices[dim].get_by_right(&id))
+            .ok_or_else(|| ComplexError::SimplexNotFound(simplex.clone()))?;
+
+        self.boundary_matrices[dim]
+            .row(row)
+            .iter()
+            .enumerate()
+            .filter(|(_, &e)| e != 0)
+            .map(|(column, _)| {
+                self.simplex_indices[dim + 1]
+                    .get_by_left(&column)
+                    .map(|&id| self.interner.resolve(id).clone())
+                    .ok_or_else(|| {
+                        ComplexError::InconsistentBoundary(format!(
+                            "{:?} has a coface in column {} with no simplex",
+                            simplex, column
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the coboundary of a simplex over the integers: each of its
+    /// cofacets, paired with the incidence number (-1)^i, where the simplex is
+    /// the cofacet's `i`th face.
+    pub fn coboundary(&self, simplex: &[usize]) -> Result<Vec<(Simplex, i64)>, ComplexError> {
+        let simplex = Simplex::from(simplex);
+
+        if simplex.is_empty() {
+            return Err(ComplexError::EmptySimplex);
+        }
+
+        Ok(self
+            .cofacets(&simplex)?
+            .into_iter()
+            .map(|cofacet| {
+                let i = cofacet.iter().position(|v| !simplex.contains(v)).unwrap();
+                (cofacet, incidence(i))
+            })
+            .collect())
+    }
+
+    /// Returns the matrix of the coboundary map δ_dim over the integers,
+    /// taking `dim`-cochains to `(dim + 1)`-cochains. It is the transpose of
+    /// the boundary matrix ∂_(dim+1), and so has the same dummy row and column.
+    pub fn coboundary_matrix(&self, dim: usize) -> GenericMatrix {
+        self.boundary_matrices[dim].transpose()
+    }
+
+    /// Returns the cohomology betti numbers β⁰ through β^dim, computed from the
+    /// ranks of the coboundary matrices. Over a field these agree with the
+    /// homology betti numbers, but reducing the coboundary matrices is often
+    /// faster, since they have fewer columns than rows.
+    pub fn cohomology_betti_numbers(&self) -> Vec<i64> {
+        let ranks = (0..self.boundary_matrices.len())
+            .into_par_iter()
+            .map(|dim| rank(&self.coboundary_matrix(dim)))
+            .collect::<Vec<_>>();
+
+        self.betti_numbers_from_ranks(&ranks)
+    }
+
+    /// Returns the (open) star of a simplex: every simplex that contains it,
+    /// including itself. Fails as [`cofaces`](Self::cofaces) does.
+    pub fn star(&self, simplex: &[usize]) -> Result<Vec<Simplex>, ComplexError> {
+        let mut star = Vec::new();
+
+        for codim in 0..self.simplex_indices.len().saturating_sub(simplex.len().saturating_sub(1)) {
+            star.extend(self.cofaces(simplex, codim)?);
+        }
+
+        Ok(star)
+    }
+
+    /// Returns the link of a simplex: every simplex disjoint from it whose
+    /// union with it is also in the complex. Fails as
+    /// [`cofaces`](Self::cofaces) does.
+    pub fn link(&self, simplex: &[usize]) -> Result<Vec<Simplex>, ComplexError> {
+        Ok(self
+            .star(simplex)?
+            .into_iter()
+            .filter(|coface| coface.len() > simplex.len())
+            .map(|coface| {
+                coface
+                    .iter()
+                    .filter(|vertex| !simplex.contains(vertex))
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .into()
+            })
+            .collect())
+    }
+
+    /// Checks the structural invariants of the complex: that the interner,
+    /// `simplices`, `simplex_indices`, and the boundary matrices agree on which simplices
+    /// exist and how they're indexed, that the complex is closed under taking
+    /// faces, that every column of a boundary matrix holds exactly the faces of
+    /// its simplex with the sign of its orientation, and that ∂∘∂ = 0 over the
+    /// integers.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let levels = self.simplex_indices.len();
+
+        if self.simplices.len() != levels || self.boundary_matrices.len() != levels {
+            report.push(format!(
+                "{} simplex maps, {} index maps, and {} boundary matrices",
+                self.simplices.len(),
+                levels,
+                self.boundary_matrices.len()
+            ));
+            return report;
+        }
+
+        for dim in 0..levels {
+            let indices = &self.simplex_indices[dim];
+            let matrix = &self.boundary_matrices[dim];
+            let num_cofaces = self.num_simplices(dim + 1);
+
+            let mut keys: Vec<usize> = indices.left_values().cloned().collect();
+            keys.sort_unstable();
+            if keys.iter().enumerate().any(|(i, &key)| key != i + 1) {
+                report.push(format!("dim {}: indices are not 1..={}", dim, indices.len()));
+            }
+
+            if matrix.nrows() != indices.len() + 1 || matrix.ncols() != num_cofaces + 1 {
+                report.push(format!(
+                    "dim {}: boundary matrix is {}x{}, expected {}x{}",
+                    dim,
+                    matrix.nrows(),
+                    matrix.ncols(),
+                    indices.len() + 1,
+                    num_cofaces + 1
+                ));
+                continue;
+            }
+
+            if matrix.row(0).iter().chain(matrix.column(0).iter()).any(|&e| e != 0) {
+                report.push(format!("dim {}: dummy row or column is nonzero", dim));
+            }
+
+            if self.simplices[dim].len() != indices.len() {
+                report.push(format!(
+                    "dim {}: {} simplices but {} indices",
+                    dim,
+                    self.simplices[dim].len(),
+                    indices.len()
+                ));
+            }
+
+            for id in self.simplices[dim].keys() {
+                if !indices.contains_right(id) {
+                    report.push(format!(
+                        "dim {}: {:?} is not indexed",
+                        dim,
+                        self.interner.resolve(*id)
+                    ));
+                }
+            }
+
+            if dim == 0 {
+                continue;
+            }
+
+            for (&column, &id) in indices.iter() {
+                let simplex = self.interner.resolve(id);
+
+                if simplex.len() != dim + 1 {
+                    report.push(format!("dim {}: {:?} has the wrong size", dim, simplex));
+                    continue;
+                }
+
+                let mut expected_rows = Vec::new();
+
+                for (i, face) in simplex.faces().into_iter().enumerate() {
+                    let face_id = self.interner.get(&face);
+
+                    match face_id.and_then(|id| self.simplex_indices[dim - 1].get_by_right(&id)) {
+                        Some(&row) => expected_rows.push((row, incidence(i))),
+                        None => {
+                            report.push(format!("{:?} is missing its face {:?}", simplex, face));
+                            continue;
+                        }
+                    }
+
+                    if !face_id
+                        .and_then(|id| self.simplices[dim - 1].get(&id))
+                        .is_some_and(|completions| completions.contains(&simplex[i]))
+                    {
+                        report.push(format!(
+                            "{:?} is not recorded as a completion of {:?}",
+                            simplex, face
+                        ));
+                    }
+                }
+
+                expected_rows.sort_unstable();
+
+                let rows: Vec<(usize, i64)> = self.boundary_matrices[dim - 1]
+                    .column(column)
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &e)| e != 0)
+                    .map(|(row, &e)| (row, e))
+                    .collect();
+
+                if rows != expected_rows {
+                    report.push(format!(
+                        "{:?} has boundary rows {:?}, expected {:?}",
+                        simplex, rows, expected_rows
+                    ));
+                }
+            }
+
+            let composition = &self.boundary_matrices[dim - 1] * &self.boundary_matrices[dim];
+
+            if composition.iter().any(|&e| e != 0) {
+                report.push(format!("dim {}: boundary of boundary is nonzero", dim + 1));
+            }
+        }
+
+        let num_indexed: usize = self.simplex_indices.iter().map(|indices| indices.len()).sum();
+        if self.interner.len() != num_indexed {
+            report.push(format!(
+                "{} interned simplices but {} indexed",
+                self.interner.len(),
+                num_indexed
+            ));
+        }
+
+        report
+    }
+
+    /// Returns the number of simplices of each dimension, starting from the
+    /// vertices. Trailing dimensions without any simplices are omitted.
+    pub fn f_vector(&self) -> Vec<usize> {
+        let mut f_vector: Vec<usize> = self
+            .simplex_indices
+            .iter()
+            .map(|indices| indices.len())
+            .collect();
+
+        while f_vector.last() == Some(&0) {
+            f_vector.pop();
+        }
+
+        f_vector
+    }
+
+    /// Returns the Euler characteristic, the alternating sum of the f-vector.
+    pub fn euler_characteristic(&self) -> i64 {
+        self.f_vector()
+            .iter()
+            .enumerate()
+            .map(|(dim, &count)| if dim % 2 == 0 { count as i64 } else { -(count as i64) })
+            .sum()
+    }
+
+    /// Return betti numbers, 1 and onward. Ranks are cached between calls, so
+    /// only the boundary matrices that changed since are reduced again.
+    pub fn betti_numbers(&self) -> Vec<i64> {
+        let ranks = self.ranks();
+        let mut betti_numbers: Vec<i64> = vec![0];
+        for (i, matrix) in self.boundary_matrices.iter().enumerate() {
+            let rank = ranks[i];
+
+            betti_numbers.push(matrix.ncols() as i64 - 1 - rank as i64);
+            betti_numbers[i] -= rank as i64;
+        }
+        betti_numbers.remove(0);
+        betti_numbers.remove(betti_numbers.len() - 1);
+
+        betti_numbers
+    }
+
+    /// Returns the betti numbers β₀ through β_dim, where dim is the dimension
+    /// of the largest simplex. By rank-nullity, β_k = n_k - rank ∂_k -
+    /// rank ∂_(k+1), where n_k is the number of k-simplices.
+    ///
+    /// In a complex with a maximum dimension, nothing is reported above it, and
+    /// the top betti number counts the cycles of the truncated complex: some of
+    /// them would be filled in by the cofaces which were never completed.
+    pub fn betti_numbers_full(&self) -> Vec<i64> {
+        self.betti_numbers_from_ranks(&self.ranks())
+    }
+
+    /// Returns a representative cycle over GF(2) for each class in a basis of
+    /// the `dim`th homology group, as the list of `dim`-simplices the cycle is
+    /// made of. There are as many cycles as the `dim`th betti number.
+    ///
+    /// The simplices are ordered by dimension and then by index, and the
+    /// boundary matrices reduced as in the standard persistence algorithm: the
+    /// cycles are those `dim`-simplices whose columns reduce to zero, and which
+    /// aren't the lowest face of any reduced `(dim + 1)`-column, read off of the
+    /// tracked column operations.
+    pub fn representative_cycles(&self, dim: usize) -> Vec<Vec<Simplex>> {
+        let num_simplices = self.num_simplices(dim);
+
+        if num_simplices == 0 {
+            return Vec::new();
+        }
+
+        // The pivot rows of ∂_(dim+1) are the simplices whose cycles are
+        // killed by a boundary.
+        let killed: HashSet<usize> = self
+            .pivots()
+            .get(dim)
+            .and_then(|pivots| pivots.as_ref())
+            .map(|pivots| {
+                pivots
+                    .iter()
+                    .map(|id| *self.simplex_indices[dim].get_by_right(id).unwrap())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Column `j` of the reduction holds the simplex with index `j + 1`.
+        // The killed columns would reduce to zero anyway, so they're cleared
+        // up front.
+        let mut boundaries = if dim == 0 {
+            vec![BTreeSet::new(); num_simplices]
+        } else {
+            self.boundary_columns(dim - 1)
+        };
+        for &index in &killed {
+            boundaries[index - 1].clear();
+        }
+        let (reduced, operations) = reduce_columns(boundaries);
+
+        reduced
+            .iter()
+            .enumerate()
+            .filter(|&(j, column)| column.is_empty() && !killed.contains(&(j + 1)))
+            .map(|(j, _)| {
+                operations[j]
+                    .iter()
+                    .map(|index| {
+                        let &id = self.simplex_indices[dim].get_by_left(index).unwrap();
+                        self.interner.resolve(id).clone()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the nonzero rows of each column of a boundary matrix, skipping
+    /// the dummy row and column.
+    fn boundary_columns(&self, mat_index: usize) -> Vec<BTreeSet<usize>> {
+        self.boundary_matrices[mat_index]
+            .column_iter()
+            .skip(1)
+            .map(|column| {
+                column
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &e)| e % 2 != 0)
+                    .map(|(row, _)| row)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the betti numbers β₀ through β_dim with coefficients in Z_p,
+    /// as in [`betti_numbers_full`](Self::betti_numbers_full). The boundary
+    /// matrices are signed, so for odd `p` a difference from the GF(2) betti
+    /// numbers points to p-torsion in the integral homology.
+    ///
+    /// Panics if `p` isn't prime.
+    pub fn betti_numbers_mod_p(&self, p: u64) -> Vec<i64> {
+        assert!(is_prime(p), "{} is not prime", p);
+
+        if p == 2 {
+            return self.betti_numbers_full();
+        }
+
+        let ranks = (0..self.boundary_matrices.len())
+            .into_par_iter()
+            .map(|mat_index| rank_mod_p(&self.boundary_matrices[mat_index], p))
+            .collect::<Vec<_>>();
+
+        self.betti_numbers_from_ranks(&ranks)
+    }
+
+    /// Returns the betti numbers β₀ through β_dim given the rank of every
+    /// boundary matrix.
+    fn betti_numbers_from_ranks(&self, ranks: &[usize]) -> Vec<i64> {
+        let f_vector = self.f_vector();
+        let ranks: Vec<i64> = ranks[..f_vector.len().saturating_sub(1)]
+            .iter()
+            .map(|&rank| rank as i64)
+            .collect();
+
+        f_vector
+            .iter()
+            .enumerate()
+            .map(|(dim, &count)| {
+                let lower_rank = if dim > 0 { ranks[dim - 1] } else { 0 };
+                let upper_rank = ranks.get(dim).cloned().unwrap_or(0);
+
+                count as i64 - lower_rank - upper_rank
+            })
+            .collect()
+    }
+
+    /// Adds a simplex, typically an edge, along with every coface it
+    /// completes, so that the complex stays the directed flag complex of its
+    /// edges: a vertex adjacent to every vertex of the simplex spans a coface
+    /// with it, unless the edges between them contain a directed cycle. The
+    /// direction of an edge is the order its vertices are given in. Adding the
+    /// reverse of an edge completes the cofaces it makes too, but the edge
+    /// keeps the direction it was first added in, and stays until both
+    /// directions are [removed](Self::remove). Cofaces above the maximum
+    /// dimension, if any, are not completed.
+    pub fn add<S: Into<Simplex>>(&mut self, simplex: S) -> Result<(), ComplexError> {
+        let simplex = simplex.into();
+
+        if simplex.is_empty() {
+            return Err(ComplexError::EmptySimplex);
+        }
+
+        if simplex.has_repeated_vertex() {
+            return Err(ComplexError::RepeatedVertex(simplex));
+        }
+
+        if self.max_dim.is_some_and(|max_dim| simplex.dim() > max_dim) {
+            return Err(ComplexError::ExceedsMaxDim(simplex));
+        }
+
+        let reversed = simplex.len() == 2
+            && self.interner.get(&simplex).is_some_and(|id| {
+                !self.reciprocal.contains(&id) && self.interner.resolve(id).orientation() != simplex.orientation()
+            });
+
+        if !self.insert(simplex.clone()) && !reversed || simplex.len() < 2 {
+            return Ok(());
+        }
+
+        if !reversed {
+            return self.complete(&simplex);
+        }
+
+        // The cofaces already through the edge were completed without its new
+        // direction, which may make directed cliques of their cofaces. They're
+        // found from the completions, which unlike the boundary matrices are
+        // up to date during a batch.
+        let mut layer = vec![simplex];
+        while !layer.is_empty() {
+            let mut cofacets = BTreeSet::new();
+
+            for simplex in &layer {
+                self.complete(simplex)?;
+
+                for &vertex in self.completions(simplex).into_iter().flatten() {
+                    let mut vertices = simplex.to_vec();
+                    vertices.push(vertex);
+                    cofacets.insert(Simplex::from(vertices));
+                }
+            }
+
+            layer = cofacets.into_iter().collect();
+        }
+
+        Ok(())
+    }
+
+    /// Adds every directed clique one vertex larger than a simplex in the
+    /// complex, below the maximum dimension, if any, along with the cofaces it
+    /// completes in turn.
+    fn complete(&mut self, simplex: &Simplex) -> Result<(), ComplexError> {
+        if self.max_dim.is_some_and(|max_dim| simplex.dim() >= max_dim) {
+            return Ok(());
+        }
+
+        // The vertices which complete every face of the simplex are exactly the
+        // ones adjacent to all of its vertices.
+        let empty = HashSet::new();
+        let mut faces = simplex.faces().into_iter();
+        let mut options: HashSet<usize> = self
+            .completions(&faces.next().unwrap())
+            .unwrap_or(&empty)
+            .clone();
+        for face in faces {
+            options = &options & self.completions(&face).unwrap_or(&empty);
+        }
+
+        for node in options {
+            let mut vertices = simplex.to_vec();
+            vertices.push(node);
+
+            if let Some(super_simplex) = self.directed_order(&vertices) {
+                self.add(super_simplex)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds a simplex along with any of its faces that are missing, so that
+    /// simplices can be added in any order, e.g. from a list of maximal
+    /// simplices. Unlike [`add`](Self::add), no cofaces are completed, and
+    /// missing faces are positively oriented.
+    pub fn add_with_closure<S: Into<Simplex>>(&mut self, simplex: S) -> Result<(), ComplexError> {
+        let simplex = simplex.into();
+
+        if simplex.is_empty() {
+            return Err(ComplexError::EmptySimplex);
+        }
+
+        if simplex.has_repeated_vertex() {
+            return Err(ComplexError::RepeatedVertex(simplex));
+        }
+
+        if self.max_dim.is_some_and(|max_dim| simplex.dim() > max_dim) {
+            return Err(ComplexError::ExceedsMaxDim(simplex));
+        }
+
+        if self.contains(&simplex) {
+            return Ok(());
+        }
+
+        if simplex.len() > 1 {
+            for face in simplex.faces() {
+                self.add_with_closure(face)?;
+            }
+        }
+
+        self.insert(simplex);
+        Ok(())
+    }
+
+    /// Orders a set of pairwise adjacent vertices from source to sink, or
+    /// returns `None` if the edges between them contain a directed cycle.
+    fn directed_order(&self, vertices: &[usize]) -> Option<Vec<usize>> {
+        directed_order(vertices, |source, target| self.has_directed_edge(source, target))
+    }
+
+    /// Returns whether there is an edge pointing from `source` to `target`,
+    /// either way for a reciprocal edge.
+    fn has_directed_edge(&self, source: usize, target: usize) -> bool {
+        source != target
+            && self.interner.get(&Simplex::from(vec![source, target])).is_some_and(|id| {
+                self.reciprocal.contains(&id) || self.interner.resolve(id).oriented_vertices()[0] == source
+            })
+    }
+
+    /// Adds several simplices, as with [`add`](Self::add), but resizes each
+    /// boundary matrix only once, at the end of the batch.
+    pub fn add_batch(&mut self, simplices: &[Vec<usize>]) -> Result<(), ComplexError> {
+        self.deferred_columns = Some(Vec::new());
+
+        let result = simplices
+            .iter()
+            .try_for_each(|simplex| self.add(simplex.clone()));
+
+        self.flush_deferred_columns();
+        result
+    }
+
+    /// Grows every boundary matrix to fit the simplices indexed during a batch
+    /// and appends the deferred columns, in the order they were added.
+    fn flush_deferred_columns(&mut self) {
+        let deferred_columns = self.deferred_columns.take().unwrap_or_default();
+
+        for mat_index in 0..self.boundary_matrices.len() {
+            let columns = deferred_columns
+                .get(mat_index)
+                .map_or(&[][..], |columns| &columns[..]);
+
+            let n_rows = self.simplex_indices[mat_index].len() + 1;
+            let old_n_cols = self.boundary_matrices[mat_index].ncols();
+            if !columns.is_empty() {
+                self.mark_dirty(mat_index);
+            }
+
+            let matrix = &mut self.boundary_matrices[mat_index];
+
+            matrix.resize_mut(n_rows, old_n_cols + columns.len(), 0);
+
+            for (offset, indices) in columns.iter().enumerate() {
+                for (i, &index) in indices.iter().enumerate() {
+                    matrix[(index, old_n_cols + offset)] = incidence(i);
+                }
+            }
+        }
+    }
+
+    /// Removes several simplices along with all of their cofaces, removing the
+    /// rows and columns of each boundary matrix and compacting the indices
+    /// only once. Nothing is removed if any of the simplices is missing, or if
+    /// the complex turns out to be inconsistent around them. As with
+    /// [`remove`](Self::remove), removing one direction of a reciprocal edge
+    /// only forgets that direction and the cofaces which needed it, unless the
+    /// batch removes both.
+    pub fn remove_batch(&mut self, simplices: &[Vec<usize>]) -> Result<(), ComplexError> {
+        let mut removed: HashSet<Simplex> = HashSet::new();
+        let mut forgotten: HashMap<SimplexId, Simplex> = HashMap::new();
+
+        for simplex in simplices {
+            if simplex.is_empty() {
+                return Err(ComplexError::EmptySimplex);
+            }
+
+            if !self.contains(simplex) {
+                return Err(ComplexError::SimplexNotFound(Simplex::from(&simplex[..])));
+            }
+
+            let simplex = Simplex::from(&simplex[..]);
+            let id = self.interner.get(&simplex).unwrap();
+            if self.reciprocal.contains(&id) && !forgotten.contains_key(&id) {
+                forgotten.insert(id, simplex);
+                continue;
+            }
+
+            if !removed.contains(&simplex) {
+                removed.extend(self.star(&simplex)?);
+            }
+        }
+
+        // The cofaces of a reciprocal edge which were only directed cliques
+        // through its forgotten direction go, and the rest are ordered by the
+        // edges left.
+        let has_edge = |source: usize, target: usize| {
+            let id = self.interner.get(&Simplex::from(vec![source, target]));
+            match id.and_then(|id| forgotten.get(&id)) {
+                Some(direction) => direction.oriented_vertices()[1] == source,
+                None => self.has_directed_edge(source, target),
+            }
+        };
+        let mut reoriented = Vec::new();
+
+        for simplex in forgotten.values() {
+            if removed.contains(simplex) {
+                continue;
+            }
+
+            let mut cofaces = self.star(simplex)?;
+            cofaces.sort_by_key(|coface| coface.dim());
+
+            for coface in cofaces.into_iter().filter(|coface| coface.dim() > 1) {
+                if removed.contains(&coface) || is_directed_order(&coface.oriented_vertices(), has_edge) {
+                    continue;
+                }
+
+                match directed_order(&coface, has_edge) {
+                    Some(order) => reoriented.push(Simplex::from(order)),
+                    None => removed.extend(self.star(&coface)?),
+                }
+            }
+        }
+
+        // Everything the removal relies on is checked before anything changes,
+        // so that an inconsistent complex is left as it was.
+        let mut ids = HashMap::with_capacity(removed.len());
+
+        for simplex in &removed {
+            let dim = simplex.dim();
+            let inconsistent = |message: &str| {
+                ComplexError::InconsistentBoundary(format!("{:?} {}", simplex, message))
+            };
+
+            let id = self
+                .interner
+                .get(simplex)
+                .ok_or_else(|| inconsistent("is in a star but isn't interned"))?;
+
+            if !self.simplices[dim].contains_key(&id) {
+                return Err(inconsistent("is interned but has no simplex entry"));
+            }
+
+            if !self.simplex_indices[dim].contains_right(&id) {
+                return Err(inconsistent("is interned but has no index"));
+            }
+
+            if dim > 0 {
+                for (i, face) in simplex.faces().into_iter().enumerate() {
+                    let completed = removed.contains(&face)
+                        || self
+                            .interner
+                            .get(&face)
+                            .and_then(|face_id| self.simplices[dim - 1].get(&face_id))
+                            .is_some_and(|completions| completions.contains(&simplex[i]));
+
+                    if !completed {
+                        return Err(inconsistent("is not recorded as a completion of its face"));
+                    }
+                }
+            }
+
+            ids.insert(simplex, id);
+        }
+
+        for (id, direction) in forgotten {
+            if removed.contains(&direction) {
+                continue;
+            }
+
+            self.reciprocal.remove(&id);
+            if self.interner.resolve(id).orientation() == direction.orientation() {
+                let mut order = direction.oriented_vertices();
+                order.reverse();
+                reoriented.push(Simplex::from(order));
+            }
+        }
+
+        let mut rows_by_dim = vec![Vec::new(); self.simplex_indices.len()];
+
+        for (simplex, id) in ids {
+            let dim = simplex.dim();
+
+            self.simplices[dim].remove(&id);
+
+            if dim > 0 {
+                for (i, face) in simplex.faces().into_iter().enumerate() {
+                    let face_id = self.interner.get(&face);
+                    if let Some(completions) = face_id.and_then(|face_id| self.simplices[dim - 1].get_mut(&face_id)) {
+                        completions.remove(&simplex[i]);
+                    }
+                }
+            }
+
+            let (row, _) = self.simplex_indices[dim].remove_by_right(&id).unwrap();
+            self.interner.release(id);
+            self.reciprocal.remove(&id);
+            rows_by_dim[dim].push(row);
+        }
+
+        for (dim, rows) in rows_by_dim.iter_mut().enumerate() {
+            if rows.is_empty() {
+                continue;
+            }
+
+            rows.sort_unstable();
+
+            // Columns of the boundary matrix below are ordered the same as the
+            // rows of this one.
+            if dim > 0 {
+                self.boundary_matrices[dim - 1] = self.boundary_matrices[dim - 1]
+                    .clone()
+                    .remove_columns_at(rows);
+                self.mark_dirty(dim - 1);
+            }
+
+            self.boundary_matrices[dim] = self.boundary_matrices[dim].clone().remove_rows_at(rows);
+            self.update_simplex_indices(dim);
+        }
+
+        for simplex in reoriented {
+            if let Some(id) = self.interner.get(&simplex) {
+                self.interner.reorient(id, simplex);
+            }
+        }
+
+        // Cofaces die before their faces, as they do in [`remove`](Self::remove).
+        if let Some(events) = &mut self.events {
+            let mut deaths = removed.into_iter().collect::<Vec<_>>();
+            deaths.sort_by(|a, b| b.dim().cmp(&a.dim()).then_with(|| a.cmp(b)));
+            events.extend(deaths.into_iter().map(|simplex| SimplexEvent {
+                kind: EventKind::Death,
+                simplex,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Removes a vertex along with every simplex containing it, as when a node
+    /// is lesioned. Its star is removed as one batch, so each boundary matrix
+    /// is shrunk and its indices compacted only once.
+    pub fn remove_vertex(&mut self, vertex: usize) -> Result<(), ComplexError> {
+        self.remove_batch(&[vec![vertex]])
+    }
+
+    pub fn update_simplex_indices(&mut self, index: usize) {
+        let mut indices: Vec<usize> = self.simplex_indices[index].left_values().cloned().collect();
+        indices.sort_unstable();
+        for (old_key, key) in indices.into_iter().enumerate() {
+            if key != old_key + 1 {
+                let new = self.simplex_indices[index].remove_by_left(&key).unwrap().1;
+                self.simplex_indices[index].insert(old_key + 1, new);
+            }
+        }
+    }
+
+    /// Removes a simplex along with all of its cofaces. Removing one direction
+    /// of an edge which was also added the other way only forgets that
+    /// direction, along with the cofaces which needed it: the edge stays,
+    /// pointing the other way.
+    pub fn remove<S: Into<Simplex>>(&mut self, simplex: S) -> Result<(), ComplexError> {
+        let simplex = simplex.into();
+
+        if simplex.is_empty() {
+            return Err(ComplexError::EmptySimplex);
+        }
+
+        if !self.contains(&simplex) {
+            return Err(ComplexError::SimplexNotFound(simplex));
+        }
+
+        if self.interner.get(&simplex).is_some_and(|id| self.reciprocal.contains(&id)) {
+            return self.remove_batch(&[simplex.oriented_vertices()]);
+        }
+
+        self.remove_with_cofaces(simplex)
+    }
+
+    /// Removes a simplex which is in the complex along with all of its
+    /// cofaces, in both directions, if it's a reciprocal edge.
+    fn remove_with_cofaces(&mut self, simplex: Simplex) -> Result<(), ComplexError> {
+        let dim = simplex.dim();
+
+        for coface in self.cofacets(&simplex)? {
+            self.remove_with_cofaces(coface)?;
+        }
+
+        let inconsistent = |message: &str| {
+            ComplexError::InconsistentBoundary(format!("{:?} {}", simplex, message))
+        };
+
+        let id = self
+            .interner
+            .get(&simplex)
+            .ok_or_else(|| inconsistent("is a coface but isn't interned"))?;
+
+        self.simplices[dim]
+            .remove(&id)
+            .ok_or_else(|| inconsistent("is indexed but has no simplex entry"))?;
+
+        let &simplex_row = self.simplex_indices[dim]
+            .get_by_right(&id)
+            .ok_or_else(|| inconsistent("is interned but has no index"))?;
+
+        if dim > 0 {
+            for (i, face) in simplex.faces().into_iter().enumerate() {
+                let completed = self
+                    .interner
+                    .get(&face)
+                    .and_then(|face_id| self.simplices[dim - 1].get_mut(&face_id))
+                    .is_some_and(|completions| completions.remove(&simplex[i]));
+
+                if !completed {
+                    return Err(inconsistent("is not recorded as a completion of its face"));
+                }
+            }
+
+            // Columns of the boundary matrix below are ordered the same as the
+            // rows of this one.
+            self.boundary_matrices[dim - 1] = self.boundary_matrices[dim - 1]
+                .clone()
+                .remove_column(simplex_row);
+            self.mark_dirty(dim - 1);
+        }
+
+        // All of the cofaces are gone, so this row is empty.
+        self.boundary_matrices[dim] = self.boundary_matrices[dim].clone().remove_row(simplex_row);
+        self.simplex_indices[dim].remove_by_right(&id);
+        self.interner.release(id);
+        self.reciprocal.remove(&id);
+        self.update_simplex_indices(dim);
+
+        if let Some(events) = &mut self.events {
+            events.push(SimplexEvent {
+                kind: EventKind::Death,
+                simplex,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts the directed cliques of a graph by dimension, up to `max_dim`,
+/// without building the boundary matrices. The `i`th entry is the number of
+/// directed `i`-simplices in the graph's directed flag complex.
+pub fn count_directed_simplices<N, E>(graph: &StableDiGraph<N, E>, max_dim: usize) -> Vec<usize> {
+    let (vertices, out_neighbors) = digraph_out_neighbors(graph);
+
+    directed_cliques(&vertices, &out_neighbors, max_dim)
+        .iter()
+        .map(|layer| layer.len())
+        .collect()
+}
+
+/// Orders a set of vertices from source to sink, so that `has_edge` holds from
+/// each vertex to every later one, or returns `None` if there is no such
+/// order. Each vertex in turn is one with an edge to every vertex not yet
+/// ordered: any will do, since the rest of an order that works still works
+/// without it.
+fn directed_order<F: Fn(usize, usize) -> bool>(vertices: &[usize], has_edge: F) -> Option<Vec<usize>> {
+    let mut remaining = vertices.to_vec();
+    let mut order = Vec::with_capacity(vertices.len());
+
+    while !remaining.is_empty() {
+        let source = remaining
+            .iter()
+            .position(|&source| remaining.iter().all(|&target| target == source || has_edge(source, target)))?;
+        order.push(remaining.remove(source));
+    }
+
+    Some(order)
+}
+
+/// Returns whether `has_edge` holds from each vertex to every later one.
+fn is_directed_order<F: Fn(usize, usize) -> bool>(order: &[usize], has_edge: F) -> bool {
+    (0..order.len()).all(|i| order[i + 1..].iter().all(|&target| has_edge(order[i], target)))
+}
+
+/// Returns the node indices of a graph along with the out-neighbors of each
+/// node, indexed by node index. Self-loops are ignored.
+fn digraph_out_neighbors<N, E>(graph: &StableDiGraph<N, E>) -> (Vec<usize>, Vec<BTreeSet<usize>>) {
+    let mut out_neighbors = vec![BTreeSet::new(); graph.node_bound()];
+
+    for edge in graph.edge_references() {
+        if edge.source() != edge.target() {
+            out_neighbors[edge.source().index()].insert(edge.target().index());
+        }
+    }
+
+    let vertices = graph.node_indices().map(|node| node.index()).collect();
+
+    (vertices, out_neighbors)
+}
+
+/// Enumerates the directed cliques of up to `max_dim + 1` vertices, grouped by
+/// dimension, where `out_neighbors[v]` is the set of vertices that `v` has an
+/// edge to. Cliques are grown by appending a common out-neighbor of all of
+/// their vertices, so each one is found exactly once, ordered from source to
+/// sink.
+fn directed_cliques(
+    vertices: &[usize],
+    out_neighbors: &[BTreeSet<usize>],
+    max_dim: usize,
+) -> Vec<Vec<Vec<usize>>> {
+    let mut layer: Vec<(Vec<usize>, BTreeSet<usize>)> = vertices
+        .iter()
+        .map(|&vertex| (vec![vertex], out_neighbors[vertex].clone()))
+        .collect();
+    let mut cliques = Vec::new();
+
+    for _ in 0..max_dim {
+        let mut next_layer = Vec::new();
+
+        for (clique, candidates) in &layer {
+            for &vertex in candidates {
+                let mut simplex = clique.clone();
+                simplex.push(vertex);
+
+                let candidates = candidates & &out_neighbors[vertex];
+                next_layer.push((simplex, candidates));
+            }
+        }
+
+        cliques.push(layer.into_iter().map(|(clique, _)| clique).collect());
+
+        if next_layer.is_empty() {
+            return cliques;
+        }
+
+        layer = next_layer;
+    }
+
+    cliques.push(layer.into_iter().map(|(clique, _)| clique).collect());
+    cliques
+}
+
+/// Reads one list of vertices per line, separated by commas or whitespace,
+/// keeping at most `columns` vertices per line if given. Blank lines and lines
+/// starting with `#` are skipped, and so is the first line if it isn't
+/// numeric, so that CSV headers are tolerated. Files compressed with gzip or
+/// zstd are decompressed, going by their extension.
+fn read_vertex_lists<P: AsRef<Path>>(
+    path: P,
+    columns: Option<usize>,
+) -> io::Result<Vec<Vec<usize>>> {
+    let reader = BufReader::new(compression::open(path)?);
+    let mut vertex_lists = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let vertices = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|token| !token.is_empty())
+            .take(columns.unwrap_or(usize::MAX))
+            .map(|token| token.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>();
+
+        match vertices {
+            Ok(vertices) => vertex_lists.push(vertices),
+            Err(_) if i == 0 => continue,
+            Err(err) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", i + 1, err),
+                ))
+            }
+        }
+    }
+
+    Ok(vertex_lists)
+}
+
+/// Enumerates the maximal cliques of an undirected graph given by its
+/// adjacency sets, each sorted in ascending order.
+fn maximal_cliques(neighbors: &[BTreeSet<usize>]) -> Vec<Vec<usize>> {
+    let mut cliques = Vec::new();
+
+    bron_kerbosch(
+        neighbors,
+        &mut Vec::new(),
+        (0..neighbors.len()).collect(),
+        BTreeSet::new(),
+        &mut cliques,
+    );
+
+    cliques
+}
+
+/// Bron–Kerbosch with Tomita pivoting: `clique` is the clique being grown,
+/// `candidates` the vertices that may still extend it, and `excluded` the
+/// vertices whose extensions have already been reported.
+fn bron_kerbosch(
+    neighbors: &[BTreeSet<usize>],
+    clique: &mut Vec<usize>,
+    mut candidates: BTreeSet<usize>,
+    mut excluded: BTreeSet<usize>,
+    cliques: &mut Vec<Vec<usize>>,
+) {
+    // Branching on the neighbors of the pivot would only find non-maximal
+    // cliques, so pick the pivot which rules out the most candidates.
+    let pivot = match candidates
+        .union(&excluded)
+        .max_by_key(|&&vertex| neighbors[vertex].intersection(&candidates).count())
+    {
+        Some(&pivot) => pivot,
+        None => {
+            let mut maximal_clique = clique.clone();
+            maximal_clique.sort_unstable();
+            cliques.push(maximal_clique);
+            return;
+        }
+    };
+
+    let branches = candidates
+        .difference(&neighbors[pivot])
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for vertex in branches {
+        clique.push(vertex);
+        bron_kerbosch(
+            neighbors,
+            clique,
+            &candidates & &neighbors[vertex],
+            &excluded & &neighbors[vertex],
+            cliques,
+        );
+        clique.pop();
+
+        candidates.remove(&vertex);
+        excluded.insert(vertex);
+    }
+}
+
+/// Returns every `k`-element subset of `elements`, preserving their order.
+fn combinations(elements: &[usize], k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+
+    let mut subsets = Vec::new();
+
+    for (i, &element) in elements.iter().enumerate() {
+        for mut subset in combinations(&elements[i + 1..], k - 1) {
+            subset.insert(0, element);
+            subsets.push(subset);
+        }
+    }
+
+    subsets
+}
+
+/// Get rank of matrix with finite field of prime order `p`, reducing its
+/// entries mod `p`.
+pub fn rank_mod_p<T: Coefficient>(mat: &DMatrix<T>, p: u64) -> usize {
+    let mul = |a: u64, b: u64| ((a as u128 * b as u128) % p as u128) as u64;
+    let mut rows: Vec<Vec<u64>> = mat
+        .row_iter()
+        .map(|row| row.iter().map(|&e| e.reduce(p)).collect())
+        .collect();
+
+    let mut mat_rank = 0;
+    for x in 0..mat.ncols() {
+        let pivot = match (mat_rank..rows.len()).find(|&i| rows[i][x] != 0) {
+            Some(pivot) => pivot,
+            None => continue,
+        };
+        rows.swap(mat_rank, pivot);
+
+        // Scale the pivot row so that the pivot is 1, by Fermat's little
+        // theorem.
+        let inverse = pow_mod(rows[mat_rank][x], p - 2, p);
+        for e in rows[mat_rank][x..].iter_mut() {
+            *e = mul(*e, inverse);
+        }
+
+        let (above, below) = rows.split_at_mut(mat_rank + 1);
+        let pivot_row = &above[mat_rank];
+        for row in below.iter_mut() {
+            let factor = row[x];
+            if factor != 0 {
+                for (e, &q) in row[x..].iter_mut().zip(&pivot_row[x..]) {
+                    *e = (*e + p - mul(factor, q)) % p;
+                }
+            }
+        }
+
+        mat_rank += 1;
+    }
+    mat_rank
+}
+
+/// Reduces the columns of a matrix over GF(2), given as sets of nonzero rows,
+/// so that no two nonzero columns share their lowest row. Returns the reduced
+/// columns along with the operations performed: the `j`th of which is the set
+/// of (one-based) original columns that were added up to give reduced column
+/// `j`.
+fn reduce_columns(
+    mut columns: Vec<BTreeSet<usize>>,
+) -> (Vec<BTreeSet<usize>>, Vec<BTreeSet<usize>>) {
+    let mut operations = (1..=columns.len())
+        .map(|j| std::iter::once(j).collect::<BTreeSet<_>>())
+        .collect::<Vec<_>>();
+    let mut column_with_low: HashMap<usize, usize> = HashMap::new();
+
+    for j in 0..columns.len() {
+        while let Some(&low) = columns[j].iter().next_back() {
+            let other = match column_with_low.get(&low) {
+                Some(&other) => other,
+                None => {
+                    column_with_low.insert(low, j);
+                    break;
+                }
+            };
+
+            columns[j] = &columns[j] ^ &columns[other];
+            operations[j] = &operations[j] ^ &operations[other];
+        }
+    }
+
+    (columns, operations)
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, p: u64) -> u64 {
+    let mut result = 1;
+    while exp > 0 {
+        if exp % 2 == 1 {
+            result = ((result as u128 * base as u128) % p as u128) as u64;
+        }
+        base = ((base as u128 * base as u128) % p as u128) as u64;
+        exp /= 2;
+    }
+    result
+}
+
+fn is_prime(n: u64) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+}
+
+/// How many columns are reduced between checks of a deadline.
+const DEADLINE_COLUMNS: usize = 64;
+
+/// Matrices with at least this many rows below the pivot have them eliminated
+/// in parallel, `ROW_BLOCK` rows per task.
+const PARALLEL_ROWS: usize = 1024;
+const ROW_BLOCK: usize = 256;
+
+/// Get rank of matrix with finite field of order 2. Rows are packed into
+/// bitsets, so that adding one row to another is a word-wise XOR.
+pub fn rank<T: Coefficient>(mat: &DMatrix<T>) -> usize {
+    let words = mat.ncols().div_ceil(64);
+    let mut rows = vec![vec![0u64; words]; mat.nrows()];
+
+    for (j, column) in mat.column_iter().enumerate() {
+        for (i, &e) in column.iter().enumerate() {
+            if e.reduce(2) == 1 {
+                rows[i][j / 64] |= 1 << (j % 64);
+            }
+        }
+    }
+
+    let mut mat_rank = 0;
+    for x in 0..mat.ncols() {
+        let (word, bit) = (x / 64, 1u64 << (x % 64));
+
+        let pivot = match (mat_rank..rows.len()).find(|&i| rows[i][word] & bit != 0) {
+            Some(pivot) => pivot,
+            None => continue,
+        };
+        rows.swap(mat_rank, pivot);
+
+        let (above, below) = rows.split_at_mut(mat_rank + 1);
+        let pivot_row = &above[mat_rank];
+        let eliminate = |row: &mut Vec<u64>| {
+            if row[word] & bit != 0 {
+                for (e, &p) in row[word..].iter_mut().zip(&pivot_row[word..]) {
+                    *e ^= p;
+                }
+            }
+        };
+
+        if below.len() >= PARALLEL_ROWS {
+            below
+                .par_chunks_mut(ROW_BLOCK)
+                .for_each(|block| block.iter_mut().for_each(eliminate));
+        } else {
+            below.iter_mut().for_each(eliminate);
+        }
+
+        mat_rank += 1;
+    }
+    mat_rank
+}
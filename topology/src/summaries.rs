@@ -0,0 +1,104 @@
+//! Fixed-size vectors summarizing persistence diagrams, so that diagrams can
+//! be compared, averaged or fed to statistical models like any other
+//! features: persistence landscapes, after Bubenik, and persistence images,
+//! after Adams et al.
+//!
+//! Both take the classes of one dimension of a diagram whose filtration runs
+//! forwards, with each class born before it dies, and sample them on grids
+//! fixed ahead of time, so that the vectors of different diagrams line up.
+//! Classes which never die are taken to live until the end of the grid.
+
+use crate::persistence::PersistencePair;
+
+/// `resolution` evenly spaced samples from `min` to `max`, both included.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Grid {
+    pub min: f64,
+    pub max: f64,
+    pub resolution: usize,
+}
+
+impl Grid {
+    /// Returns the samples.
+    pub fn points(&self) -> impl Iterator<Item = f64> {
+        let Grid { min, max, resolution } = *self;
+        (0..resolution).map(move |i| match resolution {
+            1 => (min + max) / 2.,
+            _ => min + (max - min) * i as f64 / (resolution - 1) as f64,
+        })
+    }
+
+    /// Returns the distance between neighbouring samples, or the whole range
+    /// if there's just one.
+    pub fn spacing(&self) -> f64 {
+        (self.max - self.min) / self.resolution.saturating_sub(1).max(1) as f64
+    }
+}
+
+/// Returns the births and deaths of the classes of dimension `dim`, with
+/// those which never die dying at `end`.
+fn intervals(diagram: &[PersistencePair], dim: usize, end: f64) -> Vec<(f64, f64)> {
+    diagram
+        .iter()
+        .filter(|pair| pair.dim == dim)
+        .map(|pair| (pair.birth, pair.death.unwrap_or(end).max(pair.birth)))
+        .collect()
+}
+
+/// Returns the first `num_landscapes` persistence landscapes of the classes
+/// of dimension `dim`, sampled on `grid`: `num_landscapes` runs of
+/// `grid.resolution` values, the `k`th of which is the `k`th largest of the
+/// tents `max(0, min(t - birth, death - t))` of the classes at each sample
+/// `t`.
+pub fn landscapes(diagram: &[PersistencePair], dim: usize, num_landscapes: usize, grid: &Grid) -> Vec<f64> {
+    let intervals = intervals(diagram, dim, grid.max);
+    let mut landscapes = vec![0.; num_landscapes * grid.resolution];
+    let mut tents = Vec::with_capacity(intervals.len());
+
+    for (i, t) in grid.points().enumerate() {
+        tents.clear();
+        tents.extend(
+            intervals
+                .iter()
+                .map(|&(birth, death)| (t - birth).min(death - t))
+                .filter(|&tent| tent > 0.),
+        );
+        tents.sort_by(|a, b| b.partial_cmp(a).unwrap());
+
+        for (k, &tent) in tents.iter().take(num_landscapes).enumerate() {
+            landscapes[k * grid.resolution + i] = tent;
+        }
+    }
+
+    landscapes
+}
+
+/// Returns the persistence image of the classes of dimension `dim`: each
+/// class, as the point of its birth and persistence, is spread into a
+/// Gaussian of standard deviation `sigma`, weighted by its persistence up to
+/// `persistence.max`, beyond which every class weighs 1, so that the many
+/// short-lived classes near the diagonal don't drown out the few long-lived
+/// ones. The image is sampled at the pixels of the `birth` and `persistence`
+/// grids, each the density at its center times its area, and returned as
+/// `persistence.resolution` rows of `birth.resolution` values, from the
+/// least persistent up.
+pub fn image(diagram: &[PersistencePair], dim: usize, birth: &Grid, persistence: &Grid, sigma: f64) -> Vec<f64> {
+    let intervals = intervals(diagram, dim, birth.max);
+    let area = birth.spacing() * persistence.spacing();
+    let normalization = area / (2. * std::f64::consts::PI * sigma * sigma);
+
+    let mut image = Vec::with_capacity(birth.resolution * persistence.resolution);
+    for y in persistence.points() {
+        for x in birth.points() {
+            let value = intervals.iter().fold(0., |value, &(born, died)| {
+                let lifetime = died - born;
+                let weight = if persistence.max > 0. { (lifetime / persistence.max).min(1.) } else { 1. };
+                let squared_distance = (x - born).powi(2) + (y - lifetime).powi(2);
+                value + weight * (-squared_distance / (2. * sigma * sigma)).exp()
+            });
+            image.push(value * normalization);
+        }
+    }
+
+    image
+}
@@ -0,0 +1,175 @@
+//! Random interleaved sequences of edge insertions and removals, checking the
+//! incremental bookkeeping against the invariants and against a complex
+//! rebuilt from scratch.
+
+use std::collections::BTreeSet;
+
+use topology::simplex::{ComplexError, SimplicialComplex};
+use petgraph::stable_graph::StableDiGraph;
+use proptest::prelude::*;
+
+const NUM_VERTICES: usize = 7;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Add(usize, usize),
+    Remove(usize, usize),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let edge = (0..NUM_VERTICES, 0..NUM_VERTICES).prop_filter("no self-loops", |(a, b)| a != b);
+
+    prop_oneof![
+        2 => edge.clone().prop_map(|(a, b)| Op::Add(a, b)),
+        1 => edge.prop_map(|(a, b)| Op::Remove(a, b)),
+    ]
+}
+
+fn rebuild(edges: &BTreeSet<(usize, usize)>) -> SimplicialComplex {
+    let mut graph = StableDiGraph::<(), ()>::new();
+    let nodes = (0..NUM_VERTICES)
+        .map(|_| graph.add_node(()))
+        .collect::<Vec<_>>();
+
+    for &(a, b) in edges {
+        graph.add_edge(nodes[a], nodes[b], ());
+    }
+
+    SimplicialComplex::from_digraph(&graph, NUM_VERTICES)
+}
+
+/// Removes an edge from the model of a complex's edges as the complex does,
+/// and returns whether it was there: only the given direction of a
+/// reciprocal edge, and otherwise the edge in whichever direction it points.
+fn remove_edge(edges: &mut BTreeSet<(usize, usize)>, a: usize, b: usize) -> bool {
+    if edges.contains(&(a, b)) && edges.contains(&(b, a)) {
+        return edges.remove(&(a, b));
+    }
+
+    edges.remove(&(a, b)) | edges.remove(&(b, a))
+}
+
+proptest! {
+    #[test]
+    fn incremental_matches_rebuild(ops in prop::collection::vec(op(), 1..60)) {
+        let mut complex = SimplicialComplex::new((0..NUM_VERTICES).collect(), None);
+        let mut edges = BTreeSet::new();
+
+        for op in ops {
+            match op {
+                Op::Add(a, b) => {
+                    prop_assert!(complex.add(vec![a, b]).is_ok());
+                    edges.insert((a, b));
+                }
+                Op::Remove(a, b) => {
+                    let result = complex.remove(vec![a, b]);
+
+                    if remove_edge(&mut edges, a, b) {
+                        prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+                    } else {
+                        prop_assert!(
+                            matches!(result, Err(ComplexError::SimplexNotFound(_))),
+                            "{:?}",
+                            result
+                        );
+                    }
+                }
+            }
+
+            let report = complex.validate();
+            prop_assert!(report.is_valid(), "{}", report);
+        }
+
+        let rebuilt = rebuild(&edges);
+
+        prop_assert_eq!(complex.f_vector(), rebuilt.f_vector());
+        prop_assert_eq!(complex.betti_numbers_full(), rebuilt.betti_numbers_full());
+    }
+}
+
+proptest! {
+    #[test]
+    fn batches_match_single_updates(
+        steps in prop::collection::vec(prop::collection::vec(op(), 0..8), 1..12)
+    ) {
+        let mut batched = SimplicialComplex::new((0..NUM_VERTICES).collect(), None);
+        let mut single = SimplicialComplex::new((0..NUM_VERTICES).collect(), None);
+        let mut edges = BTreeSet::new();
+
+        for step in steps {
+            // As in a simulation step, every removal is of an edge present at
+            // the start of the step, and removals precede additions.
+            let mut removed = Vec::new();
+            let mut added = Vec::new();
+
+            for op in step {
+                match op {
+                    Op::Add(a, b) => added.push(vec![a, b]),
+                    Op::Remove(a, b) => {
+                        if edges.contains(&(a, b)) || edges.contains(&(b, a)) {
+                            removed.push(vec![a, b]);
+                        }
+                    }
+                }
+            }
+
+            let result = batched.remove_batch(&removed);
+            prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+
+            for edge in &removed {
+                let (a, b) = (edge[0], edge[1]);
+
+                if remove_edge(&mut edges, a, b) {
+                    prop_assert!(single.remove(edge.clone()).is_ok());
+                }
+            }
+
+            let result = batched.add_batch(&added);
+            prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+
+            for edge in &added {
+                let (a, b) = (edge[0], edge[1]);
+                prop_assert!(single.add(edge.clone()).is_ok());
+                edges.insert((a, b));
+            }
+
+            let report = batched.validate();
+            prop_assert!(report.is_valid(), "{}", report);
+            prop_assert_eq!(batched.f_vector(), single.f_vector());
+        }
+
+        let rebuilt = rebuild(&edges);
+
+        prop_assert_eq!(batched.f_vector(), rebuilt.f_vector());
+        prop_assert_eq!(batched.betti_numbers_full(), rebuilt.betti_numbers_full());
+    }
+
+    #[test]
+    fn remove_batch_with_missing_simplex_removes_nothing(
+        ops in prop::collection::vec(op(), 1..30),
+        missing in (0..NUM_VERTICES, 0..NUM_VERTICES).prop_filter("no self-loops", |(a, b)| a != b),
+    ) {
+        let mut complex = SimplicialComplex::new((0..NUM_VERTICES).collect(), None);
+
+        for op in ops {
+            if let Op::Add(a, b) = op {
+                complex.add(vec![a, b]).unwrap();
+            }
+        }
+
+        let (a, b) = missing;
+        prop_assume!(!complex.contains(&[a, b]));
+
+        let present = complex.simplices_of_dim(1).map(|edge| edge.to_vec()).collect::<Vec<_>>();
+        let f_vector = complex.f_vector();
+
+        let mut batch = present;
+        batch.push(vec![a, b]);
+
+        prop_assert!(matches!(
+            complex.remove_batch(&batch),
+            Err(ComplexError::SimplexNotFound(_))
+        ));
+        prop_assert_eq!(complex.f_vector(), f_vector);
+    }
+}
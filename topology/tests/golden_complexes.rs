@@ -0,0 +1,173 @@
+//! Canonical complexes with known homology, built edge by edge through the
+//! public `add`/`remove` API. Every complex here is the flag complex of its
+//! edges, with edges oriented from the lower to the higher vertex so that
+//! every clique is filled in.
+
+use topology::simplex::SimplicialComplex;
+
+fn build(num_vertices: usize, edges: &[(usize, usize)]) -> SimplicialComplex {
+    let mut complex = SimplicialComplex::new((0..num_vertices).collect(), None);
+
+    for &(a, b) in edges {
+        complex.add(vec![a.min(b), a.max(b)]).unwrap();
+    }
+
+    assert_valid(&complex);
+    complex
+}
+
+fn assert_valid(complex: &SimplicialComplex) {
+    let report = complex.validate();
+    assert!(report.is_valid(), "{}", report);
+}
+
+fn circle_edges(num_vertices: usize) -> Vec<(usize, usize)> {
+    (0..num_vertices).map(|i| (i, (i + 1) % num_vertices)).collect()
+}
+
+/// The octahedron: every pair of vertices is adjacent except antipodal ones.
+fn octahedron_edges() -> Vec<(usize, usize)> {
+    let mut edges = Vec::new();
+
+    for a in 0..6 {
+        for b in a + 1..6 {
+            if b != a + 3 {
+                edges.push((a, b));
+            }
+        }
+    }
+
+    edges
+}
+
+/// A 4x4 grid with wraparound and one diagonal per square. Its cycles are long
+/// enough that the only cliques are the 32 triangles of the triangulation.
+fn torus_edges() -> Vec<(usize, usize)> {
+    let vertex = |i: usize, j: usize| (i % 4) * 4 + j % 4;
+    let mut edges = Vec::new();
+
+    for i in 0..4 {
+        for j in 0..4 {
+            edges.push((vertex(i, j), vertex(i + 1, j)));
+            edges.push((vertex(i, j), vertex(i, j + 1)));
+            edges.push((vertex(i, j), vertex(i + 1, j + 1)));
+        }
+    }
+
+    edges
+}
+
+fn complete_edges(vertices: &[usize]) -> Vec<(usize, usize)> {
+    let mut edges = Vec::new();
+
+    for (i, &a) in vertices.iter().enumerate() {
+        for &b in &vertices[i + 1..] {
+            edges.push((a, b));
+        }
+    }
+
+    edges
+}
+
+#[test]
+fn circle() {
+    let complex = build(5, &circle_edges(5));
+
+    assert_eq!(complex.f_vector(), vec![5, 5]);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 1]);
+}
+
+#[test]
+fn sphere() {
+    let complex = build(6, &octahedron_edges());
+
+    assert_eq!(complex.f_vector(), vec![6, 12, 8]);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 0, 1]);
+}
+
+#[test]
+fn torus() {
+    let complex = build(16, &torus_edges());
+
+    assert_eq!(complex.f_vector(), vec![16, 48, 32]);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 2, 1]);
+    assert_eq!(complex.betti_numbers_mod_p(3), vec![1, 2, 1]);
+    assert_eq!(complex.cohomology_betti_numbers(), vec![1, 2, 1]);
+
+    for (dim, &betti_number) in [1, 2, 1].iter().enumerate() {
+        assert_eq!(complex.representative_cycles(dim).len(), betti_number);
+    }
+}
+
+#[test]
+fn two_components() {
+    let mut edges = complete_edges(&[0, 1, 2]);
+    edges.extend(circle_edges(4).iter().map(|&(a, b)| (a + 3, b + 3)));
+
+    let complex = build(7, &edges);
+
+    assert_eq!(complex.betti_numbers_full(), vec![2, 1, 0]);
+}
+
+#[test]
+fn filled_tetrahedron() {
+    let complex = build(4, &complete_edges(&[0, 1, 2, 3]));
+
+    assert_eq!(complex.f_vector(), vec![4, 6, 4, 1]);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 0, 0, 0]);
+}
+
+#[test]
+fn removing_an_edge_from_a_tetrahedron() {
+    let mut complex = build(4, &complete_edges(&[0, 1, 2, 3]));
+
+    complex.remove(vec![0, 1]).unwrap();
+    assert_valid(&complex);
+
+    // Two triangles glued along the edge [2, 3].
+    assert_eq!(complex.f_vector(), vec![4, 5, 2]);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 0, 0]);
+}
+
+#[test]
+fn removing_a_diagonal_opens_a_hole() {
+    let mut edges = circle_edges(4);
+    edges.push((0, 2));
+    let mut complex = build(4, &edges);
+
+    assert_eq!(complex.betti_numbers_full(), vec![1, 0, 0]);
+
+    complex.remove(vec![0, 2]).unwrap();
+    assert_valid(&complex);
+
+    assert_eq!(complex.betti_numbers_full(), vec![1, 1]);
+}
+
+#[test]
+fn puncturing_and_repairing_the_sphere() {
+    let mut complex = build(6, &octahedron_edges());
+
+    // Removing an edge takes its two triangles with it, leaving a disk.
+    complex.remove(vec![0, 1]).unwrap();
+    assert_valid(&complex);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 0, 0]);
+
+    complex.add(vec![0, 1]).unwrap();
+    assert_valid(&complex);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 0, 1]);
+}
+
+#[test]
+fn tearing_the_torus_into_a_cylinder() {
+    let mut complex = build(16, &torus_edges());
+
+    // Cutting every edge that crosses between the last and first row, leaving
+    // a cylinder.
+    for j in 0..4 {
+        complex.remove(vec![j, 12 + j]).unwrap();
+        complex.remove(vec![(j + 1) % 4, 12 + j]).unwrap();
+        assert_valid(&complex);
+    }
+
+    assert_eq!(complex.betti_numbers_full(), vec![1, 1, 0]);
+}
@@ -0,0 +1,436 @@
+use topology::simplex::{rank, rank_mod_p, ComplexError, Simplex, SimplicialComplex};
+use nalgebra::{DMatrix, Point3};
+use petgraph::{graph::UnGraph, stable_graph::StableDiGraph};
+use std::time::Duration;
+
+fn complex_from_edges(num_vertices: usize, edges: &[(usize, usize)]) -> SimplicialComplex {
+    let mut complex = SimplicialComplex::new((0..num_vertices).collect(), None);
+
+    for &(source, target) in edges {
+        complex.add(vec![source, target]).unwrap();
+    }
+
+    complex
+}
+
+#[test]
+fn isolated_vertices() {
+    let complex = SimplicialComplex::new((0..3).collect(), None);
+
+    assert_eq!(complex.betti_numbers_full(), vec![3]);
+}
+
+#[test]
+fn isolated_vertex_beside_edge() {
+    let complex = complex_from_edges(3, &[(0, 1)]);
+
+    assert_eq!(complex.betti_numbers_full(), vec![2, 0]);
+}
+
+#[test]
+fn directed_cycle_is_a_circle() {
+    let complex = complex_from_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+
+    assert_eq!(complex.f_vector(), vec![3, 3]);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 1]);
+}
+
+#[test]
+fn transitive_triangle_is_filled() {
+    let complex = complex_from_edges(3, &[(0, 1), (1, 2), (0, 2)]);
+
+    assert_eq!(complex.f_vector(), vec![3, 3, 1]);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 0, 0]);
+}
+
+#[test]
+fn tetrahedron_boundary_is_a_sphere() {
+    let graph = UnGraph::<(), ()>::from_edges([(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+    let complex = SimplicialComplex::clique_complex(&graph, 2);
+
+    assert_eq!(complex.euler_characteristic(), 2);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 0, 1]);
+}
+
+#[test]
+fn filled_tetrahedron_is_contractible() {
+    let graph = UnGraph::<(), ()>::from_edges([(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+    let complex = SimplicialComplex::clique_complex(&graph, 3);
+
+    assert_eq!(complex.betti_numbers_full(), vec![1, 0, 0, 0]);
+}
+
+#[test]
+fn rips_square() {
+    let points = [
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(1.0, 0.0, 0.0),
+        Point3::new(1.0, 1.0, 0.0),
+        Point3::new(0.0, 1.0, 0.0),
+    ];
+
+    let hollow = SimplicialComplex::vietoris_rips(&points, 1.0, 3);
+    assert_eq!(hollow.betti_numbers_full(), vec![1, 1]);
+
+    let filled = SimplicialComplex::vietoris_rips(&points, 1.5, 3);
+    assert_eq!(filled.betti_numbers_full(), vec![1, 0, 0, 0]);
+}
+
+#[test]
+fn directed_cliques_keep_their_order() {
+    let complex = complex_from_edges(4, &[(3, 1), (1, 0), (3, 0), (2, 3), (2, 1), (2, 0)]);
+    let tetrahedron = complex.simplices_of_dim(3).next().unwrap();
+
+    assert_eq!(tetrahedron.oriented_vertices(), vec![2, 3, 1, 0]);
+}
+
+#[test]
+fn reciprocal_edges_stay_until_both_are_removed() {
+    // The triangle (0, 1, 2) is only a directed clique through 1 -> 0.
+    let mut complex = complex_from_edges(3, &[(0, 1), (1, 2), (2, 0), (1, 0)]);
+    assert_eq!(complex.f_vector(), vec![3, 3, 1]);
+
+    complex.remove(vec![1, 0]).unwrap();
+
+    let report = complex.validate();
+    assert!(report.is_valid(), "{}", report);
+    assert_eq!(complex.f_vector(), vec![3, 3]);
+    assert!(complex.contains(&[0, 1]));
+
+    complex.remove(vec![0, 1]).unwrap();
+
+    assert_eq!(complex.f_vector(), vec![3, 2]);
+}
+
+#[test]
+fn directed_flag_complex_of_two_triangles() {
+    let mut graph = StableDiGraph::<(), ()>::new();
+    let nodes = (0..4).map(|_| graph.add_node(())).collect::<Vec<_>>();
+
+    for &(source, target) in &[(0, 1), (1, 2), (0, 2), (2, 3), (3, 0), (1, 3)] {
+        graph.add_edge(nodes[source], nodes[target], ());
+    }
+
+    let complex = SimplicialComplex::from_digraph(&graph, 3);
+
+    assert_eq!(complex.f_vector(), vec![4, 6, 2]);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 1, 0]);
+}
+
+#[test]
+fn cached_ranks_follow_changes() {
+    let mut complex = complex_from_edges(3, &[(0, 1), (1, 2)]);
+
+    assert_eq!(complex.betti_numbers_full(), vec![1, 0]);
+    assert!(complex.dirty_dims().is_empty());
+
+    complex.add(vec![2, 0]).unwrap();
+    assert_eq!(complex.dirty_dims(), vec![0]);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 1]);
+    assert!(complex.dirty_dims().is_empty());
+
+    complex.remove(vec![1, 2]).unwrap();
+    assert_eq!(complex.dirty_dims(), vec![0]);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 0]);
+}
+
+#[test]
+fn cleared_reduction_matches_dense_ranks() {
+    // A dense flag complex, where most columns are cleared by the dimension
+    // above, and after a removal renumbers the simplices.
+    let edges = (0..9)
+        .flat_map(|i| (i + 1..9).map(move |j| (i, j)))
+        .filter(|&(i, j)| (i * 7 + j * 3) % 5 != 0)
+        .collect::<Vec<(u32, u32)>>();
+    let graph = UnGraph::<(), ()>::from_edges(edges);
+    let mut complex = SimplicialComplex::clique_complex(&graph, 4);
+
+    for step in 0..2 {
+        let dense_ranks = complex
+            .boundary_matrices
+            .iter()
+            .map(|matrix| rank(matrix) as i64)
+            .collect::<Vec<_>>();
+        let expected = complex
+            .f_vector()
+            .iter()
+            .enumerate()
+            .map(|(dim, &count)| {
+                let lower = if dim > 0 { dense_ranks[dim - 1] } else { 0 };
+                count as i64 - lower - dense_ranks[dim]
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(complex.betti_numbers_full(), expected, "step {}", step);
+
+        if step == 0 {
+            complex.remove(vec![0, 1]).unwrap();
+        }
+    }
+}
+
+#[test]
+fn rank_of_long_path_boundary() {
+    // The boundary of a path on n vertices has rank n - 1, and is tall enough
+    // to be eliminated in parallel row blocks.
+    let n = 3000;
+    let boundary = DMatrix::<u64>::from_fn(n, n - 1, |i, j| (i == j || i == j + 1) as u64);
+
+    assert_eq!(rank(&boundary), n - 1);
+}
+
+#[test]
+fn boundary_matrices_are_signed() {
+    // A filled tetrahedron, built one edge at a time and as one batch.
+    let edges = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+    let mut batched = SimplicialComplex::new((0..4).collect(), None);
+    batched
+        .add_batch(&edges.iter().map(|&(a, b)| vec![a, b]).collect::<Vec<_>>())
+        .unwrap();
+
+    for complex in &[complex_from_edges(4, &edges), batched] {
+        assert!(complex.validate().violations.is_empty());
+        let index = |simplex: Vec<usize>| {
+            let id = complex.interner.get(&Simplex::from(simplex.clone())).unwrap();
+            *complex.simplex_indices[simplex.len() - 1].get_by_right(&id).unwrap()
+        };
+
+        // ∂[0, 1, 2] = [1, 2] - [0, 2] + [0, 1].
+        let boundary = &complex.boundary_matrices[1];
+        let column = index(vec![0, 1, 2]);
+        assert_eq!(boundary[(index(vec![1, 2]), column)], 1);
+        assert_eq!(boundary[(index(vec![0, 2]), column)], -1);
+        assert_eq!(boundary[(index(vec![0, 1]), column)], 1);
+        assert_eq!(boundary.column(column).iter().filter(|&&e| e != 0).count(), 3);
+
+        for dim in 1..complex.boundary_matrices.len() {
+            let composition = &complex.boundary_matrices[dim - 1] * &complex.boundary_matrices[dim];
+            assert!(composition.iter().all(|&e| e == 0));
+        }
+        assert_eq!(rank_mod_p(&complex.boundary_matrices[1], 3), rank(&complex.boundary_matrices[1]));
+    }
+}
+
+#[test]
+fn betti_numbers_within_a_budget() {
+    let complex = complex_from_edges(5, &[(0, 1), (1, 2), (0, 2), (2, 3), (3, 0), (3, 4)]);
+    let betti = complex.betti_numbers_within(Duration::from_secs(60));
+    assert_eq!(betti.betti_numbers, complex.betti_numbers_full());
+    assert!(!betti.timed_out());
+
+    // A complete graph has more edges than are reduced between checks of the
+    // deadline, so none of its boundaries are reduced without any time, and
+    // its betti numbers are all estimates.
+    let edges = (0..16)
+        .flat_map(|i| (i + 1..16).map(move |j| (i, j)))
+        .collect::<Vec<(u32, u32)>>();
+    let complex = SimplicialComplex::clique_complex(&UnGraph::<(), ()>::from_edges(edges), 3);
+    let betti = complex.betti_numbers_within(Duration::ZERO);
+    assert!(betti.timed_out());
+    assert_eq!(betti.exact_dims, 0);
+    assert_eq!(betti.betti_numbers.len(), complex.betti_numbers_full().len());
+    assert!(betti.betti_numbers.iter().all(|&betti_number| betti_number >= 0));
+
+    // The ranks are cached once computed, so there's nothing left to time out
+    // on.
+    let exact = complex.betti_numbers_full();
+    assert_eq!(complex.betti_numbers_within(Duration::ZERO).betti_numbers, exact);
+}
+
+#[test]
+fn projective_plane_has_2_torsion() {
+    // The minimal six-vertex triangulation of the real projective plane.
+    let triangles = "0 1 2\n0 2 3\n0 3 4\n0 4 5\n0 5 1\n1 2 4\n2 3 5\n3 4 1\n4 5 2\n5 1 3\n";
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("math435-projective-plane.txt");
+    std::fs::write(&path, triangles).unwrap();
+
+    let complex = SimplicialComplex::from_simplex_list(&path).unwrap();
+
+    assert_eq!(complex.f_vector(), vec![6, 15, 10]);
+    assert_eq!(complex.betti_numbers_mod_p(2), vec![1, 1, 1]);
+    assert_eq!(complex.betti_numbers_mod_p(3), vec![1, 0, 0]);
+    assert_eq!(complex.betti_numbers_mod_p(5), vec![1, 0, 0]);
+}
+
+#[test]
+#[should_panic(expected = "not prime")]
+fn betti_numbers_mod_composite_panics() {
+    SimplicialComplex::new(vec![0], None).betti_numbers_mod_p(4);
+}
+
+#[test]
+fn coboundary_of_a_triangle_edge() {
+    let complex = complex_from_edges(4, &[(0, 1), (1, 2), (0, 2), (2, 3)]);
+
+    let mut coboundary = complex.coboundary(&[2]).unwrap();
+    coboundary.sort();
+    let coboundary = coboundary
+        .into_iter()
+        .map(|(simplex, sign)| (simplex.vertices().to_vec(), sign))
+        .collect::<Vec<_>>();
+
+    assert_eq!(coboundary, vec![(vec![0, 2], 1), (vec![1, 2], 1), (vec![2, 3], -1)]);
+
+    let coboundary = complex.coboundary(&[0, 2]).unwrap();
+    assert_eq!(coboundary.len(), 1);
+    assert_eq!(coboundary[0].0.vertices(), &[0, 1, 2]);
+    assert_eq!(coboundary[0].1, -1);
+
+    assert_eq!(complex.coboundary_matrix(0).shape(), (5, 5));
+    assert_eq!(complex.cohomology_betti_numbers(), complex.betti_numbers_full());
+}
+
+#[test]
+fn representative_cycle_of_a_circle() {
+    let complex = complex_from_edges(5, &[(0, 1), (1, 2), (0, 2), (2, 3), (3, 0), (3, 4)]);
+
+    assert_eq!(complex.betti_numbers_full(), vec![1, 1, 0]);
+
+    let cycles = complex.representative_cycles(1);
+    assert_eq!(cycles.len(), 1);
+
+    // Every vertex of a 1-cycle is the endpoint of an even number of edges.
+    let cycle = &cycles[0];
+    for vertex in 0..5 {
+        let degree = cycle.iter().filter(|edge| edge.contains(&vertex)).count();
+        assert_eq!(degree % 2, 0, "{:?}", cycle);
+    }
+    assert!(cycle.iter().any(|edge| edge.contains(&3)));
+    assert!(!cycle.iter().any(|edge| edge.contains(&4)));
+
+    assert_eq!(complex.representative_cycles(0).len(), 1);
+    assert!(complex.representative_cycles(2).is_empty());
+}
+
+#[test]
+fn max_dim_stops_coface_completion() {
+    let edges = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+
+    let mut capped = SimplicialComplex::new((0..4).collect(), Some(2));
+    let mut graph = SimplicialComplex::new((0..4).collect(), Some(1));
+    for &(source, target) in &edges {
+        capped.add(vec![source, target]).unwrap();
+        graph.add(vec![source, target]).unwrap();
+    }
+
+    assert_eq!(capped.f_vector(), vec![4, 6, 4]);
+    assert_eq!(capped.betti_numbers_full(), vec![1, 0, 1]);
+
+    assert_eq!(graph.f_vector(), vec![4, 6]);
+    assert_eq!(graph.betti_numbers_full(), vec![1, 3]);
+    assert!(matches!(
+        graph.add(vec![0, 1, 2]),
+        Err(ComplexError::ExceedsMaxDim(_))
+    ));
+}
+
+#[test]
+fn self_loops_are_rejected() {
+    let mut complex = SimplicialComplex::new((0..3).collect(), None);
+    complex.add(vec![0, 1]).unwrap();
+
+    assert!(matches!(
+        complex.add(vec![1, 1]),
+        Err(ComplexError::RepeatedVertex(_))
+    ));
+    assert!(matches!(
+        complex.add_with_closure(vec![0, 2, 2]),
+        Err(ComplexError::RepeatedVertex(_))
+    ));
+    assert!(complex.validate().is_valid());
+    assert_eq!(complex.f_vector(), vec![3, 1]);
+}
+
+#[test]
+fn add_with_closure_fills_in_faces() {
+    let mut complex = SimplicialComplex::new(Vec::new(), None);
+
+    complex.add_with_closure(vec![0, 1, 2, 3]).unwrap();
+    complex.add_with_closure(vec![5, 4, 3]).unwrap();
+    complex.add_with_closure(vec![3, 5]).unwrap();
+
+    let report = complex.validate();
+    assert!(report.is_valid(), "{}", report);
+    assert_eq!(complex.f_vector(), vec![6, 9, 5, 1]);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 0, 0, 0]);
+}
+
+#[test]
+fn inconsistent_boundary_fails_removal() {
+    let mut complex = complex_from_edges(3, &[(0, 1)]);
+
+    // A coface of vertex 0 in a column with no simplex.
+    let boundary = &mut complex.boundary_matrices[0];
+    let n_cols = boundary.ncols();
+    *boundary = boundary.clone().insert_column(n_cols, 0);
+    boundary[(1, n_cols)] = 1;
+
+    assert!(matches!(complex.star(&[0]), Err(ComplexError::InconsistentBoundary(_))));
+    assert!(matches!(complex.remove_vertex(0), Err(ComplexError::InconsistentBoundary(_))));
+    assert!(matches!(complex.remove(vec![0]), Err(ComplexError::InconsistentBoundary(_))));
+}
+
+#[test]
+fn inconsistent_batch_removes_nothing() {
+    let mut complex = complex_from_edges(3, &[(0, 1), (1, 2)]);
+
+    // Vertex 0 no longer records that 1 completes it to an edge.
+    let vertex = complex.interner.get(&Simplex::from(vec![0])).unwrap();
+    complex.simplices[0].get_mut(&vertex).unwrap().remove(&1);
+
+    assert!(matches!(
+        complex.remove_batch(&[vec![1, 2], vec![1]]),
+        Err(ComplexError::InconsistentBoundary(_))
+    ));
+    assert_eq!(complex.f_vector(), vec![3, 2]);
+    assert!(complex.contains(&[1, 2]));
+}
+
+#[test]
+fn remove_vertex_takes_its_star() {
+    // Two triangles sharing the edge (1, 2), with a hole through vertex 4.
+    let mut complex = complex_from_edges(
+        5,
+        &[(0, 1), (1, 2), (0, 2), (1, 3), (3, 2), (3, 4), (4, 0)],
+    );
+    assert_eq!(complex.betti_numbers_full(), vec![1, 1, 0]);
+
+    complex.remove_vertex(1).unwrap();
+
+    let report = complex.validate();
+    assert!(report.is_valid(), "{}", report);
+    assert!(!complex.contains(&[1]));
+    assert_eq!(complex.f_vector(), vec![4, 4]);
+    assert_eq!(complex.betti_numbers_full(), vec![1, 1]);
+
+    assert!(matches!(
+        complex.remove_vertex(1),
+        Err(ComplexError::SimplexNotFound(_))
+    ));
+}
+
+#[test]
+fn maximal_simplices_of_a_mixed_complex() {
+    // A filled triangle with a tail edge and an isolated vertex.
+    let complex = complex_from_edges(5, &[(0, 1), (1, 2), (0, 2), (2, 3)]);
+
+    let maximal = complex
+        .maximal_simplices()
+        .into_iter()
+        .map(|simplex| simplex.vertices().to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(maximal, vec![vec![4], vec![2, 3], vec![0, 1, 2]]);
+
+    let mut facets = Vec::new();
+    complex.write_facet_list(&mut facets).unwrap();
+    assert_eq!(String::from_utf8(facets.clone()).unwrap(), "4\n2 3\n0 1 2\n");
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("math435-facets.txt");
+    std::fs::write(&path, facets).unwrap();
+    let rebuilt = SimplicialComplex::from_simplex_list(&path).unwrap();
+
+    assert_eq!(rebuilt.f_vector(), complex.f_vector());
+    assert_eq!(rebuilt.betti_numbers_full(), complex.betti_numbers_full());
+}
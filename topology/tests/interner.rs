@@ -0,0 +1,33 @@
+use topology::interner::SimplexInterner;
+use topology::simplex::{Simplex, SimplicialComplex};
+
+#[test]
+fn ids_are_dense_and_reused() {
+    let mut interner = SimplexInterner::new();
+
+    let a = interner.intern(Simplex::from(vec![0, 1]));
+    let b = interner.intern(Simplex::from(vec![1, 2]));
+    assert_eq!((a, b), (0, 1));
+
+    // Interning again finds the original, whatever the vertex order.
+    assert_eq!(interner.intern(Simplex::from(vec![1, 0])), a);
+    assert_eq!(interner.resolve(a).oriented_vertices(), vec![0, 1]);
+
+    assert_eq!(interner.release(a).unwrap().vertices(), &[0, 1]);
+    assert_eq!(interner.get(&Simplex::from(vec![0, 1])), None);
+    assert_eq!(interner.intern(Simplex::from(vec![2, 3])), a);
+    assert_eq!(interner.len(), 2);
+}
+
+#[test]
+fn complex_releases_removed_simplices() {
+    let mut complex = SimplicialComplex::new((0..3).collect(), None);
+    complex.add(vec![0, 1]).unwrap();
+    complex.add(vec![1, 2]).unwrap();
+    complex.add(vec![0, 2]).unwrap();
+    assert_eq!(complex.interner.len(), 7);
+
+    complex.remove(vec![1]).unwrap();
+    assert_eq!(complex.interner.len(), 3);
+    assert!(complex.validate().is_valid());
+}
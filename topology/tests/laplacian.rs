@@ -0,0 +1,61 @@
+use topology::simplex::SimplicialComplex;
+
+fn complex_from_edges(num_vertices: usize, edges: &[(usize, usize)]) -> SimplicialComplex {
+    let mut complex = SimplicialComplex::new((0..num_vertices).collect(), None);
+
+    for &(source, target) in edges {
+        complex.add(vec![source, target]).unwrap();
+    }
+
+    complex
+}
+
+fn assert_close(actual: &[f64], expected: &[f64]) {
+    assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn square_graph_laplacian() {
+    let complex = complex_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+
+    // L_0 is the graph Laplacian of the 4-cycle.
+    assert_close(&complex.laplacian_spectrum(0, 4), &[0.0, 2.0, 2.0, 4.0]);
+    assert_close(&[complex.spectral_gap(0).unwrap()], &[2.0]);
+
+    // The square has a single hole, carried evenly by its four edges.
+    let harmonic = complex.harmonic_representatives(1);
+    assert_eq!(harmonic.len(), 1);
+    for &weight in harmonic[0].iter() {
+        assert!((weight.abs() - 0.5).abs() < 1e-9, "{}", harmonic[0]);
+    }
+}
+
+#[test]
+fn filled_triangle_has_no_harmonic_edges() {
+    let complex = complex_from_edges(3, &[(0, 1), (1, 2), (0, 2)]);
+
+    assert!(complex.harmonic_representatives(1).is_empty());
+    assert_close(&complex.laplacian_spectrum(2, 1), &[3.0]);
+    assert_eq!(complex.hodge_laplacian(1).shape(), (3, 3));
+}
+
+#[test]
+fn zero_eigenvalues_count_betti_numbers() {
+    // Vertex 5 is left isolated.
+    let complex = complex_from_edges(6, &[(0, 1), (1, 2), (0, 2), (2, 3), (3, 4), (4, 0)]);
+    let betti_numbers = complex.betti_numbers_full();
+
+    for (dim, &betti_number) in betti_numbers.iter().enumerate() {
+        let zeros = complex
+            .laplacian_spectrum(dim, usize::MAX)
+            .into_iter()
+            .filter(|&eigenvalue| eigenvalue == 0.0)
+            .count();
+
+        assert_eq!(zeros as i64, betti_number, "dim {}", dim);
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+
+use rand::{rngs::StdRng, SeedableRng};
+use topology::null_models::{
+    degree_preserving_flag, density, erdos_renyi_flag, linial_meshulam, matched_erdos_renyi_flag,
+    matched_linial_meshulam, rewire,
+};
+use topology::simplex::SimplicialComplex;
+
+#[test]
+fn linial_meshulam_extremes() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    // With no triangles, the complete graph on 6 vertices has C(5, 2) holes.
+    let empty = linial_meshulam(6, 0.0, &mut rng);
+    assert_eq!(empty.f_vector(), vec![6, 15]);
+    assert_eq!(empty.betti_numbers_full(), vec![1, 10]);
+
+    // With all of them, it's the 2-skeleton of the 5-simplex.
+    let full = linial_meshulam(6, 1.0, &mut rng);
+    assert_eq!(full.f_vector(), vec![6, 15, 20]);
+    assert_eq!(full.betti_numbers_full(), vec![1, 0, 10]);
+}
+
+#[test]
+fn erdos_renyi_flag_extremes() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    assert_eq!(erdos_renyi_flag(5, 0.0, 3, &mut rng).betti_numbers_full(), vec![5]);
+    assert_eq!(erdos_renyi_flag(5, 1.0, 3, &mut rng).f_vector(), vec![5, 10, 10, 5]);
+}
+
+#[test]
+fn matched_densities() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let complex = erdos_renyi_flag(30, 0.3, 2, &mut rng);
+
+    let runs = 20;
+    let mean_edge_density = (0..runs)
+        .map(|_| density(&matched_erdos_renyi_flag(&complex, 2, &mut rng), 1))
+        .sum::<f64>()
+        / runs as f64;
+    assert!((mean_edge_density - density(&complex, 1)).abs() < 0.02);
+
+    let mean_triangle_density = (0..runs)
+        .map(|_| density(&matched_linial_meshulam(&complex, &mut rng), 2))
+        .sum::<f64>()
+        / runs as f64;
+    assert!((mean_triangle_density - density(&complex, 2)).abs() < 0.01);
+}
+
+#[test]
+fn density_of_an_empty_complex() {
+    let complex = SimplicialComplex::new(Vec::new(), None);
+
+    assert_eq!(density(&complex, 1), 0.0);
+}
+
+#[test]
+fn rewiring_keeps_degrees() {
+    let mut rng = StdRng::seed_from_u64(2);
+    // A directed ring of 10 vertices, with chords from every even vertex.
+    let edges = (0..10)
+        .map(|a| (a, (a + 1) % 10))
+        .chain((0..10).step_by(2).map(|a| (a, (a + 4) % 10)))
+        .collect::<Vec<_>>();
+    let rewired = rewire(&edges, 1000, &mut rng);
+
+    let degrees = |edges: &[(usize, usize)]| {
+        let mut degrees = vec![(0, 0); 10];
+        for &(a, b) in edges {
+            degrees[a].1 += 1;
+            degrees[b].0 += 1;
+        }
+        degrees
+    };
+    assert_eq!(degrees(&rewired), degrees(&edges));
+    assert_ne!(rewired, edges);
+
+    let pairs = rewired.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect::<HashSet<_>>();
+    assert_eq!(pairs.len(), edges.len());
+    assert!(rewired.iter().all(|&(a, b)| a != b));
+
+    let control = degree_preserving_flag((0..10).collect(), &edges, None, &mut rng);
+    assert_eq!(control.num_simplices(0), 10);
+    assert_eq!(control.num_simplices(1), edges.len());
+}
@@ -0,0 +1,214 @@
+//! The updating persistence pairing, checked against known diagrams and
+//! against a filtration rebuilt from scratch after random updates.
+
+use proptest::prelude::*;
+use petgraph::graph::UnGraph;
+use topology::persistence::{weight_persistence, PersistencePair, UpdatingPersistence, WeightOrder};
+use topology::simplex::SimplicialComplex;
+
+const NUM_VERTICES: usize = 6;
+
+fn pair(dim: usize, birth: f64, death: Option<f64>) -> PersistencePair {
+    PersistencePair { dim, birth, death }
+}
+
+/// A hollow triangle whose edges arrive one at a time, filled in at time 4.
+fn triangle() -> UpdatingPersistence {
+    let mut persistence = UpdatingPersistence::new();
+
+    for vertex in 0..3 {
+        persistence.insert(&[vertex], 0.0).unwrap();
+    }
+
+    persistence.insert(&[0, 1], 1.0).unwrap();
+    persistence.insert(&[1, 2], 2.0).unwrap();
+    persistence.insert(&[0, 2], 3.0).unwrap();
+    persistence.insert(&[0, 1, 2], 4.0).unwrap();
+    persistence
+}
+
+#[test]
+fn filling_a_triangle() {
+    let persistence = triangle();
+
+    assert_eq!(
+        persistence.diagram(),
+        vec![
+            pair(0, 0.0, Some(1.0)),
+            pair(0, 0.0, Some(2.0)),
+            pair(0, 0.0, None),
+            pair(1, 3.0, Some(4.0)),
+        ]
+    );
+    assert_eq!(persistence.betti_numbers(), vec![1, 0, 0]);
+}
+
+#[test]
+fn removing_an_edge_reopens_the_triangle() {
+    let mut persistence = triangle();
+    persistence.remove(&[1, 2]).unwrap();
+
+    assert_eq!(persistence.len(), 5);
+    assert_eq!(
+        persistence.diagram(),
+        vec![
+            pair(0, 0.0, Some(1.0)),
+            pair(0, 0.0, Some(3.0)),
+            pair(0, 0.0, None),
+        ]
+    );
+}
+
+#[test]
+fn inserting_into_the_past() {
+    let mut persistence = triangle();
+    persistence.remove(&[0, 1]).unwrap();
+    persistence.insert(&[0, 1], 0.5).unwrap();
+
+    assert_eq!(
+        persistence.diagram(),
+        vec![
+            pair(0, 0.0, Some(0.5)),
+            pair(0, 0.0, Some(2.0)),
+            pair(0, 0.0, None),
+            pair(1, 3.0, None),
+        ]
+    );
+
+    // A simplex is never born before its faces.
+    persistence.insert(&[0, 1, 2], 1.0).unwrap();
+    assert_eq!(persistence.time(&[2, 1, 0]), Some(3.0));
+}
+
+#[test]
+fn following_a_complex() {
+    let mut complex = SimplicialComplex::new((0..4).collect(), None);
+    let mut persistence = UpdatingPersistence::new();
+    persistence.update(&complex, 0.0).unwrap();
+
+    for (time, &(a, b)) in [(0, 1), (1, 2), (2, 3), (3, 0)].iter().enumerate() {
+        complex.add(vec![a, b]).unwrap();
+        persistence.update(&complex, time as f64 + 1.0).unwrap();
+    }
+
+    assert_eq!(persistence.betti_numbers(), complex.betti_numbers_full());
+
+    complex.remove(vec![1, 2]).unwrap();
+    persistence.update(&complex, 5.0).unwrap();
+
+    assert_eq!(persistence.len(), 7);
+    assert_eq!(persistence.betti_numbers(), complex.betti_numbers_full());
+}
+
+/// A square with one diagonal, whose edges are weighted 1 through 5 around
+/// the square and then across it.
+fn weighted_square() -> (SimplicialComplex, impl Fn(usize, usize) -> f64) {
+    let graph = UnGraph::<(), ()>::from_edges([(0, 1), (1, 2), (2, 3), (0, 3), (0, 2)]);
+    let weight = |a, b| match (a, b) {
+        (0, 1) => 1.0,
+        (1, 2) => 2.0,
+        (2, 3) => 3.0,
+        (0, 3) => 4.0,
+        (0, 2) => 5.0,
+        _ => panic!("no edge between {} and {}", a, b),
+    };
+
+    (SimplicialComplex::clique_complex(&graph, 2), weight)
+}
+
+#[test]
+fn ascending_weights_fill_the_square_last() {
+    let (complex, weight) = weighted_square();
+
+    assert_eq!(
+        weight_persistence(&complex, WeightOrder::Ascending, weight),
+        vec![
+            pair(0, 1.0, Some(2.0)),
+            pair(0, 1.0, Some(3.0)),
+            pair(0, 1.0, None),
+            pair(1, 4.0, Some(5.0)),
+        ]
+    );
+}
+
+#[test]
+fn descending_weights_fill_each_triangle_as_it_closes() {
+    let (complex, weight) = weighted_square();
+
+    assert_eq!(
+        weight_persistence(&complex, WeightOrder::Descending, weight),
+        vec![
+            pair(0, 5.0, Some(4.0)),
+            pair(0, 5.0, Some(2.0)),
+            pair(0, 5.0, None),
+        ]
+    );
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Insert(usize, usize, u8),
+    RemoveEdge(usize, usize),
+    RemoveVertex(usize),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let edge = (0..NUM_VERTICES, 0..NUM_VERTICES).prop_filter("no self-loops", |(a, b)| a != b);
+
+    prop_oneof![
+        4 => (edge.clone(), 0..10u8).prop_map(|((a, b), time)| Op::Insert(a, b, time)),
+        2 => edge.prop_map(|(a, b)| Op::RemoveEdge(a, b)),
+        1 => (0..NUM_VERTICES).prop_map(Op::RemoveVertex),
+    ]
+}
+
+/// Inserts an edge and its vertices, along with every triangle it completes.
+fn insert_edge(persistence: &mut UpdatingPersistence, a: usize, b: usize, time: f64) {
+    persistence.insert(&[a], time).unwrap();
+    persistence.insert(&[b], time).unwrap();
+    persistence.insert(&[a, b], time).unwrap();
+
+    for c in 0..NUM_VERTICES {
+        if persistence.time(&[a, c]).is_some() && persistence.time(&[b, c]).is_some() {
+            persistence.insert(&[a, b, c], time).unwrap();
+        }
+    }
+}
+
+/// Rebuilds the filtration by inserting its simplices in order of time.
+fn rebuild(persistence: &UpdatingPersistence) -> UpdatingPersistence {
+    let mut simplices = persistence
+        .simplices()
+        .map(|(simplex, time)| (time, simplex.dim(), simplex.to_vec()))
+        .collect::<Vec<_>>();
+    simplices.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let mut rebuilt = UpdatingPersistence::new();
+
+    for (time, _, simplex) in simplices {
+        rebuilt.insert(&simplex, time).unwrap();
+    }
+
+    rebuilt
+}
+
+proptest! {
+    #[test]
+    fn updates_match_rebuild(ops in prop::collection::vec(op(), 1..40)) {
+        let mut persistence = UpdatingPersistence::new();
+
+        for op in ops {
+            match op {
+                Op::Insert(a, b, time) => insert_edge(&mut persistence, a, b, time as f64),
+                Op::RemoveEdge(a, b) => {
+                    let _ = persistence.remove(&[a, b]);
+                }
+                Op::RemoveVertex(a) => {
+                    let _ = persistence.remove(&[a]);
+                }
+            }
+
+            prop_assert_eq!(persistence.diagram(), rebuild(&persistence).diagram());
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use topology::recorder::{BettiRecorder, ControlRecorder, EventRecorder};
+use topology::simplex::{EventKind, SimplexEvent, SimplicialComplex};
+
+#[test]
+fn records_betti_curves() {
+    let mut complex = SimplicialComplex::new((0..4).collect(), None);
+    let mut recorder = BettiRecorder::new(Vec::new(), 3).unwrap();
+
+    recorder.record(0, &complex).unwrap();
+    for &(a, b) in &[(0, 1), (1, 2), (2, 3), (3, 0)] {
+        complex.add(vec![a, b]).unwrap();
+    }
+    recorder.record(10, &complex).unwrap();
+
+    let csv = String::from_utf8(recorder.into_inner()).unwrap();
+    assert_eq!(
+        csv,
+        "timestep,betti_0,betti_1,betti_2,simplices_0,simplices_1,simplices_2\n\
+         0,4,0,0,4,0,0\n\
+         10,1,1,0,4,4,0\n"
+    );
+}
+
+#[test]
+fn records_ratios_to_controls() {
+    // A hollow square against a path of its vertices.
+    let mut complex = SimplicialComplex::new((0..4).collect(), None);
+    let mut control = SimplicialComplex::new((0..4).collect(), None);
+    for &(a, b) in &[(0, 1), (1, 2), (2, 3), (3, 0)] {
+        complex.add(vec![a, b]).unwrap();
+    }
+    for &(a, b) in &[(0, 1), (1, 2), (2, 3)] {
+        control.add(vec![a, b]).unwrap();
+    }
+
+    let mut recorder = ControlRecorder::new(Vec::new(), 2).unwrap();
+    recorder.record(10, &complex, &control).unwrap();
+
+    let csv = String::from_utf8(recorder.into_inner()).unwrap();
+    assert_eq!(
+        csv,
+        "timestep,control_betti_0,control_betti_1,control_simplices_0,control_simplices_1,\
+         betti_ratio_0,betti_ratio_1,simplices_ratio_0,simplices_ratio_1\n\
+         10,1,0,4,3,1,,1,1.3333333333333333\n"
+    );
+}
+
+#[test]
+fn records_simplex_events() {
+    let mut complex = SimplicialComplex::new((0..3).collect(), None);
+    complex.add(vec![0, 1]).unwrap();
+    complex.record_events();
+    let mut recorder = EventRecorder::new(Vec::new()).unwrap();
+
+    complex.add_batch(&[vec![2, 1], vec![0, 2]]).unwrap();
+    recorder.record(5, &mut complex).unwrap();
+    complex.remove_vertex(1).unwrap();
+    complex.remove(vec![0, 2]).unwrap();
+    recorder.record(7, &mut complex).unwrap();
+    assert_eq!(complex.take_events(), Vec::new());
+
+    let csv = String::from_utf8(recorder.into_inner()).unwrap();
+    assert_eq!(
+        csv,
+        "timestep,event,dim,vertices\n\
+         5,birth,1,2 1\n\
+         5,birth,1,0 2\n\
+         5,birth,2,0 2 1\n\
+         7,death,2,0 2 1\n\
+         7,death,1,0 1\n\
+         7,death,1,2 1\n\
+         7,death,0,1\n\
+         7,death,1,0 2\n"
+    );
+}
+
+#[test]
+fn replaying_events_rebuilds_the_complex() {
+    let mut complex = SimplicialComplex::new((0..5).collect(), None);
+    complex.record_events();
+    let edges = [(0, 1), (1, 2), (0, 2), (2, 3), (1, 3), (0, 3), (3, 4)];
+    for &(a, b) in &edges {
+        complex.add(vec![a, b]).unwrap();
+    }
+    complex.remove(vec![1, 2]).unwrap();
+    complex.add(vec![2, 1]).unwrap();
+
+    let mut replayed = SimplicialComplex::new((0..5).collect(), None);
+    for SimplexEvent { kind, simplex } in complex.take_events() {
+        match kind {
+            EventKind::Birth => replayed.add_with_closure(simplex).unwrap(),
+            EventKind::Death => replayed.remove(simplex).unwrap(),
+        }
+    }
+
+    assert_eq!(replayed.f_vector(), complex.f_vector());
+    assert_eq!(replayed.betti_numbers(), complex.betti_numbers());
+    assert!(replayed.validate().is_valid());
+}
@@ -0,0 +1,62 @@
+use topology::persistence::PersistencePair;
+use topology::summaries::{self, Grid};
+
+fn pair(dim: usize, birth: f64, death: Option<f64>) -> PersistencePair {
+    PersistencePair { dim, birth, death }
+}
+
+fn diagram() -> Vec<PersistencePair> {
+    vec![
+        pair(0, 0., Some(2.)),
+        pair(0, 0., None),
+        pair(1, 1., Some(3.)),
+        pair(1, 2., Some(3.)),
+    ]
+}
+
+#[test]
+fn landscapes_are_the_largest_tents() {
+    let grid = Grid {
+        min: 0.,
+        max: 4.,
+        resolution: 5,
+    };
+    assert_eq!(grid.points().collect::<Vec<_>>(), vec![0., 1., 2., 3., 4.]);
+
+    assert_eq!(
+        summaries::landscapes(&diagram(), 1, 2, &grid),
+        vec![0., 0., 1., 0., 0., 0., 0., 0., 0., 0.]
+    );
+    // The class which never dies lives until the end of the grid.
+    assert_eq!(
+        summaries::landscapes(&diagram(), 0, 2, &grid),
+        vec![0., 1., 2., 1., 0., 0., 1., 0., 0., 0.]
+    );
+    assert_eq!(summaries::landscapes(&diagram(), 2, 1, &grid), vec![0.; 5]);
+}
+
+#[test]
+fn images_weigh_classes_by_persistence() {
+    let birth = Grid {
+        min: 0.,
+        max: 3.,
+        resolution: 4,
+    };
+    let persistence = Grid {
+        min: 0.,
+        max: 3.,
+        resolution: 4,
+    };
+    let image = summaries::image(&diagram(), 1, &birth, &persistence, 0.1);
+    assert_eq!(image.len(), 16);
+
+    // The classes are at (1, 2) and (2, 1), weighing 2/3 and 1/3, and the
+    // pixels are 1 by 1.
+    let peak = 1. / (2. * std::f64::consts::PI * 0.01);
+    assert!((image[2 * 4 + 1] - peak * 2. / 3.).abs() < 1e-9);
+    assert!((image[4 + 2] - peak / 3.).abs() < 1e-9);
+    let mut elsewhere = image.iter().enumerate().filter(|&(i, _)| i != 9 && i != 6);
+    assert!(elsewhere.all(|(_, &value)| value < 1e-9));
+
+    assert!(summaries::image(&[], 1, &birth, &persistence, 0.1).iter().all(|&value| value == 0.));
+}